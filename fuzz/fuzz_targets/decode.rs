@@ -0,0 +1,25 @@
+//! Decode arbitrary bytes with arbitrary descriptions, and encode and decode
+//! values generated from them. Run with `cargo fuzz run decode`.
+
+#![no_main]
+
+use libfuzzer_sys::arbitrary::{Arbitrary, Unstructured};
+use libfuzzer_sys::fuzz_target;
+use serde_descr::Description;
+
+#[path = "../../tests/common/fuzz.rs"]
+mod harness;
+#[path = "../../tests/common/scoped.rs"]
+mod scoped;
+
+fuzz_target!(|data: &[u8]| {
+    let mut u = Unstructured::new(data);
+    let Ok(description) = Description::arbitrary(&mut u) else {
+        return;
+    };
+    let Ok(seed) = u64::arbitrary(&mut u) else {
+        return;
+    };
+    harness::decode(&description, u.take_rest());
+    harness::generate(&description, seed);
+});
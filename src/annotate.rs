@@ -0,0 +1,133 @@
+use std::fmt::Display;
+
+use serde_value::Value;
+
+use crate::{
+    deserializer::{FieldsDecode, SchemaDecode, SchemaDecodeItems},
+    diff::{field_path, key_path, show},
+    project::{unwrap_value, Position, Shape},
+    SchemaDecoder,
+};
+
+/// The schema of a place in a value, at a path in the syntax of
+/// [`Query`](crate::Query), with the value itself if it holds no others.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Annotation {
+    pub path: String,
+    pub schema: String,
+    pub value: Option<Value>,
+}
+
+impl SchemaDecoder<'_> {
+    /// Walk a dynamic value decoded with this decoder, listing the schema of
+    /// every struct, enum, collection and scalar in it.
+    pub fn annotate(&self, value: &Value) -> Vec<Annotation> {
+        let mut out = Vec::new();
+        annotate(
+            String::new(),
+            Position::Schema(self.schema.lookup(&self.items)),
+            &self.items,
+            value,
+            &mut out,
+        );
+        out
+    }
+}
+
+fn annotate<'a, 'b>(
+    path: String,
+    position: Position<'a, 'b>,
+    items: &'a SchemaDecodeItems<'b>,
+    value: &Value,
+    out: &mut Vec<Annotation>,
+) {
+    let inner = unwrap_value(value);
+    let unwrapped = position.unwrap_for(items, value);
+    let schema = match (inner, &unwrapped) {
+        // `None` has the type of the option around it.
+        (Value::Option(None), _) => match position {
+            Position::Schema(schema) => schema.expected().to_string(),
+            Position::Fields(_) => "option".to_string(),
+        },
+        (_, Position::Schema(schema)) => schema.expected().to_string(),
+        (_, Position::Fields(FieldsDecode::Named(_))) => "struct variant".to_string(),
+        (_, Position::Fields(FieldsDecode::Tuple(_))) => "tuple variant".to_string(),
+    };
+    let child = |path: String, segment: &str, value: &Value, out: &mut Vec<Annotation>| {
+        let position = unwrapped.step(segment, items);
+        match position {
+            Ok(position) => annotate(path, position, items, value, out),
+            Err(error) => out.push(Annotation {
+                path,
+                schema: error,
+                value: Some(value.clone()),
+            }),
+        }
+    };
+
+    let shape = unwrapped.shape();
+    if let Shape::Enum(_) = shape {
+        match shape.entries(inner).into_iter().next() {
+            Some((name, content)) => {
+                out.push(Annotation {
+                    path: path.clone(),
+                    schema: format!("{schema}::{name}"),
+                    value: None,
+                });
+                if *content != Value::Unit {
+                    child(format!("{path}::{name}"), &name, &content, out);
+                }
+            }
+            None => out.push(Annotation {
+                path,
+                schema,
+                value: Some(inner.clone()),
+            }),
+        }
+        return;
+    }
+    match inner {
+        Value::Map(entries) if matches!(unwrapped, Position::Schema(SchemaDecode::Map(_))) => {
+            out.push(Annotation {
+                path: path.clone(),
+                schema,
+                value: None,
+            });
+            for (key, value) in entries {
+                child(format!("{path}{}", key_path(key)), "", value, out);
+            }
+        }
+        Value::Map(_) | Value::Seq(_) => {
+            out.push(Annotation {
+                path: path.clone(),
+                schema,
+                value: None,
+            });
+            for (name, value) in shape.entries(inner) {
+                let path = match inner {
+                    Value::Seq(_) => format!("{path}[{name}]"),
+                    _ => format!("{path}{}", field_path(&name)),
+                };
+                child(path, &name, &value, out);
+            }
+        }
+        inner => out.push(Annotation {
+            path,
+            schema,
+            value: Some(inner.clone()),
+        }),
+    }
+}
+
+impl Display for Annotation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let path = match self.path.is_empty() {
+            true => ".",
+            false => &self.path,
+        };
+        match &self.value {
+            Some(value) => write!(f, "{path}: {} = {}", self.schema, show(value)),
+            None => write!(f, "{path}: {}", self.schema),
+        }
+    }
+}
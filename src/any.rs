@@ -0,0 +1,350 @@
+//! Serde helpers for fields described by [`Schema::Any`](crate::Schema::Any),
+//! e.g. `#[serde(with = "serde_descr::any")] extra: serde_json::Value`.
+//!
+//! Human-readable formats hold the value as is. Compact formats can't decode
+//! a value without knowing its type, so they hold an envelope instead: an
+//! enum `Any` with one variant per kind of value, which recursively wraps
+//! the elements of sequences and the entries of maps.
+
+use std::collections::BTreeMap;
+
+use serde::{
+    de::{
+        DeserializeOwned, DeserializeSeed, EnumAccess, Error, MapAccess, SeqAccess, VariantAccess,
+        Visitor,
+    },
+    ser::{SerializeMap, SerializeSeq},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use serde_value::{Value, ValueDeserializer};
+
+use crate::deserializer::MAX_DEPTH;
+
+const NAME: &str = "Any";
+
+const VARIANTS: &[&str] = &[
+    "Unit", "Bool", "U8", "U16", "U32", "U64", "I8", "I16", "I32", "I64", "F32", "F64", "Char",
+    "String", "Bytes", "None", "Some", "Newtype", "Seq", "Map",
+];
+
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Serialize + ?Sized,
+    S: Serializer,
+{
+    match serializer.is_human_readable() {
+        true => value.serialize(serializer),
+        false => {
+            let value = serde_value::to_value(value).map_err(serde::ser::Error::custom)?;
+            Envelope(&value).serialize(serializer)
+        }
+    }
+}
+
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: DeserializeOwned,
+    D: Deserializer<'de>,
+{
+    match deserializer.is_human_readable() {
+        true => T::deserialize(deserializer),
+        false => {
+            let value = decode(deserializer, MAX_DEPTH)?;
+            T::deserialize(ValueDeserializer::<D::Error>::new(value))
+        }
+    }
+}
+
+/// Read an envelope into the value it holds, if that nests at most `depth`
+/// levels deep.
+pub(crate) fn decode<'de, D>(deserializer: D, depth: usize) -> Result<Value, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    EnvelopeSeed { depth }.deserialize(deserializer)
+}
+
+struct Envelope<'a>(&'a Value);
+
+impl Serialize for Envelope<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let index = variant_index(self.0);
+        let variant = VARIANTS[index as usize];
+        match self.0 {
+            Value::Unit | Value::Option(None) => {
+                serializer.serialize_unit_variant(NAME, index, variant)
+            }
+            Value::Option(Some(v)) | Value::Newtype(v) => {
+                serializer.serialize_newtype_variant(NAME, index, variant, &Envelope(v))
+            }
+            Value::Seq(v) => {
+                serializer.serialize_newtype_variant(NAME, index, variant, &EnvelopeSeq(v))
+            }
+            Value::Map(v) => {
+                serializer.serialize_newtype_variant(NAME, index, variant, &EnvelopeMap(v))
+            }
+            // Primitives serialize as themselves.
+            v => serializer.serialize_newtype_variant(NAME, index, variant, v),
+        }
+    }
+}
+
+fn variant_index(value: &Value) -> u32 {
+    match value {
+        Value::Unit => 0,
+        Value::Bool(_) => 1,
+        Value::U8(_) => 2,
+        Value::U16(_) => 3,
+        Value::U32(_) => 4,
+        Value::U64(_) => 5,
+        Value::I8(_) => 6,
+        Value::I16(_) => 7,
+        Value::I32(_) => 8,
+        Value::I64(_) => 9,
+        Value::F32(_) => 10,
+        Value::F64(_) => 11,
+        Value::Char(_) => 12,
+        Value::String(_) => 13,
+        Value::Bytes(_) => 14,
+        Value::Option(None) => 15,
+        Value::Option(Some(_)) => 16,
+        Value::Newtype(_) => 17,
+        Value::Seq(_) => 18,
+        Value::Map(_) => 19,
+    }
+}
+
+struct EnvelopeSeq<'a>(&'a [Value]);
+
+impl Serialize for EnvelopeSeq<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.0.len()))?;
+        self.0
+            .iter()
+            .try_for_each(|v| seq.serialize_element(&Envelope(v)))?;
+        seq.end()
+    }
+}
+
+struct EnvelopeMap<'a>(&'a BTreeMap<Value, Value>);
+
+impl Serialize for EnvelopeMap<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        self.0
+            .iter()
+            .try_for_each(|(k, v)| map.serialize_entry(&Envelope(k), &Envelope(v)))?;
+        map.end()
+    }
+}
+
+/// Reads an envelope nested at most `depth` levels deep.
+#[derive(Clone, Copy)]
+struct EnvelopeSeed {
+    depth: usize,
+}
+
+impl EnvelopeSeed {
+    /// The seed of the values inside this one.
+    fn nested<E: Error>(self) -> Result<Self, E> {
+        match self.depth.checked_sub(1) {
+            Some(depth) => Ok(Self { depth }),
+            None => Err(E::custom("value of enum Any nested too deeply")),
+        }
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for EnvelopeSeed {
+    type Value = Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_enum(NAME, VARIANTS, self)
+    }
+}
+
+impl<'de> Visitor<'de> for EnvelopeSeed {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "enum {NAME}")
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+    where
+        A: EnumAccess<'de>,
+    {
+        let (index, variant) = data.variant::<VariantIndex>()?;
+        Ok(match index.0 {
+            0 => variant.unit_variant().map(|()| Value::Unit)?,
+            1 => Value::Bool(variant.newtype_variant()?),
+            2 => Value::U8(variant.newtype_variant()?),
+            3 => Value::U16(variant.newtype_variant()?),
+            4 => Value::U32(variant.newtype_variant()?),
+            5 => Value::U64(variant.newtype_variant()?),
+            6 => Value::I8(variant.newtype_variant()?),
+            7 => Value::I16(variant.newtype_variant()?),
+            8 => Value::I32(variant.newtype_variant()?),
+            9 => Value::I64(variant.newtype_variant()?),
+            10 => Value::F32(variant.newtype_variant()?),
+            11 => Value::F64(variant.newtype_variant()?),
+            12 => Value::Char(variant.newtype_variant()?),
+            13 => Value::String(variant.newtype_variant()?),
+            14 => Value::Bytes(variant.newtype_variant::<ByteBuf>()?.0),
+            15 => variant.unit_variant().map(|()| Value::Option(None))?,
+            16 => Value::Option(Some(Box::new(
+                variant.newtype_variant_seed(self.nested()?)?,
+            ))),
+            17 => Value::Newtype(Box::new(variant.newtype_variant_seed(self.nested()?)?)),
+            18 => variant.newtype_variant_seed(EnvelopeSeqSeed(self.nested()?))?,
+            _ => variant.newtype_variant_seed(EnvelopeMapSeed(self.nested()?))?,
+        })
+    }
+}
+
+/// Reads the elements of a `Seq` envelope.
+struct EnvelopeSeqSeed(EnvelopeSeed);
+
+impl<'de> DeserializeSeed<'de> for EnvelopeSeqSeed {
+    type Value = Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de> Visitor<'de> for EnvelopeSeqSeed {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "a sequence of enum {NAME}")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut values = Vec::new();
+        while let Some(value) = seq.next_element_seed(self.0)? {
+            values.push(value);
+        }
+        Ok(Value::Seq(values))
+    }
+}
+
+/// Reads the entries of a `Map` envelope.
+struct EnvelopeMapSeed(EnvelopeSeed);
+
+impl<'de> DeserializeSeed<'de> for EnvelopeMapSeed {
+    type Value = Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for EnvelopeMapSeed {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "a map of enum {NAME}")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut entries = BTreeMap::new();
+        while let Some((key, value)) = map.next_entry_seed(self.0, self.0)? {
+            entries.insert(key, value);
+        }
+        Ok(Value::Map(entries))
+    }
+}
+
+struct VariantIndex(usize);
+
+impl<'de> Deserialize<'de> for VariantIndex {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct IndexVisitor;
+
+        impl Visitor<'_> for IndexVisitor {
+            type Value = VariantIndex;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "a variant of enum {NAME}")
+            }
+
+            fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> {
+                match (v as usize) < VARIANTS.len() {
+                    true => Ok(VariantIndex(v as usize)),
+                    false => Err(E::invalid_value(serde::de::Unexpected::Unsigned(v), &self)),
+                }
+            }
+
+            fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+                VARIANTS
+                    .iter()
+                    .position(|name| *name == v)
+                    .map(VariantIndex)
+                    .ok_or_else(|| E::unknown_variant(v, VARIANTS))
+            }
+        }
+
+        deserializer.deserialize_identifier(IndexVisitor)
+    }
+}
+
+struct ByteBuf(Vec<u8>);
+
+impl<'de> Deserialize<'de> for ByteBuf {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct BytesVisitor;
+
+        impl<'de> Visitor<'de> for BytesVisitor {
+            type Value = ByteBuf;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "bytes")
+            }
+
+            fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+                Ok(ByteBuf(v.to_vec()))
+            }
+
+            fn visit_byte_buf<E: Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+                Ok(ByteBuf(v))
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::SeqAccess<'de>,
+            {
+                let mut bytes = Vec::new();
+                while let Some(byte) = seq.next_element()? {
+                    bytes.push(byte);
+                }
+                Ok(ByteBuf(bytes))
+            }
+        }
+
+        deserializer.deserialize_byte_buf(BytesVisitor)
+    }
+}
@@ -0,0 +1,288 @@
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+};
+
+use serde_json::{json, Map, Value};
+
+use crate::{
+    schema::{identifier, identifiers},
+    Description, EnumRepr, EnumSchema, FieldsSchema, Metadata, RenameAll, Schema, SchemaItem,
+    SchemaName, SimpleSchema, StructSchema,
+};
+
+impl Description {
+    /// Export the description as an Avro schema. Records and enums are
+    /// defined where they are first used and referred to by name after that,
+    /// and enums with data are unions of a record per variant. Avro says
+    /// less than descriptions: 64-bit unsigned integers are `long`s, 128-bit
+    /// integers are `bytes`, maps with keys other than strings are arrays of
+    /// key-value records, and `Any` values are strings of JSON.
+    pub fn avro(&self) -> Value {
+        let names = identifiers(self.items.keys());
+        let exporter = Exporter {
+            descr: self,
+            taken: RefCell::new(names.values().cloned().collect()),
+            names,
+            defined: RefCell::default(),
+        };
+        exporter.item(&self.schema, "Root")
+    }
+}
+
+struct Exporter<'a> {
+    descr: &'a Description,
+    names: BTreeMap<&'a SchemaName, String>,
+    /// The names of named schemas and of records made up so far.
+    taken: RefCell<BTreeSet<String>>,
+    /// What later uses of the named schemas defined so far refer to: their
+    /// names, or the names of the records in their unions.
+    defined: RefCell<BTreeMap<String, Value>>,
+}
+
+impl Exporter<'_> {
+    fn item(&self, item: &SchemaItem, hint: &str) -> Value {
+        match item {
+            SchemaItem::Schema(schema) => self.schema(schema, hint),
+            SchemaItem::Named(name) => self.named(name),
+        }
+    }
+
+    fn named(&self, name: &SchemaName) -> Value {
+        let Some(schema) = self.descr.items.get(name) else {
+            return json!("null");
+        };
+        if let Some(scalar) = self.scalar(schema) {
+            return scalar;
+        }
+        let id = &self.names[name];
+        if let Some(reference) = self.defined.borrow().get(id) {
+            return reference.clone();
+        }
+        match schema {
+            Schema::Struct(s) => {
+                self.defined.borrow_mut().insert(id.clone(), json!(id));
+                self.record(id, s)
+            }
+            Schema::Enum(s) => self.enumeration(id, s),
+            // Only records can refer to themselves, so other schemas are
+            // held in one.
+            schema => {
+                self.defined.borrow_mut().insert(id.clone(), json!(id));
+                let value = json!({ "name": "value", "type": self.schema(schema, id) });
+                json!({ "type": "record", "name": id, "fields": [value] })
+            }
+        }
+    }
+
+    /// The primitive type of schemas without fields of their own.
+    fn scalar(&self, schema: &Schema) -> Option<Value> {
+        let schema = match schema {
+            Schema::Newtype(s) => self.descr.resolve_json(&s.value),
+            Schema::Constrained(s) => self.descr.resolve_json(&s.value),
+            Schema::HumanReadable(s) => self.descr.resolve_json(&s.readable),
+            schema => Some(schema),
+        };
+        let ty = match schema {
+            // A cycle of newtypes has no values.
+            None | Some(Schema::UnitStruct(_)) => "null",
+            Some(Schema::Simple(s)) => simple(*s),
+            Some(Schema::Any) => "string",
+            Some(_) => return None,
+        };
+        Some(json!(ty))
+    }
+
+    fn schema(&self, schema: &Schema, hint: &str) -> Value {
+        if let Some(scalar) = self.scalar(schema) {
+            return scalar;
+        }
+        match schema {
+            // Unions cannot hold unions, so options of enums add a branch.
+            Schema::Option(s) => match self.item(&s.value, hint) {
+                Value::Array(mut branches) => {
+                    if !branches.contains(&json!("null")) {
+                        branches.insert(0, json!("null"));
+                    }
+                    Value::Array(branches)
+                }
+                value => json!(["null", value]),
+            },
+            Schema::Seq(s) => self.array(&s.value, hint),
+            Schema::Set(s) => self.array(&s.value, hint),
+            Schema::Array(s) => self.array(&s.value, hint),
+            Schema::Map(s) => match self.descr.resolve_json(&s.key) {
+                Some(Schema::Simple(SimpleSchema::String | SimpleSchema::Char)) => {
+                    json!({ "type": "map", "values": self.item(&s.value, &format!("{hint}Value")) })
+                }
+                _ => {
+                    let entry = self.fresh(&format!("{hint}Entry"));
+                    let key = self.item(&s.key, &format!("{entry}Key"));
+                    let value = self.item(&s.value, &format!("{entry}Value"));
+                    let key = json!({ "name": "key", "type": key });
+                    let value = json!({ "name": "value", "type": value });
+                    json!({
+                        "type": "array",
+                        "items": { "type": "record", "name": entry, "fields": [key, value] },
+                    })
+                }
+            },
+            Schema::Tuple(s) => {
+                let name = self.fresh(hint);
+                let fields = self.tuple_fields(&s.values, &name);
+                json!({ "type": "record", "name": name, "fields": fields })
+            }
+            Schema::Struct(s) => self.record(&self.fresh(hint), s),
+            Schema::Enum(s) => self.enumeration(&self.fresh(hint), s),
+            Schema::Newtype(s) => self.item(&s.value, hint),
+            Schema::Constrained(s) => self.item(&s.value, hint),
+            Schema::HumanReadable(s) => self.item(&s.readable, hint),
+            Schema::Simple(_) | Schema::UnitStruct(_) | Schema::Any => {
+                unreachable!("scalars are handled above")
+            }
+        }
+    }
+
+    fn array(&self, item: &SchemaItem, hint: &str) -> Value {
+        json!({ "type": "array", "items": self.item(item, &format!("{hint}Item")) })
+    }
+
+    /// `hint`, numbered if a record already has that name.
+    fn fresh(&self, hint: &str) -> String {
+        let base = identifier(hint);
+        let mut taken = self.taken.borrow_mut();
+        let mut name = base.clone();
+        for n in 2.. {
+            if taken.insert(name.clone()) {
+                break;
+            }
+            name = format!("{base}{n}");
+        }
+        name
+    }
+
+    fn record(&self, name: &str, s: &StructSchema) -> Value {
+        let fields = self.fields(&s.fields, s.rename_all, name);
+        let mut record = Map::new();
+        record.insert("type".to_string(), json!("record"));
+        record.insert("name".to_string(), json!(name));
+        record.extend(doc(&s.meta));
+        record.insert("fields".to_string(), Value::Array(fields));
+        Value::Object(record)
+    }
+
+    fn fields(
+        &self,
+        fields: &FieldsSchema,
+        rename_all: Option<RenameAll>,
+        record: &str,
+    ) -> Vec<Value> {
+        let fields = match fields {
+            FieldsSchema::Tuple(t) => return self.tuple_fields(&t.values, record),
+            FieldsSchema::Named(fields) => &fields.fields,
+        };
+        fields
+            .iter()
+            .filter(|f| !(f.skip_serializing && f.skip_deserializing))
+            .map(|f| {
+                let name = identifier(&f.wire_name(rename_all));
+                let hint = format!("{record}_{}", RenameAll::Pascal.apply_to_field(&name));
+                let mut field = Map::new();
+                field.insert("name".to_string(), json!(name));
+                field.insert("type".to_string(), self.item(&f.value, &hint));
+                field.extend(doc(&f.meta));
+                if !f.aliases.is_empty() {
+                    let aliases: Vec<_> = f.aliases.iter().map(|alias| identifier(alias)).collect();
+                    field.insert("aliases".to_string(), json!(aliases));
+                }
+                // The default of a union is one of its first branch, which
+                // is null for options.
+                let optional = matches!(self.descr.resolve_json(&f.value), Some(Schema::Option(_)));
+                let default = match &f.default {
+                    Some(default) => serde_json::to_value(default).ok(),
+                    None => optional.then_some(Value::Null),
+                };
+                if let Some(default) = default.filter(|default| !optional || default.is_null()) {
+                    field.insert("default".to_string(), default);
+                }
+                Value::Object(field)
+            })
+            .collect()
+    }
+
+    fn tuple_fields(&self, values: &[SchemaItem], record: &str) -> Vec<Value> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                let ty = self.item(value, &format!("{record}_{i}"));
+                json!({ "name": format!("_{i}"), "type": ty })
+            })
+            .collect()
+    }
+
+    /// An Avro enum for enums without data, and a union of a record per
+    /// variant for others.
+    fn enumeration(&self, name: &str, s: &EnumSchema) -> Value {
+        let unit = s.variants.iter().all(|v| match &v.fields {
+            FieldsSchema::Tuple(t) => t.values.is_empty(),
+            FieldsSchema::Named(_) => false,
+        });
+        if unit || matches!(s.repr, EnumRepr::Integer { .. }) {
+            self.defined
+                .borrow_mut()
+                .insert(name.to_string(), json!(name));
+            let symbols: Vec<_> = s
+                .variants
+                .iter()
+                .map(|v| identifier(&v.wire_name(s.rename_all)))
+                .collect();
+            let mut enumeration = Map::new();
+            enumeration.insert("type".to_string(), json!("enum"));
+            enumeration.insert("name".to_string(), json!(name));
+            enumeration.extend(doc(&s.meta));
+            enumeration.insert("symbols".to_string(), json!(symbols));
+            return Value::Object(enumeration);
+        }
+
+        let records: Vec<String> = s
+            .variants
+            .iter()
+            .map(|v| self.fresh(&format!("{name}_{}", v.wire_name(s.rename_all))))
+            .collect();
+        self.defined
+            .borrow_mut()
+            .insert(name.to_string(), json!(records));
+        let branches = s.variants.iter().zip(&records).map(|(v, record)| {
+            let mut branch = Map::new();
+            branch.insert("type".to_string(), json!("record"));
+            branch.insert("name".to_string(), json!(record));
+            branch.extend(doc(&v.meta));
+            let fields = self.fields(&v.fields, None, record);
+            branch.insert("fields".to_string(), Value::Array(fields));
+            Value::Object(branch)
+        });
+        Value::Array(branches.collect())
+    }
+}
+
+fn simple(s: SimpleSchema) -> &'static str {
+    match s {
+        SimpleSchema::Unit => "null",
+        SimpleSchema::Bool => "boolean",
+        SimpleSchema::U8
+        | SimpleSchema::U16
+        | SimpleSchema::I8
+        | SimpleSchema::I16
+        | SimpleSchema::I32 => "int",
+        SimpleSchema::U32 | SimpleSchema::U64 | SimpleSchema::I64 => "long",
+        SimpleSchema::F32 => "float",
+        SimpleSchema::F64 => "double",
+        SimpleSchema::Char | SimpleSchema::String => "string",
+        SimpleSchema::U128 | SimpleSchema::I128 | SimpleSchema::Bytes => "bytes",
+    }
+}
+
+fn doc(meta: &Metadata) -> Option<(String, Value)> {
+    meta.doc.as_ref().map(|doc| ("doc".to_string(), json!(doc)))
+}
@@ -0,0 +1,797 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+};
+
+use serde::Serialize;
+
+use crate::{
+    idl::{self, Layout},
+    Description, DeserializerOptions, EnumRepr, EnumSchema, EnumTag, FieldsSchema, IntEncoding,
+    NamedFieldSchema, NamedFieldsSchema, OptionFormat, RenameAll, Schema, SchemaItem, SchemaName,
+    SeqSchema, SetSchema, SimpleSchema, StructFormat,
+};
+
+/// A difference between two descriptions, at a path like `Type`,
+/// `Type.field` or `Enum::Variant.0` (empty for the root), with types and
+/// values in the text form of [`Description::idl`].
+#[derive(PartialEq, Eq, Clone, Debug, Serialize)]
+pub struct SchemaChange {
+    pub path: String,
+    #[serde(flatten)]
+    pub kind: ChangeKind,
+    /// Whether values written with the old description may fail to decode
+    /// with the new one, or decode to something else.
+    pub breaking: bool,
+}
+
+/// What changed, serialized as the `change` of a [`SchemaChange`].
+#[derive(PartialEq, Eq, Clone, Debug, Serialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum ChangeKind {
+    Added {
+        element: Element,
+    },
+    Removed {
+        element: Element,
+    },
+    /// A named schema is known by another name, or a field or variant has
+    /// another name in Rust (shown if it does) or on the wire.
+    Renamed {
+        element: Element,
+        old: String,
+        new: String,
+    },
+    /// A field moved among the others, or a variant has another index.
+    Moved {
+        old: usize,
+        new: usize,
+    },
+    Type {
+        old: String,
+        new: String,
+    },
+    Default {
+        old: Option<String>,
+        new: Option<String>,
+    },
+    /// An enum is represented differently.
+    Repr {
+        old: String,
+        new: String,
+    },
+    /// A variant of an integer enum has another discriminant.
+    Discriminant {
+        old: i64,
+        new: i64,
+    },
+}
+
+/// What was added, removed or renamed.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Element {
+    Type,
+    Field,
+    Variant,
+}
+
+impl Description {
+    /// List what changed from this description to `new`: the named schemas,
+    /// fields and variants added, removed or renamed, and the types,
+    /// defaults, enum representations and variant tags that differ. A named
+    /// schema only in `new` that takes the place of one only in this
+    /// description is taken for a rename of it.
+    ///
+    /// A change is breaking if a [`SchemaDecoder`](crate::SchemaDecoder) of
+    /// `new` may fail to decode values written with this description in the
+    /// profile `opts`, or decode them to something else.
+    pub fn diff(&self, new: &Description, opts: &DeserializerOptions) -> Vec<SchemaChange> {
+        let mut differ = Differ {
+            old: self,
+            new,
+            opts,
+            renames: BTreeMap::new(),
+            pending: Vec::new(),
+            changes: Vec::new(),
+        };
+        differ.item(String::new(), &self.schema, &new.schema);
+        differ.renamed();
+        for (name, old) in &self.items {
+            if let Some(new) = new.items.get(name) {
+                differ.schema(name.to_string(), old, new);
+                differ.renamed();
+            }
+        }
+
+        let renamed: BTreeSet<_> = differ.renames.values().copied().collect();
+        for name in self.items.keys() {
+            if !new.items.contains_key(name) && !differ.renames.contains_key(name) {
+                let kind = ChangeKind::Removed {
+                    element: Element::Type,
+                };
+                differ.push(name.to_string(), kind, false);
+            }
+        }
+        for name in new.items.keys() {
+            if !self.items.contains_key(name) && !renamed.contains(name) {
+                let kind = ChangeKind::Added {
+                    element: Element::Type,
+                };
+                differ.push(name.to_string(), kind, false);
+            }
+        }
+        differ.changes
+    }
+}
+
+/// Pairs of schemas taken to read each other while finding out whether
+/// they do, so that recursive types terminate.
+type Assumed = BTreeSet<(*const Schema, *const Schema)>;
+
+struct Differ<'a> {
+    old: &'a Description,
+    new: &'a Description,
+    opts: &'a DeserializerOptions,
+    /// Named schemas of the old description and the ones of the new
+    /// description they became.
+    renames: BTreeMap<&'a SchemaName, &'a SchemaName>,
+    /// Renames whose schemas have yet to be compared.
+    pending: Vec<(&'a SchemaName, &'a SchemaName)>,
+    changes: Vec<SchemaChange>,
+}
+
+impl<'a> Differ<'a> {
+    fn push(&mut self, path: String, kind: ChangeKind, breaking: bool) {
+        self.changes.push(SchemaChange {
+            path,
+            kind,
+            breaking,
+        });
+    }
+
+    /// Compare the schemas of the renames found so far, which may find more.
+    fn renamed(&mut self) {
+        while !self.pending.is_empty() {
+            for (old, new) in std::mem::take(&mut self.pending) {
+                let kind = ChangeKind::Renamed {
+                    element: Element::Type,
+                    old: old.to_string(),
+                    new: new.to_string(),
+                };
+                self.push(new.to_string(), kind, false);
+                let (old_items, new_items) = (&self.old.items, &self.new.items);
+                self.schema(new.to_string(), &old_items[old], &new_items[new]);
+            }
+        }
+    }
+
+    fn item(&mut self, path: String, old: &'a SchemaItem, new: &'a SchemaItem) {
+        match (old, new) {
+            (SchemaItem::Schema(old), SchemaItem::Schema(new)) => self.schema(path, old, new),
+            _ if self.equivalent(old, new) => {}
+            _ => {
+                let breaking = !self.reads(old, new, &mut Assumed::new());
+                let kind = ChangeKind::Type {
+                    old: idl::item(old),
+                    new: idl::item(new),
+                };
+                self.push(path, kind, breaking);
+            }
+        }
+    }
+
+    fn schema(&mut self, path: String, old: &'a Schema, new: &'a Schema) {
+        match (old, new) {
+            (Schema::Struct(o), Schema::Struct(n)) => {
+                self.fields(path, &o.fields, &n.fields, (o.rename_all, n.rename_all))
+            }
+            (Schema::Enum(o), Schema::Enum(n)) => self.enumeration(path, o, n),
+            (Schema::Newtype(o), Schema::Newtype(n)) => self.item(path, &o.value, &n.value),
+            _ if self.equivalent_schema(old, new) => {}
+            _ => {
+                let breaking = !self.reads_schema(old, new, &mut Assumed::new());
+                let kind = ChangeKind::Type {
+                    old: idl::inline(old),
+                    new: idl::inline(new),
+                };
+                self.push(path, kind, breaking);
+            }
+        }
+    }
+
+    fn fields(
+        &mut self,
+        path: String,
+        old: &'a FieldsSchema,
+        new: &'a FieldsSchema,
+        rules: (Option<RenameAll>, Option<RenameAll>),
+    ) {
+        match (old, new) {
+            (FieldsSchema::Tuple(o), FieldsSchema::Tuple(n)) => {
+                for (i, (o, n)) in o.values.iter().zip(&n.values).enumerate() {
+                    self.item(format!("{path}.{i}"), o, n);
+                }
+                for i in n.values.len()..o.values.len() {
+                    let kind = ChangeKind::Removed {
+                        element: Element::Field,
+                    };
+                    self.push(format!("{path}.{i}"), kind, true);
+                }
+                for i in o.values.len()..n.values.len() {
+                    let kind = ChangeKind::Added {
+                        element: Element::Field,
+                    };
+                    self.push(format!("{path}.{i}"), kind, true);
+                }
+            }
+            (FieldsSchema::Named(o), FieldsSchema::Named(n)) => {
+                self.named_fields(path, o, n, rules)
+            }
+            _ => {
+                let kind = ChangeKind::Type {
+                    old: fields_text(old),
+                    new: fields_text(new),
+                };
+                self.push(path, kind, true);
+            }
+        }
+    }
+
+    fn named_fields(
+        &mut self,
+        path: String,
+        old: &'a NamedFieldsSchema,
+        new: &'a NamedFieldsSchema,
+        (old_rule, new_rule): (Option<RenameAll>, Option<RenameAll>),
+    ) {
+        // Fields that are never written aren't part of the encoding.
+        let old: Vec<_> = old.fields.iter().filter(|f| !f.skip_serializing).collect();
+        let new: Vec<_> = new.fields.iter().filter(|f| !f.skip_serializing).collect();
+        let by_name = self.opts.struct_format == StructFormat::Map;
+        let pairs = pair(
+            (old.len(), new.len()),
+            |i, j| old[i].name == new[j].name,
+            |i, j| {
+                accepts(new[j], new_rule, &old[i].wire_name(old_rule))
+                    || (i == j && old[i].value == new[j].value)
+            },
+        );
+
+        let flattened = new.iter().any(|f| f.flatten);
+        for (i, o) in old.iter().enumerate() {
+            if !pairs.iter().any(|&(pi, _)| pi == i) {
+                let kind = ChangeKind::Removed {
+                    element: Element::Field,
+                };
+                self.push(format!("{path}.{}", o.name), kind, !by_name || !flattened);
+            }
+        }
+        let order: Vec<_> = pairs.iter().map(|&(i, _)| i).collect();
+        let moved = moved(&order);
+        for (j, n) in new.iter().enumerate() {
+            let path = format!("{path}.{}", n.name);
+            let Some(p) = pairs.iter().position(|&(_, pj)| pj == j) else {
+                let kind = ChangeKind::Added {
+                    element: Element::Field,
+                };
+                self.push(path, kind, !by_name || !self.fillable(n));
+                continue;
+            };
+            let (i, o) = (pairs[p].0, old[pairs[p].0]);
+            let (old_name, new_name) = (o.wire_name(old_rule), n.wire_name(new_rule));
+            if o.name != n.name || old_name != new_name {
+                let breaking = by_name && !o.flatten && !accepts(n, new_rule, &old_name);
+                let (old, new) = match o.name != n.name {
+                    true => (o.name.clone(), n.name.clone()),
+                    false => (old_name.into_owned(), new_name.into_owned()),
+                };
+                let kind = ChangeKind::Renamed {
+                    element: Element::Field,
+                    old,
+                    new,
+                };
+                self.push(path.clone(), kind, breaking);
+            }
+            if moved[p] {
+                self.push(path.clone(), ChangeKind::Moved { old: i, new: j }, !by_name);
+            }
+            self.item(path.clone(), &o.value, &n.value);
+            if o.default != n.default {
+                let kind = ChangeKind::Default {
+                    old: o.default.as_ref().map(idl::literal),
+                    new: n.default.as_ref().map(idl::literal),
+                };
+                // Only values that leave the field out decode differently.
+                self.push(path, kind, o.skip_serializing_if.is_some());
+            }
+        }
+    }
+
+    fn enumeration(&mut self, path: String, old: &'a EnumSchema, new: &'a EnumSchema) {
+        if old.repr != new.repr {
+            let kind = ChangeKind::Repr {
+                old: repr(&old.repr),
+                new: repr(&new.repr),
+            };
+            self.push(path.clone(), kind, !old.variants.is_empty());
+        }
+        let integer = matches!(
+            (&old.repr, &new.repr),
+            (EnumRepr::Integer { .. }, EnumRepr::Integer { .. })
+        );
+        let by_name = self.opts.enum_tag == EnumTag::Name && !integer;
+        let pairs = pair(
+            (old.variants.len(), new.variants.len()),
+            |i, j| old.variants[i].name == new.variants[j].name,
+            |i, j| {
+                let (o, n) = (&old.variants[i], &new.variants[j]);
+                let name = o.wire_name(old.rename_all);
+                n.wire_name(new.rename_all) == name
+                    || n.aliases.iter().any(|alias| *alias == name)
+                    || (i == j && o.fields == n.fields)
+            },
+        );
+
+        for (i, o) in old.variants.iter().enumerate() {
+            if !pairs.iter().any(|&(pi, _)| pi == i) {
+                let kind = ChangeKind::Removed {
+                    element: Element::Variant,
+                };
+                self.push(format!("{path}::{}", o.name), kind, true);
+            }
+        }
+        let (old_discriminants, new_discriminants) = (old.discriminants(), new.discriminants());
+        for (j, n) in new.variants.iter().enumerate() {
+            let path = format!("{path}::{}", n.name);
+            let Some(&(i, _)) = pairs.iter().find(|&&(_, pj)| pj == j) else {
+                let kind = ChangeKind::Added {
+                    element: Element::Variant,
+                };
+                self.push(path, kind, false);
+                continue;
+            };
+            let o = &old.variants[i];
+            let (old_name, new_name) = (o.wire_name(old.rename_all), n.wire_name(new.rename_all));
+            if o.name != n.name || old_name != new_name {
+                let breaking = by_name
+                    && old_name != new_name
+                    && !n.aliases.iter().any(|alias| *alias == old_name);
+                let (old, new) = match o.name != n.name {
+                    true => (o.name.clone(), n.name.clone()),
+                    false => (old_name.into_owned(), new_name.into_owned()),
+                };
+                let kind = ChangeKind::Renamed {
+                    element: Element::Variant,
+                    old,
+                    new,
+                };
+                self.push(path.clone(), kind, breaking);
+            }
+            if integer {
+                let (old, new) = (old_discriminants[i], new_discriminants[j]);
+                if old != new {
+                    self.push(path.clone(), ChangeKind::Discriminant { old, new }, true);
+                }
+            } else if o.id.unwrap_or(i) != n.id.unwrap_or(j) {
+                let kind = ChangeKind::Moved {
+                    old: o.id.unwrap_or(i),
+                    new: n.id.unwrap_or(j),
+                };
+                self.push(path.clone(), kind, self.opts.enum_tag == EnumTag::Index);
+            }
+            self.fields(path, &o.fields, &n.fields, (None, None));
+        }
+    }
+
+    /// Whether two types are the same but for the names of renamed schemas.
+    /// Named schemas aren't looked into: they are compared on their own.
+    fn equivalent(&mut self, old: &'a SchemaItem, new: &'a SchemaItem) -> bool {
+        match (old, new) {
+            (SchemaItem::Named(old), SchemaItem::Named(new)) => self.same_name(old, new),
+            (SchemaItem::Schema(old), SchemaItem::Schema(new)) => self.equivalent_schema(old, new),
+            _ => false,
+        }
+    }
+
+    fn equivalent_schema(&mut self, old: &'a Schema, new: &'a Schema) -> bool {
+        match (old, new) {
+            (Schema::Option(o), Schema::Option(n)) => self.equivalent(&o.value, &n.value),
+            (Schema::Seq(o), Schema::Seq(n)) => self.equivalent(&o.value, &n.value),
+            (Schema::Set(o), Schema::Set(n)) => self.equivalent(&o.value, &n.value),
+            (Schema::Array(o), Schema::Array(n)) => {
+                o.len == n.len && self.equivalent(&o.value, &n.value)
+            }
+            (Schema::Map(o), Schema::Map(n)) => {
+                self.equivalent(&o.key, &n.key) && self.equivalent(&o.value, &n.value)
+            }
+            (Schema::HumanReadable(o), Schema::HumanReadable(n)) => {
+                self.equivalent(&o.readable, &n.readable) && self.equivalent(&o.compact, &n.compact)
+            }
+            (Schema::Constrained(o), Schema::Constrained(n)) => {
+                o.constraints == n.constraints && self.equivalent(&o.value, &n.value)
+            }
+            (Schema::Tuple(o), Schema::Tuple(n)) => {
+                o.values.len() == n.values.len()
+                    && o.values
+                        .iter()
+                        .zip(&n.values)
+                        .all(|(o, n)| self.equivalent(o, n))
+            }
+            _ => old == new,
+        }
+    }
+
+    /// Whether `old` and `new` name the same schema, taking `new` for a
+    /// rename of `old` if it can be one and `old` hasn't been renamed yet.
+    fn same_name(&mut self, old: &'a SchemaName, new: &'a SchemaName) -> bool {
+        if let Some(renamed) = self.renames.get(old) {
+            return *renamed == new;
+        }
+        if old == new {
+            return true;
+        }
+        let rename = self.old.items.contains_key(old)
+            && !self.new.items.contains_key(old)
+            && self.new.items.contains_key(new)
+            && !self.old.items.contains_key(new)
+            && !self.renames.values().any(|renamed| *renamed == new);
+        if rename {
+            self.renames.insert(old, new);
+            self.pending.push((old, new));
+        }
+        rename
+    }
+
+    /// Whether a decoder of `new` reads every value of `old` written with
+    /// the old description. The changes of a named schema that is the same
+    /// in both are listed, and judged, on their own.
+    fn reads(&self, old: &SchemaItem, new: &SchemaItem, assumed: &mut Assumed) -> bool {
+        if let (SchemaItem::Named(old), SchemaItem::Named(new)) = (old, new) {
+            if self
+                .renames
+                .get(old)
+                .map_or(old == new, |renamed| *renamed == new)
+            {
+                return true;
+            }
+        }
+        match (lookup(self.old, old), lookup(self.new, new)) {
+            (Some(old), Some(new)) => self.reads_schema(old, new, assumed),
+            _ => false,
+        }
+    }
+
+    fn reads_schema(&self, old: &Schema, new: &Schema, assumed: &mut Assumed) -> bool {
+        if !assumed.insert((std::ptr::from_ref(old), std::ptr::from_ref(new))) {
+            return true;
+        }
+        let opts = self.opts;
+        match (old, new) {
+            (_, Schema::Any) => matches!(old, Schema::Any) || opts.self_describing,
+            // Newtype structs are written as their value.
+            (Schema::Newtype(o), _) => {
+                lookup(self.old, &o.value).is_some_and(|o| self.reads_schema(o, new, assumed))
+            }
+            (_, Schema::Newtype(n)) => {
+                lookup(self.new, &n.value).is_some_and(|n| self.reads_schema(old, n, assumed))
+            }
+            (Schema::Constrained(o), Schema::Constrained(n)) if o.constraints == n.constraints => {
+                self.reads(&o.value, &n.value, assumed)
+            }
+            (Schema::Constrained(o), _) => {
+                lookup(self.old, &o.value).is_some_and(|o| self.reads_schema(o, new, assumed))
+            }
+            // Values that met no or other constraints may not meet these.
+            (_, Schema::Constrained(_)) => false,
+            (Schema::Simple(o), Schema::Simple(n)) => o == n || widens(*o, *n, opts.int_encoding),
+            (
+                Schema::Simple(SimpleSchema::Unit) | Schema::UnitStruct(_),
+                Schema::Simple(SimpleSchema::Unit) | Schema::UnitStruct(_),
+            ) => true,
+            (Schema::Option(o), Schema::Option(n)) => self.reads(&o.value, &n.value, assumed),
+            // A nullable option reads any other value as `Some`.
+            (_, Schema::Option(n)) if opts.option_format == OptionFormat::Nullable => {
+                lookup(self.new, &n.value).is_some_and(|n| self.reads_schema(old, n, assumed))
+            }
+            (
+                Schema::Seq(SeqSchema { value: o, .. }) | Schema::Set(SetSchema { value: o, .. }),
+                Schema::Seq(SeqSchema { value: n, .. }) | Schema::Set(SetSchema { value: n, .. }),
+            ) => self.reads(o, n, assumed),
+            // Arrays are written as tuples, without their length.
+            (Schema::Array(o), Schema::Array(n)) => {
+                o.len == n.len && self.reads(&o.value, &n.value, assumed)
+            }
+            (Schema::Array(o), Schema::Tuple(n)) => {
+                o.len == n.values.len() && n.values.iter().all(|n| self.reads(&o.value, n, assumed))
+            }
+            (Schema::Tuple(o), Schema::Array(n)) => {
+                o.values.len() == n.len && o.values.iter().all(|o| self.reads(o, &n.value, assumed))
+            }
+            (
+                Schema::Array(o),
+                Schema::Seq(SeqSchema { value: n, .. }) | Schema::Set(SetSchema { value: n, .. }),
+            ) if opts.self_describing => self.reads(&o.value, n, assumed),
+            (Schema::Tuple(o), Schema::Tuple(n)) => {
+                o.values.len() == n.values.len()
+                    && o.values
+                        .iter()
+                        .zip(&n.values)
+                        .all(|(o, n)| self.reads(o, n, assumed))
+            }
+            (Schema::Map(o), Schema::Map(n)) => {
+                self.reads(&o.key, &n.key, assumed) && self.reads(&o.value, &n.value, assumed)
+            }
+            (Schema::HumanReadable(o), Schema::HumanReadable(n)) => {
+                self.reads(&o.readable, &n.readable, assumed)
+                    && self.reads(&o.compact, &n.compact, assumed)
+            }
+            (Schema::Struct(o), Schema::Struct(n)) => {
+                let rules = (o.rename_all, n.rename_all);
+                self.reads_fields(&o.fields, &n.fields, rules, assumed)
+            }
+            (Schema::Enum(o), Schema::Enum(n)) => self.reads_enum(o, n, assumed),
+            _ => false,
+        }
+    }
+
+    fn reads_fields(
+        &self,
+        old: &FieldsSchema,
+        new: &FieldsSchema,
+        rules: (Option<RenameAll>, Option<RenameAll>),
+        assumed: &mut Assumed,
+    ) -> bool {
+        match (old, new) {
+            (FieldsSchema::Tuple(o), FieldsSchema::Tuple(n)) => {
+                o.values.len() == n.values.len()
+                    && o.values
+                        .iter()
+                        .zip(&n.values)
+                        .all(|(o, n)| self.reads(o, n, assumed))
+            }
+            (FieldsSchema::Named(o), FieldsSchema::Named(n)) => {
+                self.reads_named(o, n, rules, assumed)
+            }
+            _ => false,
+        }
+    }
+
+    fn reads_named(
+        &self,
+        old: &NamedFieldsSchema,
+        new: &NamedFieldsSchema,
+        (old_rule, new_rule): (Option<RenameAll>, Option<RenameAll>),
+        assumed: &mut Assumed,
+    ) -> bool {
+        let old: Vec<_> = old.fields.iter().filter(|f| !f.skip_serializing).collect();
+        let new: Vec<_> = new.fields.iter().filter(|f| !f.skip_serializing).collect();
+        if self.opts.struct_format == StructFormat::Tuple {
+            return old.len() == new.len()
+                && old
+                    .iter()
+                    .zip(&new)
+                    .all(|(o, n)| self.reads(&o.value, &n.value, assumed));
+        }
+
+        // Every field written is read, unless a flattened field takes the
+        // ones left over...
+        let flattened = new.iter().any(|f| f.flatten);
+        for o in &old {
+            let name = o.wire_name(old_rule);
+            let read = new.iter().find(|n| match o.flatten {
+                true => n.flatten && n.name == o.name,
+                false => accepts(n, new_rule, &name),
+            });
+            let reads = match read {
+                Some(n) => {
+                    self.reads(&o.value, &n.value, assumed)
+                        && (o.skip_serializing_if.is_none() || self.fillable(n))
+                }
+                None => flattened && !o.flatten,
+            };
+            if !reads {
+                return false;
+            }
+        }
+        // ...and every field read is written or can be left out.
+        new.iter().all(|n| {
+            self.fillable(n)
+                || old.iter().any(|o| match n.flatten {
+                    true => o.flatten && o.name == n.name,
+                    false => !o.flatten && accepts(n, new_rule, &o.wire_name(old_rule)),
+                })
+        })
+    }
+
+    fn reads_enum(&self, old: &EnumSchema, new: &EnumSchema, assumed: &mut Assumed) -> bool {
+        if old.repr != new.repr {
+            return old.variants.is_empty();
+        }
+        let integer = matches!(old.repr, EnumRepr::Integer { .. });
+        let (old_discriminants, new_discriminants) = (old.discriminants(), new.discriminants());
+        old.variants.iter().enumerate().all(|(i, o)| {
+            let j = match (integer, self.opts.enum_tag) {
+                (true, _) => new_discriminants
+                    .iter()
+                    .position(|d| *d == old_discriminants[i]),
+                (false, EnumTag::Index) => new
+                    .variants
+                    .iter()
+                    .enumerate()
+                    .position(|(j, n)| n.id.unwrap_or(j) == o.id.unwrap_or(i)),
+                (false, EnumTag::Name) => {
+                    let name = o.wire_name(old.rename_all);
+                    new.variants.iter().position(|n| {
+                        n.wire_name(new.rename_all) == name
+                            || n.aliases.iter().any(|alias| *alias == name)
+                    })
+                }
+            };
+            j.is_some_and(|j| {
+                self.reads_fields(&o.fields, &new.variants[j].fields, (None, None), assumed)
+            })
+        })
+    }
+
+    /// Whether the new description decodes a field that values leave out.
+    fn fillable(&self, field: &NamedFieldSchema) -> bool {
+        field.default.is_some()
+            || (self.opts.option_format == OptionFormat::Nullable
+                && field.skip_serializing_if.is_none()
+                && matches!(lookup(self.new, &field.value), Some(Schema::Option(_))))
+    }
+}
+
+fn lookup<'a>(descr: &'a Description, item: &'a SchemaItem) -> Option<&'a Schema> {
+    match item {
+        SchemaItem::Schema(schema) => Some(schema),
+        SchemaItem::Named(name) => descr.items.get(name),
+    }
+}
+
+/// Whether `field` is read under the wire name `name`.
+fn accepts(field: &NamedFieldSchema, rule: Option<RenameAll>, name: &str) -> bool {
+    !field.flatten && (field.wire_name(rule) == name || field.aliases.iter().any(|a| a == name))
+}
+
+/// Pair up the members of two lists of these lengths: first those that
+/// `same` matches, then among the rest those that `renamed` does. The pairs
+/// are in the order of the second list.
+fn pair(
+    (n, m): (usize, usize),
+    same: impl Fn(usize, usize) -> bool,
+    renamed: impl Fn(usize, usize) -> bool,
+) -> Vec<(usize, usize)> {
+    let (mut old, mut new) = (vec![false; n], vec![false; m]);
+    let mut pairs = Vec::new();
+    for matches in [&same as &dyn Fn(usize, usize) -> bool, &renamed] {
+        for (j, paired) in new.iter_mut().enumerate() {
+            if *paired {
+                continue;
+            }
+            if let Some(i) = (0..n).find(|&i| !old[i] && matches(i, j)) {
+                (old[i], *paired) = (true, true);
+                pairs.push((i, j));
+            }
+        }
+    }
+    pairs.sort_by_key(|&(_, j)| j);
+    pairs
+}
+
+/// Which of the old positions of members, in their new order, moved: those
+/// not in a longest increasing subsequence of them.
+fn moved(order: &[usize]) -> Vec<bool> {
+    // len[i] is the length of the longest increasing subsequence ending at
+    // i, and prev[i] the position before i in it.
+    let mut len = vec![1; order.len()];
+    let mut prev = vec![None; order.len()];
+    for i in 0..order.len() {
+        for j in 0..i {
+            if order[j] < order[i] && len[j] + 1 > len[i] {
+                (len[i], prev[i]) = (len[j] + 1, Some(j));
+            }
+        }
+    }
+    let mut moved = vec![true; order.len()];
+    let mut at = (0..order.len()).max_by_key(|&i| len[i]);
+    while let Some(i) = at {
+        moved[i] = false;
+        at = prev[i];
+    }
+    moved
+}
+
+/// Whether every value of the number type `old` is written the way the
+/// wider `new` is read.
+fn widens(old: SimpleSchema, new: SimpleSchema, encoding: IntEncoding) -> bool {
+    let integer = |s| match s {
+        SimpleSchema::U8 => Some((false, 8)),
+        SimpleSchema::U16 => Some((false, 16)),
+        SimpleSchema::U32 => Some((false, 32)),
+        SimpleSchema::U64 => Some((false, 64)),
+        SimpleSchema::U128 => Some((false, 128)),
+        SimpleSchema::I8 => Some((true, 8)),
+        SimpleSchema::I16 => Some((true, 16)),
+        SimpleSchema::I32 => Some((true, 32)),
+        SimpleSchema::I64 => Some((true, 64)),
+        SimpleSchema::I128 => Some((true, 128)),
+        _ => None,
+    };
+    match (encoding, integer(old), integer(new)) {
+        (IntEncoding::Native, Some((old_signed, o)), Some((new_signed, n))) => {
+            n > o && (new_signed || !old_signed)
+        }
+        (IntEncoding::Native, _, _) => old == SimpleSchema::F32 && new == SimpleSchema::F64,
+        // Varints are the same for every width of a signedness, but bytes
+        // are written as they are.
+        (IntEncoding::Varint, Some((old_signed, o)), Some((new_signed, n))) => {
+            old_signed == new_signed && o > 8 && n > o
+        }
+        _ => false,
+    }
+}
+
+/// The fields of a struct or variant as they follow its name.
+fn fields_text(fields: &FieldsSchema) -> String {
+    match fields {
+        FieldsSchema::Tuple(t) => idl::tuple(&t.values, true),
+        FieldsSchema::Named(f) => idl::named_fields(f, Layout::Inline)
+            .trim_start()
+            .to_string(),
+    }
+}
+
+fn repr(repr: &EnumRepr) -> String {
+    match repr {
+        EnumRepr::ExternallyTagged => "externally tagged".to_string(),
+        EnumRepr::InternallyTagged { tag } => format!("tag = {tag:?}"),
+        EnumRepr::AdjacentlyTagged { tag, content } => {
+            format!("tag = {tag:?}, content = {content:?}")
+        }
+        EnumRepr::Integer { repr } => format!("repr({repr})"),
+    }
+}
+
+impl Display for Element {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Element::Type => "type",
+            Element::Field => "field",
+            Element::Variant => "variant",
+        })
+    }
+}
+
+impl Display for SchemaChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let path = match self.path.is_empty() {
+            true => "root",
+            false => &self.path,
+        };
+        match &self.kind {
+            ChangeKind::Added { element } => write!(f, "+ {path}: {element} added")?,
+            ChangeKind::Removed { element } => write!(f, "- {path}: {element} removed")?,
+            ChangeKind::Renamed { element, old, new } => {
+                write!(f, "~ {path}: {element} renamed {old} -> {new}")?
+            }
+            ChangeKind::Moved { old, new } => write!(f, "~ {path}: position {old} -> {new}")?,
+            ChangeKind::Type { old, new } => write!(f, "~ {path}: type {old} -> {new}")?,
+            ChangeKind::Default { old, new } => write!(
+                f,
+                "~ {path}: default {} -> {}",
+                old.as_deref().unwrap_or("none"),
+                new.as_deref().unwrap_or("none")
+            )?,
+            ChangeKind::Repr { old, new } => write!(f, "~ {path}: representation {old} -> {new}")?,
+            ChangeKind::Discriminant { old, new } => {
+                write!(f, "~ {path}: discriminant {old} -> {new}")?
+            }
+        }
+        match self.breaking {
+            true => write!(f, " (breaking)"),
+            false => Ok(()),
+        }
+    }
+}
@@ -1,13 +1,35 @@
 use std::{
     borrow::Cow,
-    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    cell::{Cell, RefCell},
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, LinkedList, VecDeque},
+    ffi::{OsStr, OsString},
+    marker::PhantomData,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6},
+    num::{
+        NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
+        NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize, Wrapping,
+    },
+    ops::{Bound, Range, RangeFrom, RangeInclusive, RangeTo},
+    path::{Path, PathBuf},
     rc::Rc,
-    sync::{Arc, Mutex, RwLock},
+    sync::{
+        atomic::{
+            AtomicBool, AtomicI16, AtomicI32, AtomicI64, AtomicI8, AtomicIsize, AtomicU16,
+            AtomicU32, AtomicU64, AtomicU8, AtomicUsize,
+        },
+        Arc, Mutex, RwLock,
+    },
+    time::{Duration, SystemTime},
 };
 
 use serde::{Deserialize, Serialize};
 
-use crate::{is_default, MapSchema, Schema, SchemaItem, SchemaName, SeqSchema, SimpleSchema};
+use crate::{
+    is_default, ArraySchema, EnumSchema, FieldsSchema, HumanReadableSchema, MapSchema, Metadata,
+    NamedFieldSchema, NamedFieldsSchema, OptionSchema, Schema, SchemaItem, SchemaName, SeqSchema,
+    SetSchema, SimpleSchema, StructSchema, TupleSchema, UnitStructSchema, VariantSchema,
+};
 
 pub trait Describe {
     fn schema_name() -> SchemaName;
@@ -40,8 +62,85 @@ impl Description {
         }
     }
 
-    pub fn add<F: FnOnce() -> R, R: Into<Schema>>(&mut self, name: SchemaName, schema: F) {
-        self.items.entry(name).or_insert_with(|| schema().into());
+    /// Add a named schema, returning `true` if it was not yet present. Impls
+    /// should only add the schemas of their dependencies in that case, so that
+    /// recursive types terminate.
+    pub fn add<F: FnOnce() -> R, R: Into<Schema>>(&mut self, name: SchemaName, schema: F) -> bool {
+        match self.items.entry(name) {
+            std::collections::btree_map::Entry::Occupied(_) => false,
+            std::collections::btree_map::Entry::Vacant(entry) => {
+                entry.insert(schema().into());
+                true
+            }
+        }
+    }
+
+    /// A 64-bit FNV-1a hash of the text form of the description, the same
+    /// on every platform and run. Only what values are written and read with
+    /// goes in: documentation, deprecations, examples and annotations can
+    /// change without changing it, constraints and defaults cannot.
+    pub fn fingerprint(&self) -> u64 {
+        let mut wire = self.clone();
+        if let SchemaItem::Schema(schema) = &mut wire.schema {
+            strip_metadata(schema);
+        }
+        wire.items.values_mut().for_each(strip_metadata);
+        wire.idl()
+            .bytes()
+            .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+            })
+    }
+}
+
+/// Clear the [`Metadata`] of a schema and of the schemas inlined in it.
+fn strip_metadata(schema: &mut Schema) {
+    fn item(item: &mut SchemaItem) {
+        if let SchemaItem::Schema(schema) = item {
+            strip_metadata(schema);
+        }
+    }
+
+    fn fields(fields: &mut FieldsSchema) {
+        match fields {
+            FieldsSchema::Tuple(t) => t.values.iter_mut().for_each(item),
+            FieldsSchema::Named(named) => {
+                for field in &mut named.fields {
+                    field.meta = Metadata::default();
+                    item(&mut field.value);
+                }
+            }
+        }
+    }
+
+    match schema {
+        Schema::Simple(_) | Schema::UnitStruct(_) | Schema::Any => {}
+        Schema::Option(s) => item(&mut s.value),
+        Schema::Tuple(s) => s.values.iter_mut().for_each(item),
+        Schema::Seq(s) => item(&mut s.value),
+        Schema::Set(s) => item(&mut s.value),
+        Schema::Array(s) => item(&mut s.value),
+        Schema::Map(s) => {
+            item(&mut s.key);
+            item(&mut s.value);
+        }
+        Schema::Struct(s) => {
+            s.meta = Metadata::default();
+            fields(&mut s.fields);
+        }
+        Schema::Enum(s) => {
+            s.meta = Metadata::default();
+            for variant in &mut s.variants {
+                variant.meta = Metadata::default();
+                fields(&mut variant.fields);
+            }
+        }
+        Schema::Newtype(s) => item(&mut s.value),
+        Schema::HumanReadable(s) => {
+            item(&mut s.readable);
+            item(&mut s.compact);
+        }
+        Schema::Constrained(s) => item(&mut s.value),
     }
 }
 
@@ -215,6 +314,16 @@ impl Describe for String {
     }
 }
 
+impl Describe for char {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("char")
+    }
+
+    fn schema() -> SchemaItem {
+        SimpleSchema::Char.into()
+    }
+}
+
 impl Describe for str {
     fn schema_name() -> SchemaName {
         SchemaName::new("str")
@@ -225,15 +334,10 @@ impl Describe for str {
     }
 }
 
-// impl Describe for Vec<u8> {
-//     fn schema_name() -> SchemaName {
-//         SchemaName::new("bytes")
-//     }
-
-//     fn schema() -> SchemaItem {
-//         SimpleSchema::Bytes.into()
-//     }
-// }
+// `Vec<u8>` is a sequence and `[u8; N]` an array, as serde writes them. Byte
+// strings come from the `serde_bytes` types (behind the feature of that name),
+// or from a hand-written `SimpleSchema::Bytes` for fields with
+// `#[serde(with = "serde_bytes")]`.
 
 impl<T: Describe> Describe for Vec<T> {
     fn schema_name() -> SchemaName {
@@ -269,7 +373,7 @@ impl<T: Describe> Describe for BTreeSet<T> {
     }
 
     fn schema() -> SchemaItem {
-        SeqSchema::new(T::schema()).into()
+        SetSchema::new(T::schema()).into()
     }
 
     fn add_schema(map: &mut Description) {
@@ -283,7 +387,7 @@ impl<T: Describe> Describe for HashSet<T> {
     }
 
     fn schema() -> SchemaItem {
-        SeqSchema::new(T::schema()).into()
+        SetSchema::new(T::schema()).into()
     }
 
     fn add_schema(map: &mut Description) {
@@ -325,7 +429,7 @@ impl<K: Describe, V: Describe> Describe for HashMap<K, V> {
     }
 }
 
-impl<T: Describe> Describe for &T {
+impl<T: Describe + ?Sized> Describe for &T {
     fn schema_name() -> SchemaName {
         T::schema_name()
     }
@@ -339,7 +443,7 @@ impl<T: Describe> Describe for &T {
     }
 }
 
-impl<T: Describe> Describe for &mut T {
+impl<T: Describe + ?Sized> Describe for &mut T {
     fn schema_name() -> SchemaName {
         T::schema_name()
     }
@@ -353,7 +457,7 @@ impl<T: Describe> Describe for &mut T {
     }
 }
 
-impl<T: Describe> Describe for Box<T> {
+impl<T: Describe + ?Sized> Describe for Box<T> {
     fn schema_name() -> SchemaName {
         T::schema_name()
     }
@@ -367,7 +471,7 @@ impl<T: Describe> Describe for Box<T> {
     }
 }
 
-impl<T: Describe> Describe for Rc<T> {
+impl<T: Describe + ?Sized> Describe for Rc<T> {
     fn schema_name() -> SchemaName {
         T::schema_name()
     }
@@ -381,7 +485,7 @@ impl<T: Describe> Describe for Rc<T> {
     }
 }
 
-impl<T: Describe> Describe for Arc<T> {
+impl<T: Describe + ?Sized> Describe for Arc<T> {
     fn schema_name() -> SchemaName {
         T::schema_name()
     }
@@ -395,7 +499,7 @@ impl<T: Describe> Describe for Arc<T> {
     }
 }
 
-impl<T: Describe + Clone> Describe for Cow<'_, T> {
+impl<T: Describe + ToOwned + ?Sized> Describe for Cow<'_, T> {
     fn schema_name() -> SchemaName {
         T::schema_name()
     }
@@ -436,3 +540,497 @@ impl<T: Describe> Describe for RwLock<T> {
         T::add_schema(map)
     }
 }
+
+impl<T: Describe> Describe for Option<T> {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("std::option::Option").argument(T::schema_name())
+    }
+
+    fn schema() -> SchemaItem {
+        OptionSchema::new(T::schema()).into()
+    }
+
+    fn add_schema(map: &mut Description) {
+        T::add_schema(map);
+    }
+}
+
+impl<T: Describe, const N: usize> Describe for [T; N] {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("array")
+            .argument(T::schema_name())
+            .argument(SchemaName::new(N.to_string()))
+    }
+
+    fn schema() -> SchemaItem {
+        ArraySchema::new(T::schema(), N).into()
+    }
+
+    fn add_schema(map: &mut Description) {
+        T::add_schema(map);
+    }
+}
+
+macro_rules! describe_tuple {
+    ($($t:ident)+) => {
+        impl<$($t: Describe),+> Describe for ($($t,)+) {
+            fn schema_name() -> SchemaName {
+                SchemaName::new("tuple")$(.argument($t::schema_name()))+
+            }
+
+            fn schema() -> SchemaItem {
+                TupleSchema::new()$(.element($t::schema()))+.into()
+            }
+
+            fn add_schema(map: &mut Description) {
+                $($t::add_schema(map);)+
+            }
+        }
+    };
+}
+
+describe_tuple!(T0);
+describe_tuple!(T0 T1);
+describe_tuple!(T0 T1 T2);
+describe_tuple!(T0 T1 T2 T3);
+describe_tuple!(T0 T1 T2 T3 T4);
+describe_tuple!(T0 T1 T2 T3 T4 T5);
+describe_tuple!(T0 T1 T2 T3 T4 T5 T6);
+describe_tuple!(T0 T1 T2 T3 T4 T5 T6 T7);
+describe_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8);
+describe_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9);
+describe_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10);
+describe_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11);
+describe_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12);
+describe_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12 T13);
+describe_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12 T13 T14);
+describe_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12 T13 T14 T15);
+
+impl<T: Describe> Describe for VecDeque<T> {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("std::collections::VecDeque").argument(T::schema_name())
+    }
+
+    fn schema() -> SchemaItem {
+        SeqSchema::new(T::schema()).into()
+    }
+
+    fn add_schema(map: &mut Description) {
+        T::add_schema(map);
+    }
+}
+
+impl<T: Describe> Describe for LinkedList<T> {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("std::collections::LinkedList").argument(T::schema_name())
+    }
+
+    fn schema() -> SchemaItem {
+        SeqSchema::new(T::schema()).into()
+    }
+
+    fn add_schema(map: &mut Description) {
+        T::add_schema(map);
+    }
+}
+
+impl<T: Describe> Describe for BinaryHeap<T> {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("std::collections::BinaryHeap").argument(T::schema_name())
+    }
+
+    fn schema() -> SchemaItem {
+        SeqSchema::new(T::schema()).into()
+    }
+
+    fn add_schema(map: &mut Description) {
+        T::add_schema(map);
+    }
+}
+
+impl<T: Describe + Copy> Describe for Cell<T> {
+    fn schema_name() -> SchemaName {
+        T::schema_name()
+    }
+
+    fn schema() -> SchemaItem {
+        T::schema()
+    }
+
+    fn add_schema(map: &mut Description) {
+        T::add_schema(map)
+    }
+}
+
+impl<T: Describe> Describe for RefCell<T> {
+    fn schema_name() -> SchemaName {
+        T::schema_name()
+    }
+
+    fn schema() -> SchemaItem {
+        T::schema()
+    }
+
+    fn add_schema(map: &mut Description) {
+        T::add_schema(map)
+    }
+}
+
+impl<T: Describe> Describe for Wrapping<T> {
+    fn schema_name() -> SchemaName {
+        T::schema_name()
+    }
+
+    fn schema() -> SchemaItem {
+        T::schema()
+    }
+
+    fn add_schema(map: &mut Description) {
+        T::add_schema(map)
+    }
+}
+
+impl<T: Describe> Describe for Reverse<T> {
+    fn schema_name() -> SchemaName {
+        T::schema_name()
+    }
+
+    fn schema() -> SchemaItem {
+        T::schema()
+    }
+
+    fn add_schema(map: &mut Description) {
+        T::add_schema(map)
+    }
+}
+
+impl<T: ?Sized> Describe for PhantomData<T> {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("std::marker::PhantomData")
+    }
+
+    fn schema() -> SchemaItem {
+        UnitStructSchema::new("PhantomData").into()
+    }
+}
+
+/// Types serialized exactly like another type, e.g. `NonZeroU32` as `u32`.
+macro_rules! describe_as {
+    ($($t:ty => $as:ty),+ $(,)?) => {
+        $(
+            impl Describe for $t {
+                fn schema_name() -> SchemaName {
+                    <$as>::schema_name()
+                }
+
+                fn schema() -> SchemaItem {
+                    <$as>::schema()
+                }
+            }
+        )+
+    };
+}
+
+describe_as!(
+    NonZeroU8 => u8,
+    NonZeroU16 => u16,
+    NonZeroU32 => u32,
+    NonZeroU64 => u64,
+    NonZeroU128 => u128,
+    NonZeroUsize => usize,
+    NonZeroI8 => i8,
+    NonZeroI16 => i16,
+    NonZeroI32 => i32,
+    NonZeroI64 => i64,
+    NonZeroI128 => i128,
+    NonZeroIsize => isize,
+    AtomicBool => bool,
+    AtomicU8 => u8,
+    AtomicU16 => u16,
+    AtomicU32 => u32,
+    AtomicU64 => u64,
+    AtomicUsize => usize,
+    AtomicI8 => i8,
+    AtomicI16 => i16,
+    AtomicI32 => i32,
+    AtomicI64 => i64,
+    AtomicIsize => isize,
+    PathBuf => str,
+    Path => str,
+);
+
+impl Describe for OsString {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("std::ffi::OsString")
+    }
+
+    fn add_schema(map: &mut Description) {
+        map.add(Self::schema_name(), || {
+            EnumSchema::new("OsString")
+                .variant(VariantSchema::new(
+                    "Unix",
+                    TupleSchema::new().element(Vec::<u8>::schema()),
+                ))
+                .variant(VariantSchema::new(
+                    "Windows",
+                    TupleSchema::new().element(Vec::<u16>::schema()),
+                ))
+        });
+    }
+}
+
+impl Describe for OsStr {
+    fn schema_name() -> SchemaName {
+        OsString::schema_name()
+    }
+
+    fn add_schema(map: &mut Description) {
+        OsString::add_schema(map)
+    }
+}
+
+impl Describe for Duration {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("std::time::Duration")
+    }
+
+    fn add_schema(map: &mut Description) {
+        map.add(Self::schema_name(), || {
+            StructSchema::new(
+                "Duration",
+                NamedFieldsSchema::new()
+                    .field(NamedFieldSchema::new("secs", u64::schema()))
+                    .field(NamedFieldSchema::new("nanos", u32::schema())),
+            )
+        });
+    }
+}
+
+impl Describe for SystemTime {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("std::time::SystemTime")
+    }
+
+    fn add_schema(map: &mut Description) {
+        map.add(Self::schema_name(), || {
+            StructSchema::new(
+                "SystemTime",
+                NamedFieldsSchema::new()
+                    .field(NamedFieldSchema::new("secs_since_epoch", u64::schema()))
+                    .field(NamedFieldSchema::new("nanos_since_epoch", u32::schema())),
+            )
+        });
+    }
+}
+
+impl<T: Describe> Describe for Range<T> {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("std::ops::Range").argument(T::schema_name())
+    }
+
+    fn add_schema(map: &mut Description) {
+        if map.add(Self::schema_name(), || {
+            StructSchema::new(
+                "Range",
+                NamedFieldsSchema::new()
+                    .field(NamedFieldSchema::new("start", T::schema()))
+                    .field(NamedFieldSchema::new("end", T::schema())),
+            )
+        }) {
+            T::add_schema(map);
+        }
+    }
+}
+
+impl<T: Describe> Describe for RangeInclusive<T> {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("std::ops::RangeInclusive").argument(T::schema_name())
+    }
+
+    fn add_schema(map: &mut Description) {
+        if map.add(Self::schema_name(), || {
+            StructSchema::new(
+                "RangeInclusive",
+                NamedFieldsSchema::new()
+                    .field(NamedFieldSchema::new("start", T::schema()))
+                    .field(NamedFieldSchema::new("end", T::schema())),
+            )
+        }) {
+            T::add_schema(map);
+        }
+    }
+}
+
+impl<T: Describe> Describe for RangeFrom<T> {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("std::ops::RangeFrom").argument(T::schema_name())
+    }
+
+    fn add_schema(map: &mut Description) {
+        if map.add(Self::schema_name(), || {
+            StructSchema::new(
+                "RangeFrom",
+                NamedFieldsSchema::new().field(NamedFieldSchema::new("start", T::schema())),
+            )
+        }) {
+            T::add_schema(map);
+        }
+    }
+}
+
+impl<T: Describe> Describe for RangeTo<T> {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("std::ops::RangeTo").argument(T::schema_name())
+    }
+
+    fn add_schema(map: &mut Description) {
+        if map.add(Self::schema_name(), || {
+            StructSchema::new(
+                "RangeTo",
+                NamedFieldsSchema::new().field(NamedFieldSchema::new("end", T::schema())),
+            )
+        }) {
+            T::add_schema(map);
+        }
+    }
+}
+
+impl<T: Describe> Describe for Bound<T> {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("std::ops::Bound").argument(T::schema_name())
+    }
+
+    fn add_schema(map: &mut Description) {
+        if map.add(Self::schema_name(), || {
+            EnumSchema::new("Bound")
+                .variant(VariantSchema::new("Unbounded", TupleSchema::new()))
+                .variant(VariantSchema::new(
+                    "Included",
+                    TupleSchema::new().element(T::schema()),
+                ))
+                .variant(VariantSchema::new(
+                    "Excluded",
+                    TupleSchema::new().element(T::schema()),
+                ))
+        }) {
+            T::add_schema(map);
+        }
+    }
+}
+
+/// A type that is a string in human-readable formats, like the network types,
+/// and `compact` otherwise.
+pub(crate) fn readable_string(compact: SchemaItem) -> SchemaItem {
+    HumanReadableSchema::new(SimpleSchema::String, compact).into()
+}
+
+impl Describe for Ipv4Addr {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("std::net::Ipv4Addr")
+    }
+
+    fn schema() -> SchemaItem {
+        readable_string(<[u8; 4]>::schema())
+    }
+}
+
+impl Describe for Ipv6Addr {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("std::net::Ipv6Addr")
+    }
+
+    fn schema() -> SchemaItem {
+        readable_string(<[u8; 16]>::schema())
+    }
+}
+
+impl Describe for IpAddr {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("std::net::IpAddr")
+    }
+
+    fn schema() -> SchemaItem {
+        readable_string(Self::schema_name().into())
+    }
+
+    fn add_schema(map: &mut Description) {
+        map.add(Self::schema_name(), || {
+            EnumSchema::new("IpAddr")
+                .variant(VariantSchema::new(
+                    "V4",
+                    TupleSchema::new().element(Ipv4Addr::schema()),
+                ))
+                .variant(VariantSchema::new(
+                    "V6",
+                    TupleSchema::new().element(Ipv6Addr::schema()),
+                ))
+        });
+    }
+}
+
+impl Describe for SocketAddrV4 {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("std::net::SocketAddrV4")
+    }
+
+    fn schema() -> SchemaItem {
+        readable_string(<(Ipv4Addr, u16)>::schema())
+    }
+}
+
+impl Describe for SocketAddrV6 {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("std::net::SocketAddrV6")
+    }
+
+    fn schema() -> SchemaItem {
+        readable_string(<(Ipv6Addr, u16)>::schema())
+    }
+}
+
+impl Describe for SocketAddr {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("std::net::SocketAddr")
+    }
+
+    fn schema() -> SchemaItem {
+        readable_string(Self::schema_name().into())
+    }
+
+    fn add_schema(map: &mut Description) {
+        map.add(Self::schema_name(), || {
+            EnumSchema::new("SocketAddr")
+                .variant(VariantSchema::new(
+                    "V4",
+                    TupleSchema::new().element(SocketAddrV4::schema()),
+                ))
+                .variant(VariantSchema::new(
+                    "V6",
+                    TupleSchema::new().element(SocketAddrV6::schema()),
+                ))
+        });
+    }
+}
+
+// Dynamically typed values. In compact formats, fields of these types must be
+// serialized with `#[serde(with = "serde_descr::any")]`.
+
+impl Describe for serde_json::Value {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("serde_json::Value")
+    }
+
+    fn schema() -> SchemaItem {
+        SchemaItem::Schema(Box::new(Schema::Any))
+    }
+}
+
+impl Describe for serde_value::Value {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("serde_value::Value")
+    }
+
+    fn schema() -> SchemaItem {
+        SchemaItem::Schema(Box::new(Schema::Any))
+    }
+}
@@ -1,26 +1,56 @@
-use std::collections::BTreeMap;
+use std::{
+    cell::Cell,
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+    marker::PhantomData,
+    sync::Mutex,
+};
 
 use serde::de::{
-    value::{StrDeserializer, StringDeserializer},
-    DeserializeSeed, Deserializer, Error, MapAccess, SeqAccess, Visitor,
+    value::{MapDeserializer, SeqDeserializer, StrDeserializer, StringDeserializer},
+    DeserializeSeed, Deserializer, EnumAccess, Error, MapAccess, SeqAccess, Unexpected,
+    VariantAccess, Visitor,
 };
+use serde_value::{Value, ValueDeserializer};
 
 use crate::{
-    schema::EnumRepr, Description, Schema, SchemaItem, SchemaName, SimpleSchema, VariantSchema,
+    schema::EnumRepr, Constraints, Description, Expected, FieldsSchema, RenameAll, Schema,
+    SchemaItem, SchemaName, SimpleSchema,
 };
 
 pub struct SchemaDeserializer<'a, 'b, D> {
     schema: &'a SchemaDecode<'b>,
-    items: &'a SchemaDecodeItems<'b>,
+    context: Context<'a, 'b>,
     deserializer: D,
 }
 
 pub struct SchemaDecoder<'a> {
-    schema: SchemaDecodeItem<'a>,
-    items: SchemaDecodeItems<'a>,
+    pub(crate) schema: SchemaDecodeItem<'a>,
+    pub(crate) items: SchemaDecodeItems<'a>,
+}
+
+pub struct SchemaSeed<'a, 'b, T> {
+    schema: &'a SchemaDecode<'b>,
+    context: Context<'a, 'b>,
+    seed: T,
 }
 
-type SchemaDecodeItems<'a> = BTreeMap<&'a SchemaName, SchemaDecode<'a>>;
+pub(crate) type SchemaDecodeItems<'a> = BTreeMap<&'a SchemaName, SchemaDecode<'a>>;
+
+#[derive(Clone, Copy)]
+struct Context<'a, 'b> {
+    items: &'a SchemaDecodeItems<'b>,
+    opts: &'a DeserializerOptions,
+    /// Whether the format is human-readable, which picks between the two
+    /// alternatives of a [`HumanReadableDecode`]. Unknown until the first
+    /// deserializer is seen.
+    human_readable: Option<bool>,
+    /// How many values the current one is nested in.
+    depth: usize,
+    /// Roughly how many bytes the values decoded so far take, shared by all
+    /// of them. Set by the outermost [`SchemaSeed`].
+    allocated: Option<&'a Cell<usize>>,
+}
 
 pub enum SchemaDecodeItem<'a> {
     Decode(Box<SchemaDecode<'a>>),
@@ -32,32 +62,190 @@ pub enum SchemaDecode<'a> {
     Option(OptionDecode<'a>),
     Tuple(TupleDecode<'a>),
     Seq(SeqDecode<'a>),
+    Set(SeqDecode<'a>),
+    Array(ArrayDecode<'a>),
     Map(MapDecode<'a>),
-    Struct(StructDeserializer<'a>),
-    Enum(EnumDeserializer<'a>),
+    Struct(StructDecode<'a>),
+    Enum(EnumDecode<'a>),
+    Newtype(NewtypeDecode<'a>),
+    UnitStruct(UnitStructDecode),
+    HumanReadable(HumanReadableDecode<'a>),
+    Constrained(ConstrainedDecode<'a>),
+    Any,
+    /// What a reference resolves to when the description doesn't define it,
+    /// or when following it never reaches a schema that reads any input.
+    /// Decoding it is an error.
+    Invalid(&'static str),
+}
+
+/// How deep values may nest, like the recursion limit of `serde_json`.
+pub(crate) const MAX_DEPTH: usize = 128;
+
+static MISSING: SchemaDecode<'static> = SchemaDecode::Invalid("a reference to a missing schema");
+
+pub(crate) static CYCLE: SchemaDecode<'static> =
+    SchemaDecode::Invalid("a cycle of transparent schemas");
+
+pub struct OptionDecode<'a> {
+    pub(crate) value: SchemaDecodeItem<'a>,
+}
+
+pub struct TupleDecode<'a> {
+    pub(crate) values: Vec<SchemaDecodeItem<'a>>,
+}
+
+pub struct SeqDecode<'a> {
+    pub(crate) value: SchemaDecodeItem<'a>,
+}
+
+pub struct ArrayDecode<'a> {
+    pub(crate) value: SchemaDecodeItem<'a>,
+    pub(crate) len: usize,
+}
+
+pub struct MapDecode<'a> {
+    pub(crate) key: SchemaDecodeItem<'a>,
+    pub(crate) value: SchemaDecodeItem<'a>,
+}
+
+pub struct NewtypeDecode<'a> {
+    pub(crate) name: &'static str,
+    pub(crate) value: SchemaDecodeItem<'a>,
+    pub(crate) transparent: bool,
+}
+
+pub struct UnitStructDecode {
+    pub(crate) name: &'static str,
+}
+
+pub struct HumanReadableDecode<'a> {
+    pub(crate) readable: SchemaDecodeItem<'a>,
+    pub(crate) compact: SchemaDecodeItem<'a>,
+}
+
+pub struct ConstrainedDecode<'a> {
+    pub(crate) value: SchemaDecodeItem<'a>,
+    pub(crate) constraints: &'a Constraints,
+}
+
+pub struct StructDecode<'a> {
+    pub(crate) name: &'static str,
+    pub(crate) fields: FieldsDecode<'a>,
+}
+
+pub enum FieldsDecode<'a> {
+    Tuple(TupleDecode<'a>),
+    Named(NamedFieldsDecode<'a>),
+}
+
+pub struct NamedFieldsDecode<'a> {
+    pub(crate) fields: Vec<NamedFieldDecode<'a>>,
+    pub(crate) names: &'static [&'static str],
+}
+
+pub struct NamedFieldDecode<'a> {
+    pub(crate) name: &'static str,
+    pub(crate) aliases: &'a [String],
+    pub(crate) value: SchemaDecodeItem<'a>,
+    pub(crate) default: Option<&'a Value>,
+    pub(crate) flatten: bool,
+    /// Whether the field may be left out, depending on its value.
+    pub(crate) optional: bool,
 }
 
+pub struct EnumDecode<'a> {
+    pub(crate) name: &'static str,
+    pub(crate) repr: &'a EnumRepr,
+    pub(crate) variants: Vec<VariantDecode<'a>>,
+    pub(crate) names: &'static [&'static str],
+}
+
+pub struct VariantDecode<'a> {
+    pub(crate) name: &'static str,
+    pub(crate) index: usize,
+    pub(crate) aliases: &'a [String],
+    pub(crate) fields: FieldsDecode<'a>,
+    pub(crate) discriminant: i64,
+}
+
+/// Wire conventions of the format a [`SchemaDeserializer`] reads from.
+///
+/// The named profiles cover the formats we support out of the box. A custom
+/// profile is built by starting from the closest one and overriding the rules
+/// that differ, e.g. `DeserializerOptions::json().enum_tag(EnumTag::Index)`.
+///
+/// The profiles only bound how deep values nest. Untrusted input should be
+/// decoded with limits on lengths and sizes as well, e.g.
+/// `DeserializerOptions::bincode_varint().max_len(1 << 16).max_bytes(1 << 26)`.
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub struct DeserializerOptions {
-    enum_format: EnumFormat,
-    struct_format: StructFormat,
+    pub(crate) enum_tag: EnumTag,
+    pub(crate) struct_format: StructFormat,
+    pub(crate) option_format: OptionFormat,
+    pub(crate) int_encoding: IntEncoding,
+    pub(crate) self_describing: bool,
     borrowing: bool,
+    max_depth: usize,
+    max_len: usize,
+    max_bytes: usize,
+    max_string_len: usize,
 }
 
-enum EnumFormat {
-    Tuple,
-    Map,
+/// How an enum variant is identified on the wire.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum EnumTag {
+    /// By its index (or explicit id), e.g. bincode's `u32` tag.
+    Index,
+    /// By its (renamed) name, e.g. `{"Variant": ...}` in JSON.
+    Name,
 }
 
-enum StructFormat {
+/// How a struct with named fields is laid out on the wire.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum StructFormat {
+    /// Field values in declaration order, without names.
     Tuple,
+    /// A map keyed by field name.
     Map,
 }
 
+/// How an `Option` is encoded.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum OptionFormat {
+    /// An explicit `None`/`Some` marker; every field is always present.
+    Tagged,
+    /// A null value; an absent struct field also decodes as `None`.
+    Nullable,
+}
+
+/// How integers (including lengths and variant tags) are encoded.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum IntEncoding {
+    Fixed,
+    Varint,
+    /// Integers are written as text or in a self-describing encoding.
+    Native,
+}
+
 impl<'a, 'b: 'a, D> SchemaDeserializer<'a, 'b, D> {
-    pub fn new(decoder: &'a SchemaDecoder<'b>, deserializer: D) -> Self {
-        Self {
-            schema: decoder.schema.lookup(&decoder.items),
+    pub fn new<'de>(
+        decoder: &'a SchemaDecoder<'b>,
+        opts: &'a DeserializerOptions,
+        deserializer: D,
+    ) -> Self
+    where
+        D: Deserializer<'de>,
+    {
+        let context = Context {
             items: &decoder.items,
+            opts,
+            human_readable: Some(deserializer.is_human_readable()),
+            depth: 0,
+            allocated: None,
+        };
+        Self {
+            schema: context.lookup(&decoder.schema),
+            context,
             deserializer,
         }
     }
@@ -74,6 +262,22 @@ impl<'a> SchemaDecoder<'a> {
                 .collect(),
         }
     }
+
+    /// Wrap `seed` so that it reads its input through this decoder, e.g.
+    /// `options.deserialize_seed(decoder.seed(&opts, PhantomData::<T>), bytes)`.
+    pub fn seed<'b, T>(&'b self, opts: &'b DeserializerOptions, seed: T) -> SchemaSeed<'b, 'a, T> {
+        SchemaSeed {
+            schema: self.schema.lookup(&self.items),
+            context: Context {
+                items: &self.items,
+                opts,
+                human_readable: None,
+                depth: 0,
+                allocated: None,
+            },
+            seed,
+        }
+    }
 }
 
 impl<'a> SchemaDecodeItem<'a> {
@@ -84,47 +288,511 @@ impl<'a> SchemaDecodeItem<'a> {
         }
     }
 
-    fn lookup<'b>(&'b self, items: &'b SchemaDecodeItems<'a>) -> &'b SchemaDecode<'a> {
+    pub(crate) fn lookup<'b>(&'b self, items: &'b SchemaDecodeItems<'a>) -> &'b SchemaDecode<'a> {
         match self {
             SchemaDecodeItem::Decode(decode) => decode,
-            SchemaDecodeItem::Named(name) => items.get(name).unwrap(),
+            SchemaDecodeItem::Named(name) => items.get(name).unwrap_or(&MISSING),
         }
     }
 }
 
 impl<'a> SchemaDecode<'a> {
     fn new(schema: &'a Schema) -> Self {
-        todo!()
+        match schema {
+            Schema::Simple(s) => SchemaDecode::Simple(*s),
+            Schema::Option(s) => SchemaDecode::Option(OptionDecode {
+                value: SchemaDecodeItem::new(&s.value),
+            }),
+            Schema::Tuple(s) => SchemaDecode::Tuple(TupleDecode::new(&s.values)),
+            Schema::Seq(s) => SchemaDecode::Seq(SeqDecode {
+                value: SchemaDecodeItem::new(&s.value),
+            }),
+            Schema::Set(s) => SchemaDecode::Set(SeqDecode {
+                value: SchemaDecodeItem::new(&s.value),
+            }),
+            Schema::Array(s) => SchemaDecode::Array(ArrayDecode {
+                value: SchemaDecodeItem::new(&s.value),
+                len: s.len,
+            }),
+            Schema::Map(s) => SchemaDecode::Map(MapDecode {
+                key: SchemaDecodeItem::new(&s.key),
+                value: SchemaDecodeItem::new(&s.value),
+            }),
+            Schema::Struct(s) => SchemaDecode::Struct(StructDecode {
+                name: intern(s.rename.as_deref().unwrap_or(&s.name)),
+                fields: FieldsDecode::new(&s.fields, s.rename_all),
+            }),
+            Schema::Enum(s) => {
+                let variants = s
+                    .variants
+                    .iter()
+                    .zip(s.discriminants())
+                    .enumerate()
+                    .map(|(i, (v, discriminant))| VariantDecode {
+                        name: intern(&v.wire_name(s.rename_all)),
+                        index: v.id.unwrap_or(i),
+                        aliases: &v.aliases,
+                        fields: FieldsDecode::new(&v.fields, None),
+                        discriminant,
+                    })
+                    .collect::<Vec<_>>();
+                SchemaDecode::Enum(EnumDecode {
+                    name: intern(s.rename.as_deref().unwrap_or(&s.name)),
+                    repr: &s.repr,
+                    names: intern_all(variants.iter().map(|v| v.name)),
+                    variants,
+                })
+            }
+            Schema::Newtype(s) => SchemaDecode::Newtype(NewtypeDecode {
+                name: intern(s.rename.as_deref().unwrap_or(&s.name)),
+                value: SchemaDecodeItem::new(&s.value),
+                transparent: s.transparent,
+            }),
+            Schema::UnitStruct(s) => SchemaDecode::UnitStruct(UnitStructDecode {
+                name: intern(s.rename.as_deref().unwrap_or(&s.name)),
+            }),
+            Schema::HumanReadable(s) => SchemaDecode::HumanReadable(HumanReadableDecode {
+                readable: SchemaDecodeItem::new(&s.readable),
+                compact: SchemaDecodeItem::new(&s.compact),
+            }),
+            Schema::Constrained(s) => SchemaDecode::Constrained(ConstrainedDecode {
+                value: SchemaDecodeItem::new(&s.value),
+                constraints: &s.constraints,
+            }),
+            Schema::Any => SchemaDecode::Any,
+        }
+    }
+
+    /// Follow `inner` from this schema to the schema it wraps, for as long as
+    /// it returns one. Inline schemas are finite, so only references can go
+    /// round in circles, and following one more of them than there are items
+    /// means they do: that gives [`CYCLE`].
+    pub(crate) fn peel<'s, F>(&'s self, items: &'s SchemaDecodeItems<'a>, mut inner: F) -> &'s Self
+    where
+        F: FnMut(&'s Self) -> Option<&'s SchemaDecodeItem<'a>>,
+    {
+        let mut schema = self;
+        let mut references = 0;
+        while let Some(item) = inner(schema) {
+            if let SchemaDecodeItem::Named(_) = item {
+                references += 1;
+                if references > items.len() {
+                    return &CYCLE;
+                }
+            }
+            schema = item.lookup(items);
+        }
+        schema
+    }
+
+    pub(crate) fn expected(&self) -> Expected<'_> {
+        match self {
+            SchemaDecode::Simple(s) => Expected::Simple(*s),
+            SchemaDecode::Option(_) => Expected::Option,
+            SchemaDecode::Tuple(s) => Expected::Tuple(s.values.len()),
+            SchemaDecode::Seq(_) => Expected::Seq,
+            SchemaDecode::Set(_) => Expected::Set,
+            SchemaDecode::Array(s) => Expected::Array(s.len),
+            SchemaDecode::Map(_) => Expected::Map,
+            SchemaDecode::Struct(s) => Expected::Struct(s.name),
+            SchemaDecode::Enum(s) => Expected::Enum(s.name),
+            SchemaDecode::Newtype(s) => Expected::Newtype(s.name),
+            SchemaDecode::UnitStruct(s) => Expected::UnitStruct(s.name),
+            SchemaDecode::HumanReadable(_) => Expected::HumanReadable,
+            SchemaDecode::Constrained(_) => Expected::Constrained,
+            SchemaDecode::Any => Expected::Any,
+            SchemaDecode::Invalid(reason) => Expected::Invalid(reason),
+        }
+    }
+}
+
+impl<'a> TupleDecode<'a> {
+    fn new(values: &'a [SchemaItem]) -> Self {
+        Self {
+            values: values.iter().map(SchemaDecodeItem::new).collect(),
+        }
+    }
+}
+
+impl<'a> FieldsDecode<'a> {
+    fn new(fields: &'a FieldsSchema, rename_all: Option<RenameAll>) -> Self {
+        match fields {
+            FieldsSchema::Tuple(s) => FieldsDecode::Tuple(TupleDecode::new(&s.values)),
+            FieldsSchema::Named(s) => {
+                // Fields that are never written aren't part of the encoding.
+                let fields = s
+                    .fields
+                    .iter()
+                    .filter(|f| !f.skip_serializing)
+                    .map(|f| NamedFieldDecode {
+                        name: intern(&f.wire_name(rename_all)),
+                        aliases: &f.aliases,
+                        value: SchemaDecodeItem::new(&f.value),
+                        default: f.default.as_ref(),
+                        flatten: f.flatten,
+                        optional: f.skip_serializing_if.is_some(),
+                    })
+                    .collect::<Vec<_>>();
+                FieldsDecode::Named(NamedFieldsDecode {
+                    names: intern_all(fields.iter().filter(|f| !f.flatten).map(|f| f.name)),
+                    fields,
+                })
+            }
+        }
+    }
+
+    fn expected<'b>(&'b self, name: &'b str) -> Expected<'b> {
+        match self {
+            FieldsDecode::Tuple(s) if s.values.is_empty() => Expected::Simple(SimpleSchema::Unit),
+            FieldsDecode::Tuple(s) => Expected::Tuple(s.values.len()),
+            FieldsDecode::Named(_) => Expected::Struct(name),
+        }
+    }
+}
+
+impl<'a> NamedFieldsDecode<'a> {
+    pub(crate) fn find(&self, name: &str) -> Option<usize> {
+        self.fields.iter().position(|f| {
+            !f.flatten && (f.name == name || f.aliases.iter().any(|alias| alias == name))
+        })
+    }
+
+    pub(crate) fn flattened(&self) -> Option<&NamedFieldDecode<'a>> {
+        self.fields.iter().find(|f| f.flatten)
+    }
+
+    /// Why a format that writes field values without their names can't
+    /// decode these fields, if it can't. Serde writes a struct with
+    /// flattened fields as a map of unknown length, and a field that is
+    /// skipped depending on its value can't be told apart from the next one.
+    fn positional_error(&self, container: impl Display) -> Option<String> {
+        self.fields
+            .iter()
+            .find_map(|f| match (f.flatten, f.optional) {
+                (true, _) => Some(format!(
+                    "cannot decode flattened field {} of {container}: \
+                 the format doesn't write field names",
+                    f.name
+                )),
+                (false, true) => Some(format!(
+                    "cannot decode field {} of {container}: it is skipped depending on its value, \
+                 and the format doesn't write field names",
+                    f.name
+                )),
+                (false, false) => None,
+            })
+    }
+
+    /// Find the schema of a key that isn't one of our own fields among the
+    /// flattened fields, like serde's `FlatMapDeserializer` hands them out:
+    /// flattened structs take their own fields, and a flattened map takes
+    /// anything.
+    pub(crate) fn find_flattened<'s>(
+        &'s self,
+        items: &'s SchemaDecodeItems<'a>,
+        name: &str,
+    ) -> Option<&'s SchemaDecodeItem<'a>> {
+        self.find_flattened_within(items, name, MAX_DEPTH)
+    }
+
+    /// Like [`Self::find_flattened`], looking at most `depth` flattened
+    /// structs deep, so that a struct flattened into itself ends.
+    fn find_flattened_within<'s>(
+        &'s self,
+        items: &'s SchemaDecodeItems<'a>,
+        name: &str,
+        depth: usize,
+    ) -> Option<&'s SchemaDecodeItem<'a>> {
+        let depth = depth.checked_sub(1)?;
+        self.fields.iter().filter(|f| f.flatten).find_map(|f| {
+            let mut item = &f.value;
+            let schema = item.lookup(items).peel(items, |schema| {
+                let inner = match schema {
+                    SchemaDecode::Option(s) => Some(&s.value),
+                    SchemaDecode::Newtype(s) if s.transparent => Some(&s.value),
+                    SchemaDecode::Constrained(s) => Some(&s.value),
+                    _ => None,
+                };
+                item = inner.unwrap_or(item);
+                inner
+            });
+            match schema {
+                SchemaDecode::Struct(StructDecode {
+                    fields: FieldsDecode::Named(inner),
+                    ..
+                }) => match inner.find(name) {
+                    Some(i) => Some(&inner.fields[i].value),
+                    None => inner.find_flattened_within(items, name, depth),
+                },
+                SchemaDecode::Map(s) => Some(&s.value),
+                SchemaDecode::Any => Some(item),
+                _ => None,
+            }
+        })
+    }
+}
+
+impl<'a> EnumDecode<'a> {
+    pub(crate) fn by_name(&self, name: &str) -> Option<usize> {
+        self.variants
+            .iter()
+            .position(|v| v.name == name || v.aliases.iter().any(|alias| alias == name))
+    }
+
+    fn by_index(&self, index: u64) -> Option<usize> {
+        self.variants.iter().position(|v| v.index as u64 == index)
+    }
+
+    pub(crate) fn by_discriminant(&self, discriminant: i64) -> Option<usize> {
+        self.variants
+            .iter()
+            .position(|v| v.discriminant == discriminant)
+    }
+
+    /// The largest number of fields following the tag of an internally
+    /// tagged variant.
+    fn max_tagged_fields(&self, context: Context) -> usize {
+        self.variants
+            .iter()
+            .map(
+                |v| match tagged_content::<serde::de::value::Error>(v, context) {
+                    Ok(TaggedContent::Fields(f)) => f.fields.len(),
+                    _ => 0,
+                },
+            )
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+impl<'a> VariantDecode<'a> {
+    fn is_unit(&self) -> bool {
+        matches!(&self.fields, FieldsDecode::Tuple(s) if s.values.is_empty())
+    }
+}
+
+static NO_FIELDS: NamedFieldsDecode<'static> = NamedFieldsDecode {
+    fields: Vec::new(),
+    names: &[],
+};
+
+/// Serde wants `'static` names for structs, enums, fields and variants. They
+/// are interned so that every distinct name is leaked at most once.
+pub(crate) fn intern(name: &str) -> &'static str {
+    static NAMES: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+    let mut names = NAMES.lock().unwrap_or_else(|e| e.into_inner());
+    match names.get(name) {
+        Some(name) => name,
+        None => {
+            let name: &'static str = Box::leak(name.into());
+            names.insert(name);
+            name
+        }
+    }
+}
+
+fn intern_all<'a, I: Iterator<Item = &'a str>>(names: I) -> &'static [&'static str] {
+    static LISTS: Mutex<BTreeSet<&'static [&'static str]>> = Mutex::new(BTreeSet::new());
+    let names = names.map(intern).collect::<Vec<_>>();
+    let mut lists = LISTS.lock().unwrap_or_else(|e| e.into_inner());
+    match lists.get(names.as_slice()) {
+        Some(names) => names,
+        None => {
+            let names: &'static [&'static str] = Box::leak(names.into_boxed_slice());
+            lists.insert(names);
+            names
+        }
     }
 }
 
 impl DeserializerOptions {
     pub fn text() -> Self {
+        Self::json()
+    }
+
+    pub fn binary() -> Self {
+        Self::bincode_varint()
+    }
+
+    /// `serde_json`: externally tagged enums by name, structs as objects,
+    /// `None` as `null`.
+    pub fn json() -> Self {
         DeserializerOptions {
-            enum_format: EnumFormat::Map,
+            enum_tag: EnumTag::Name,
             struct_format: StructFormat::Map,
+            option_format: OptionFormat::Nullable,
+            int_encoding: IntEncoding::Native,
+            self_describing: true,
             borrowing: false,
+            max_depth: MAX_DEPTH,
+            max_len: usize::MAX,
+            max_bytes: usize::MAX,
+            max_string_len: usize::MAX,
         }
     }
 
-    pub fn binary() -> Self {
+    /// `bincode::serialize` and friends, i.e. `DefaultOptions` with
+    /// `with_fixint_encoding()`.
+    pub fn bincode_fixint() -> Self {
+        DeserializerOptions {
+            int_encoding: IntEncoding::Fixed,
+            ..Self::bincode_varint()
+        }
+    }
+
+    /// `bincode::DefaultOptions::new()`.
+    pub fn bincode_varint() -> Self {
+        DeserializerOptions {
+            enum_tag: EnumTag::Index,
+            struct_format: StructFormat::Tuple,
+            option_format: OptionFormat::Tagged,
+            int_encoding: IntEncoding::Varint,
+            self_describing: false,
+            borrowing: false,
+            max_depth: MAX_DEPTH,
+            max_len: usize::MAX,
+            max_bytes: usize::MAX,
+            max_string_len: usize::MAX,
+        }
+    }
+
+    /// `bitcode::serialize`.
+    pub fn bitcode() -> Self {
+        DeserializerOptions {
+            int_encoding: IntEncoding::Fixed,
+            ..Self::bincode_varint()
+        }
+    }
+
+    /// `postcard::to_allocvec`: variants by their index and options by a
+    /// `0` or `1` byte, as in bincode, but with other varints: integers
+    /// wider than a byte, variant indices and lengths are LEB128, zigzag
+    /// encoded if signed, where bincode marks wide ones with a size byte.
+    pub fn postcard() -> Self {
+        DeserializerOptions {
+            enum_tag: EnumTag::Index,
+            struct_format: StructFormat::Tuple,
+            option_format: OptionFormat::Tagged,
+            int_encoding: IntEncoding::Varint,
+            self_describing: false,
+            borrowing: false,
+            max_depth: MAX_DEPTH,
+            max_len: usize::MAX,
+            max_bytes: usize::MAX,
+            max_string_len: usize::MAX,
+        }
+    }
+
+    /// `rmp_serde::to_vec`: structs as arrays, enums tagged by name. Like
+    /// JSON, `None` is nil and `Some(x)` is `x`, so `Some(None)` reads back
+    /// as `None`.
+    pub fn msgpack_compact() -> Self {
         DeserializerOptions {
-            enum_format: EnumFormat::Tuple,
             struct_format: StructFormat::Tuple,
+            ..Self::msgpack_named()
+        }
+    }
+
+    /// `rmp_serde::to_vec_named`: structs as maps, enums tagged by name.
+    pub fn msgpack_named() -> Self {
+        DeserializerOptions {
+            enum_tag: EnumTag::Name,
+            struct_format: StructFormat::Map,
+            option_format: OptionFormat::Nullable,
+            int_encoding: IntEncoding::Native,
+            self_describing: true,
+            borrowing: false,
+            max_depth: MAX_DEPTH,
+            max_len: usize::MAX,
+            max_bytes: usize::MAX,
+            max_string_len: usize::MAX,
+        }
+    }
+
+    /// `ciborium::into_writer`: laid out like JSON, with structs as maps,
+    /// enums tagged by name and `None` as null, but in CBOR's own types:
+    /// map keys keep theirs, e.g. the integer keys of a `BTreeMap<u32, _>`,
+    /// and byte strings are bytes. Semantic tags on values the schema gives
+    /// a type, such as an epoch time on a `u64`, are read through.
+    pub fn cbor() -> Self {
+        DeserializerOptions {
+            enum_tag: EnumTag::Name,
+            struct_format: StructFormat::Map,
+            option_format: OptionFormat::Nullable,
+            int_encoding: IntEncoding::Native,
+            self_describing: true,
             borrowing: false,
+            max_depth: MAX_DEPTH,
+            max_len: usize::MAX,
+            max_bytes: usize::MAX,
+            max_string_len: usize::MAX,
         }
     }
 
+    pub fn enum_tag(mut self, enum_tag: EnumTag) -> Self {
+        self.enum_tag = enum_tag;
+        self
+    }
+
+    pub fn struct_format(mut self, struct_format: StructFormat) -> Self {
+        self.struct_format = struct_format;
+        self
+    }
+
+    pub fn option_format(mut self, option_format: OptionFormat) -> Self {
+        self.option_format = option_format;
+        self
+    }
+
+    /// Whether the inner deserializer supports `deserialize_any`.
+    pub fn self_describing(mut self, self_describing: bool) -> Self {
+        self.self_describing = self_describing;
+        self
+    }
+
     pub fn borrowing(mut self) -> Self {
         self.borrowing = true;
         self
     }
-}
 
-struct SchemaSeed<'a, 'b: 'a, T> {
-    schema: &'a SchemaDecode<'b>,
-    items: &'a SchemaDecodeItems<'b>,
-    seed: T,
+    /// How many levels values may nest, 128 by default. Deeper input is
+    /// an error rather than a stack overflow.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// How many elements a sequence and entries a map may have. Formats
+    /// with length prefixes can otherwise claim elements that take no
+    /// input, e.g. a `Vec<()>` of `u64::MAX` units.
+    pub fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len;
+        self
+    }
+
+    /// Roughly how much memory the values of one decode may take: the
+    /// length of every string and byte string, and the size of a
+    /// [`Value`] for every element, entry and buffered value. Bounds the
+    /// values read through [`SchemaDecoder::seed`] together, and each value
+    /// read through a [`SchemaDeserializer`] on its own.
+    pub fn max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// How many bytes a string or byte string may have.
+    pub fn max_string_len(mut self, max_string_len: usize) -> Self {
+        self.max_string_len = max_string_len;
+        self
+    }
+
+    /// The integer encoding the inner deserializer has to be configured
+    /// with, e.g. to pick between bincode's `with_varint_encoding()` and
+    /// `with_fixint_encoding()`. The schema is followed the same way for
+    /// every encoding.
+    pub fn int_encoding(&self) -> IntEncoding {
+        self.int_encoding
+    }
 }
 
 impl<'de, T: DeserializeSeed<'de>> DeserializeSeed<'de> for SchemaSeed<'_, '_, T> {
@@ -134,133 +802,284 @@ impl<'de, T: DeserializeSeed<'de>> DeserializeSeed<'de> for SchemaSeed<'_, '_, T
     where
         D: Deserializer<'de>,
     {
+        let mut context = self.context.nested()?;
+        if context.human_readable.is_none() {
+            context.human_readable = Some(deserializer.is_human_readable());
+        }
+        let allocated = Cell::new(0);
+        if context.allocated.is_none() {
+            context.allocated = Some(&allocated);
+        }
         self.seed.deserialize(SchemaDeserializer {
-            schema: self.schema,
-            items: self.items,
+            schema: context.resolve(self.schema),
+            context,
             deserializer,
         })
     }
 }
 
-impl<'de, D: Deserializer<'de>> Deserializer<'de> for SchemaDeserializer<'_, '_, D> {
-    type Error = D::Error;
+impl<'a, 'b: 'a, 'de, D: Deserializer<'de>> SchemaDeserializer<'a, 'b, D> {
+    fn deserialize_struct_schema<V>(
+        self,
+        s: &'a StructDecode<'b>,
+        visitor: V,
+    ) -> Result<V::Value, D::Error>
+    where
+        V: Visitor<'de>,
+    {
+        deserialize_fields(self.deserializer, s.name, &s.fields, self.context, visitor)
+    }
 
-    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    fn deserialize_enum_schema<V>(
+        self,
+        s: &'a EnumDecode<'b>,
+        visitor: V,
+        any: bool,
+    ) -> Result<V::Value, D::Error>
     where
         V: Visitor<'de>,
     {
-        match self.schema {
-            SchemaDecode::Simple(s) => match s {
-                SimpleSchema::Unit => self.deserializer.deserialize_unit(visitor),
-                SimpleSchema::Bool => self.deserializer.deserialize_bool(visitor),
-                SimpleSchema::U8 => self.deserializer.deserialize_u8(visitor),
-                SimpleSchema::U16 => self.deserializer.deserialize_u16(visitor),
-                SimpleSchema::U32 => self.deserializer.deserialize_u32(visitor),
-                SimpleSchema::U64 => self.deserializer.deserialize_u64(visitor),
-                SimpleSchema::U128 => self.deserializer.deserialize_u128(visitor),
-                SimpleSchema::I8 => self.deserializer.deserialize_i8(visitor),
-                SimpleSchema::I16 => self.deserializer.deserialize_i16(visitor),
-                SimpleSchema::I32 => self.deserializer.deserialize_i32(visitor),
-                SimpleSchema::I64 => self.deserializer.deserialize_i64(visitor),
-                SimpleSchema::I128 => self.deserializer.deserialize_i128(visitor),
-                SimpleSchema::F32 => self.deserializer.deserialize_f32(visitor),
-                SimpleSchema::F64 => self.deserializer.deserialize_f64(visitor),
-                SimpleSchema::Char => self.deserializer.deserialize_char(visitor),
-                SimpleSchema::String => match self.opts.borrowing {
-                    true => self.deserializer.deserialize_str(visitor),
-                    false => self.deserializer.deserialize_string(visitor),
-                },
-                SimpleSchema::Bytes => match self.opts.borrowing {
-                    true => self.deserializer.deserialize_bytes(visitor),
-                    false => self.deserializer.deserialize_byte_buf(visitor),
+        match s.repr {
+            EnumRepr::ExternallyTagged => self.deserializer.deserialize_enum(
+                s.name,
+                s.names,
+                ExternallyTaggedEnumVisitor {
+                    schema: s,
+                    context: self.context,
+                    visitor,
+                    any,
                 },
-            },
-            Schema::Option(s) => self.deserializer.deserialize_option(OptionVisitor {
-                value: &s.value,
-                opts: self.opts,
-                visitor,
-            }),
-            Schema::Seq(s) => self.deserializer.deserialize_seq(SeqVisitor {
-                value: &s.value,
-                opts: self.opts,
-                visitor,
-            }),
-            Schema::Map(s) => self.deserializer.deserialize_map(MapVisitor {
-                key: &s.key,
-                value: &s.value,
-                opts: self.opts,
-                visitor,
-            }),
-            Schema::Newtype(s) => visitor.visit_newtype_struct(self),
-            Schema::Struct(s) => match self.opts.struct_format {
-                StructFormat::Tuple => self.deserializer.deserialize_tuple(
-                    s.fields.len(),
-                    TupleStructVisitor {
-                        fields: &s.fields,
-                        opts: self.opts,
+            ),
+            EnumRepr::InternallyTagged { tag } => match self.context.opts.self_describing {
+                true => {
+                    let value = BufferSeed(self.context).deserialize(self.deserializer)?;
+                    visit_internally_tagged(s, tag, value, self.context, visitor)
+                }
+                false => self.deserializer.deserialize_tuple(
+                    1 + s.max_tagged_fields(self.context),
+                    InternallyTaggedEnumVisitor {
+                        schema: s,
+                        tag,
+                        context: self.context,
                         visitor,
                     },
                 ),
-                StructFormat::Map => self.deserializer.deserialize_map(MapStructVisitor {
-                    fields: &s.fields,
-                    opts: self.opts,
-                    visitor,
-                }),
             },
-            Schema::Enum(s) => match self.opts.enum_format {
-                EnumFormat::Tuple => self.deserializer.deserialize_tuple(
-                    2,
-                    TupleEnumVisitor {
-                        variants: &s.variants,
-                        opts: self.opts,
-                        visitor,
-                    },
-                ),
-                EnumFormat::Map => match s.repr {
-                    EnumRepr::ExternallyTagged => {
-                        self.deserializer
-                            .deserialize_map(ExternallyTaggedEnumVisitor {
-                                variants: &s.variants,
-                                opts: self.opts,
-                                visitor,
-                            })
-                    }
-                    EnumRepr::InternallyTagged { tag } => {
-                        todo!()
-                        //self.deserializer.deserialize_map(InternallyTaggedEnumVisitor(tag, &s.variants, visitor)),
+            EnumRepr::AdjacentlyTagged { tag, content } => {
+                match self.context.opts.self_describing {
+                    true => {
+                        let value = BufferSeed(self.context).deserialize(self.deserializer)?;
+                        visit_adjacently_tagged(s, tag, content, value, self.context, visitor)
                     }
-                    EnumRepr::AdjacentlyTagged { tag, content } => {
-                        todo!()
-                        // 		self.deserializer.deserialize_map(AdjacentlyTaggedEnumVisitor(
-                        //     tag,
-                        //     content,
-                        //     &s.variants,
-                        //     visitor,
-                        // )),
-                    }
-                },
-            },
-            Schema::Tuple(s) => self.deserializer.deserialize_tuple(
-                s.values.len(),
-                TupleVisitor {
-                    values: &s.values,
-                    opts: self.opts,
+                    false => self.deserializer.deserialize_tuple(
+                        2,
+                        AdjacentlyTaggedEnumVisitor {
+                            schema: s,
+                            tag,
+                            content,
+                            context: self.context,
+                            visitor,
+                        },
+                    ),
+                }
+            }
+            EnumRepr::Integer { repr } => {
+                let visitor = DiscriminantVisitor {
+                    schema: s,
+                    repr: *repr,
+                    context: self.context,
                     visitor,
-                },
-            ),
+                };
+                match repr {
+                    SimpleSchema::U8 => self.deserializer.deserialize_u8(visitor),
+                    SimpleSchema::U16 => self.deserializer.deserialize_u16(visitor),
+                    SimpleSchema::U32 => self.deserializer.deserialize_u32(visitor),
+                    SimpleSchema::U64 => self.deserializer.deserialize_u64(visitor),
+                    SimpleSchema::I8 => self.deserializer.deserialize_i8(visitor),
+                    SimpleSchema::I16 => self.deserializer.deserialize_i16(visitor),
+                    SimpleSchema::I32 => self.deserializer.deserialize_i32(visitor),
+                    SimpleSchema::I64 => self.deserializer.deserialize_i64(visitor),
+                    repr => Err(D::Error::custom(format!(
+                        "invalid discriminant type {repr} of enum {}",
+                        s.name
+                    ))),
+                }
+            }
         }
     }
+}
 
-    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value, Self::Error>
-    where
-        V: Visitor<'de>,
-    {
-        match self.schema {
-            Schema::Simple(SimpleSchema::Bool) => self.deserializer.deserialize_bool(visitor),
-            s => Err(Self::Error::custom(format!(
+/// Reads the fields of a struct or struct-like variant, presenting named
+/// fields as a map regardless of the wire layout.
+fn deserialize_fields<'a, 'b: 'a, 'de, D, V>(
+    deserializer: D,
+    name: &'static str,
+    fields: &'a FieldsDecode<'b>,
+    context: Context<'a, 'b>,
+    visitor: V,
+) -> Result<V::Value, D::Error>
+where
+    D: Deserializer<'de>,
+    V: Visitor<'de>,
+{
+    match fields {
+        FieldsDecode::Tuple(t) => deserializer.deserialize_tuple_struct(
+            name,
+            t.values.len(),
+            TupleVisitor {
+                values: &t.values,
+                context,
+                visitor,
+            },
+        ),
+        FieldsDecode::Named(f) => {
+            let visitor = StructVisitor {
+                fields: f,
+                context,
+                visitor,
+            };
+            match (context.opts.struct_format, f.flattened()) {
+                (StructFormat::Tuple, _) => match f.positional_error(Expected::Struct(name)) {
+                    Some(e) => Err(D::Error::custom(e)),
+                    None => deserializer.deserialize_tuple(f.fields.len(), visitor),
+                },
+                (StructFormat::Map, None) => {
+                    deserializer.deserialize_struct(name, f.names, visitor)
+                }
+                (StructFormat::Map, Some(_)) => deserializer.deserialize_map(visitor),
+            }
+        }
+    }
+}
+
+impl<'a, 'b: 'a, 'de, D: Deserializer<'de>> Deserializer<'de> for SchemaDeserializer<'a, 'b, D> {
+    type Error = D::Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.schema {
+            SchemaDecode::Simple(s) => {
+                let visitor = SimpleVisitor {
+                    schema: *s,
+                    context: self.context,
+                    visitor,
+                };
+                match s {
+                    SimpleSchema::Unit => self.deserializer.deserialize_unit(visitor),
+                    SimpleSchema::Bool => self.deserializer.deserialize_bool(visitor),
+                    SimpleSchema::U8 => self.deserializer.deserialize_u8(visitor),
+                    SimpleSchema::U16 => self.deserializer.deserialize_u16(visitor),
+                    SimpleSchema::U32 => self.deserializer.deserialize_u32(visitor),
+                    SimpleSchema::U64 => self.deserializer.deserialize_u64(visitor),
+                    SimpleSchema::U128 => self.deserializer.deserialize_u128(visitor),
+                    SimpleSchema::I8 => self.deserializer.deserialize_i8(visitor),
+                    SimpleSchema::I16 => self.deserializer.deserialize_i16(visitor),
+                    SimpleSchema::I32 => self.deserializer.deserialize_i32(visitor),
+                    SimpleSchema::I64 => self.deserializer.deserialize_i64(visitor),
+                    SimpleSchema::I128 => self.deserializer.deserialize_i128(visitor),
+                    SimpleSchema::F32 => self.deserializer.deserialize_f32(visitor),
+                    SimpleSchema::F64 => self.deserializer.deserialize_f64(visitor),
+                    SimpleSchema::Char => self.deserializer.deserialize_char(visitor),
+                    SimpleSchema::String => match self.context.opts.borrowing {
+                        true => self.deserializer.deserialize_str(visitor),
+                        false => self.deserializer.deserialize_string(visitor),
+                    },
+                    SimpleSchema::Bytes => match self.context.opts.borrowing {
+                        true => self.deserializer.deserialize_bytes(visitor),
+                        false => self.deserializer.deserialize_byte_buf(visitor),
+                    },
+                }
+            }
+            SchemaDecode::Option(s) => self.deserializer.deserialize_option(OptionVisitor {
+                value: self.context.lookup(&s.value),
+                context: self.context,
+                visitor,
+            }),
+            SchemaDecode::Seq(s) | SchemaDecode::Set(s) => {
+                self.deserializer.deserialize_seq(SeqVisitor {
+                    value: self.context.lookup(&s.value),
+                    context: self.context,
+                    visitor,
+                })
+            }
+            SchemaDecode::Array(s) => self.deserializer.deserialize_tuple(
+                s.len,
+                ArrayVisitor {
+                    value: &s.value,
+                    len: s.len,
+                    context: self.context,
+                    visitor,
+                },
+            ),
+            SchemaDecode::Map(s) => self.deserializer.deserialize_map(MapVisitor {
+                key: self.context.lookup(&s.key),
+                value: self.context.lookup(&s.value),
+                context: self.context,
+                visitor,
+            }),
+            SchemaDecode::Struct(s) => self.deserialize_struct_schema(s, visitor),
+            SchemaDecode::Enum(s) => self.deserialize_enum_schema(s, visitor, true),
+            SchemaDecode::Tuple(s) => self.deserializer.deserialize_tuple(
+                s.values.len(),
+                TupleVisitor {
+                    values: &s.values,
+                    context: self.context,
+                    visitor,
+                },
+            ),
+            SchemaDecode::Newtype(s) => self.deserializer.deserialize_newtype_struct(
+                s.name,
+                NewtypeVisitor {
+                    value: self.context.lookup(&s.value),
+                    context: self.context,
+                    visitor,
+                },
+            ),
+            SchemaDecode::UnitStruct(s) => self.deserializer.deserialize_unit_struct(
+                s.name,
+                SimpleVisitor {
+                    schema: SimpleSchema::Unit,
+                    context: self.context,
+                    visitor,
+                },
+            ),
+            SchemaDecode::HumanReadable(_) | SchemaDecode::Constrained(_) => SchemaDeserializer {
+                schema: self.context.resolve(self.schema),
+                ..self
+            }
+            .deserialize_any(visitor),
+            SchemaDecode::Any => {
+                let value = match self.context.human_readable {
+                    Some(true) => BufferSeed(self.context).deserialize(self.deserializer)?,
+                    _ => crate::any::decode(
+                        self.deserializer,
+                        self.context
+                            .opts
+                            .max_depth
+                            .saturating_sub(self.context.depth),
+                    )?,
+                };
+                ValueDeserializer::<D::Error>::new(value).deserialize_any(visitor)
+            }
+            SchemaDecode::Invalid(reason) => Err(Self::Error::custom(format!(
+                "invalid description: {reason}"
+            ))),
+        }
+    }
+
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.schema {
+            SchemaDecode::Any => self.deserializer.deserialize_bool(visitor),
+            SchemaDecode::Simple(SimpleSchema::Bool) => self.deserializer.deserialize_bool(visitor),
+            s => Err(Self::Error::custom(format!(
                 "invalid type {}, expected {}",
-                s.kind(),
-                Kind::Simple(SimpleSchema::Bool)
+                s.expected(),
+                Expected::Simple(SimpleSchema::Bool)
             ))),
         }
     }
@@ -270,11 +1089,18 @@ impl<'de, D: Deserializer<'de>> Deserializer<'de> for SchemaDeserializer<'_, '_,
         V: Visitor<'de>,
     {
         match self.schema {
-            Schema::Simple(SimpleSchema::I8) => self.deserializer.deserialize_i8(visitor),
+            SchemaDecode::Any => self.deserializer.deserialize_i8(visitor),
+            SchemaDecode::Simple(SimpleSchema::I8) => self.deserializer.deserialize_i8(visitor),
+            SchemaDecode::Enum(
+                s @ EnumDecode {
+                    repr: EnumRepr::Integer { .. },
+                    ..
+                },
+            ) => self.deserialize_enum_schema(s, visitor, false),
             s => Err(Self::Error::custom(format!(
                 "invalid type {}, expected {}",
-                s.kind(),
-                Kind::Simple(SimpleSchema::I8)
+                s.expected(),
+                Expected::Simple(SimpleSchema::I8)
             ))),
         }
     }
@@ -284,12 +1110,19 @@ impl<'de, D: Deserializer<'de>> Deserializer<'de> for SchemaDeserializer<'_, '_,
         V: Visitor<'de>,
     {
         match self.schema {
-            Schema::Simple(SimpleSchema::I8) => self.deserializer.deserialize_i8(visitor),
-            Schema::Simple(SimpleSchema::I16) => self.deserializer.deserialize_i16(visitor),
+            SchemaDecode::Any => self.deserializer.deserialize_i16(visitor),
+            SchemaDecode::Simple(SimpleSchema::I8) => self.deserializer.deserialize_i8(visitor),
+            SchemaDecode::Simple(SimpleSchema::I16) => self.deserializer.deserialize_i16(visitor),
+            SchemaDecode::Enum(
+                s @ EnumDecode {
+                    repr: EnumRepr::Integer { .. },
+                    ..
+                },
+            ) => self.deserialize_enum_schema(s, visitor, false),
             s => Err(Self::Error::custom(format!(
                 "invalid type {}, expected {}",
-                s.kind(),
-                Kind::Simple(SimpleSchema::I16)
+                s.expected(),
+                Expected::Simple(SimpleSchema::I16)
             ))),
         }
     }
@@ -299,13 +1132,20 @@ impl<'de, D: Deserializer<'de>> Deserializer<'de> for SchemaDeserializer<'_, '_,
         V: Visitor<'de>,
     {
         match self.schema {
-            Schema::Simple(SimpleSchema::I8) => self.deserializer.deserialize_i8(visitor),
-            Schema::Simple(SimpleSchema::I16) => self.deserializer.deserialize_i16(visitor),
-            Schema::Simple(SimpleSchema::I32) => self.deserializer.deserialize_i32(visitor),
+            SchemaDecode::Any => self.deserializer.deserialize_i32(visitor),
+            SchemaDecode::Simple(SimpleSchema::I8) => self.deserializer.deserialize_i8(visitor),
+            SchemaDecode::Simple(SimpleSchema::I16) => self.deserializer.deserialize_i16(visitor),
+            SchemaDecode::Simple(SimpleSchema::I32) => self.deserializer.deserialize_i32(visitor),
+            SchemaDecode::Enum(
+                s @ EnumDecode {
+                    repr: EnumRepr::Integer { .. },
+                    ..
+                },
+            ) => self.deserialize_enum_schema(s, visitor, false),
             s => Err(Self::Error::custom(format!(
                 "invalid type {}, expected {}",
-                s.kind(),
-                Kind::Simple(SimpleSchema::I32)
+                s.expected(),
+                Expected::Simple(SimpleSchema::I32)
             ))),
         }
     }
@@ -315,14 +1155,21 @@ impl<'de, D: Deserializer<'de>> Deserializer<'de> for SchemaDeserializer<'_, '_,
         V: Visitor<'de>,
     {
         match self.schema {
-            Schema::Simple(SimpleSchema::I8) => self.deserializer.deserialize_i8(visitor),
-            Schema::Simple(SimpleSchema::I16) => self.deserializer.deserialize_i16(visitor),
-            Schema::Simple(SimpleSchema::I32) => self.deserializer.deserialize_i32(visitor),
-            Schema::Simple(SimpleSchema::I64) => self.deserializer.deserialize_i64(visitor),
+            SchemaDecode::Any => self.deserializer.deserialize_i64(visitor),
+            SchemaDecode::Simple(SimpleSchema::I8) => self.deserializer.deserialize_i8(visitor),
+            SchemaDecode::Simple(SimpleSchema::I16) => self.deserializer.deserialize_i16(visitor),
+            SchemaDecode::Simple(SimpleSchema::I32) => self.deserializer.deserialize_i32(visitor),
+            SchemaDecode::Simple(SimpleSchema::I64) => self.deserializer.deserialize_i64(visitor),
+            SchemaDecode::Enum(
+                s @ EnumDecode {
+                    repr: EnumRepr::Integer { .. },
+                    ..
+                },
+            ) => self.deserialize_enum_schema(s, visitor, false),
             s => Err(Self::Error::custom(format!(
                 "invalid type {}, expected {}",
-                s.kind(),
-                Kind::Simple(SimpleSchema::I64)
+                s.expected(),
+                Expected::Simple(SimpleSchema::I64)
             ))),
         }
     }
@@ -332,15 +1179,22 @@ impl<'de, D: Deserializer<'de>> Deserializer<'de> for SchemaDeserializer<'_, '_,
         V: Visitor<'de>,
     {
         match self.schema {
-            Schema::Simple(SimpleSchema::I8) => self.deserializer.deserialize_i8(visitor),
-            Schema::Simple(SimpleSchema::I16) => self.deserializer.deserialize_i16(visitor),
-            Schema::Simple(SimpleSchema::I32) => self.deserializer.deserialize_i32(visitor),
-            Schema::Simple(SimpleSchema::I64) => self.deserializer.deserialize_i64(visitor),
-            Schema::Simple(SimpleSchema::I128) => self.deserializer.deserialize_i128(visitor),
+            SchemaDecode::Any => self.deserializer.deserialize_i128(visitor),
+            SchemaDecode::Simple(SimpleSchema::I8) => self.deserializer.deserialize_i8(visitor),
+            SchemaDecode::Simple(SimpleSchema::I16) => self.deserializer.deserialize_i16(visitor),
+            SchemaDecode::Simple(SimpleSchema::I32) => self.deserializer.deserialize_i32(visitor),
+            SchemaDecode::Simple(SimpleSchema::I64) => self.deserializer.deserialize_i64(visitor),
+            SchemaDecode::Simple(SimpleSchema::I128) => self.deserializer.deserialize_i128(visitor),
+            SchemaDecode::Enum(
+                s @ EnumDecode {
+                    repr: EnumRepr::Integer { .. },
+                    ..
+                },
+            ) => self.deserialize_enum_schema(s, visitor, false),
             s => Err(Self::Error::custom(format!(
                 "invalid type {}, expected {}",
-                s.kind(),
-                Kind::Simple(SimpleSchema::I128)
+                s.expected(),
+                Expected::Simple(SimpleSchema::I128)
             ))),
         }
     }
@@ -350,11 +1204,18 @@ impl<'de, D: Deserializer<'de>> Deserializer<'de> for SchemaDeserializer<'_, '_,
         V: Visitor<'de>,
     {
         match self.schema {
-            Schema::Simple(SimpleSchema::U8) => self.deserializer.deserialize_u8(visitor),
+            SchemaDecode::Any => self.deserializer.deserialize_u8(visitor),
+            SchemaDecode::Simple(SimpleSchema::U8) => self.deserializer.deserialize_u8(visitor),
+            SchemaDecode::Enum(
+                s @ EnumDecode {
+                    repr: EnumRepr::Integer { .. },
+                    ..
+                },
+            ) => self.deserialize_enum_schema(s, visitor, false),
             s => Err(Self::Error::custom(format!(
                 "invalid type {}, expected {}",
-                s.kind(),
-                Kind::Simple(SimpleSchema::U8)
+                s.expected(),
+                Expected::Simple(SimpleSchema::U8)
             ))),
         }
     }
@@ -364,12 +1225,19 @@ impl<'de, D: Deserializer<'de>> Deserializer<'de> for SchemaDeserializer<'_, '_,
         V: Visitor<'de>,
     {
         match self.schema {
-            Schema::Simple(SimpleSchema::U8) => self.deserializer.deserialize_u8(visitor),
-            Schema::Simple(SimpleSchema::U16) => self.deserializer.deserialize_u16(visitor),
+            SchemaDecode::Any => self.deserializer.deserialize_u16(visitor),
+            SchemaDecode::Simple(SimpleSchema::U8) => self.deserializer.deserialize_u8(visitor),
+            SchemaDecode::Simple(SimpleSchema::U16) => self.deserializer.deserialize_u16(visitor),
+            SchemaDecode::Enum(
+                s @ EnumDecode {
+                    repr: EnumRepr::Integer { .. },
+                    ..
+                },
+            ) => self.deserialize_enum_schema(s, visitor, false),
             s => Err(Self::Error::custom(format!(
                 "invalid type {}, expected {}",
-                s.kind(),
-                Kind::Simple(SimpleSchema::U16)
+                s.expected(),
+                Expected::Simple(SimpleSchema::U16)
             ))),
         }
     }
@@ -379,13 +1247,20 @@ impl<'de, D: Deserializer<'de>> Deserializer<'de> for SchemaDeserializer<'_, '_,
         V: Visitor<'de>,
     {
         match self.schema {
-            Schema::Simple(SimpleSchema::U8) => self.deserializer.deserialize_u8(visitor),
-            Schema::Simple(SimpleSchema::U16) => self.deserializer.deserialize_u16(visitor),
-            Schema::Simple(SimpleSchema::U32) => self.deserializer.deserialize_u32(visitor),
+            SchemaDecode::Any => self.deserializer.deserialize_u32(visitor),
+            SchemaDecode::Simple(SimpleSchema::U8) => self.deserializer.deserialize_u8(visitor),
+            SchemaDecode::Simple(SimpleSchema::U16) => self.deserializer.deserialize_u16(visitor),
+            SchemaDecode::Simple(SimpleSchema::U32) => self.deserializer.deserialize_u32(visitor),
+            SchemaDecode::Enum(
+                s @ EnumDecode {
+                    repr: EnumRepr::Integer { .. },
+                    ..
+                },
+            ) => self.deserialize_enum_schema(s, visitor, false),
             s => Err(Self::Error::custom(format!(
                 "invalid type {}, expected {}",
-                s.kind(),
-                Kind::Simple(SimpleSchema::U32)
+                s.expected(),
+                Expected::Simple(SimpleSchema::U32)
             ))),
         }
     }
@@ -395,14 +1270,21 @@ impl<'de, D: Deserializer<'de>> Deserializer<'de> for SchemaDeserializer<'_, '_,
         V: Visitor<'de>,
     {
         match self.schema {
-            Schema::Simple(SimpleSchema::U8) => self.deserializer.deserialize_u8(visitor),
-            Schema::Simple(SimpleSchema::U16) => self.deserializer.deserialize_u16(visitor),
-            Schema::Simple(SimpleSchema::U32) => self.deserializer.deserialize_u32(visitor),
-            Schema::Simple(SimpleSchema::U64) => self.deserializer.deserialize_u64(visitor),
+            SchemaDecode::Any => self.deserializer.deserialize_u64(visitor),
+            SchemaDecode::Simple(SimpleSchema::U8) => self.deserializer.deserialize_u8(visitor),
+            SchemaDecode::Simple(SimpleSchema::U16) => self.deserializer.deserialize_u16(visitor),
+            SchemaDecode::Simple(SimpleSchema::U32) => self.deserializer.deserialize_u32(visitor),
+            SchemaDecode::Simple(SimpleSchema::U64) => self.deserializer.deserialize_u64(visitor),
+            SchemaDecode::Enum(
+                s @ EnumDecode {
+                    repr: EnumRepr::Integer { .. },
+                    ..
+                },
+            ) => self.deserialize_enum_schema(s, visitor, false),
             s => Err(Self::Error::custom(format!(
                 "invalid type {}, expected {}",
-                s.kind(),
-                Kind::Simple(SimpleSchema::U64)
+                s.expected(),
+                Expected::Simple(SimpleSchema::U64)
             ))),
         }
     }
@@ -412,15 +1294,22 @@ impl<'de, D: Deserializer<'de>> Deserializer<'de> for SchemaDeserializer<'_, '_,
         V: Visitor<'de>,
     {
         match self.schema {
-            Schema::Simple(SimpleSchema::U8) => self.deserializer.deserialize_u8(visitor),
-            Schema::Simple(SimpleSchema::U16) => self.deserializer.deserialize_u16(visitor),
-            Schema::Simple(SimpleSchema::U32) => self.deserializer.deserialize_u32(visitor),
-            Schema::Simple(SimpleSchema::U64) => self.deserializer.deserialize_u64(visitor),
-            Schema::Simple(SimpleSchema::U128) => self.deserializer.deserialize_u128(visitor),
+            SchemaDecode::Any => self.deserializer.deserialize_u128(visitor),
+            SchemaDecode::Simple(SimpleSchema::U8) => self.deserializer.deserialize_u8(visitor),
+            SchemaDecode::Simple(SimpleSchema::U16) => self.deserializer.deserialize_u16(visitor),
+            SchemaDecode::Simple(SimpleSchema::U32) => self.deserializer.deserialize_u32(visitor),
+            SchemaDecode::Simple(SimpleSchema::U64) => self.deserializer.deserialize_u64(visitor),
+            SchemaDecode::Simple(SimpleSchema::U128) => self.deserializer.deserialize_u128(visitor),
+            SchemaDecode::Enum(
+                s @ EnumDecode {
+                    repr: EnumRepr::Integer { .. },
+                    ..
+                },
+            ) => self.deserialize_enum_schema(s, visitor, false),
             s => Err(Self::Error::custom(format!(
                 "invalid type {}, expected {}",
-                s.kind(),
-                Kind::Simple(SimpleSchema::U128)
+                s.expected(),
+                Expected::Simple(SimpleSchema::U128)
             ))),
         }
     }
@@ -430,11 +1319,12 @@ impl<'de, D: Deserializer<'de>> Deserializer<'de> for SchemaDeserializer<'_, '_,
         V: Visitor<'de>,
     {
         match self.schema {
-            Schema::Simple(SimpleSchema::F32) => self.deserializer.deserialize_f32(visitor),
+            SchemaDecode::Any => self.deserializer.deserialize_f32(visitor),
+            SchemaDecode::Simple(SimpleSchema::F32) => self.deserializer.deserialize_f32(visitor),
             s => Err(Self::Error::custom(format!(
                 "invalid type {}, expected {}",
-                s.kind(),
-                Kind::Simple(SimpleSchema::F32)
+                s.expected(),
+                Expected::Simple(SimpleSchema::F32)
             ))),
         }
     }
@@ -444,12 +1334,13 @@ impl<'de, D: Deserializer<'de>> Deserializer<'de> for SchemaDeserializer<'_, '_,
         V: Visitor<'de>,
     {
         match self.schema {
-            Schema::Simple(SimpleSchema::F32) => self.deserializer.deserialize_f32(visitor),
-            Schema::Simple(SimpleSchema::F64) => self.deserializer.deserialize_f64(visitor),
+            SchemaDecode::Any => self.deserializer.deserialize_f64(visitor),
+            SchemaDecode::Simple(SimpleSchema::F32) => self.deserializer.deserialize_f32(visitor),
+            SchemaDecode::Simple(SimpleSchema::F64) => self.deserializer.deserialize_f64(visitor),
             s => Err(Self::Error::custom(format!(
                 "invalid type {}, expected {}",
-                s.kind(),
-                Kind::Simple(SimpleSchema::F64)
+                s.expected(),
+                Expected::Simple(SimpleSchema::F64)
             ))),
         }
     }
@@ -459,11 +1350,12 @@ impl<'de, D: Deserializer<'de>> Deserializer<'de> for SchemaDeserializer<'_, '_,
         V: Visitor<'de>,
     {
         match self.schema {
-            Schema::Simple(SimpleSchema::Char) => self.deserializer.deserialize_char(visitor),
+            SchemaDecode::Any => self.deserializer.deserialize_char(visitor),
+            SchemaDecode::Simple(SimpleSchema::Char) => self.deserializer.deserialize_char(visitor),
             s => Err(Self::Error::custom(format!(
                 "invalid type {}, expected {}",
-                s.kind(),
-                Kind::Simple(SimpleSchema::Char)
+                s.expected(),
+                Expected::Simple(SimpleSchema::Char)
             ))),
         }
     }
@@ -473,11 +1365,18 @@ impl<'de, D: Deserializer<'de>> Deserializer<'de> for SchemaDeserializer<'_, '_,
         V: Visitor<'de>,
     {
         match self.schema {
-            Schema::Simple(SimpleSchema::String) => self.deserializer.deserialize_str(visitor),
+            SchemaDecode::Any => self.deserializer.deserialize_str(visitor),
+            SchemaDecode::Simple(SimpleSchema::String) => {
+                self.deserializer.deserialize_str(SimpleVisitor {
+                    schema: SimpleSchema::String,
+                    context: self.context,
+                    visitor,
+                })
+            }
             s => Err(Self::Error::custom(format!(
                 "invalid type {}, expected {}",
-                s.kind(),
-                Kind::Simple(SimpleSchema::String)
+                s.expected(),
+                Expected::Simple(SimpleSchema::String)
             ))),
         }
     }
@@ -487,11 +1386,18 @@ impl<'de, D: Deserializer<'de>> Deserializer<'de> for SchemaDeserializer<'_, '_,
         V: Visitor<'de>,
     {
         match self.schema {
-            Schema::Simple(SimpleSchema::String) => self.deserializer.deserialize_string(visitor),
+            SchemaDecode::Any => self.deserializer.deserialize_string(visitor),
+            SchemaDecode::Simple(SimpleSchema::String) => {
+                self.deserializer.deserialize_string(SimpleVisitor {
+                    schema: SimpleSchema::String,
+                    context: self.context,
+                    visitor,
+                })
+            }
             s => Err(Self::Error::custom(format!(
                 "invalid type {}, expected {}",
-                s.kind(),
-                Kind::Simple(SimpleSchema::String)
+                s.expected(),
+                Expected::Simple(SimpleSchema::String)
             ))),
         }
     }
@@ -501,11 +1407,28 @@ impl<'de, D: Deserializer<'de>> Deserializer<'de> for SchemaDeserializer<'_, '_,
         V: Visitor<'de>,
     {
         match self.schema {
-            Schema::Simple(SimpleSchema::Bytes) => self.deserializer.deserialize_bytes(visitor),
+            SchemaDecode::Any => self.deserializer.deserialize_bytes(visitor),
+            SchemaDecode::Simple(SimpleSchema::Bytes) => {
+                self.deserializer.deserialize_bytes(SimpleVisitor {
+                    schema: SimpleSchema::Bytes,
+                    context: self.context,
+                    visitor,
+                })
+            }
+            // Byte sequences written element by element.
+            SchemaDecode::Seq(s) if self.context.is_byte(&s.value) => {
+                self.deserializer.deserialize_seq(visitor)
+            }
+            SchemaDecode::Tuple(s) if s.values.iter().all(|v| self.context.is_byte(v)) => {
+                self.deserializer.deserialize_tuple(s.values.len(), visitor)
+            }
+            SchemaDecode::Array(s) if self.context.is_byte(&s.value) => {
+                self.deserializer.deserialize_tuple(s.len, visitor)
+            }
             s => Err(Self::Error::custom(format!(
                 "invalid type {}, expected {}",
-                s.kind(),
-                Kind::Simple(SimpleSchema::Bytes)
+                s.expected(),
+                Expected::Simple(SimpleSchema::Bytes)
             ))),
         }
     }
@@ -515,11 +1438,28 @@ impl<'de, D: Deserializer<'de>> Deserializer<'de> for SchemaDeserializer<'_, '_,
         V: Visitor<'de>,
     {
         match self.schema {
-            Schema::Simple(SimpleSchema::Bytes) => self.deserializer.deserialize_byte_buf(visitor),
+            SchemaDecode::Any => self.deserializer.deserialize_byte_buf(visitor),
+            SchemaDecode::Simple(SimpleSchema::Bytes) => {
+                self.deserializer.deserialize_byte_buf(SimpleVisitor {
+                    schema: SimpleSchema::Bytes,
+                    context: self.context,
+                    visitor,
+                })
+            }
+            // Byte sequences written element by element.
+            SchemaDecode::Seq(s) if self.context.is_byte(&s.value) => {
+                self.deserializer.deserialize_seq(visitor)
+            }
+            SchemaDecode::Tuple(s) if s.values.iter().all(|v| self.context.is_byte(v)) => {
+                self.deserializer.deserialize_tuple(s.values.len(), visitor)
+            }
+            SchemaDecode::Array(s) if self.context.is_byte(&s.value) => {
+                self.deserializer.deserialize_tuple(s.len, visitor)
+            }
             s => Err(Self::Error::custom(format!(
                 "invalid type {}, expected {}",
-                s.kind(),
-                Kind::Simple(SimpleSchema::Bytes)
+                s.expected(),
+                Expected::Simple(SimpleSchema::Bytes)
             ))),
         }
     }
@@ -529,11 +1469,15 @@ impl<'de, D: Deserializer<'de>> Deserializer<'de> for SchemaDeserializer<'_, '_,
         V: Visitor<'de>,
     {
         match self.schema {
-            Schema::Simple(SimpleSchema::Unit) => self.deserializer.deserialize_unit(visitor),
+            SchemaDecode::Any => self.deserializer.deserialize_unit(visitor),
+            SchemaDecode::Simple(SimpleSchema::Unit) => self.deserializer.deserialize_unit(visitor),
+            SchemaDecode::UnitStruct(s) => {
+                self.deserializer.deserialize_unit_struct(s.name, visitor)
+            }
             s => Err(Self::Error::custom(format!(
                 "invalid type {}, expected {}",
-                s.kind(),
-                Kind::Simple(SimpleSchema::Unit)
+                s.expected(),
+                Expected::Simple(SimpleSchema::Unit)
             ))),
         }
     }
@@ -543,12 +1487,13 @@ impl<'de, D: Deserializer<'de>> Deserializer<'de> for SchemaDeserializer<'_, '_,
         V: Visitor<'de>,
     {
         match self.schema {
-            Schema::Option(s) => self.deserializer.deserialize_option(OptionVisitor {
-                value: &s.value,
-                opts: self.opts,
+            SchemaDecode::Any => self.deserializer.deserialize_option(visitor),
+            SchemaDecode::Option(s) => self.deserializer.deserialize_option(OptionVisitor {
+                value: self.context.lookup(&s.value),
+                context: self.context,
                 visitor,
             }),
-            s => visitor.visit_some(self),
+            _ => visitor.visit_some(self),
         }
     }
 
@@ -561,13 +1506,17 @@ impl<'de, D: Deserializer<'de>> Deserializer<'de> for SchemaDeserializer<'_, '_,
         V: Visitor<'de>,
     {
         match self.schema {
-            Schema::Simple(SimpleSchema::Unit) => {
+            SchemaDecode::Any => self.deserializer.deserialize_unit_struct(name, visitor),
+            SchemaDecode::Simple(SimpleSchema::Unit) => {
                 self.deserializer.deserialize_unit_struct(name, visitor)
             }
+            SchemaDecode::UnitStruct(s) => {
+                self.deserializer.deserialize_unit_struct(s.name, visitor)
+            }
             s => Err(Self::Error::custom(format!(
                 "invalid type {}, expected {}",
-                s.kind(),
-                Kind::Simple(SimpleSchema::Unit)
+                s.expected(),
+                Expected::UnitStruct(name)
             ))),
         }
     }
@@ -580,7 +1529,19 @@ impl<'de, D: Deserializer<'de>> Deserializer<'de> for SchemaDeserializer<'_, '_,
     where
         V: Visitor<'de>,
     {
-        visitor.visit_newtype_struct(self)
+        match self.schema {
+            SchemaDecode::Any => self.deserializer.deserialize_newtype_struct(name, visitor),
+            SchemaDecode::Newtype(s) => self.deserializer.deserialize_newtype_struct(
+                s.name,
+                NewtypeVisitor {
+                    value: self.context.lookup(&s.value),
+                    context: self.context,
+                    visitor,
+                },
+            ),
+            // A newtype described by its inner value, e.g. `Wrapping<T>`.
+            _ => visitor.visit_newtype_struct(self),
+        }
     }
 
     fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
@@ -588,15 +1549,21 @@ impl<'de, D: Deserializer<'de>> Deserializer<'de> for SchemaDeserializer<'_, '_,
         V: Visitor<'de>,
     {
         match self.schema {
-            Schema::Seq(s) => self.deserializer.deserialize_seq(SeqVisitor {
-                value: &s.value,
-                opts: self.opts,
-                visitor,
-            }),
+            SchemaDecode::Any => self.deserializer.deserialize_seq(visitor),
+            SchemaDecode::Seq(s) | SchemaDecode::Set(s) => {
+                self.deserializer.deserialize_seq(SeqVisitor {
+                    value: self.context.lookup(&s.value),
+                    context: self.context,
+                    visitor,
+                })
+            }
+            SchemaDecode::Simple(SimpleSchema::Bytes) => self
+                .deserializer
+                .deserialize_byte_buf(BytesAsSeqVisitor(visitor)),
             s => Err(Self::Error::custom(format!(
                 "invalid type {}, expected {}",
-                s.kind(),
-                Kind::Seq
+                s.expected(),
+                Expected::Seq
             ))),
         }
     }
@@ -606,18 +1573,31 @@ impl<'de, D: Deserializer<'de>> Deserializer<'de> for SchemaDeserializer<'_, '_,
         V: Visitor<'de>,
     {
         match self.schema {
-            Schema::Tuple(s) => self.deserializer.deserialize_tuple(
+            SchemaDecode::Any => self.deserializer.deserialize_tuple(len, visitor),
+            SchemaDecode::Tuple(s) => self.deserializer.deserialize_tuple(
                 s.values.len(),
                 TupleVisitor {
                     values: &s.values,
-                    opts: self.opts,
+                    context: self.context,
+                    visitor,
+                },
+            ),
+            SchemaDecode::Array(s) => self.deserializer.deserialize_tuple(
+                s.len,
+                ArrayVisitor {
+                    value: &s.value,
+                    len: s.len,
+                    context: self.context,
                     visitor,
                 },
             ),
+            SchemaDecode::Simple(SimpleSchema::Bytes) => self
+                .deserializer
+                .deserialize_byte_buf(BytesAsSeqVisitor(visitor)),
             s => Err(Self::Error::custom(format!(
                 "invalid type {}, expected {}",
-                s.kind(),
-                Kind::Tuple
+                s.expected(),
+                Expected::Tuple(len)
             ))),
         }
     }
@@ -632,19 +1612,28 @@ impl<'de, D: Deserializer<'de>> Deserializer<'de> for SchemaDeserializer<'_, '_,
         V: Visitor<'de>,
     {
         match self.schema {
-            Schema::Tuple(s) => self.deserializer.deserialize_tuple_struct(
+            SchemaDecode::Any => self
+                .deserializer
+                .deserialize_tuple_struct(name, len, visitor),
+            SchemaDecode::Tuple(s) => self.deserializer.deserialize_tuple_struct(
                 name,
                 s.values.len(),
                 TupleVisitor {
                     values: &s.values,
-                    opts: self.opts,
+                    context: self.context,
                     visitor,
                 },
             ),
+            SchemaDecode::Struct(
+                s @ StructDecode {
+                    fields: FieldsDecode::Tuple(_),
+                    ..
+                },
+            ) => self.deserialize_struct_schema(s, visitor),
             s => Err(Self::Error::custom(format!(
                 "invalid type {}, expected {}",
-                s.kind(),
-                Kind::Tuple
+                s.expected(),
+                Expected::Tuple(len)
             ))),
         }
     }
@@ -654,16 +1643,29 @@ impl<'de, D: Deserializer<'de>> Deserializer<'de> for SchemaDeserializer<'_, '_,
         V: Visitor<'de>,
     {
         match self.schema {
-            Schema::Map(s) => self.deserializer.deserialize_map(MapVisitor {
-                key: &s.key,
-                value: &s.value,
-                opts: self.opts,
+            SchemaDecode::Any => self.deserializer.deserialize_map(visitor),
+            SchemaDecode::Map(s) => self.deserializer.deserialize_map(MapVisitor {
+                key: self.context.lookup(&s.key),
+                value: self.context.lookup(&s.value),
+                context: self.context,
                 visitor,
             }),
+            SchemaDecode::Struct(
+                s @ StructDecode {
+                    fields: FieldsDecode::Named(_),
+                    ..
+                },
+            ) => self.deserialize_struct_schema(s, visitor),
+            SchemaDecode::Enum(
+                s @ EnumDecode {
+                    repr: EnumRepr::InternallyTagged { .. } | EnumRepr::AdjacentlyTagged { .. },
+                    ..
+                },
+            ) => self.deserialize_enum_schema(s, visitor, true),
             s => Err(Self::Error::custom(format!(
                 "invalid type {}, expected {}",
-                s.kind(),
-                Kind::Map
+                s.expected(),
+                Expected::Map
             ))),
         }
     }
@@ -678,25 +1680,19 @@ impl<'de, D: Deserializer<'de>> Deserializer<'de> for SchemaDeserializer<'_, '_,
         V: Visitor<'de>,
     {
         match self.schema {
-            Schema::Struct(s) => match self.opts.struct_format {
-                StructFormat::Tuple => self.deserializer.deserialize_tuple(
-                    s.fields.len(),
-                    TupleStructVisitor {
-                        fields: &s.fields,
-                        opts: self.opts,
-                        visitor,
-                    },
-                ),
-                StructFormat::Map => self.deserializer.deserialize_map(MapStructVisitor {
-                    fields: &s.fields,
-                    opts: self.opts,
-                    visitor,
-                }),
-            },
+            SchemaDecode::Any => self.deserializer.deserialize_struct(name, fields, visitor),
+            SchemaDecode::Struct(s) => self.deserialize_struct_schema(s, visitor),
+            SchemaDecode::Map(_) => self.deserialize_map(visitor),
+            SchemaDecode::Enum(
+                s @ EnumDecode {
+                    repr: EnumRepr::InternallyTagged { .. } | EnumRepr::AdjacentlyTagged { .. },
+                    ..
+                },
+            ) => self.deserialize_enum_schema(s, visitor, true),
             s => Err(Self::Error::custom(format!(
                 "invalid type {}, expected {}",
-                s.kind(),
-                Kind::Struct
+                s.expected(),
+                Expected::Struct(name)
             ))),
         }
     }
@@ -711,31 +1707,12 @@ impl<'de, D: Deserializer<'de>> Deserializer<'de> for SchemaDeserializer<'_, '_,
         V: Visitor<'de>,
     {
         match self.schema {
-            Schema::Enum(s) => match self.opts.enum_format {
-                EnumFormat::Tuple => self
-                    .deserializer
-                    .deserialize_tuple(2, TupleEnumVisitor(&s.variants, visitor)),
-                EnumFormat::Map => match s.repr {
-                    EnumRepr::ExternallyTagged => self
-                        .deserializer
-                        .deserialize_map(ExternallyTaggedEnumVisitor(&s.variants, visitor)),
-                    EnumRepr::InternallyTagged { tag } => self
-                        .deserializer
-                        .deserialize_map(InternallyTaggedEnumVisitor(tag, &s.variants, visitor)),
-                    EnumRepr::AdjacentlyTagged { tag, content } => self
-                        .deserializer
-                        .deserialize_map(AdjacentlyTaggedEnumVisitor(
-                            tag,
-                            content,
-                            &s.variants,
-                            visitor,
-                        )),
-                },
-            },
+            SchemaDecode::Any => self.deserializer.deserialize_enum(name, variants, visitor),
+            SchemaDecode::Enum(s) => self.deserialize_enum_schema(s, visitor, false),
             s => Err(Self::Error::custom(format!(
                 "invalid type {}, expected {}",
-                s.kind(),
-                Kind::Enum
+                s.expected(),
+                Expected::Enum(name)
             ))),
         }
     }
@@ -745,13 +1722,14 @@ impl<'de, D: Deserializer<'de>> Deserializer<'de> for SchemaDeserializer<'_, '_,
         V: Visitor<'de>,
     {
         match self.schema {
-            Schema::Simple(SimpleSchema::String) => {
+            SchemaDecode::Any => self.deserializer.deserialize_identifier(visitor),
+            SchemaDecode::Simple(SimpleSchema::String) => {
                 self.deserializer.deserialize_identifier(visitor)
             }
             s => Err(Self::Error::custom(format!(
                 "invalid type {}, expected {}",
-                s.kind(),
-                Kind::Simple(SimpleSchema::String)
+                s.expected(),
+                Expected::Simple(SimpleSchema::String)
             ))),
         }
     }
@@ -760,21 +1738,112 @@ impl<'de, D: Deserializer<'de>> Deserializer<'de> for SchemaDeserializer<'_, '_,
     where
         V: Visitor<'de>,
     {
-        self.deserialize_any(visitor)
+        match (self.context.opts.self_describing, self.schema) {
+            (true, _) => self.deserializer.deserialize_ignored_any(visitor),
+            // Skipped strings are read in place, and neither copied out nor
+            // counted against `max_bytes`.
+            (false, SchemaDecode::Simple(SimpleSchema::String)) => {
+                self.deserializer.deserialize_str(visitor)
+            }
+            (false, SchemaDecode::Simple(SimpleSchema::Bytes)) => {
+                self.deserializer.deserialize_bytes(visitor)
+            }
+            (false, _) => self.deserialize_any(visitor),
+        }
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.deserializer.is_human_readable()
     }
 }
 
-struct OptionVisitor<'a, V> {
-    value: &'a Schema,
-    opts: &'a DeserializerOptions,
+impl<'a, 'b> Context<'a, 'b> {
+    /// The context of a value nested in the current one, or an error if
+    /// that is too deep: a hostile input could otherwise overflow the stack.
+    fn nested<E: Error>(mut self) -> Result<Self, E> {
+        self.depth += 1;
+        match self.depth > self.opts.max_depth {
+            true => Err(E::custom(format!(
+                "nesting deeper than {} levels",
+                self.opts.max_depth
+            ))),
+            false => Ok(self),
+        }
+    }
+
+    /// Count the next element of a sequence, or entry of a map, of which
+    /// `len` were read before.
+    fn element<E: Error>(&self, len: &mut usize, what: &str, unit: &str) -> Result<(), E> {
+        *len += 1;
+        if *len > self.opts.max_len {
+            return Err(E::custom(format!(
+                "{what} longer than {} {unit}",
+                self.opts.max_len
+            )));
+        }
+        self.allocate(std::mem::size_of::<Value>())
+    }
+
+    /// Check the length of a string or byte string.
+    fn string<E: Error>(&self, len: usize) -> Result<(), E> {
+        if len > self.opts.max_string_len {
+            return Err(E::custom(format!(
+                "string longer than {} bytes",
+                self.opts.max_string_len
+            )));
+        }
+        self.allocate(len)
+    }
+
+    fn allocate<E: Error>(&self, bytes: usize) -> Result<(), E> {
+        let Some(allocated) = self.allocated else {
+            return Ok(());
+        };
+        allocated.set(allocated.get().saturating_add(bytes));
+        match allocated.get() > self.opts.max_bytes {
+            true => Err(E::custom(format!(
+                "decoded values take more than {} bytes",
+                self.opts.max_bytes
+            ))),
+            false => Ok(()),
+        }
+    }
+
+    fn lookup(&self, item: &'a SchemaDecodeItem<'b>) -> &'a SchemaDecode<'b> {
+        self.resolve(item.lookup(self.items))
+    }
+
+    fn is_byte(&self, item: &'a SchemaDecodeItem<'b>) -> bool {
+        matches!(self.lookup(item), SchemaDecode::Simple(SimpleSchema::U8))
+    }
+
+    /// Pick the alternative of a human-readable schema that matches the
+    /// format and see through constraints and transparent newtypes, so that
+    /// the rest of the deserializer never sees any of them.
+    fn resolve(&self, schema: &'a SchemaDecode<'b>) -> &'a SchemaDecode<'b> {
+        schema.peel(self.items, |schema| match schema {
+            SchemaDecode::HumanReadable(s) => match self.human_readable.unwrap_or(false) {
+                true => Some(&s.readable),
+                false => Some(&s.compact),
+            },
+            SchemaDecode::Newtype(s) if s.transparent => Some(&s.value),
+            SchemaDecode::Constrained(s) => Some(&s.value),
+            _ => None,
+        })
+    }
+}
+
+struct OptionVisitor<'a, 'b, V> {
+    value: &'a SchemaDecode<'b>,
+    context: Context<'a, 'b>,
     visitor: V,
 }
 
-impl<'de, V: Visitor<'de>> Visitor<'de> for OptionVisitor<'_, V> {
+impl<'de, V: Visitor<'de>> Visitor<'de> for OptionVisitor<'_, '_, V> {
     type Value = V::Value;
 
     fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", Kind::Option)
+        write!(f, "{}", Expected::Option)
     }
 
     fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
@@ -783,7 +1852,7 @@ impl<'de, V: Visitor<'de>> Visitor<'de> for OptionVisitor<'_, V> {
     {
         self.visitor.visit_some(SchemaDeserializer {
             schema: self.value,
-            opts: self.opts,
+            context: self.context.nested()?,
             deserializer,
         })
     }
@@ -794,213 +1863,1477 @@ impl<'de, V: Visitor<'de>> Visitor<'de> for OptionVisitor<'_, V> {
     {
         self.visitor.visit_none()
     }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        self.visitor.visit_none()
+    }
 }
 
-struct SeqVisitor<'a, V> {
-    value: &'a Schema,
-    opts: &'a DeserializerOptions,
+struct NewtypeVisitor<'a, 'b, V> {
+    value: &'a SchemaDecode<'b>,
+    context: Context<'a, 'b>,
+    visitor: V,
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for NewtypeVisitor<'_, '_, V> {
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.visitor.expecting(f)
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.visitor.visit_newtype_struct(SchemaDeserializer {
+            schema: self.value,
+            context: self.context.nested()?,
+            deserializer,
+        })
+    }
+}
+
+struct SeqVisitor<'a, 'b, V> {
+    value: &'a SchemaDecode<'b>,
+    context: Context<'a, 'b>,
+    visitor: V,
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for SeqVisitor<'_, '_, V> {
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", Expected::Seq)
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        self.visitor.visit_seq(SchemaSeqAccess {
+            schema: self.value,
+            context: self.context,
+            len: 0,
+            seq,
+        })
+    }
+}
+
+struct SchemaSeqAccess<'a, 'b, A> {
+    schema: &'a SchemaDecode<'b>,
+    context: Context<'a, 'b>,
+    /// How many elements were read.
+    len: usize,
+    seq: A,
+}
+
+impl<'de, A: SeqAccess<'de>> SeqAccess<'de> for SchemaSeqAccess<'_, '_, A> {
+    type Error = A::Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        let element = self.seq.next_element_seed(SchemaSeed {
+            schema: self.schema,
+            context: self.context,
+            seed,
+        })?;
+        if element.is_some() {
+            self.context
+                .element(&mut self.len, "sequence", "elements")?;
+        }
+        Ok(element)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        let hint = self.seq.size_hint()?;
+        Some(hint.min(self.context.opts.max_len))
+    }
+}
+
+struct MapVisitor<'a, 'b, V> {
+    key: &'a SchemaDecode<'b>,
+    value: &'a SchemaDecode<'b>,
+    context: Context<'a, 'b>,
+    visitor: V,
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for MapVisitor<'_, '_, V> {
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", Expected::Map)
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        self.visitor.visit_map(SchemaMapAccess {
+            key: self.key,
+            value: self.value,
+            context: self.context,
+            len: 0,
+            map,
+        })
+    }
+}
+
+struct SchemaMapAccess<'a, 'b, A> {
+    key: &'a SchemaDecode<'b>,
+    value: &'a SchemaDecode<'b>,
+    context: Context<'a, 'b>,
+    /// How many entries were read.
+    len: usize,
+    map: A,
+}
+
+impl<'de, A: MapAccess<'de>> MapAccess<'de> for SchemaMapAccess<'_, '_, A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        let key = self.map.next_key_seed(SchemaSeed {
+            schema: self.key,
+            context: self.context,
+            seed,
+        })?;
+        if key.is_some() {
+            self.context.element(&mut self.len, "map", "entries")?;
+        }
+        Ok(key)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        self.map.next_value_seed(SchemaSeed {
+            schema: self.value,
+            context: self.context,
+            seed,
+        })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        let hint = self.map.size_hint()?;
+        Some(hint.min(self.context.opts.max_len))
+    }
+}
+
+struct TupleVisitor<'a, 'b, V> {
+    values: &'a [SchemaDecodeItem<'b>],
+    context: Context<'a, 'b>,
     visitor: V,
 }
 
-impl<'de, V: Visitor<'de>> Visitor<'de> for SeqVisitor<'_, V> {
+impl<'de, V: Visitor<'de>> Visitor<'de> for TupleVisitor<'_, '_, V> {
     type Value = V::Value;
 
     fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", Kind::Seq)
+        write!(f, "{}", Expected::Tuple(self.values.len()))
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        self.visitor.visit_seq(TupleSeqAccess {
+            values: self.values.iter(),
+            context: self.context,
+            seq,
+        })
+    }
+}
+
+struct ArrayVisitor<'a, 'b, V> {
+    value: &'a SchemaDecodeItem<'b>,
+    len: usize,
+    context: Context<'a, 'b>,
+    visitor: V,
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for ArrayVisitor<'_, '_, V> {
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", Expected::Array(self.len))
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        self.visitor.visit_seq(TupleSeqAccess {
+            values: std::iter::repeat_n(self.value, self.len),
+            context: self.context,
+            seq,
+        })
+    }
+}
+
+struct TupleSeqAccess<'a, 'b, I, A> {
+    values: I,
+    context: Context<'a, 'b>,
+    seq: A,
+}
+
+impl<'a, 'b: 'a, 'de, I, A> SeqAccess<'de> for TupleSeqAccess<'a, 'b, I, A>
+where
+    I: ExactSizeIterator<Item = &'a SchemaDecodeItem<'b>>,
+    A: SeqAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.values.next() {
+            Some(schema) => self.seq.next_element_seed(SchemaSeed {
+                schema: self.context.lookup(schema),
+                context: self.context,
+                seed,
+            }),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.values.len())
+    }
+}
+
+struct StructVisitor<'a, 'b, V> {
+    fields: &'a NamedFieldsDecode<'b>,
+    context: Context<'a, 'b>,
+    visitor: V,
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for StructVisitor<'_, '_, V> {
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "struct with {} fields", self.fields.fields.len())
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        self.visitor.visit_map(TupleStructAccess {
+            fields: self.fields.fields.iter().enumerate(),
+            value: None,
+            context: self.context,
+            seq,
+        })
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        self.visitor
+            .visit_map(MapStructAccess::new(self.fields, self.context, map))
+    }
+}
+
+struct TupleStructAccess<'a, 'b, I, A> {
+    fields: I,
+    value: Option<(usize, &'a NamedFieldDecode<'b>)>,
+    context: Context<'a, 'b>,
+    seq: A,
+}
+
+impl<'a, 'b: 'a, 'de, I, A> MapAccess<'de> for TupleStructAccess<'a, 'b, I, A>
+where
+    I: ExactSizeIterator<Item = (usize, &'a NamedFieldDecode<'b>)>,
+    A: SeqAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        match self.fields.next() {
+            Some((i, field)) => {
+                self.value = Some((i, field));
+                Ok(Some(seed.deserialize(StrDeserializer::new(field.name))?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let (i, field) = self
+            .value
+            .take()
+            .ok_or_else(|| A::Error::custom("invalid use of next_value_seed"))?;
+        self.seq
+            .next_element_seed(SchemaSeed {
+                schema: self.context.lookup(&field.value),
+                context: self.context,
+                seed,
+            })?
+            .ok_or_else(|| A::Error::invalid_length(i, &"more struct fields"))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.fields.len())
+    }
+}
+
+struct MapStructAccess<'a, 'b, A> {
+    fields: &'a NamedFieldsDecode<'b>,
+    seen: Vec<bool>,
+    missing: Option<usize>,
+    value: Option<FieldValue<'a, 'b>>,
+    context: Context<'a, 'b>,
+    /// How many entries were read, which flattened maps leave unbounded.
+    len: usize,
+    map: A,
+}
+
+enum FieldValue<'a, 'b> {
+    Present(&'a SchemaDecode<'b>),
+    Missing(Value),
+}
+
+impl<'a, 'b, A> MapStructAccess<'a, 'b, A> {
+    fn new(fields: &'a NamedFieldsDecode<'b>, context: Context<'a, 'b>, map: A) -> Self {
+        Self {
+            fields,
+            seen: vec![false; fields.fields.len()],
+            missing: None,
+            value: None,
+            context,
+            len: 0,
+            map,
+        }
+    }
+}
+
+impl<'de, A: MapAccess<'de>> MapAccess<'de> for MapStructAccess<'_, '_, A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        if self.missing.is_none() {
+            let key = self.map.next_key_seed(FieldSeed(self.fields))?;
+            if key.is_some() {
+                self.context.element(&mut self.len, "map", "entries")?;
+            }
+            match key {
+                Some(FieldKey::Flattened(name)) => {
+                    let value = self
+                        .fields
+                        .find_flattened(self.context.items, &name)
+                        .ok_or_else(|| A::Error::unknown_field(&name, self.fields.names))?;
+                    self.value = Some(FieldValue::Present(self.context.lookup(value)));
+                    return Ok(Some(seed.deserialize(StringDeserializer::new(name))?));
+                }
+                Some(FieldKey::Field(i)) => {
+                    let field = &self.fields.fields[i];
+                    if std::mem::replace(&mut self.seen[i], true) {
+                        return Err(A::Error::duplicate_field(self.fields.names[i]));
+                    }
+                    self.value = Some(FieldValue::Present(self.context.lookup(&field.value)));
+                    return Ok(Some(seed.deserialize(StrDeserializer::new(field.name))?));
+                }
+                None => self.missing = Some(0),
+            }
+        }
+
+        /* Fill in fields the format was allowed to leave out. */
+        let start = self.missing.unwrap_or_default();
+        for (i, field) in self.fields.fields.iter().enumerate().skip(start) {
+            if self.seen[i] || field.flatten {
+                continue;
+            }
+            let value = match (field.default, self.context.lookup(&field.value)) {
+                (Some(default), _) => default.clone(),
+                // A field skipped by its value was left out on purpose, and
+                // the type reads its absence itself.
+                (None, SchemaDecode::Option(_))
+                    if self.context.opts.option_format == OptionFormat::Nullable
+                        && !field.optional =>
+                {
+                    Value::Option(None)
+                }
+                _ => continue,
+            };
+            self.missing = Some(i + 1);
+            self.value = Some(FieldValue::Missing(value));
+            return Ok(Some(seed.deserialize(StrDeserializer::new(field.name))?));
+        }
+        self.missing = Some(self.fields.fields.len());
+        Ok(None)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        match self.value.take() {
+            Some(FieldValue::Present(schema)) => self.map.next_value_seed(SchemaSeed {
+                schema,
+                context: self.context,
+                seed,
+            }),
+            Some(FieldValue::Missing(value)) => seed.deserialize(ValueDeserializer::new(value)),
+            None => Err(A::Error::custom("invalid use of next_value_seed")),
+        }
+    }
+}
+
+/// Resolves a struct field key (by name, alias or index) to its position.
+struct FieldSeed<'a, 'b>(&'a NamedFieldsDecode<'b>);
+
+enum FieldKey {
+    Field(usize),
+    /// A key for one of the flattened fields.
+    Flattened(String),
+}
+
+impl<'de> DeserializeSeed<'de> for FieldSeed<'_, '_> {
+    type Value = FieldKey;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_identifier(self)
+    }
+}
+
+impl<'de> Visitor<'de> for FieldSeed<'_, '_> {
+    type Value = FieldKey;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "field identifier")
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        match v < self.0.fields.len() as u64 {
+            true => Ok(FieldKey::Field(v as usize)),
+            false => Err(E::invalid_value(Unexpected::Unsigned(v), &self)),
+        }
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        match (self.0.find(v), self.0.flattened()) {
+            (Some(i), _) => Ok(FieldKey::Field(i)),
+            (None, Some(_)) => Ok(FieldKey::Flattened(v.to_string())),
+            (None, None) => Err(E::unknown_field(v, self.0.names)),
+        }
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        match std::str::from_utf8(v) {
+            Ok(v) => self.visit_str(v),
+            Err(_) => Err(E::invalid_value(Unexpected::Bytes(v), &self)),
+        }
+    }
+}
+
+/// Reads the discriminant of an integer enum, passing it on as an integer
+/// of the enum's repr once it is known to name a variant.
+struct DiscriminantVisitor<'a, 'b, V> {
+    schema: &'a EnumDecode<'b>,
+    repr: SimpleSchema,
+    context: Context<'a, 'b>,
+    visitor: V,
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for DiscriminantVisitor<'_, '_, V> {
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "a discriminant of enum {}", self.schema.name)
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        match self.schema.by_discriminant(v) {
+            Some(_) => SimpleVisitor {
+                schema: self.repr,
+                context: self.context,
+                visitor: self.visitor,
+            }
+            .visit_i64(v),
+            None => Err(E::invalid_value(Unexpected::Signed(v), &self)),
+        }
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        match i64::try_from(v)
+            .ok()
+            .and_then(|d| self.schema.by_discriminant(d))
+        {
+            Some(_) => SimpleVisitor {
+                schema: self.repr,
+                context: self.context,
+                visitor: self.visitor,
+            }
+            .visit_u64(v),
+            None => Err(E::invalid_value(Unexpected::Unsigned(v), &self)),
+        }
+    }
+}
+
+/// Resolves a variant tag to the variant's position, according to the
+/// profile's [`EnumTag`].
+struct VariantSeed<'a, 'b> {
+    schema: &'a EnumDecode<'b>,
+    context: Context<'a, 'b>,
+}
+
+impl<'de> DeserializeSeed<'de> for VariantSeed<'_, '_> {
+    type Value = usize;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_identifier(self)
+    }
+}
+
+impl<'de> Visitor<'de> for VariantSeed<'_, '_> {
+    type Value = usize;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.context.opts.enum_tag {
+            EnumTag::Index => write!(f, "variant index of enum {}", self.schema.name),
+            EnumTag::Name => write!(f, "variant name of enum {}", self.schema.name),
+        }
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        match self.context.opts.enum_tag {
+            EnumTag::Index => self
+                .schema
+                .by_index(v)
+                .ok_or_else(|| E::invalid_value(Unexpected::Unsigned(v), &self)),
+            EnumTag::Name => Err(E::invalid_type(Unexpected::Unsigned(v), &self)),
+        }
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        match self.context.opts.enum_tag {
+            EnumTag::Index => Err(E::invalid_type(Unexpected::Str(v), &self)),
+            EnumTag::Name => self
+                .schema
+                .by_name(v)
+                .ok_or_else(|| E::unknown_variant(v, self.schema.names)),
+        }
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        match std::str::from_utf8(v) {
+            Ok(v) => self.visit_str(v),
+            Err(_) => Err(E::invalid_value(Unexpected::Bytes(v), &self)),
+        }
+    }
+}
+
+struct ExternallyTaggedEnumVisitor<'a, 'b, V> {
+    schema: &'a EnumDecode<'b>,
+    context: Context<'a, 'b>,
+    visitor: V,
+    /// Present the enum as a self-describing value (a variant name or a
+    /// single-entry map) rather than through `visit_enum`.
+    any: bool,
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for ExternallyTaggedEnumVisitor<'_, '_, V> {
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", Expected::Enum(self.schema.name))
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+    where
+        A: EnumAccess<'de>,
+    {
+        if !self.any {
+            return self.visitor.visit_enum(SchemaEnumAccess {
+                schema: self.schema,
+                context: self.context,
+                data,
+            });
+        }
+
+        let (i, variant) = data.variant_seed(VariantSeed {
+            schema: self.schema,
+            context: self.context,
+        })?;
+        let schema = &self.schema.variants[i];
+        match &schema.fields {
+            FieldsDecode::Tuple(s) if s.values.is_empty() => {
+                variant.unit_variant()?;
+                self.visitor.visit_str(schema.name)
+            }
+            _ => self.visitor.visit_map(VariantMapAccess {
+                variant: Some((schema, variant)),
+                value: None,
+                context: self.context,
+            }),
+        }
+    }
+}
+
+struct SchemaEnumAccess<'a, 'b, A> {
+    schema: &'a EnumDecode<'b>,
+    context: Context<'a, 'b>,
+    data: A,
+}
+
+impl<'a, 'b: 'a, 'de, A: EnumAccess<'de>> EnumAccess<'de> for SchemaEnumAccess<'a, 'b, A> {
+    type Error = A::Error;
+    type Variant = SchemaVariantAccess<'a, 'b, A::Variant>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let (i, variant) = self.data.variant_seed(VariantSeed {
+            schema: self.schema,
+            context: self.context,
+        })?;
+        let schema = &self.schema.variants[i];
+        let value = seed.deserialize(StrDeserializer::new(schema.name))?;
+        Ok((
+            value,
+            SchemaVariantAccess {
+                schema,
+                context: self.context,
+                variant,
+            },
+        ))
+    }
+}
+
+struct SchemaVariantAccess<'a, 'b, A> {
+    schema: &'a VariantDecode<'b>,
+    context: Context<'a, 'b>,
+    variant: A,
+}
+
+impl<'a, 'b: 'a, 'de, A: VariantAccess<'de>> SchemaVariantAccess<'a, 'b, A> {
+    fn invalid_type(&self, expected: Expected) -> A::Error {
+        A::Error::custom(format!(
+            "invalid type {} variant, expected {} variant",
+            self.schema.fields.expected(self.schema.name),
+            expected
+        ))
+    }
+}
+
+impl<'a, 'b: 'a, 'de, A: VariantAccess<'de>> VariantAccess<'de> for SchemaVariantAccess<'a, 'b, A> {
+    type Error = A::Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        match &self.schema.fields {
+            FieldsDecode::Tuple(s) if s.values.is_empty() => self.variant.unit_variant(),
+            _ => Err(self.invalid_type(Expected::Simple(SimpleSchema::Unit))),
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match &self.schema.fields {
+            FieldsDecode::Tuple(s) if s.values.len() == 1 => {
+                self.variant.newtype_variant_seed(SchemaSeed {
+                    schema: self.context.lookup(&s.values[0]),
+                    context: self.context,
+                    seed,
+                })
+            }
+            _ => Err(self.invalid_type(Expected::Tuple(1))),
+        }
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match &self.schema.fields {
+            FieldsDecode::Tuple(s) => self.variant.tuple_variant(
+                s.values.len(),
+                TupleVisitor {
+                    values: &s.values,
+                    context: self.context,
+                    visitor,
+                },
+            ),
+            FieldsDecode::Named(_) => Err(self.invalid_type(Expected::Tuple(len))),
+        }
+    }
+
+    fn struct_variant<V>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match &self.schema.fields {
+            FieldsDecode::Named(s) => {
+                if self.context.opts.struct_format == StructFormat::Tuple {
+                    let variant = format_args!("variant {}", self.schema.name);
+                    if let Some(e) = s.positional_error(variant) {
+                        return Err(A::Error::custom(e));
+                    }
+                }
+                self.variant.struct_variant(
+                    s.names,
+                    StructVisitor {
+                        fields: s,
+                        context: self.context,
+                        visitor,
+                    },
+                )
+            }
+            FieldsDecode::Tuple(_) => Err(self.invalid_type(Expected::Struct(self.schema.name))),
+        }
+    }
+}
+
+/// Presents a non-unit variant as a single-entry map `{variant: content}`.
+struct VariantMapAccess<'a, 'b, A> {
+    variant: Option<(&'a VariantDecode<'b>, A)>,
+    value: Option<(&'a VariantDecode<'b>, A)>,
+    context: Context<'a, 'b>,
+}
+
+impl<'a, 'b: 'a, 'de, A: VariantAccess<'de>> MapAccess<'de> for VariantMapAccess<'a, 'b, A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        match self.variant.take() {
+            Some((schema, variant)) => {
+                let key = seed.deserialize(StrDeserializer::new(schema.name))?;
+                self.value = Some((schema, variant));
+                Ok(Some(key))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let (schema, variant) = self
+            .value
+            .take()
+            .ok_or_else(|| A::Error::custom("invalid use of next_value_seed"))?;
+        seed.deserialize(VariantDeserializer {
+            schema,
+            context: self.context,
+            variant,
+        })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.variant.is_some() as usize)
+    }
+}
+
+/// Presents the content of a variant as a self-describing value.
+struct VariantDeserializer<'a, 'b, A> {
+    schema: &'a VariantDecode<'b>,
+    context: Context<'a, 'b>,
+    variant: A,
+}
+
+impl<'a, 'b: 'a, 'de, A: VariantAccess<'de>> Deserializer<'de> for VariantDeserializer<'a, 'b, A> {
+    type Error = A::Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match &self.schema.fields {
+            FieldsDecode::Tuple(s) if s.values.is_empty() => {
+                self.variant.unit_variant()?;
+                visitor.visit_unit()
+            }
+            FieldsDecode::Tuple(s) if s.values.len() == 1 => {
+                self.variant.newtype_variant_seed(SchemaSeed {
+                    schema: self.context.lookup(&s.values[0]),
+                    context: self.context,
+                    seed: AnySeed(visitor),
+                })
+            }
+            FieldsDecode::Tuple(s) => self.variant.tuple_variant(
+                s.values.len(),
+                TupleVisitor {
+                    values: &s.values,
+                    context: self.context,
+                    visitor,
+                },
+            ),
+            FieldsDecode::Named(s) => self.variant.struct_variant(
+                s.names,
+                StructVisitor {
+                    fields: s,
+                    context: self.context,
+                    visitor,
+                },
+            ),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+/// Feeds a visitor from `deserialize_any`.
+struct AnySeed<V>(V);
+
+impl<'de, V: Visitor<'de>> DeserializeSeed<'de> for AnySeed<V> {
+    type Value = V::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self.0)
+    }
+}
+
+/// Reads a value of a self-describing format within the limits of the
+/// profile, for what has to be read before its schema is known: the content
+/// of internally and adjacently tagged enums, and values of enum Any.
+struct BufferSeed<'a, 'b>(Context<'a, 'b>);
+
+impl<'de> DeserializeSeed<'de> for BufferSeed<'_, '_> {
+    type Value = Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for BufferSeed<'_, '_> {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "any value")
+    }
+
+    fn visit_bool<E: Error>(self, v: bool) -> Result<Value, E> {
+        Ok(Value::Bool(v))
+    }
+
+    fn visit_i8<E: Error>(self, v: i8) -> Result<Value, E> {
+        Ok(Value::I8(v))
+    }
+
+    fn visit_i16<E: Error>(self, v: i16) -> Result<Value, E> {
+        Ok(Value::I16(v))
+    }
+
+    fn visit_i32<E: Error>(self, v: i32) -> Result<Value, E> {
+        Ok(Value::I32(v))
+    }
+
+    fn visit_i64<E: Error>(self, v: i64) -> Result<Value, E> {
+        Ok(Value::I64(v))
+    }
+
+    fn visit_u8<E: Error>(self, v: u8) -> Result<Value, E> {
+        Ok(Value::U8(v))
+    }
+
+    fn visit_u16<E: Error>(self, v: u16) -> Result<Value, E> {
+        Ok(Value::U16(v))
+    }
+
+    fn visit_u32<E: Error>(self, v: u32) -> Result<Value, E> {
+        Ok(Value::U32(v))
+    }
+
+    fn visit_u64<E: Error>(self, v: u64) -> Result<Value, E> {
+        Ok(Value::U64(v))
+    }
+
+    fn visit_f32<E: Error>(self, v: f32) -> Result<Value, E> {
+        Ok(Value::F32(v))
+    }
+
+    fn visit_f64<E: Error>(self, v: f64) -> Result<Value, E> {
+        Ok(Value::F64(v))
+    }
+
+    fn visit_char<E: Error>(self, v: char) -> Result<Value, E> {
+        Ok(Value::Char(v))
+    }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<Value, E> {
+        self.0.string(v.len())?;
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn visit_string<E: Error>(self, v: String) -> Result<Value, E> {
+        self.0.string(v.len())?;
+        Ok(Value::String(v))
+    }
+
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Value, E> {
+        self.0.string(v.len())?;
+        Ok(Value::Bytes(v.to_vec()))
+    }
+
+    fn visit_byte_buf<E: Error>(self, v: Vec<u8>) -> Result<Value, E> {
+        self.0.string(v.len())?;
+        Ok(Value::Bytes(v))
+    }
+
+    fn visit_none<E: Error>(self) -> Result<Value, E> {
+        Ok(Value::Option(None))
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = BufferSeed(self.0.nested()?).deserialize(deserializer)?;
+        Ok(Value::Option(Some(Box::new(value))))
+    }
+
+    fn visit_unit<E: Error>(self) -> Result<Value, E> {
+        Ok(Value::Unit)
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = BufferSeed(self.0.nested()?).deserialize(deserializer)?;
+        Ok(Value::Newtype(Box::new(value)))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let context = self.0.nested()?;
+        let (mut values, mut len) = (Vec::new(), 0);
+        while let Some(value) = seq.next_element_seed(BufferSeed(context))? {
+            context.element(&mut len, "sequence", "elements")?;
+            values.push(value);
+        }
+        Ok(Value::Seq(values))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let context = self.0.nested()?;
+        let (mut entries, mut len) = (BTreeMap::new(), 0);
+        while let Some((k, v)) = map.next_entry_seed(BufferSeed(context), BufferSeed(context))? {
+            context.element(&mut len, "map", "entries")?;
+            entries.insert(k, v);
+        }
+        Ok(Value::Map(entries))
+    }
+}
+
+/// Converts whatever primitive the format produced into the schema's type, so
+/// that self-describing views look the same regardless of the format.
+/// Presents a byte string to a visitor that expects a sequence of `u8`, e.g.
+/// a `Vec<u8>` or `[u8; N]` described as bytes.
+struct BytesAsSeqVisitor<V>(V);
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for BytesAsSeqVisitor<V> {
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.0.expecting(f)
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        self.0.visit_seq(SeqDeserializer::new(v.iter().copied()))
+    }
+
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        self.0.visit_seq(SeqDeserializer::new(v.into_iter()))
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        self.0.visit_seq(seq)
+    }
+}
+
+struct SimpleVisitor<'a, 'b, V> {
+    schema: SimpleSchema,
+    context: Context<'a, 'b>,
+    visitor: V,
+}
+
+impl<V> SimpleVisitor<'_, '_, V> {
+    fn out_of_range<E: Error>(&self, unexp: Unexpected) -> E {
+        E::invalid_value(unexp, &format!("{}", self.schema).as_str())
+    }
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for SimpleVisitor<'_, '_, V> {
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.schema)
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        match self.schema {
+            SimpleSchema::Bool => self.visitor.visit_bool(v),
+            _ => Err(E::invalid_type(Unexpected::Bool(v), &self)),
+        }
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        self.visit_i128(v as i128)
+    }
+
+    fn visit_i128<E>(self, v: i128) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        let unexp = Unexpected::Signed(v as i64);
+        match self.schema {
+            SimpleSchema::U8 => match u8::try_from(v) {
+                Ok(v) => self.visitor.visit_u8(v),
+                Err(_) => Err(self.out_of_range(unexp)),
+            },
+            SimpleSchema::U16 => match u16::try_from(v) {
+                Ok(v) => self.visitor.visit_u16(v),
+                Err(_) => Err(self.out_of_range(unexp)),
+            },
+            SimpleSchema::U32 => match u32::try_from(v) {
+                Ok(v) => self.visitor.visit_u32(v),
+                Err(_) => Err(self.out_of_range(unexp)),
+            },
+            SimpleSchema::U64 => match u64::try_from(v) {
+                Ok(v) => self.visitor.visit_u64(v),
+                Err(_) => Err(self.out_of_range(unexp)),
+            },
+            SimpleSchema::U128 => match u128::try_from(v) {
+                Ok(v) => self.visitor.visit_u128(v),
+                Err(_) => Err(self.out_of_range(unexp)),
+            },
+            SimpleSchema::I8 => match i8::try_from(v) {
+                Ok(v) => self.visitor.visit_i8(v),
+                Err(_) => Err(self.out_of_range(unexp)),
+            },
+            SimpleSchema::I16 => match i16::try_from(v) {
+                Ok(v) => self.visitor.visit_i16(v),
+                Err(_) => Err(self.out_of_range(unexp)),
+            },
+            SimpleSchema::I32 => match i32::try_from(v) {
+                Ok(v) => self.visitor.visit_i32(v),
+                Err(_) => Err(self.out_of_range(unexp)),
+            },
+            SimpleSchema::I64 => match i64::try_from(v) {
+                Ok(v) => self.visitor.visit_i64(v),
+                Err(_) => Err(self.out_of_range(unexp)),
+            },
+            SimpleSchema::I128 => self.visitor.visit_i128(v),
+            SimpleSchema::F32 => self.visitor.visit_f32(v as f32),
+            SimpleSchema::F64 => self.visitor.visit_f64(v as f64),
+            _ => Err(E::invalid_type(unexp, &self)),
+        }
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        self.visit_u128(v as u128)
+    }
+
+    fn visit_u128<E>(self, v: u128) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        match (self.schema, i128::try_from(v)) {
+            (SimpleSchema::U128, _) => self.visitor.visit_u128(v),
+            (_, Ok(v)) => self.visit_i128(v),
+            (_, Err(_)) => Err(self.out_of_range(Unexpected::Other("128-bit integer"))),
+        }
+    }
+
+    fn visit_f32<E>(self, v: f32) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        match self.schema {
+            SimpleSchema::F32 => self.visitor.visit_f32(v),
+            _ => self.visit_f64(v as f64),
+        }
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        match self.schema {
+            SimpleSchema::F32 => self.visitor.visit_f32(v as f32),
+            SimpleSchema::F64 => self.visitor.visit_f64(v),
+            _ => Err(E::invalid_type(Unexpected::Float(v), &self)),
+        }
+    }
+
+    fn visit_char<E>(self, v: char) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        match self.schema {
+            SimpleSchema::Char => self.visitor.visit_char(v),
+            _ => self.visit_str(v.encode_utf8(&mut [0; 4])),
+        }
     }
 
-    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
     where
-        A: SeqAccess<'de>,
+        E: Error,
     {
-        self.visitor.visit_seq(SchemaSeqAccess {
-            schema: self.value,
-            opts: self.opts,
-            seq,
-        })
+        self.context.string(v.len())?;
+        let mut chars = v.chars();
+        match (self.schema, chars.next(), chars.next()) {
+            (SimpleSchema::String, _, _) => self.visitor.visit_str(v),
+            (SimpleSchema::Char, Some(c), None) => self.visitor.visit_char(c),
+            _ => Err(E::invalid_type(Unexpected::Str(v), &self)),
+        }
     }
-}
-
-struct SchemaSeqAccess<'a, A> {
-    schema: &'a Schema,
-    opts: &'a DeserializerOptions,
-    seq: A,
-}
-
-impl<'de, A: SeqAccess<'de>> SeqAccess<'de> for SchemaSeqAccess<'_, A> {
-    type Error = A::Error;
 
-    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
     where
-        T: DeserializeSeed<'de>,
+        E: Error,
     {
-        self.seq.next_element_seed(SchemaSeed {
-            schema: self.schema,
-            opts: self.opts,
-            seed,
-        })
+        match self.schema {
+            SimpleSchema::String => {
+                self.context.string(v.len())?;
+                self.visitor.visit_borrowed_str(v)
+            }
+            _ => self.visit_str(v),
+        }
     }
-}
-
-struct MapVisitor<'a, V> {
-    key: &'a Schema,
-    value: &'a Schema,
-    opts: &'a DeserializerOptions,
-    visitor: V,
-}
-
-impl<'de, V: Visitor<'de>> Visitor<'de> for MapVisitor<'_, V> {
-    type Value = V::Value;
 
-    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", Kind::Seq)
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        match self.schema {
+            SimpleSchema::String => {
+                self.context.string(v.len())?;
+                self.visitor.visit_string(v)
+            }
+            _ => self.visit_str(&v),
+        }
     }
 
-    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
     where
-        A: MapAccess<'de>,
+        E: Error,
     {
-        self.visitor.visit_map(SchemaMapAccess {
-            key: self.key,
-            value: self.value,
-            opts: self.opts,
-            map,
-        })
+        self.context.string(v.len())?;
+        match self.schema {
+            SimpleSchema::Bytes => self.visitor.visit_bytes(v),
+            _ => Err(E::invalid_type(Unexpected::Bytes(v), &self)),
+        }
     }
-}
-
-struct SchemaMapAccess<'a, A> {
-    key: &'a Schema,
-    value: &'a Schema,
-    opts: &'a DeserializerOptions,
-    map: A,
-}
-
-impl<'de, A: MapAccess<'de>> MapAccess<'de> for SchemaMapAccess<'_, A> {
-    type Error = A::Error;
 
-    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E>
     where
-        K: DeserializeSeed<'de>,
+        E: Error,
     {
-        self.map.next_key_seed(SchemaSeed {
-            schema: self.key,
-            opts: self.opts,
-            seed,
-        })
+        self.context.string(v.len())?;
+        match self.schema {
+            SimpleSchema::Bytes => self.visitor.visit_borrowed_bytes(v),
+            _ => Err(E::invalid_type(Unexpected::Bytes(v), &self)),
+        }
     }
 
-    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
     where
-        V: DeserializeSeed<'de>,
+        E: Error,
     {
-        self.map.next_value_seed(SchemaSeed {
-            schema: self.value,
-            opts: self.opts,
-            seed,
-        })
+        self.context.string(v.len())?;
+        match self.schema {
+            SimpleSchema::Bytes => self.visitor.visit_byte_buf(v),
+            _ => Err(E::invalid_type(Unexpected::Bytes(&v), &self)),
+        }
     }
-}
-
-struct TupleVisitor<'a, V> {
-    values: &'a [Schema],
-    opts: &'a DeserializerOptions,
-    visitor: V,
-}
-
-impl<'de, V: Visitor<'de>> Visitor<'de> for TupleVisitor<'_, V> {
-    type Value = V::Value;
 
-    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", Kind::Tuple)
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        match self.schema {
+            SimpleSchema::Unit => self.visitor.visit_unit(),
+            _ => Err(E::invalid_type(Unexpected::Unit, &self)),
+        }
     }
 
-    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    /// Formats without a native byte string (JSON) write bytes as a sequence.
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
     where
         A: SeqAccess<'de>,
     {
-        self.visitor.visit_seq(TupleSeqAccess {
-            values: self.values.iter(),
-            opts: self.opts,
-            seq,
-        })
+        match self.schema {
+            SimpleSchema::Bytes => {
+                let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or_default().min(4096));
+                while let Some(byte) = seq.next_element()? {
+                    bytes.push(byte);
+                    if bytes.len() > self.context.opts.max_string_len {
+                        break;
+                    }
+                }
+                self.context.string(bytes.len())?;
+                self.visitor.visit_byte_buf(bytes)
+            }
+            _ => Err(A::Error::invalid_type(Unexpected::Seq, &self)),
+        }
     }
 }
 
-struct TupleSeqAccess<'a, I, A> {
-    values: I,
-    opts: &'a DeserializerOptions,
-    seq: A,
+/// The map-like content following the tag of an internally tagged variant.
+enum TaggedContent<'a, 'b> {
+    Fields(&'a NamedFieldsDecode<'b>),
+    Map(&'a SchemaDecode<'b>, &'a SchemaDecode<'b>),
 }
 
-impl<'a, 'de, I: Iterator<Item = &'a Schema>, A: SeqAccess<'de>> SeqAccess<'de>
-    for TupleSeqAccess<'a, I, A>
-{
-    type Error = A::Error;
+fn tagged_content<'a, 'b: 'a, E: Error>(
+    variant: &'a VariantDecode<'b>,
+    context: Context<'a, 'b>,
+) -> Result<TaggedContent<'a, 'b>, E> {
+    match &variant.fields {
+        FieldsDecode::Named(f) => Ok(TaggedContent::Fields(f)),
+        FieldsDecode::Tuple(t) if t.values.is_empty() => Ok(TaggedContent::Fields(&NO_FIELDS)),
+        FieldsDecode::Tuple(t) if t.values.len() == 1 => match context.lookup(&t.values[0]) {
+            SchemaDecode::Struct(StructDecode {
+                fields: FieldsDecode::Named(f),
+                ..
+            }) => Ok(TaggedContent::Fields(f)),
+            SchemaDecode::Map(m) => Ok(TaggedContent::Map(
+                context.lookup(&m.key),
+                context.lookup(&m.value),
+            )),
+            s => Err(E::custom(format!(
+                "internally tagged variant {} cannot hold {}",
+                variant.name,
+                s.expected()
+            ))),
+        },
+        FieldsDecode::Tuple(_) => Err(E::custom(format!(
+            "internally tagged variant {} cannot be a tuple variant",
+            variant.name
+        ))),
+    }
+}
 
-    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
-    where
-        T: DeserializeSeed<'de>,
-    {
-        match self.values.next() {
-            Some(schema) => self.seq.next_element_seed(SchemaSeed {
-                schema,
-                opts: self.opts,
-                seed,
-            }),
-            None => Ok(None),
+fn visit_internally_tagged<'a, 'b: 'a, 'de, V, E>(
+    schema: &'a EnumDecode<'b>,
+    tag: &'a str,
+    value: Value,
+    context: Context<'a, 'b>,
+    visitor: V,
+) -> Result<V::Value, E>
+where
+    V: Visitor<'de>,
+    E: Error,
+{
+    let mut entries = match value {
+        Value::Map(entries) => entries,
+        // Formats that write structs as arrays write the tag first.
+        Value::Seq(values) => {
+            let len = values.len();
+            return ValueDeserializer::<E>::new(Value::Seq(values)).deserialize_tuple(
+                len,
+                InternallyTaggedEnumVisitor {
+                    schema,
+                    tag,
+                    context,
+                    visitor,
+                },
+            );
         }
+        _ => {
+            return Err(E::custom(format!(
+                "expected {}",
+                Expected::Enum(schema.name)
+            )))
+        }
+    };
+    let name = match entries.remove(&Value::String(tag.to_string())) {
+        Some(Value::String(name)) => name,
+        Some(_) => return Err(E::custom(format!("invalid tag for enum {}", schema.name))),
+        None => return Err(E::missing_field(intern(tag))),
+    };
+    let variant = schema
+        .by_name(&name)
+        .map(|i| &schema.variants[i])
+        .ok_or_else(|| E::unknown_variant(&name, schema.names))?;
+    let map = MapDeserializer::new(entries.into_iter().map(|(k, v)| {
+        (
+            ValueDeserializer::<E>::new(k),
+            ValueDeserializer::<E>::new(v),
+        )
+    }));
+    match tagged_content(variant, context)? {
+        TaggedContent::Fields(fields) => visitor.visit_map(TaggedAccess {
+            tag: Some((tag, variant.name)),
+            value: None,
+            content: MapStructAccess::new(fields, context, map),
+        }),
+        TaggedContent::Map(key, value) => visitor.visit_map(TaggedAccess {
+            tag: Some((tag, variant.name)),
+            value: None,
+            content: SchemaMapAccess {
+                key,
+                value,
+                context,
+                len: 0,
+                map,
+            },
+        }),
     }
 }
 
-struct TupleStructVisitor<'a, V> {
-    fields: &'a [FieldSchema],
-    opts: &'a DeserializerOptions,
+struct InternallyTaggedEnumVisitor<'a, 'b, V> {
+    schema: &'a EnumDecode<'b>,
+    tag: &'a str,
+    context: Context<'a, 'b>,
     visitor: V,
 }
 
-impl<'de, V: Visitor<'de>> Visitor<'de> for TupleStructVisitor<'_, V> {
+impl<'de, V: Visitor<'de>> Visitor<'de> for InternallyTaggedEnumVisitor<'_, '_, V> {
     type Value = V::Value;
 
     fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", Kind::Struct)
+        write!(f, "{}", Expected::Enum(self.schema.name))
     }
 
-    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
     where
         A: SeqAccess<'de>,
     {
-        self.visitor.visit_map(TupleStructAccess {
-            fields: self.fields.iter(),
-            value: None,
-            opts: self.opts,
-            seq,
-        })
+        let name: String = seq
+            .next_element()?
+            .ok_or_else(|| A::Error::missing_field(intern(self.tag)))?;
+        let variant = self
+            .schema
+            .by_name(&name)
+            .map(|i| &self.schema.variants[i])
+            .ok_or_else(|| A::Error::unknown_variant(&name, self.schema.names))?;
+        match tagged_content(variant, self.context)? {
+            TaggedContent::Fields(fields) => self.visitor.visit_map(TaggedAccess {
+                tag: Some((self.tag, variant.name)),
+                value: None,
+                content: TupleStructAccess {
+                    fields: fields.fields.iter().enumerate(),
+                    value: None,
+                    context: self.context,
+                    seq,
+                },
+            }),
+            TaggedContent::Map(_, _) => Err(A::Error::custom(format!(
+                "internally tagged variant {} holds a map, which needs a self-describing format",
+                variant.name
+            ))),
+        }
     }
 }
 
-struct TupleStructAccess<'a, I, A> {
-    fields: I,
-    value: Option<&'a Schema>,
-    opts: &'a DeserializerOptions,
-    seq: A,
+/// Yields the `tag: variant` entry of an internally tagged enum before the
+/// variant's own entries.
+struct TaggedAccess<'a, A> {
+    tag: Option<(&'a str, &'static str)>,
+    value: Option<&'static str>,
+    content: A,
 }
 
-impl<'a, 'de, I: Iterator<Item = &'a FieldSchema>, A: SeqAccess<'de>> MapAccess<'de>
-    for TupleStructAccess<'a, I, A>
-{
+impl<'de, A: MapAccess<'de>> MapAccess<'de> for TaggedAccess<'_, A> {
     type Error = A::Error;
 
     fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
     where
         K: DeserializeSeed<'de>,
     {
-        match self.fields.next() {
-            Some(field) => {
-                self.value = Some(&field.value);
-                Ok(Some(seed.deserialize(StrDeserializer::new(&field.name))?))
+        match self.tag.take() {
+            Some((tag, name)) => {
+                self.value = Some(name);
+                Ok(Some(seed.deserialize(StrDeserializer::new(tag))?))
             }
-            None => Ok(None),
+            None => self.content.next_key_seed(seed),
         }
     }
 
@@ -1008,254 +3341,281 @@ impl<'a, 'de, I: Iterator<Item = &'a FieldSchema>, A: SeqAccess<'de>> MapAccess<
     where
         V: DeserializeSeed<'de>,
     {
-        let value = self
-            .value
-            .take()
-            .ok_or_else(|| A::Error::custom("invalid use of next_value_seed"))?;
-        self.seq.next_element_seed(SchemaSeed {
-            schema: value,
-            opts: self.opts,
-            seed,
-        })
+        match self.value.take() {
+            Some(name) => seed.deserialize(StrDeserializer::new(name)),
+            None => self.content.next_value_seed(seed),
+        }
     }
 }
 
-struct MapStructVisitor<'a, V> {
-    fields: &'a [FieldSchema],
-    opts: &'a DeserializerOptions,
+fn visit_adjacently_tagged<'a, 'b: 'a, 'de, V, E>(
+    schema: &'a EnumDecode<'b>,
+    tag: &'a str,
+    content: &'a str,
+    value: Value,
+    context: Context<'a, 'b>,
+    visitor: V,
+) -> Result<V::Value, E>
+where
+    V: Visitor<'de>,
+    E: Error,
+{
+    let mut entries = match value {
+        Value::Map(entries) => entries,
+        Value::Seq(values) => {
+            return ValueDeserializer::<E>::new(Value::Seq(values)).deserialize_tuple(
+                2,
+                AdjacentlyTaggedEnumVisitor {
+                    schema,
+                    tag,
+                    content,
+                    context,
+                    visitor,
+                },
+            );
+        }
+        _ => {
+            return Err(E::custom(format!(
+                "expected {}",
+                Expected::Enum(schema.name)
+            )))
+        }
+    };
+    let i = match entries.remove(&Value::String(tag.to_string())) {
+        Some(value) => {
+            VariantTagSeed { schema, context }.deserialize(ValueDeserializer::new(value))?
+        }
+        None => return Err(E::missing_field(intern(tag))),
+    };
+    let variant = &schema.variants[i];
+    let value = entries.remove(&Value::String(content.to_string()));
+    if value.is_none() && !variant.is_unit() {
+        return Err(E::missing_field(intern(content)));
+    }
+    visitor.visit_map(AdjacentAccess {
+        keys: [tag, content],
+        variant,
+        context,
+        content: BufferedContent(value, PhantomData),
+        state: 0,
+    })
+}
+
+struct AdjacentlyTaggedEnumVisitor<'a, 'b, V> {
+    schema: &'a EnumDecode<'b>,
+    tag: &'a str,
+    content: &'a str,
+    context: Context<'a, 'b>,
     visitor: V,
 }
 
-impl<'de, V: Visitor<'de>> Visitor<'de> for MapStructVisitor<'_, V> {
+impl<'de, V: Visitor<'de>> Visitor<'de> for AdjacentlyTaggedEnumVisitor<'_, '_, V> {
     type Value = V::Value;
 
     fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", Kind::Struct)
+        write!(f, "{}", Expected::Enum(self.schema.name))
     }
 
-    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
     where
-        A: MapAccess<'de>,
+        A: SeqAccess<'de>,
     {
-        self.visitor.visit_map(MapStructAccess {
-            fields: self.fields,
-            value: None,
-            opts: self.opts,
-            map,
+        let i = seq
+            .next_element_seed(VariantTagSeed {
+                schema: self.schema,
+                context: self.context,
+            })?
+            .ok_or_else(|| A::Error::missing_field(intern(self.tag)))?;
+        self.visitor.visit_map(AdjacentAccess {
+            keys: [self.tag, self.content],
+            variant: &self.schema.variants[i],
+            context: self.context,
+            content: SeqContent(seq),
+            state: 0,
         })
     }
 }
 
-struct MapStructAccess<'a, A> {
-    fields: &'a [FieldSchema],
-    value: Option<&'a Schema>,
-    opts: &'a DeserializerOptions,
-    map: A,
+/// Yields `tag: variant` followed by `content: ...` (unless the variant is a
+/// unit variant), in that order.
+struct AdjacentAccess<'a, 'b, C> {
+    keys: [&'a str; 2],
+    variant: &'a VariantDecode<'b>,
+    context: Context<'a, 'b>,
+    content: C,
+    state: usize,
 }
 
-impl<'de, A: MapAccess<'de>> MapAccess<'de> for MapStructAccess<'_, A> {
-    type Error = A::Error;
+impl<'de, C: ContentSource<'de>> MapAccess<'de> for AdjacentAccess<'_, '_, C> {
+    type Error = C::Error;
 
     fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
     where
         K: DeserializeSeed<'de>,
     {
-        match self.map.next_key::<String>()? {
-            Some(key) => {
-                let field = self
-                    .fields
-                    .iter()
-                    .find(|field| field.name == key)
-                    .ok_or_else(|| A::Error::custom("unknown field"))?;
-                self.value = Some(&field.value);
-                Ok(Some(seed.deserialize(StringDeserializer::new(key))?))
-            }
-            None => Ok(None),
-        }
+        let key = match self.state {
+            0 => self.keys[0],
+            2 if !self.variant.is_unit() => self.keys[1],
+            _ => return Ok(None),
+        };
+        self.state += 1;
+        Ok(Some(seed.deserialize(StrDeserializer::new(key))?))
     }
 
     fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
     where
         V: DeserializeSeed<'de>,
     {
-        let value = self
-            .value
-            .take()
-            .ok_or_else(|| A::Error::custom("invalid use of next_value_seed"))?;
-        self.map.next_value_seed(SchemaSeed {
-            schema: value,
-            opts: self.opts,
-            seed,
-        })
+        self.state += 1;
+        match self.state {
+            2 => seed.deserialize(StrDeserializer::new(self.variant.name)),
+            4 => self.content.next_content(VariantContentSeed {
+                variant: self.variant,
+                context: self.context,
+                seed,
+            }),
+            _ => Err(C::Error::custom("invalid use of next_value_seed")),
+        }
     }
 }
 
-struct TupleEnumVisitor<'a, V> {
-    variants: &'a [VariantSchema],
-    opts: &'a DeserializerOptions,
-    visitor: V,
+/// Where the content of an adjacently tagged enum is read from.
+trait ContentSource<'de> {
+    type Error: Error;
+
+    fn next_content<S>(&mut self, seed: S) -> Result<S::Value, Self::Error>
+    where
+        S: DeserializeSeed<'de>;
 }
 
-impl<'de, V: Visitor<'de>> Visitor<'de> for TupleEnumVisitor<'_, V> {
-    type Value = V::Value;
+/// Content that was buffered because the format may put it before the tag.
+struct BufferedContent<E>(Option<Value>, PhantomData<E>);
 
-    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", Kind::Enum)
-    }
+impl<'de, E: Error> ContentSource<'de> for BufferedContent<E> {
+    type Error = E;
 
-    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    fn next_content<S>(&mut self, seed: S) -> Result<S::Value, Self::Error>
     where
-        A: SeqAccess<'de>,
+        S: DeserializeSeed<'de>,
     {
-        let tag = seq
-            .next_element::<usize>()?
-            .ok_or_else(|| A::Error::custom("missing tag"))?;
-        let variant = self
-            .variants
-            .get(tag)
-            .ok_or_else(|| A::Error::custom("invalid variant"))?;
-        /* TODO: do not assume externally tagged enum */
-        match variant {
-            VariantSchema::Unit(s) => self.visitor.visit_map(TupleEnumAccess {
-                tag: Some(&s.name),
-                value: Some(todo!()),
-                opts: self.opts,
-                seq,
-            }),
-            VariantSchema::Newtype(s) => self.visitor.visit_map(TupleEnumAccess {
-                tag: Some(&s.name),
-                value: Some(&s.value),
-                opts: self.opts,
-                seq,
-            }),
-            VariantSchema::Tuple(s) => self.visitor.visit_map(TupleEnumAccess {
-                tag: Some(&s.name),
-                value: Some(todo!()),
-                opts: self.opts,
-                seq,
-            }),
-            VariantSchema::Struct(s) => self.visitor.visit_map(TupleEnumAccess {
-                tag: Some(&s.name),
-                value: Some(todo!()),
-                opts: self.opts,
-                seq,
-            }),
+        match self.0.take() {
+            Some(value) => seed.deserialize(ValueDeserializer::new(value)),
+            None => Err(E::custom("invalid use of next_value_seed")),
         }
     }
 }
 
-struct TupleEnumAccess<'a, A> {
-    tag: Option<&'a str>,
-    value: Option<&'a Schema>,
-    opts: &'a DeserializerOptions,
-    seq: A,
-}
+struct SeqContent<A>(A);
 
-impl<'de, A: SeqAccess<'de>> MapAccess<'de> for TupleEnumAccess<'_, A> {
+impl<'de, A: SeqAccess<'de>> ContentSource<'de> for SeqContent<A> {
     type Error = A::Error;
 
-    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    fn next_content<S>(&mut self, seed: S) -> Result<S::Value, Self::Error>
     where
-        K: DeserializeSeed<'de>,
+        S: DeserializeSeed<'de>,
     {
-        match self.tag.take() {
-            Some(tag) => Ok(Some(seed.deserialize(StrDeserializer::new(tag))?)),
-            None => Ok(None),
-        }
+        self.0
+            .next_element_seed(seed)?
+            .ok_or_else(|| A::Error::invalid_length(1, &"variant content"))
     }
+}
 
-    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+/// Reads a variant tag written as a unit variant, as adjacently tagged enums
+/// do.
+struct VariantTagSeed<'a, 'b> {
+    schema: &'a EnumDecode<'b>,
+    context: Context<'a, 'b>,
+}
+
+impl<'de> DeserializeSeed<'de> for VariantTagSeed<'_, '_> {
+    type Value = usize;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
     where
-        V: DeserializeSeed<'de>,
+        D: Deserializer<'de>,
     {
-        let value = self
-            .value
-            .take()
-            .ok_or_else(|| A::Error::custom("invalid use of next_value_seed"))?;
-        self.seq.next_element_seed(SchemaSeed {
-            schema: value,
-            opts: self.opts,
-            seed,
-        })
+        deserializer.deserialize_enum(self.schema.name, self.schema.names, self)
     }
 }
 
-struct ExternallyTaggedEnumVisitor<'a, V> {
-    variants: &'a [VariantSchema],
-    opts: &'a DeserializerOptions,
-    visitor: V,
-}
-
-impl<'de, V: Visitor<'de>> Visitor<'de> for ExternallyTaggedEnumVisitor<'_, V> {
-    type Value = V::Value;
+impl<'de> Visitor<'de> for VariantTagSeed<'_, '_> {
+    type Value = usize;
 
     fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", Kind::Enum)
+        write!(f, "variant tag of enum {}", self.schema.name)
     }
 
-    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
     where
-        A: MapAccess<'de>,
+        A: EnumAccess<'de>,
     {
-        let tag = map
-            .next_key::<String>()?
-            .ok_or_else(|| A::Error::custom("missing tag"))?;
-        let variant = self
-            .variants
-            .iter()
-            .find(|variant| match variant {
-                VariantSchema::Unit(s) => s.name == tag,
-                VariantSchema::Newtype(s) => s.name == tag,
-                VariantSchema::Tuple(s) => s.name == tag,
-                VariantSchema::Struct(s) => s.name == tag,
-            })
-            .ok_or_else(|| A::Error::custom("invalid variant"))?;
-        match variant {
-            VariantSchema::Unit(_) => todo!(),
-            VariantSchema::Newtype(s) => self.visitor.visit_map(ExternallyTaggedEnumAccess {
-                tag: Some(&s.name),
-                value: Some(&s.value),
-                opts: self.opts,
-                map,
-            }),
-            VariantSchema::Tuple(_) => todo!(),
-            VariantSchema::Struct(_) => todo!(),
-        }
+        let (i, variant) = data.variant_seed(VariantSeed {
+            schema: self.schema,
+            context: self.context,
+        })?;
+        variant.unit_variant()?;
+        Ok(i)
     }
 }
 
-struct ExternallyTaggedEnumAccess<'a, A> {
-    tag: Option<&'a str>,
-    value: Option<&'a Schema>,
-    opts: &'a DeserializerOptions,
-    map: A,
+/// Reads the content of a variant as a standalone value.
+struct VariantContentSeed<'a, 'b, S> {
+    variant: &'a VariantDecode<'b>,
+    context: Context<'a, 'b>,
+    seed: S,
 }
 
-impl<'de, A: MapAccess<'de>> MapAccess<'de> for ExternallyTaggedEnumAccess<'_, A> {
-    type Error = A::Error;
+impl<'de, S: DeserializeSeed<'de>> DeserializeSeed<'de> for VariantContentSeed<'_, '_, S> {
+    type Value = S::Value;
 
-    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
     where
-        K: DeserializeSeed<'de>,
+        D: Deserializer<'de>,
     {
-        match self.tag.take() {
-            Some(tag) => Ok(Some(seed.deserialize(StrDeserializer::new(tag))?)),
-            None => Ok(None),
+        match &self.variant.fields {
+            FieldsDecode::Tuple(s) if s.values.len() == 1 => {
+                self.seed.deserialize(SchemaDeserializer {
+                    schema: self.context.lookup(&s.values[0]),
+                    context: self.context.nested()?,
+                    deserializer,
+                })
+            }
+            fields => self.seed.deserialize(FieldsDeserializer {
+                name: self.variant.name,
+                fields,
+                context: self.context,
+                deserializer,
+            }),
         }
     }
+}
 
-    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+struct FieldsDeserializer<'a, 'b, D> {
+    name: &'static str,
+    fields: &'a FieldsDecode<'b>,
+    context: Context<'a, 'b>,
+    deserializer: D,
+}
+
+impl<'a, 'b: 'a, 'de, D: Deserializer<'de>> Deserializer<'de> for FieldsDeserializer<'a, 'b, D> {
+    type Error = D::Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
-        V: DeserializeSeed<'de>,
+        V: Visitor<'de>,
     {
-        let value = self
-            .value
-            .take()
-            .ok_or_else(|| A::Error::custom("invalid use of next_value_seed"))?;
-        self.map.next_value_seed(SchemaSeed {
-            schema: value,
-            opts: self.opts,
-            seed,
-        })
+        deserialize_fields(
+            self.deserializer,
+            self.name,
+            self.fields,
+            self.context,
+            visitor,
+        )
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
     }
 }
@@ -0,0 +1,338 @@
+use std::fmt::Display;
+
+use serde_value::Value;
+
+use crate::{
+    deserializer::{SchemaDecode, SchemaDecodeItems},
+    project::{key_string, unwrap_value, Position, Shape},
+    SchemaDecoder,
+};
+
+/// A difference between two values of the same described type, at a path in
+/// the syntax of [`Query`](crate::Query).
+#[derive(PartialEq, Clone, Debug)]
+pub enum ValueChange {
+    /// A value was replaced by another one of the same variant or shape.
+    Changed {
+        path: String,
+        old: Value,
+        new: Value,
+    },
+    /// An enum holds a different variant.
+    Variant {
+        path: String,
+        old: String,
+        new: String,
+        old_value: Value,
+        new_value: Value,
+    },
+    /// A map entry or sequence element was added.
+    Added { path: String, value: Value },
+    /// A map entry or sequence element was removed.
+    Removed { path: String, value: Value },
+}
+
+impl SchemaDecoder<'_> {
+    /// Compare two dynamic values decoded with this decoder (possibly from
+    /// different formats). Struct fields and tuple elements are compared in
+    /// place, map entries by key and sequence elements by aligning them on
+    /// their longest common subsequence, or by position where millions of
+    /// pairs of elements differ.
+    pub fn diff(&self, old: &Value, new: &Value) -> Vec<ValueChange> {
+        let mut changes = Vec::new();
+        diff(
+            String::new(),
+            Position::Schema(self.schema.lookup(&self.items)),
+            &self.items,
+            old,
+            new,
+            &mut changes,
+        );
+        changes
+    }
+}
+
+fn diff<'a, 'b>(
+    path: String,
+    position: Position<'a, 'b>,
+    items: &'a SchemaDecodeItems<'b>,
+    old: &Value,
+    new: &Value,
+    changes: &mut Vec<ValueChange>,
+) {
+    if old == new {
+        return;
+    }
+    let position = position.unwrap_for(items, old);
+    let (old_inner, new_inner) = (unwrap_value(old), unwrap_value(new));
+    let changed = |changes: &mut Vec<ValueChange>| {
+        changes.push(ValueChange::Changed {
+            path: path.clone(),
+            old: old.clone(),
+            new: new.clone(),
+        })
+    };
+    if [old_inner, new_inner]
+        .iter()
+        .any(|v| matches!(v, Value::Option(None)))
+    {
+        return changed(changes);
+    }
+    match (position, old_inner, new_inner) {
+        (
+            Position::Schema(SchemaDecode::Seq(_) | SchemaDecode::Set(_)),
+            Value::Seq(old),
+            Value::Seq(new),
+        ) => diff_seq(&path, position, items, old, new, changes),
+        (Position::Schema(SchemaDecode::Map(_)), Value::Map(old), Value::Map(new)) => {
+            for (key, old_value) in old {
+                let path = format!("{path}{}", key_path(key));
+                match new.get(key) {
+                    Some(new_value) => {
+                        let child = position.step(&key_string(key).unwrap_or_default(), items);
+                        match child {
+                            Ok(child) => diff(path, child, items, old_value, new_value, changes),
+                            Err(_) => changes.push(ValueChange::Changed {
+                                path,
+                                old: old_value.clone(),
+                                new: new_value.clone(),
+                            }),
+                        }
+                    }
+                    None => changes.push(ValueChange::Removed {
+                        path,
+                        value: old_value.clone(),
+                    }),
+                }
+            }
+            for (key, new_value) in new.iter().filter(|(key, _)| !old.contains_key(key)) {
+                changes.push(ValueChange::Added {
+                    path: format!("{path}{}", key_path(key)),
+                    value: new_value.clone(),
+                });
+            }
+        }
+        (_, Value::Map(_) | Value::Seq(_), Value::Map(_) | Value::Seq(_)) => {
+            let shape = position.shape();
+            let (old_entries, new_entries) = (shape.entries(old_inner), shape.entries(new_inner));
+            match shape {
+                Shape::Enum(_) => match (old_entries.first(), new_entries.first()) {
+                    (Some((old_name, old_value)), Some((new_name, new_value)))
+                        if old_name == new_name =>
+                    {
+                        match position.step(old_name, items) {
+                            Ok(child) => diff(
+                                format!("{path}::{old_name}"),
+                                child,
+                                items,
+                                old_value,
+                                new_value,
+                                changes,
+                            ),
+                            Err(_) => changed(changes),
+                        }
+                    }
+                    (Some((old_name, _)), Some((new_name, _))) => {
+                        changes.push(ValueChange::Variant {
+                            path,
+                            old: old_name.clone(),
+                            new: new_name.clone(),
+                            old_value: old.clone(),
+                            new_value: new.clone(),
+                        })
+                    }
+                    _ => changed(changes),
+                },
+                Shape::Entries if old_entries.len() == new_entries.len() => {
+                    for (name, old_value) in &old_entries {
+                        let new_value = match new_entries.iter().find(|(n, _)| n == name) {
+                            Some((_, new_value)) => new_value,
+                            None => return changed(changes),
+                        };
+                        let path = match old_inner {
+                            Value::Seq(_) => format!("{path}[{name}]"),
+                            _ => format!("{path}{}", field_path(name)),
+                        };
+                        match position.step(name, items) {
+                            Ok(child) => diff(path, child, items, old_value, new_value, changes),
+                            Err(_) => changes.push(ValueChange::Changed {
+                                path,
+                                old: old_value.clone().into_owned(),
+                                new: new_value.clone().into_owned(),
+                            }),
+                        }
+                    }
+                }
+                Shape::Entries => changed(changes),
+            }
+        }
+        // Unit variants of externally tagged enums are plain strings, and the
+        // variants of integer enums their discriminants.
+        _ if matches!(position.shape(), Shape::Enum(_)) => {
+            let shape = position.shape();
+            match (
+                shape.entries(old_inner).first(),
+                shape.entries(new_inner).first(),
+            ) {
+                (Some((old_name, _)), Some((new_name, _))) if old_name != new_name => {
+                    changes.push(ValueChange::Variant {
+                        path,
+                        old: old_name.clone(),
+                        new: new_name.clone(),
+                        old_value: old.clone(),
+                        new_value: new.clone(),
+                    })
+                }
+                _ => changed(changes),
+            }
+        }
+        _ => changed(changes),
+    }
+}
+
+/// Above this many pairs of differing elements, sequences are compared by
+/// position instead: aligning them takes time and memory for every pair.
+const MAX_ALIGNED: usize = 1 << 22;
+
+/// Aligns two sequences on their longest common subsequence. Between two
+/// aligned elements, removed and added elements are paired up as changes.
+fn diff_seq<'a, 'b>(
+    path: &str,
+    position: Position<'a, 'b>,
+    items: &'a SchemaDecodeItems<'b>,
+    old: &[Value],
+    new: &[Value],
+    changes: &mut Vec<ValueChange>,
+) {
+    let gap = |removed: &[usize], added: &[usize], changes: &mut Vec<ValueChange>| {
+        for (&i, &j) in removed.iter().zip(added) {
+            match position.step(&i.to_string(), items) {
+                Ok(child) => diff(
+                    format!("{path}[{i}]"),
+                    child,
+                    items,
+                    &old[i],
+                    &new[j],
+                    changes,
+                ),
+                Err(_) => changes.push(ValueChange::Changed {
+                    path: format!("{path}[{i}]"),
+                    old: old[i].clone(),
+                    new: new[j].clone(),
+                }),
+            }
+        }
+        for &i in removed.iter().skip(added.len()) {
+            changes.push(ValueChange::Removed {
+                path: format!("{path}[{i}]"),
+                value: old[i].clone(),
+            });
+        }
+        for &j in added.iter().skip(removed.len()) {
+            changes.push(ValueChange::Added {
+                path: format!("{path}[{j}]"),
+                value: new[j].clone(),
+            });
+        }
+    };
+
+    // Values that mostly agree share long runs at either end, which align
+    // as they are.
+    let start = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let end = old[start..]
+        .iter()
+        .rev()
+        .zip(new[start..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (n, m) = (old.len() - end, new.len() - end);
+    if (n - start).saturating_mul(m - start) > MAX_ALIGNED {
+        let removed = (start..n).collect::<Vec<_>>();
+        gap(&removed, &(start..m).collect::<Vec<_>>(), changes);
+        return;
+    }
+
+    // lcs[i][j] is the length of the LCS of old[start + i..n] and
+    // new[start + j..m].
+    let (rows, cols) = (n - start, m - start);
+    let mut lcs = vec![vec![0usize; cols + 1]; rows + 1];
+    for i in (0..rows).rev() {
+        for j in (0..cols).rev() {
+            lcs[i][j] = match old[start + i] == new[start + j] {
+                true => lcs[i + 1][j + 1] + 1,
+                false => lcs[i + 1][j].max(lcs[i][j + 1]),
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let (mut removed, mut added) = (Vec::new(), Vec::new());
+    while i < rows || j < cols {
+        if i < rows && j < cols && old[start + i] == new[start + j] {
+            gap(&removed, &added, changes);
+            removed.clear();
+            added.clear();
+            i += 1;
+            j += 1;
+        } else if j == cols || (i < rows && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            removed.push(start + i);
+            i += 1;
+        } else {
+            added.push(start + j);
+            j += 1;
+        }
+    }
+    gap(&removed, &added, changes);
+}
+
+pub(crate) fn field_path(name: &str) -> String {
+    match !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        true => format!(".{name}"),
+        false => format!(".{}", quote(name)),
+    }
+}
+
+pub(crate) fn key_path(key: &Value) -> String {
+    match key {
+        Value::String(s) => format!("[{}]", quote(s)),
+        key => match key_string(key) {
+            Some(key) if key.parse::<i64>().is_ok() => format!("[{key}]"),
+            Some(key) => format!("[{}]", quote(&key)),
+            None => format!("[{key:?}]"),
+        },
+    }
+}
+
+pub(crate) fn quote(s: &str) -> String {
+    let escaped = s
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t");
+    format!("\"{escaped}\"")
+}
+
+pub(crate) fn show(value: &Value) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| format!("{value:?}"))
+}
+
+impl Display for ValueChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let root = |path: &String| match path.is_empty() {
+            true => ".".to_string(),
+            false => path.clone(),
+        };
+        match self {
+            ValueChange::Changed { path, old, new } => {
+                write!(f, "~ {}: {} -> {}", root(path), show(old), show(new))
+            }
+            ValueChange::Variant { path, old, new, .. } => {
+                write!(f, "~ {}: variant {old} -> {new}", root(path))
+            }
+            ValueChange::Added { path, value } => write!(f, "+ {}: {}", root(path), show(value)),
+            ValueChange::Removed { path, value } => {
+                write!(f, "- {}: {}", root(path), show(value))
+            }
+        }
+    }
+}
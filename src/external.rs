@@ -0,0 +1,478 @@
+// Describe impls for types from other crates, each behind the cargo feature
+// named after the crate. They follow the encoding of that crate's own serde
+// impls, with its `serde` feature and no other serde-related features.
+
+#[cfg(feature = "arrayvec")]
+mod arrayvec {
+    use ::arrayvec::{ArrayString, ArrayVec};
+
+    use crate::{Describe, Description, SchemaItem, SchemaName, SeqSchema, SimpleSchema};
+
+    impl<T: Describe, const CAP: usize> Describe for ArrayVec<T, CAP> {
+        fn schema_name() -> SchemaName {
+            SchemaName::new("arrayvec::ArrayVec")
+                .argument(T::schema_name())
+                .argument(SchemaName::new(CAP.to_string()))
+        }
+
+        fn schema() -> SchemaItem {
+            SeqSchema::new(T::schema()).into()
+        }
+
+        fn add_schema(map: &mut Description) {
+            T::add_schema(map);
+        }
+    }
+
+    impl<const CAP: usize> Describe for ArrayString<CAP> {
+        fn schema_name() -> SchemaName {
+            SchemaName::new("arrayvec::ArrayString").argument(SchemaName::new(CAP.to_string()))
+        }
+
+        fn schema() -> SchemaItem {
+            SimpleSchema::String.into()
+        }
+    }
+}
+
+#[cfg(feature = "bytes")]
+mod bytes {
+    use ::bytes::{Bytes, BytesMut};
+
+    use crate::{Describe, SchemaItem, SchemaName, SimpleSchema};
+
+    impl Describe for Bytes {
+        fn schema_name() -> SchemaName {
+            SchemaName::new("bytes::Bytes")
+        }
+
+        fn schema() -> SchemaItem {
+            SimpleSchema::Bytes.into()
+        }
+    }
+
+    impl Describe for BytesMut {
+        fn schema_name() -> SchemaName {
+            SchemaName::new("bytes::BytesMut")
+        }
+
+        fn schema() -> SchemaItem {
+            SimpleSchema::Bytes.into()
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+mod chrono {
+    use ::chrono::{DateTime, Month, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Weekday};
+
+    use crate::{Describe, SchemaItem, SchemaName, SimpleSchema};
+
+    // Every chrono type is a string, in compact formats as well.
+    macro_rules! describe_string {
+        ($($t:ty => $name:literal),+ $(,)?) => {$(
+            impl Describe for $t {
+                fn schema_name() -> SchemaName {
+                    SchemaName::new($name)
+                }
+
+                fn schema() -> SchemaItem {
+                    SimpleSchema::String.into()
+                }
+            }
+        )+};
+    }
+
+    describe_string!(
+        NaiveDate => "chrono::NaiveDate",
+        NaiveTime => "chrono::NaiveTime",
+        NaiveDateTime => "chrono::NaiveDateTime",
+        Weekday => "chrono::Weekday",
+        Month => "chrono::Month",
+    );
+
+    impl<Tz: TimeZone> Describe for DateTime<Tz> {
+        fn schema_name() -> SchemaName {
+            SchemaName::new("chrono::DateTime")
+        }
+
+        fn schema() -> SchemaItem {
+            SimpleSchema::String.into()
+        }
+    }
+}
+
+#[cfg(feature = "either")]
+mod either {
+    use ::either::Either;
+
+    use crate::{Describe, Description, EnumSchema, SchemaName, TupleSchema, VariantSchema};
+
+    impl<L: Describe, R: Describe> Describe for Either<L, R> {
+        fn schema_name() -> SchemaName {
+            SchemaName::new("either::Either")
+                .argument(L::schema_name())
+                .argument(R::schema_name())
+        }
+
+        fn add_schema(map: &mut Description) {
+            if map.add(Self::schema_name(), || {
+                EnumSchema::new("Either")
+                    .variant(VariantSchema::new(
+                        "Left",
+                        TupleSchema::new().element(L::schema()),
+                    ))
+                    .variant(VariantSchema::new(
+                        "Right",
+                        TupleSchema::new().element(R::schema()),
+                    ))
+            }) {
+                L::add_schema(map);
+                R::add_schema(map);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "hashbrown")]
+mod hashbrown {
+    use ::hashbrown::{HashMap, HashSet};
+
+    use crate::{Describe, Description, MapSchema, SchemaItem, SchemaName, SetSchema};
+
+    impl<K: Describe, V: Describe, S> Describe for HashMap<K, V, S> {
+        fn schema_name() -> SchemaName {
+            SchemaName::new("hashbrown::HashMap")
+                .argument(K::schema_name())
+                .argument(V::schema_name())
+        }
+
+        fn schema() -> SchemaItem {
+            MapSchema::new(K::schema(), V::schema()).into()
+        }
+
+        fn add_schema(map: &mut Description) {
+            K::add_schema(map);
+            V::add_schema(map);
+        }
+    }
+
+    impl<T: Describe, S> Describe for HashSet<T, S> {
+        fn schema_name() -> SchemaName {
+            SchemaName::new("hashbrown::HashSet").argument(T::schema_name())
+        }
+
+        fn schema() -> SchemaItem {
+            SetSchema::new(T::schema()).into()
+        }
+
+        fn add_schema(map: &mut Description) {
+            T::add_schema(map);
+        }
+    }
+}
+
+#[cfg(feature = "indexmap")]
+mod indexmap {
+    use ::indexmap::{IndexMap, IndexSet};
+
+    use crate::{Describe, Description, MapSchema, SchemaItem, SchemaName, SetSchema};
+
+    impl<K: Describe, V: Describe, S> Describe for IndexMap<K, V, S> {
+        fn schema_name() -> SchemaName {
+            SchemaName::new("indexmap::IndexMap")
+                .argument(K::schema_name())
+                .argument(V::schema_name())
+        }
+
+        fn schema() -> SchemaItem {
+            MapSchema::new(K::schema(), V::schema()).into()
+        }
+
+        fn add_schema(map: &mut Description) {
+            K::add_schema(map);
+            V::add_schema(map);
+        }
+    }
+
+    impl<T: Describe, S> Describe for IndexSet<T, S> {
+        fn schema_name() -> SchemaName {
+            SchemaName::new("indexmap::IndexSet").argument(T::schema_name())
+        }
+
+        fn schema() -> SchemaItem {
+            SetSchema::new(T::schema()).into()
+        }
+
+        fn add_schema(map: &mut Description) {
+            T::add_schema(map);
+        }
+    }
+}
+
+#[cfg(feature = "ipnet")]
+mod ipnet {
+    use ::ipnet::{IpNet, Ipv4Net, Ipv6Net};
+
+    use crate::{
+        describe::readable_string, Describe, Description, EnumSchema, SchemaItem, SchemaName,
+        TupleSchema, VariantSchema,
+    };
+
+    // Compact encodings are the address octets followed by the prefix length.
+
+    impl Describe for Ipv4Net {
+        fn schema_name() -> SchemaName {
+            SchemaName::new("ipnet::Ipv4Net")
+        }
+
+        fn schema() -> SchemaItem {
+            readable_string(<[u8; 5]>::schema())
+        }
+    }
+
+    impl Describe for Ipv6Net {
+        fn schema_name() -> SchemaName {
+            SchemaName::new("ipnet::Ipv6Net")
+        }
+
+        fn schema() -> SchemaItem {
+            readable_string(<[u8; 17]>::schema())
+        }
+    }
+
+    impl Describe for IpNet {
+        fn schema_name() -> SchemaName {
+            SchemaName::new("ipnet::IpNet")
+        }
+
+        fn schema() -> SchemaItem {
+            readable_string(Self::schema_name().into())
+        }
+
+        fn add_schema(map: &mut Description) {
+            map.add(Self::schema_name(), || {
+                EnumSchema::new("IpNet")
+                    .variant(VariantSchema::new(
+                        "V4",
+                        TupleSchema::new().element(Ipv4Net::schema()),
+                    ))
+                    .variant(VariantSchema::new(
+                        "V6",
+                        TupleSchema::new().element(Ipv6Net::schema()),
+                    ))
+            });
+        }
+    }
+}
+
+#[cfg(feature = "ordered-float")]
+mod ordered_float {
+    use ::ordered_float::{NotNan, OrderedFloat};
+
+    use crate::{Describe, Description, SchemaItem, SchemaName};
+
+    impl<T: Describe> Describe for OrderedFloat<T> {
+        fn schema_name() -> SchemaName {
+            T::schema_name()
+        }
+
+        fn schema() -> SchemaItem {
+            T::schema()
+        }
+
+        fn add_schema(map: &mut Description) {
+            T::add_schema(map)
+        }
+    }
+
+    impl<T: Describe> Describe for NotNan<T> {
+        fn schema_name() -> SchemaName {
+            T::schema_name()
+        }
+
+        fn schema() -> SchemaItem {
+            T::schema()
+        }
+
+        fn add_schema(map: &mut Description) {
+            T::add_schema(map)
+        }
+    }
+}
+
+#[cfg(feature = "rust_decimal")]
+mod rust_decimal {
+    use ::rust_decimal::Decimal;
+
+    use crate::{Describe, SchemaItem, SchemaName, SimpleSchema};
+
+    impl Describe for Decimal {
+        fn schema_name() -> SchemaName {
+            SchemaName::new("rust_decimal::Decimal")
+        }
+
+        fn schema() -> SchemaItem {
+            SimpleSchema::String.into()
+        }
+    }
+}
+
+#[cfg(feature = "semver")]
+mod semver {
+    use ::semver::{Comparator, Version, VersionReq};
+
+    use crate::{Describe, SchemaItem, SchemaName, SimpleSchema};
+
+    macro_rules! describe_string {
+        ($($t:ty => $name:literal),+ $(,)?) => {$(
+            impl Describe for $t {
+                fn schema_name() -> SchemaName {
+                    SchemaName::new($name)
+                }
+
+                fn schema() -> SchemaItem {
+                    SimpleSchema::String.into()
+                }
+            }
+        )+};
+    }
+
+    describe_string!(
+        Version => "semver::Version",
+        VersionReq => "semver::VersionReq",
+        Comparator => "semver::Comparator",
+    );
+}
+
+#[cfg(feature = "serde_bytes")]
+mod serde_bytes {
+    use ::serde_bytes::{ByteArray, ByteBuf, Bytes};
+
+    use crate::{Describe, SchemaItem, SchemaName, SimpleSchema};
+
+    impl Describe for ByteBuf {
+        fn schema_name() -> SchemaName {
+            SchemaName::new("serde_bytes::ByteBuf")
+        }
+
+        fn schema() -> SchemaItem {
+            SimpleSchema::Bytes.into()
+        }
+    }
+
+    impl Describe for Bytes {
+        fn schema_name() -> SchemaName {
+            SchemaName::new("serde_bytes::Bytes")
+        }
+
+        fn schema() -> SchemaItem {
+            SimpleSchema::Bytes.into()
+        }
+    }
+
+    impl<const N: usize> Describe for ByteArray<N> {
+        fn schema_name() -> SchemaName {
+            SchemaName::new("serde_bytes::ByteArray").argument(SchemaName::new(N.to_string()))
+        }
+
+        fn schema() -> SchemaItem {
+            SimpleSchema::Bytes.into()
+        }
+    }
+}
+
+#[cfg(feature = "smallvec")]
+mod smallvec {
+    use ::smallvec::{Array, SmallVec};
+
+    use crate::{Describe, Description, SchemaItem, SchemaName, SeqSchema};
+
+    impl<A: Array> Describe for SmallVec<A>
+    where
+        A::Item: Describe,
+    {
+        fn schema_name() -> SchemaName {
+            SchemaName::new("smallvec::SmallVec").argument(A::Item::schema_name())
+        }
+
+        fn schema() -> SchemaItem {
+            SeqSchema::new(A::Item::schema()).into()
+        }
+
+        fn add_schema(map: &mut Description) {
+            A::Item::add_schema(map);
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+mod time {
+    use ::time::{
+        Date, Duration, Month, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset, Weekday,
+    };
+
+    use crate::{describe::readable_string, Describe, SchemaItem, SchemaName};
+
+    // Our `time` feature enables `time/serde-human-readable`, so these are
+    // strings in human-readable formats. Compact encodings are tuples of the
+    // date and time components.
+    macro_rules! describe_time {
+        ($($t:ty => $name:literal: $compact:ty),+ $(,)?) => {$(
+            impl Describe for $t {
+                fn schema_name() -> SchemaName {
+                    SchemaName::new($name)
+                }
+
+                fn schema() -> SchemaItem {
+                    readable_string(<$compact>::schema())
+                }
+            }
+        )+};
+    }
+
+    describe_time!(
+        Date => "time::Date": (i32, u16),
+        Time => "time::Time": (u8, u8, u8, u32),
+        PrimitiveDateTime => "time::PrimitiveDateTime": (i32, u16, u8, u8, u8, u32),
+        OffsetDateTime => "time::OffsetDateTime": (i32, u16, u8, u8, u8, u32, i8, i8, i8),
+        UtcOffset => "time::UtcOffset": (i8, i8, i8),
+        Duration => "time::Duration": (i64, i32),
+        Weekday => "time::Weekday": u8,
+        Month => "time::Month": u8,
+    );
+}
+
+#[cfg(feature = "url")]
+mod url {
+    use ::url::Url;
+
+    use crate::{Describe, SchemaItem, SchemaName, SimpleSchema};
+
+    impl Describe for Url {
+        fn schema_name() -> SchemaName {
+            SchemaName::new("url::Url")
+        }
+
+        fn schema() -> SchemaItem {
+            SimpleSchema::String.into()
+        }
+    }
+}
+
+#[cfg(feature = "uuid")]
+mod uuid {
+    use ::uuid::Uuid;
+
+    use crate::{describe::readable_string, Describe, SchemaItem, SchemaName, SimpleSchema};
+
+    impl Describe for Uuid {
+        fn schema_name() -> SchemaName {
+            SchemaName::new("uuid::Uuid")
+        }
+
+        fn schema() -> SchemaItem {
+            readable_string(SimpleSchema::Bytes.into())
+        }
+    }
+}
@@ -0,0 +1,231 @@
+//! Random descriptions for fuzzing, which exercise every kind of schema and
+//! attribute, including descriptions that no Rust type has: references to
+//! missing items, cycles of transparent schemas, duplicate names and
+//! constraints with invalid patterns.
+
+use std::collections::BTreeMap;
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+use serde_value::Value;
+
+use crate::{
+    ArraySchema, ConstrainedSchema, Constraints, Description, EnumRepr, EnumSchema, FieldsSchema,
+    HumanReadableSchema, MapSchema, NamedFieldSchema, NamedFieldsSchema, NewtypeSchema,
+    OptionSchema, RenameAll, Schema, SchemaItem, SchemaName, SeqSchema, SetSchema, SimpleSchema,
+    StructSchema, TupleSchema, UnitStructSchema, VariantSchema,
+};
+
+/// Items are named from a small set, so that references often find them.
+const ITEMS: &[&str] = &["A", "B", "C", "D"];
+
+/// Field, variant and tag names, a few of them equal after renaming.
+const NAMES: &[&str] = &["a", "b", "c", "type", "t", "c2", "field_name", "FieldName"];
+
+const PATTERNS: &[&str] = &["^[a-z]+$", "[0-9]", "^.{0,3}$", "(", ""];
+
+const SIMPLE: &[SimpleSchema] = &[
+    SimpleSchema::Unit,
+    SimpleSchema::Bool,
+    SimpleSchema::U8,
+    SimpleSchema::U16,
+    SimpleSchema::U32,
+    SimpleSchema::U64,
+    SimpleSchema::U128,
+    SimpleSchema::I8,
+    SimpleSchema::I16,
+    SimpleSchema::I32,
+    SimpleSchema::I64,
+    SimpleSchema::I128,
+    SimpleSchema::F32,
+    SimpleSchema::F64,
+    SimpleSchema::Char,
+    SimpleSchema::String,
+    SimpleSchema::Bytes,
+];
+
+const RENAME_ALL: &[RenameAll] = &[
+    RenameAll::Lower,
+    RenameAll::Upper,
+    RenameAll::Pascal,
+    RenameAll::Camel,
+    RenameAll::Snake,
+    RenameAll::ScreamingSnake,
+    RenameAll::Kebab,
+    RenameAll::ScreamingKebab,
+];
+
+/// How deep inline schemas nest; references can still recurse.
+const MAX_DEPTH: usize = 4;
+
+impl<'a> Arbitrary<'a> for Description {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut description = Description::new(item(u, 0)?);
+        for _ in 0..u.int_in_range(0..=ITEMS.len())? {
+            let name = SchemaName::new(*u.choose(ITEMS)?);
+            description.items.insert(name, schema(u, 0)?);
+        }
+        Ok(description)
+    }
+}
+
+fn item(u: &mut Unstructured, depth: usize) -> Result<SchemaItem> {
+    match u.ratio(1, 3)? {
+        true => Ok(SchemaItem::Named(SchemaName::new(*u.choose(ITEMS)?))),
+        false => Ok(SchemaItem::Schema(Box::new(schema(u, depth + 1)?))),
+    }
+}
+
+fn name(u: &mut Unstructured) -> Result<String> {
+    Ok(u.choose(NAMES)?.to_string())
+}
+
+fn schema(u: &mut Unstructured, depth: usize) -> Result<Schema> {
+    if depth >= MAX_DEPTH {
+        return Ok(Schema::Simple(*u.choose(SIMPLE)?));
+    }
+    Ok(match u.int_in_range(0..=14)? {
+        0 => OptionSchema::new(item(u, depth)?).into(),
+        1 => {
+            let mut tuple = TupleSchema::new();
+            for _ in 0..u.int_in_range(0..=3)? {
+                tuple = tuple.element(item(u, depth)?);
+            }
+            tuple.into()
+        }
+        2 => SeqSchema::new(item(u, depth)?).into(),
+        3 => SetSchema::new(item(u, depth)?).into(),
+        4 => ArraySchema::new(item(u, depth)?, u.int_in_range(0..=4)?).into(),
+        5 => MapSchema::new(item(u, depth)?, item(u, depth)?).into(),
+        6 => {
+            let mut s = StructSchema::new(name(u)?, fields(u, depth)?);
+            if u.ratio(1, 4)? {
+                s.rename = Some(name(u)?);
+            }
+            s.rename_all = match u.ratio(1, 4)? {
+                true => Some(*u.choose(RENAME_ALL)?),
+                false => None,
+            };
+            s.into()
+        }
+        7 => enum_schema(u, depth)?.into(),
+        8 => {
+            let newtype = NewtypeSchema::new(name(u)?, item(u, depth)?);
+            match bool::arbitrary(u)? {
+                true => newtype.transparent().into(),
+                false => newtype.into(),
+            }
+        }
+        9 => UnitStructSchema::new(name(u)?).into(),
+        10 => HumanReadableSchema::new(item(u, depth)?, item(u, depth)?).into(),
+        11 => ConstrainedSchema::new(item(u, depth)?, constraints(u)?).into(),
+        12 => Schema::Any,
+        _ => Schema::Simple(*u.choose(SIMPLE)?),
+    })
+}
+
+fn fields(u: &mut Unstructured, depth: usize) -> Result<FieldsSchema> {
+    if bool::arbitrary(u)? {
+        let mut tuple = TupleSchema::new();
+        for _ in 0..u.int_in_range(0..=3)? {
+            tuple = tuple.element(item(u, depth)?);
+        }
+        return Ok(tuple.into());
+    }
+    let mut fields = NamedFieldsSchema::new();
+    for _ in 0..u.int_in_range(0..=4)? {
+        let mut field = NamedFieldSchema::new(name(u)?, item(u, depth)?);
+        if u.ratio(1, 4)? {
+            field.rename = Some(name(u)?);
+        }
+        if u.ratio(1, 4)? {
+            field.aliases.push(name(u)?);
+        }
+        if u.ratio(1, 4)? {
+            field.default = Some(value(u)?);
+        }
+        field.flatten = u.ratio(1, 5)?;
+        field.skip_serializing = u.ratio(1, 8)?;
+        field.skip_deserializing = u.ratio(1, 8)?;
+        if u.ratio(1, 8)? {
+            field.skip_serializing_if = Some("Option::is_none".to_string());
+        }
+        fields = fields.field(field);
+    }
+    Ok(fields.into())
+}
+
+fn enum_schema(u: &mut Unstructured, depth: usize) -> Result<EnumSchema> {
+    let mut s = EnumSchema::new(name(u)?);
+    let repr = match u.int_in_range(0..=4)? {
+        0 => EnumRepr::InternallyTagged { tag: name(u)? },
+        1 => EnumRepr::AdjacentlyTagged {
+            tag: name(u)?,
+            content: name(u)?,
+        },
+        2 => EnumRepr::Integer {
+            repr: *u.choose(SIMPLE)?,
+        },
+        _ => EnumRepr::ExternallyTagged,
+    };
+    for _ in 0..u.int_in_range(0..=4)? {
+        let fields = match repr {
+            EnumRepr::Integer { .. } => FieldsSchema::Tuple(TupleSchema::new()),
+            _ => fields(u, depth)?,
+        };
+        let mut variant = VariantSchema::new(name(u)?, fields);
+        if u.ratio(1, 4)? {
+            variant.rename = Some(name(u)?);
+        }
+        if u.ratio(1, 4)? {
+            variant.aliases.push(name(u)?);
+        }
+        if u.ratio(1, 4)? {
+            variant.id = Some(u.int_in_range(0..=8)?);
+        }
+        if u.ratio(1, 4)? {
+            variant.discriminant = Some(i64::arbitrary(u)?);
+        }
+        variant.other = u.ratio(1, 8)?;
+        s = s.variant(variant);
+    }
+    if u.ratio(1, 4)? {
+        s.rename_all = Some(*u.choose(RENAME_ALL)?);
+    }
+    Ok(s.repr(repr))
+}
+
+fn constraints(u: &mut Unstructured) -> Result<Constraints> {
+    let mut constraints = Constraints::new();
+    if bool::arbitrary(u)? {
+        constraints = constraints.min(i64::arbitrary(u)?);
+    }
+    if bool::arbitrary(u)? {
+        constraints = constraints.max(u64::arbitrary(u)?);
+    }
+    if bool::arbitrary(u)? {
+        constraints = constraints.min_length(u.int_in_range(0..=8)?);
+    }
+    if bool::arbitrary(u)? {
+        constraints = constraints.max_length(u.int_in_range(0..=8)?);
+    }
+    if bool::arbitrary(u)? {
+        constraints = constraints.pattern(*u.choose(PATTERNS)?);
+    }
+    if bool::arbitrary(u)? {
+        constraints = constraints.key_pattern(*u.choose(PATTERNS)?);
+    }
+    constraints.unique = bool::arbitrary(u)?;
+    Ok(constraints)
+}
+
+/// A default, which need not match the type of its field.
+fn value(u: &mut Unstructured) -> Result<Value> {
+    Ok(match u.int_in_range(0..=5)? {
+        0 => Value::Unit,
+        1 => Value::Bool(bool::arbitrary(u)?),
+        2 => Value::U8(u8::arbitrary(u)?),
+        3 => Value::I64(i64::arbitrary(u)?),
+        4 => Value::String(name(u)?),
+        _ => Value::Map(BTreeMap::from([(Value::String(name(u)?), Value::Unit)])),
+    })
+}
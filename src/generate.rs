@@ -0,0 +1,558 @@
+use std::collections::BTreeMap;
+
+use regex::Regex;
+use regex_syntax::hir::{Class, Hir, HirKind};
+use serde_value::Value;
+
+use crate::{
+    deserializer::{FieldsDecode, SchemaDecode, SchemaDecodeItem, SchemaDecodeItems},
+    Constraints, Description, EnumRepr, SchemaDecoder, SimpleSchema,
+};
+
+/// How far past `max_depth` a value may nest while the generator closes it
+/// off, before it gives up on a type that can't be built smaller.
+const DEPTH_SLACK: usize = 32;
+
+/// Characters for strings without a pattern, a few of them outside ASCII.
+const CHARS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789 _-.éλ🦀";
+
+/// Makes random dynamic values of a described type, in the shape the
+/// decoder produces, e.g. to drive property tests and load tests of
+/// consumers that only know the schema. Values respect the constraints of
+/// the schema; [`SchemaDecoder::serializable`] writes them to any format.
+///
+/// The same seed always gives the same values. Past `max_depth`, options
+/// are `None`, collections are as short as their constraints allow and enums
+/// prefer variants with fewer fields, so that recursive types terminate.
+/// Human-readable types are generated in their compact form. Types with
+/// invariants that the schema doesn't express, like the nanoseconds of a
+/// `Duration` or a `NonZeroU32`, may reject generated values.
+pub struct Generator<'a> {
+    #[cfg_attr(not(feature = "proptest"), allow(dead_code))]
+    descr: &'a Description,
+    decoder: SchemaDecoder<'a>,
+    rng: Rng,
+    max_depth: usize,
+    max_len: usize,
+}
+
+impl<'a> Generator<'a> {
+    pub fn new(descr: &'a Description) -> Self {
+        Self {
+            descr,
+            decoder: SchemaDecoder::new(descr),
+            rng: Rng(0),
+            max_depth: 8,
+            max_len: 8,
+        }
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.rng = Rng(seed);
+        self
+    }
+
+    /// The nesting depth after which values are closed off.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// The largest number of elements, entries or characters in a
+    /// collection or string, unless constraints ask for more.
+    pub fn max_len(mut self, len: usize) -> Self {
+        self.max_len = len;
+        self
+    }
+
+    /// The decoder of the description, e.g. to serialize generated values.
+    pub fn decoder(&self) -> &SchemaDecoder<'a> {
+        &self.decoder
+    }
+
+    pub fn generate(&mut self) -> Result<Value, String> {
+        let mut state = State {
+            items: &self.decoder.items,
+            rng: &mut self.rng,
+            max_depth: self.max_depth,
+            max_len: self.max_len,
+        };
+        state.item(&self.decoder.schema, 0, &Constraints::default())
+    }
+
+    /// A strategy for values of the description. The values don't shrink.
+    #[cfg(feature = "proptest")]
+    pub fn strategy(&self) -> proptest::strategy::BoxedStrategy<Value> {
+        use proptest::prelude::*;
+
+        let descr = std::sync::Arc::new(self.descr.clone());
+        let (max_depth, max_len) = (self.max_depth, self.max_len);
+        any::<u64>()
+            .prop_filter_map("the type has no value within the limits", move |seed| {
+                Generator::new(&descr)
+                    .seed(seed)
+                    .max_depth(max_depth)
+                    .max_len(max_len)
+                    .generate()
+                    .ok()
+            })
+            .boxed()
+    }
+
+    /// A value seeded from fuzzer input.
+    #[cfg(feature = "arbitrary")]
+    pub fn arbitrary(&mut self, u: &mut arbitrary::Unstructured) -> arbitrary::Result<Value> {
+        self.rng = Rng(u.arbitrary()?);
+        self.generate()
+            .map_err(|_| arbitrary::Error::IncorrectFormat)
+    }
+}
+
+struct State<'a, 'b> {
+    items: &'a SchemaDecodeItems<'b>,
+    rng: &'a mut Rng,
+    max_depth: usize,
+    max_len: usize,
+}
+
+impl<'a, 'b> State<'a, 'b> {
+    fn item(
+        &mut self,
+        item: &'a SchemaDecodeItem<'b>,
+        depth: usize,
+        constraints: &Constraints,
+    ) -> Result<Value, String> {
+        self.value(item.lookup(self.items), depth, constraints)
+    }
+
+    fn value(
+        &mut self,
+        schema: &'a SchemaDecode<'b>,
+        depth: usize,
+        constraints: &Constraints,
+    ) -> Result<Value, String> {
+        if depth > self.max_depth + DEPTH_SLACK {
+            return Err(format!(
+                "cannot generate a value of {} within {} levels",
+                schema.expected(),
+                self.max_depth
+            ));
+        }
+        let closing = depth >= self.max_depth;
+        let none = Constraints::default();
+        Ok(match schema {
+            SchemaDecode::Simple(s) => self.simple(*s, constraints)?,
+            SchemaDecode::Option(s) => match closing || self.rng.chance(2) {
+                true => Value::Option(None),
+                false => Value::Option(Some(Box::new(self.item(
+                    &s.value,
+                    depth + 1,
+                    constraints,
+                )?))),
+            },
+            SchemaDecode::Tuple(s) => Value::Seq(
+                s.values
+                    .iter()
+                    .map(|v| self.item(v, depth + 1, &none))
+                    .collect::<Result<_, _>>()?,
+            ),
+            SchemaDecode::Seq(s) => {
+                Value::Seq(self.elements(&s.value, depth, constraints, constraints.unique)?)
+            }
+            SchemaDecode::Set(s) => {
+                Value::Seq(self.elements(&s.value, depth, constraints, true)?)
+            }
+            SchemaDecode::Array(s) => Value::Seq(
+                (0..s.len)
+                    .map(|_| self.item(&s.value, depth + 1, &none))
+                    .collect::<Result<_, _>>()?,
+            ),
+            SchemaDecode::Map(s) => {
+                let keys = match &constraints.key_pattern {
+                    Some(pattern) => Constraints::new().pattern(pattern.clone()),
+                    None => Constraints::default(),
+                };
+                let (min, len) = self.len(constraints, closing);
+                let mut entries = BTreeMap::new();
+                for _ in 0..len * 4 + 16 {
+                    if entries.len() >= len {
+                        break;
+                    }
+                    let key = self.item(&s.key, depth + 1, &keys)?;
+                    let value = self.item(&s.value, depth + 1, &none)?;
+                    entries.insert(key, value);
+                }
+                if entries.len() < min {
+                    return Err(format!("cannot generate {min} distinct keys"));
+                }
+                Value::Map(entries)
+            }
+            SchemaDecode::Struct(s) => self.fields(&s.fields, depth)?,
+            SchemaDecode::Enum(s) => {
+                let index = match closing {
+                    // The variants with the fewest fields.
+                    true => {
+                        let sizes = s.variants.iter().map(|v| fields_len(&v.fields));
+                        let fewest = sizes.clone().min().unwrap_or(0);
+                        let candidates: Vec<_> = sizes
+                            .enumerate()
+                            .filter(|&(_, size)| size == fewest)
+                            .map(|(i, _)| i)
+                            .collect();
+                        match candidates.is_empty() {
+                            true => return Err(format!("enum {} has no variants", s.name)),
+                            false => candidates[self.rng.below(candidates.len() as u128) as usize],
+                        }
+                    }
+                    false if s.variants.is_empty() => {
+                        return Err(format!("enum {} has no variants", s.name))
+                    }
+                    false => self.rng.below(s.variants.len() as u128) as usize,
+                };
+                let variant = &s.variants[index];
+                let name = Value::String(variant.name.to_string());
+                let unit = fields_len(&variant.fields) == 0;
+                let content = match &variant.fields {
+                    FieldsDecode::Tuple(t) if t.values.len() == 1 => {
+                        self.item(&t.values[0], depth + 1, &none)?
+                    }
+                    fields => self.fields(fields, depth)?,
+                };
+                match s.repr {
+                    EnumRepr::ExternallyTagged if unit => name,
+                    EnumRepr::ExternallyTagged => Value::Map(BTreeMap::from([(name, content)])),
+                    EnumRepr::InternallyTagged { tag } => {
+                        let mut entries = match content {
+                            Value::Map(entries) => entries,
+                            _ => BTreeMap::new(),
+                        };
+                        entries.insert(Value::String(tag.clone()), name);
+                        Value::Map(entries)
+                    }
+                    EnumRepr::AdjacentlyTagged { tag, content: key } => {
+                        let mut entries = BTreeMap::from([(Value::String(tag.clone()), name)]);
+                        if !unit {
+                            entries.insert(Value::String(key.clone()), content);
+                        }
+                        Value::Map(entries)
+                    }
+                    EnumRepr::Integer { repr } => int_value(*repr, variant.discriminant.into()),
+                }
+            }
+            SchemaDecode::Newtype(s) => {
+                let value = self.item(&s.value, depth + 1, constraints)?;
+                match s.transparent {
+                    true => value,
+                    false => Value::Newtype(Box::new(value)),
+                }
+            }
+            SchemaDecode::UnitStruct(_) => Value::Unit,
+            SchemaDecode::HumanReadable(s) => self.item(&s.compact, depth + 1, constraints)?,
+            SchemaDecode::Constrained(s) => {
+                let merged = merge(constraints, s.constraints);
+                self.item(&s.value, depth + 1, &merged)?
+            }
+            SchemaDecode::Any => match self.rng.below(3) {
+                0 => Value::Bool(self.rng.chance(2)),
+                1 => Value::I64(self.rng.range(-1000, 1000) as i64),
+                _ => Value::String(self.string(&none)?),
+            },
+            SchemaDecode::Invalid(reason) => return Err(format!("cannot generate {reason}")),
+        })
+    }
+
+    /// Struct fields as a map keyed by name, tuple fields as a sequence.
+    fn fields(&mut self, fields: &'a FieldsDecode<'b>, depth: usize) -> Result<Value, String> {
+        let none = Constraints::default();
+        match fields {
+            FieldsDecode::Tuple(t) => Ok(Value::Seq(
+                t.values
+                    .iter()
+                    .map(|v| self.item(v, depth + 1, &none))
+                    .collect::<Result<_, _>>()?,
+            )),
+            FieldsDecode::Named(fields) => {
+                let mut entries = BTreeMap::new();
+                for field in &fields.fields {
+                    if field.optional && self.rng.chance(2) {
+                        continue;
+                    }
+                    let value = self.item(&field.value, depth + 1, &none)?;
+                    match (field.flatten, value) {
+                        (true, Value::Map(inner)) => {
+                            for (key, value) in inner {
+                                entries.entry(key).or_insert(value);
+                            }
+                        }
+                        (true, _) => {}
+                        (false, value) => {
+                            entries.insert(Value::String(field.name.to_string()), value);
+                        }
+                    }
+                }
+                Ok(Value::Map(entries))
+            }
+        }
+    }
+
+    fn elements(
+        &mut self,
+        item: &'a SchemaDecodeItem<'b>,
+        depth: usize,
+        constraints: &Constraints,
+        unique: bool,
+    ) -> Result<Vec<Value>, String> {
+        let none = Constraints::default();
+        let (min, len) = self.len(constraints, depth >= self.max_depth);
+        let mut values = Vec::with_capacity(len);
+        for _ in 0..len * 4 + 16 {
+            if values.len() >= len {
+                break;
+            }
+            let value = self.item(item, depth + 1, &none)?;
+            if !unique || !values.contains(&value) {
+                values.push(value);
+            }
+        }
+        match values.len() < min {
+            true => Err(format!("cannot generate {min} distinct elements")),
+            false => Ok(values),
+        }
+    }
+
+    /// The minimum length allowed by the constraints and a random length.
+    fn len(&mut self, constraints: &Constraints, closing: bool) -> (usize, usize) {
+        let min = constraints.min_length.unwrap_or(0);
+        let max = match closing {
+            true => min,
+            false => constraints
+                .max_length
+                .unwrap_or(usize::MAX)
+                .min(self.max_len.max(min)),
+        };
+        (
+            min,
+            self.rng.range(min as i128, max.max(min) as i128) as usize,
+        )
+    }
+
+    fn simple(&mut self, s: SimpleSchema, constraints: &Constraints) -> Result<Value, String> {
+        if let Some((min, max)) = int_range(s) {
+            let min = match &constraints.min {
+                Some(bound) => min.max(int_bound(bound, f64::ceil)),
+                None => min,
+            };
+            let max = match &constraints.max {
+                Some(bound) => max.min(int_bound(bound, f64::floor)),
+                None => max,
+            };
+            if min > max {
+                return Err(format!("no {s} between {min} and {max}"));
+            }
+            // Edge values find more bugs than uniform ones.
+            let n = match self.rng.below(8) {
+                0 => min,
+                1 => max,
+                _ => self.rng.range(min, max),
+            };
+            return Ok(int_value(s, n));
+        }
+        Ok(match s {
+            SimpleSchema::Unit => Value::Unit,
+            SimpleSchema::Bool => Value::Bool(self.rng.chance(2)),
+            SimpleSchema::F32 | SimpleSchema::F64 => {
+                let min = constraints.min.as_ref().and_then(|n| n.as_f64());
+                let max = constraints.max.as_ref().and_then(|n| n.as_f64());
+                let (min, max) = match (min, max) {
+                    (Some(min), Some(max)) => (min, max),
+                    (Some(min), None) => (min, min + 1e6),
+                    (None, Some(max)) => (max - 1e6, max),
+                    (None, None) => (-1e6, 1e6),
+                };
+                if min > max {
+                    return Err(format!("no {s} between {min} and {max}"));
+                }
+                let x = min + (max - min) * self.rng.unit();
+                match s {
+                    SimpleSchema::F32 => Value::F32((x as f32).clamp(min as f32, max as f32)),
+                    _ => Value::F64(x),
+                }
+            }
+            SimpleSchema::Char => Value::Char(self.char()),
+            SimpleSchema::String => Value::String(self.string(constraints)?),
+            SimpleSchema::Bytes => {
+                let (_, len) = self.len(constraints, false);
+                Value::Bytes((0..len).map(|_| self.rng.next() as u8).collect())
+            }
+            _ => unreachable!("integers are handled above"),
+        })
+    }
+
+    fn char(&mut self) -> char {
+        let n = CHARS.chars().count() as u128;
+        CHARS.chars().nth(self.rng.below(n) as usize).unwrap_or('a')
+    }
+
+    fn string(&mut self, constraints: &Constraints) -> Result<String, String> {
+        let pattern = match &constraints.pattern {
+            None => {
+                let (_, len) = self.len(constraints, false);
+                return Ok((0..len).map(|_| self.char()).collect());
+            }
+            Some(pattern) => pattern,
+        };
+        let invalid = |e: &dyn std::fmt::Display| format!("invalid pattern {pattern:?}: {e}");
+        let hir = regex_syntax::parse(pattern).map_err(|e| invalid(&e))?;
+        let regex = Regex::new(pattern).map_err(|e| invalid(&e))?;
+        let min = constraints.min_length.unwrap_or(0);
+        let max = constraints.max_length.unwrap_or(usize::MAX);
+        // Look-arounds and length constraints are checked, not built in.
+        for _ in 0..64 {
+            let mut s = String::new();
+            self.hir(&hir, &mut s);
+            let len = s.chars().count();
+            if (min..=max).contains(&len) && regex.is_match(&s) {
+                return Ok(s);
+            }
+        }
+        Err(format!("cannot generate a string matching {pattern:?}"))
+    }
+
+    /// Append a string that matches `hir`.
+    fn hir(&mut self, hir: &Hir, out: &mut String) {
+        match hir.kind() {
+            HirKind::Empty | HirKind::Look(_) => {}
+            HirKind::Literal(literal) => out.push_str(&String::from_utf8_lossy(&literal.0)),
+            HirKind::Class(Class::Unicode(class)) => {
+                let ranges = class.ranges();
+                if !ranges.is_empty() {
+                    let range = ranges[self.rng.below(ranges.len() as u128) as usize];
+                    let (start, end) = (range.start() as i128, range.end() as i128);
+                    let c = char::from_u32(self.rng.range(start, end) as u32);
+                    out.push(c.unwrap_or(range.start()));
+                }
+            }
+            HirKind::Class(Class::Bytes(class)) => {
+                let ranges: Vec<_> = class.ranges().iter().filter(|r| r.start() < 0x80).collect();
+                if !ranges.is_empty() {
+                    let range = ranges[self.rng.below(ranges.len() as u128) as usize];
+                    let end = range.end().min(0x7f);
+                    let byte = self.rng.range(range.start().into(), end.into()) as u8;
+                    out.push(byte as char);
+                }
+            }
+            HirKind::Repetition(repetition) => {
+                let min = repetition.min as usize;
+                let max = match repetition.max {
+                    Some(max) => (max as usize).min(min + self.max_len),
+                    None => min + self.max_len,
+                };
+                for _ in 0..self.rng.range(min as i128, max as i128) {
+                    self.hir(&repetition.sub, out);
+                }
+            }
+            HirKind::Capture(capture) => self.hir(&capture.sub, out),
+            HirKind::Concat(hirs) => hirs.iter().for_each(|hir| self.hir(hir, out)),
+            HirKind::Alternation(hirs) => {
+                let i = self.rng.below(hirs.len() as u128) as usize;
+                self.hir(&hirs[i], out);
+            }
+        }
+    }
+}
+
+fn fields_len(fields: &FieldsDecode) -> usize {
+    match fields {
+        FieldsDecode::Tuple(t) => t.values.len(),
+        FieldsDecode::Named(n) => n.fields.len(),
+    }
+}
+
+/// Constraints of nested constrained schemas: the outer ones win.
+fn merge(outer: &Constraints, inner: &Constraints) -> Constraints {
+    Constraints {
+        min: outer.min.clone().or_else(|| inner.min.clone()),
+        max: outer.max.clone().or_else(|| inner.max.clone()),
+        min_length: outer.min_length.or(inner.min_length),
+        max_length: outer.max_length.or(inner.max_length),
+        pattern: outer.pattern.clone().or_else(|| inner.pattern.clone()),
+        unique: outer.unique || inner.unique,
+        key_pattern: outer
+            .key_pattern
+            .clone()
+            .or_else(|| inner.key_pattern.clone()),
+    }
+}
+
+/// The values of an integer type that fit in a dynamic value.
+fn int_range(s: SimpleSchema) -> Option<(i128, i128)> {
+    Some(match s {
+        SimpleSchema::U8 => (0, u8::MAX.into()),
+        SimpleSchema::U16 => (0, u16::MAX.into()),
+        SimpleSchema::U32 => (0, u32::MAX.into()),
+        SimpleSchema::U64 | SimpleSchema::U128 => (0, u64::MAX.into()),
+        SimpleSchema::I8 => (i8::MIN.into(), i8::MAX.into()),
+        SimpleSchema::I16 => (i16::MIN.into(), i16::MAX.into()),
+        SimpleSchema::I32 => (i32::MIN.into(), i32::MAX.into()),
+        SimpleSchema::I64 | SimpleSchema::I128 => (i64::MIN.into(), i64::MAX.into()),
+        _ => return None,
+    })
+}
+
+fn int_bound(bound: &serde_json::Number, round: fn(f64) -> f64) -> i128 {
+    match (bound.as_i64(), bound.as_u64()) {
+        (Some(n), _) => n.into(),
+        (_, Some(n)) => n.into(),
+        _ => round(bound.as_f64().unwrap_or_default()) as i128,
+    }
+}
+
+/// `n`, which must be in [`int_range`] of `s`, as a value of type `s`.
+fn int_value(s: SimpleSchema, n: i128) -> Value {
+    match s {
+        SimpleSchema::U8 => Value::U8(n as u8),
+        SimpleSchema::U16 => Value::U16(n as u16),
+        SimpleSchema::U32 => Value::U32(n as u32),
+        SimpleSchema::U64 | SimpleSchema::U128 => Value::U64(n as u64),
+        SimpleSchema::I8 => Value::I8(n as i8),
+        SimpleSchema::I16 => Value::I16(n as i16),
+        SimpleSchema::I32 => Value::I32(n as i32),
+        _ => Value::I64(n as i64),
+    }
+}
+
+/// SplitMix64: small, and stable across versions, so that a seed always
+/// gives the same values.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`, or 0 if `n` is 0.
+    fn below(&mut self, n: u128) -> u128 {
+        match n {
+            0 => 0,
+            n => ((u128::from(self.next()) << 64) | u128::from(self.next())) % n,
+        }
+    }
+
+    /// A number in `min..=max`.
+    fn range(&mut self, min: i128, max: i128) -> i128 {
+        min + self.below((max - min) as u128 + 1) as i128
+    }
+
+    fn chance(&mut self, one_in: u128) -> bool {
+        self.below(one_in) == 0
+    }
+
+    /// A number in `0.0..1.0`.
+    fn unit(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
@@ -0,0 +1,1284 @@
+use std::str::FromStr;
+
+use serde_value::Value;
+
+use crate::{
+    ArraySchema, ConstrainedSchema, Constraints, Deprecated, Description, EnumRepr, EnumSchema,
+    FieldsSchema, HumanReadableSchema, MapSchema, Metadata, NamedFieldSchema, NamedFieldsSchema,
+    NewtypeSchema, OptionSchema, RenameAll, Schema, SchemaItem, SchemaName, SeqSchema, SetSchema,
+    SimpleSchema, StructSchema, TupleSchema, UnitStructSchema, VariantSchema,
+};
+
+impl Description {
+    /// The description in a compact, Rust-like language for reading and
+    /// reviewing, which [`Description::from_idl`] reads back into the same
+    /// description:
+    ///
+    /// ```text
+    /// root MyStruct<External>;
+    ///
+    /// struct MyStruct<External> {
+    ///     string: string,
+    ///     #[serde(rename = "n", default = 0u64)]
+    ///     int: u64,
+    ///     value: External,
+    /// }
+    ///
+    /// /// Documentation.
+    /// #[serde(tag = "type", rename_all = "snake_case")]
+    /// enum External {
+    ///     Unit,
+    ///     Newtype(Option<string>),
+    ///     Tuple(string, [u8; 4]),
+    ///     Struct {
+    ///         id: #[validate(min = 1)] u64,
+    ///     },
+    /// }
+    /// ```
+    ///
+    /// `root` gives the described type, and then every named schema is
+    /// declared: structs and enums with their full name, which ends with
+    /// the name of the Rust type, and other schemas as `type Name = ...;`.
+    ///
+    /// Types are written as `bool`, `u8` and the other simple schemas,
+    /// `any`, `Option<T>`, `Vec<T>`, `Set<T>`, `Map<K, V>`, `[T; N]`,
+    /// tuples such as `(A, B)` and `(A,)`, `HumanReadable<R, C>` and
+    /// references to named schemas, quoted where they would read as
+    /// something else. Structs and enums can also be defined where a type
+    /// is expected, on one line. Constraints are prefixed as
+    /// `#[validate(...)]`, with the names of [`Constraints`]' builders.
+    ///
+    /// Serde attributes are written as in Rust, with `default` taking the
+    /// default value. Integer enums take `#[repr(...)]`, variants that are
+    /// not at their index on the wire `#[index = N]`, and metadata is
+    /// written as `///` comments, `#[deprecated]`, `#[example(...)]` and
+    /// `#[annotation(key = "value")]`. Values are Rust-like literals with
+    /// the type of integers and floats as a suffix, e.g. `1u8`, `b"..."`,
+    /// `Some(())`, `[1i32]` or `{"key": 1.5f64}`.
+    pub fn idl(&self) -> String {
+        let mut out = format!("root {};\n", item(&self.schema));
+        for (name, schema) in &self.items {
+            out.push('\n');
+            out.push_str(&declaration(name, schema));
+        }
+        out
+    }
+
+    /// Read a description written by [`Description::idl`]. `//` comments
+    /// are ignored.
+    pub fn from_idl(idl: &str) -> Result<Description, String> {
+        let mut parser = Parser {
+            input: idl,
+            pos: 0,
+            depth: 0,
+        };
+        parser.description()
+    }
+}
+
+/// The simple schemas, by the names their `Display` gives them.
+const SIMPLE: [SimpleSchema; 17] = [
+    SimpleSchema::Unit,
+    SimpleSchema::Bool,
+    SimpleSchema::U8,
+    SimpleSchema::U16,
+    SimpleSchema::U32,
+    SimpleSchema::U64,
+    SimpleSchema::U128,
+    SimpleSchema::I8,
+    SimpleSchema::I16,
+    SimpleSchema::I32,
+    SimpleSchema::I64,
+    SimpleSchema::I128,
+    SimpleSchema::F32,
+    SimpleSchema::F64,
+    SimpleSchema::Char,
+    SimpleSchema::String,
+    SimpleSchema::Bytes,
+];
+
+/// The words that start a type other than a reference to a named schema,
+/// besides the simple schemas.
+const KEYWORDS: &[&str] = &[
+    "any",
+    "Option",
+    "Vec",
+    "Set",
+    "Map",
+    "HumanReadable",
+    "struct",
+    "enum",
+];
+
+fn simple(word: &str) -> Option<SimpleSchema> {
+    SIMPLE.into_iter().find(|s| s.to_string() == word)
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// The name of the struct or enum of a named schema, the last segment of
+/// its path.
+fn short(name: &SchemaName) -> &str {
+    name.0.rsplit("::").next().unwrap_or_default()
+}
+
+/// A struct, enum, field or variant name, quoted unless it is an
+/// identifier.
+fn ident(name: &str) -> String {
+    match name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(is_word_char)
+    {
+        true => name.to_string(),
+        false => format!("{name:?}"),
+    }
+}
+
+/// A schema name, its path quoted unless it is words separated by `::`
+/// and, where a type is expected, reads as a reference.
+fn schema_name(name: &SchemaName, in_type: bool) -> String {
+    let path = name
+        .0
+        .split("::")
+        .all(|segment| !segment.is_empty() && segment.chars().all(is_word_char));
+    let keyword = in_type && (KEYWORDS.contains(&name.0.as_str()) || simple(&name.0).is_some());
+    let mut out = match path && !keyword {
+        true => name.0.clone(),
+        false => format!("{:?}", name.0),
+    };
+    if !name.1.is_empty() {
+        let args: Vec<_> = name.1.iter().map(|arg| schema_name(arg, false)).collect();
+        out.push_str(&format!("<{}>", args.join(", ")));
+    }
+    out
+}
+
+fn declaration(name: &SchemaName, schema: &Schema) -> String {
+    let head = schema_name(name, false);
+    let (inner, end) = match schema {
+        Schema::Struct(s) => match s.fields {
+            FieldsSchema::Named(_) => (&s.name, ""),
+            FieldsSchema::Tuple(_) => (&s.name, ";"),
+        },
+        Schema::Enum(s) => (&s.name, ""),
+        Schema::Newtype(s) => (&s.name, ";"),
+        Schema::UnitStruct(s) => (&s.name, ";"),
+        _ => return format!("type {head} = {};\n", inline(schema)),
+    };
+    match inner == short(name) {
+        true => format!("{}{end}\n", definition(schema, &head, Layout::Block(0))),
+        false => format!("type {head} = {};\n", inline(schema)),
+    }
+}
+
+/// Where the members of a definition go.
+#[derive(Clone, Copy)]
+pub(crate) enum Layout {
+    /// On lines of their own, the definition being indented this much.
+    Block(usize),
+    /// On the line of the definition, within a type.
+    Inline,
+}
+
+impl Layout {
+    fn nested(self) -> Layout {
+        match self {
+            Layout::Block(indent) => Layout::Block(indent + 4),
+            Layout::Inline => Layout::Inline,
+        }
+    }
+}
+
+pub(crate) fn item(item: &SchemaItem) -> String {
+    match item {
+        SchemaItem::Named(name) => schema_name(name, true),
+        SchemaItem::Schema(schema) => inline(schema),
+    }
+}
+
+pub(crate) fn inline(schema: &Schema) -> String {
+    match schema {
+        Schema::Simple(s) => s.to_string(),
+        Schema::Option(s) => format!("Option<{}>", item(&s.value)),
+        Schema::Seq(s) => format!("Vec<{}>", item(&s.value)),
+        Schema::Set(s) => format!("Set<{}>", item(&s.value)),
+        Schema::Array(s) => format!("[{}; {}]", item(&s.value), s.len),
+        Schema::Map(s) => format!("Map<{}, {}>", item(&s.key), item(&s.value)),
+        Schema::HumanReadable(s) => {
+            format!("HumanReadable<{}, {}>", item(&s.readable), item(&s.compact))
+        }
+        Schema::Tuple(s) => tuple(&s.values, true),
+        Schema::Constrained(s) => format!("{} {}", validate(&s.constraints), item(&s.value)),
+        Schema::Any => "any".to_string(),
+        Schema::Struct(StructSchema { name, .. })
+        | Schema::Enum(EnumSchema { name, .. })
+        | Schema::Newtype(NewtypeSchema { name, .. })
+        | Schema::UnitStruct(UnitStructSchema { name, .. }) => {
+            definition(schema, &ident(name), Layout::Inline)
+        }
+    }
+}
+
+/// A parenthesized list of types, with a trailing comma if it has one
+/// element and `one_comma`.
+pub(crate) fn tuple(values: &[SchemaItem], one_comma: bool) -> String {
+    let values: Vec<_> = values.iter().map(item).collect();
+    match values.len() == 1 && one_comma {
+        true => format!("({},)", values[0]),
+        false => format!("({})", values.join(", ")),
+    }
+}
+
+fn validate(constraints: &Constraints) -> String {
+    let mut args = Vec::new();
+    if let Some(min) = &constraints.min {
+        args.push(format!("min = {min}"));
+    }
+    if let Some(max) = &constraints.max {
+        args.push(format!("max = {max}"));
+    }
+    if let Some(len) = constraints.min_length {
+        args.push(format!("min_length = {len}"));
+    }
+    if let Some(len) = constraints.max_length {
+        args.push(format!("max_length = {len}"));
+    }
+    if let Some(pattern) = &constraints.pattern {
+        args.push(format!("pattern = {pattern:?}"));
+    }
+    if constraints.unique {
+        args.push("unique".to_string());
+    }
+    if let Some(pattern) = &constraints.key_pattern {
+        args.push(format!("key_pattern = {pattern:?}"));
+    }
+    match args.is_empty() {
+        true => "#[validate]".to_string(),
+        false => format!("#[validate({})]", args.join(", ")),
+    }
+}
+
+/// The definition of a struct or enum named `head`, with its attributes.
+fn definition(schema: &Schema, head: &str, layout: Layout) -> String {
+    let mut serde = Vec::new();
+    let no_meta = Metadata::default();
+    let (meta, mut attrs, body) = match schema {
+        Schema::Struct(s) => {
+            string_arg(&mut serde, "rename", &s.rename);
+            rename_all_arg(&mut serde, s.rename_all);
+            let body = match &s.fields {
+                FieldsSchema::Named(fields) => named_fields(fields, layout),
+                FieldsSchema::Tuple(t) => tuple(&t.values, true),
+            };
+            (&s.meta, Vec::new(), format!("struct {head}{body}"))
+        }
+        Schema::Newtype(s) => {
+            string_arg(&mut serde, "rename", &s.rename);
+            if s.transparent {
+                serde.push("transparent".to_string());
+            }
+            let body = format!("struct {head}({})", item(&s.value));
+            (&no_meta, Vec::new(), body)
+        }
+        Schema::UnitStruct(s) => {
+            string_arg(&mut serde, "rename", &s.rename);
+            (&no_meta, Vec::new(), format!("struct {head}"))
+        }
+        Schema::Enum(s) => {
+            let mut attrs = Vec::new();
+            match &s.repr {
+                EnumRepr::ExternallyTagged => {}
+                EnumRepr::InternallyTagged { tag } => serde.push(format!("tag = {tag:?}")),
+                EnumRepr::AdjacentlyTagged { tag, content } => {
+                    serde.push(format!("tag = {tag:?}"));
+                    serde.push(format!("content = {content:?}"));
+                }
+                EnumRepr::Integer { repr } => attrs.push(format!("#[repr({repr})]")),
+            }
+            string_arg(&mut serde, "rename", &s.rename);
+            rename_all_arg(&mut serde, s.rename_all);
+            let variants = s
+                .variants
+                .iter()
+                .map(|v| variant(v, layout.nested()))
+                .collect();
+            let body = format!("enum {head}{}", braces(variants, layout));
+            (&s.meta, attrs, body)
+        }
+        schema => return inline(schema),
+    };
+    if !serde.is_empty() {
+        attrs.push(format!("#[serde({})]", serde.join(", ")));
+    }
+    format!("{}{body}", attributes(meta, attrs, layout))
+}
+
+pub(crate) fn named_fields(fields: &NamedFieldsSchema, layout: Layout) -> String {
+    let fields = fields
+        .fields
+        .iter()
+        .map(|f| field(f, layout.nested()))
+        .collect();
+    braces(fields, layout)
+}
+
+/// Members between braces, each formatted for `layout.nested()`.
+fn braces(members: Vec<String>, layout: Layout) -> String {
+    match layout {
+        _ if members.is_empty() => " {}".to_string(),
+        Layout::Block(indent) => {
+            let members: String = members.iter().map(|m| format!("{m},\n")).collect();
+            format!(" {{\n{members}{}}}", " ".repeat(indent))
+        }
+        Layout::Inline => format!(" {{ {} }}", members.join(", ")),
+    }
+}
+
+fn field(field: &NamedFieldSchema, layout: Layout) -> String {
+    let mut serde = Vec::new();
+    string_arg(&mut serde, "rename", &field.rename);
+    for alias in &field.aliases {
+        serde.push(format!("alias = {alias:?}"));
+    }
+    if let Some(default) = &field.default {
+        serde.push(format!("default = {}", literal(default)));
+    }
+    if field.flatten {
+        serde.push("flatten".to_string());
+    }
+    match (field.skip_serializing, field.skip_deserializing) {
+        (true, true) => serde.push("skip".to_string()),
+        (true, false) => serde.push("skip_serializing".to_string()),
+        (false, true) => serde.push("skip_deserializing".to_string()),
+        (false, false) => {}
+    }
+    string_arg(
+        &mut serde,
+        "skip_serializing_if",
+        &field.skip_serializing_if,
+    );
+    let attrs = match serde.is_empty() {
+        true => Vec::new(),
+        false => vec![format!("#[serde({})]", serde.join(", "))],
+    };
+    format!(
+        "{}{}: {}",
+        attributes(&field.meta, attrs, layout),
+        ident(&field.name),
+        item(&field.value)
+    )
+}
+
+fn variant(variant: &VariantSchema, layout: Layout) -> String {
+    let mut serde = Vec::new();
+    string_arg(&mut serde, "rename", &variant.rename);
+    for alias in &variant.aliases {
+        serde.push(format!("alias = {alias:?}"));
+    }
+    if variant.other {
+        serde.push("other".to_string());
+    }
+    let mut attrs = Vec::new();
+    if !serde.is_empty() {
+        attrs.push(format!("#[serde({})]", serde.join(", ")));
+    }
+    if let Some(index) = variant.id {
+        attrs.push(format!("#[index = {index}]"));
+    }
+    let fields = match &variant.fields {
+        FieldsSchema::Tuple(t) if t.values.is_empty() => String::new(),
+        FieldsSchema::Tuple(t) => tuple(&t.values, false),
+        FieldsSchema::Named(fields) => named_fields(fields, layout),
+    };
+    let discriminant = match variant.discriminant {
+        Some(discriminant) => format!(" = {discriminant}"),
+        None => String::new(),
+    };
+    format!(
+        "{}{}{fields}{discriminant}",
+        attributes(&variant.meta, attrs, layout),
+        ident(&variant.name)
+    )
+}
+
+fn string_arg(args: &mut Vec<String>, key: &str, value: &Option<String>) {
+    if let Some(value) = value {
+        args.push(format!("{key} = {value:?}"));
+    }
+}
+
+fn rename_all_arg(args: &mut Vec<String>, rule: Option<RenameAll>) {
+    if let Some(rule) = rule.and_then(|rule| serde_json::to_value(rule).ok()) {
+        args.push(format!("rename_all = {rule}"));
+    }
+}
+
+/// The documentation and attributes before a definition, field or variant,
+/// and the indentation of the line they lead to.
+fn attributes(meta: &Metadata, mut attrs: Vec<String>, layout: Layout) -> String {
+    if let Some(deprecated) = &meta.deprecated {
+        let mut args = Vec::new();
+        string_arg(&mut args, "since", &deprecated.since);
+        string_arg(&mut args, "note", &deprecated.note);
+        attrs.push(match args.is_empty() {
+            true => "#[deprecated]".to_string(),
+            false => format!("#[deprecated({})]", args.join(", ")),
+        });
+    }
+    for example in &meta.examples {
+        attrs.push(format!("#[example({})]", literal(example)));
+    }
+    if !meta.annotations.is_empty() {
+        let args: Vec<_> = meta
+            .annotations
+            .iter()
+            .map(|(key, value)| format!("{} = {value:?}", ident(key)))
+            .collect();
+        attrs.push(format!("#[annotation({})]", args.join(", ")));
+    }
+    match layout {
+        Layout::Block(indent) => {
+            let pad = " ".repeat(indent);
+            let mut out = String::new();
+            for line in meta.doc.iter().flat_map(|doc| doc.split('\n')) {
+                match line.is_empty() {
+                    true => out.push_str(&format!("{pad}///\n")),
+                    false => out.push_str(&format!("{pad}/// {line}\n")),
+                }
+            }
+            for attr in attrs {
+                out.push_str(&format!("{pad}{attr}\n"));
+            }
+            out + &pad
+        }
+        Layout::Inline => {
+            let doc = meta.doc.iter().map(|doc| format!("#[doc = {doc:?}]"));
+            doc.chain(attrs).map(|attr| attr + " ").collect()
+        }
+    }
+}
+
+pub(crate) fn literal(value: &Value) -> String {
+    fn float(ty: &str, n: f64, text: String) -> String {
+        match n {
+            n if n.is_nan() => format!("{ty}::NAN"),
+            f64::INFINITY => format!("{ty}::INFINITY"),
+            f64::NEG_INFINITY => format!("{ty}::NEG_INFINITY"),
+            _ => format!("{text}{ty}"),
+        }
+    }
+
+    match value {
+        Value::Bool(b) => b.to_string(),
+        Value::U8(n) => format!("{n}u8"),
+        Value::U16(n) => format!("{n}u16"),
+        Value::U32(n) => format!("{n}u32"),
+        Value::U64(n) => format!("{n}u64"),
+        Value::I8(n) => format!("{n}i8"),
+        Value::I16(n) => format!("{n}i16"),
+        Value::I32(n) => format!("{n}i32"),
+        Value::I64(n) => format!("{n}i64"),
+        Value::F32(n) => float("f32", *n as f64, n.to_string()),
+        Value::F64(n) => float("f64", *n, n.to_string()),
+        Value::Char(c) => format!("{c:?}"),
+        Value::String(s) => format!("{s:?}"),
+        Value::Unit => "()".to_string(),
+        Value::Option(None) => "None".to_string(),
+        Value::Option(Some(v)) => format!("Some({})", literal(v)),
+        Value::Newtype(v) => format!("Newtype({})", literal(v)),
+        Value::Seq(values) => {
+            let values: Vec<_> = values.iter().map(literal).collect();
+            format!("[{}]", values.join(", "))
+        }
+        Value::Map(entries) => {
+            let entries: Vec<_> = entries
+                .iter()
+                .map(|(k, v)| format!("{}: {}", literal(k), literal(v)))
+                .collect();
+            format!("{{{}}}", entries.join(", "))
+        }
+        Value::Bytes(bytes) => format!("b\"{}\"", bytes.escape_ascii()),
+    }
+}
+
+/// Where `pos` is in `input`, for errors.
+fn location(input: &str, pos: usize) -> String {
+    let before = &input[..pos];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    format!(
+        "line {}, column {}",
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1
+    )
+}
+
+/// How deep types and values nest, so that parsing hostile input does not
+/// overflow the stack.
+const MAX_DEPTH: usize = 128;
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+    depth: usize,
+}
+
+/// The documentation and attributes before a definition, field or
+/// variant, taken by what they turn out to be on.
+struct Attributes<'a> {
+    input: &'a str,
+    pos: usize,
+    meta: Metadata,
+    serde: Vec<(&'a str, Option<Value>)>,
+    repr: Option<SimpleSchema>,
+    index: Option<usize>,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn error(&self, msg: &str) -> String {
+        format!("{msg} at {}", location(self.input, self.pos))
+    }
+
+    /// Skip whitespace and comments, but not documentation.
+    fn skip_ws(&mut self) {
+        loop {
+            let rest = self.rest().trim_start();
+            self.pos = self.input.len() - rest.len();
+            match rest.starts_with("//") && !rest.starts_with("///") {
+                true => self.pos += rest.find('\n').unwrap_or(rest.len()),
+                false => return,
+            }
+        }
+    }
+
+    fn peek(&mut self, token: &str) -> bool {
+        self.skip_ws();
+        self.rest().starts_with(token)
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        match self.peek(token) {
+            true => {
+                self.pos += token.len();
+                true
+            }
+            false => false,
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), String> {
+        match self.eat(token) {
+            true => Ok(()),
+            false => Err(self.error(&format!("expected {token:?}"))),
+        }
+    }
+
+    fn keyword(&mut self, word: &str) -> bool {
+        self.skip_ws();
+        match self.rest().strip_prefix(word) {
+            Some(after) if !after.starts_with(is_word_char) => {
+                self.pos += word.len();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Parse items with `each` up to `close`, separated by commas, with an
+    /// optional trailing one.
+    fn list<F>(&mut self, close: &str, mut each: F) -> Result<(), String>
+    where
+        F: FnMut(&mut Self) -> Result<(), String>,
+    {
+        while !self.eat(close) {
+            each(self)?;
+            if !self.eat(",") {
+                return self.expect(close);
+            }
+        }
+        Ok(())
+    }
+
+    fn word(&mut self) -> Result<&'a str, String> {
+        self.skip_ws();
+        let rest = self.rest();
+        let len = rest.find(|c| !is_word_char(c)).unwrap_or(rest.len());
+        match len {
+            0 => Err(self.error("expected a name")),
+            _ => {
+                self.pos += len;
+                Ok(&rest[..len])
+            }
+        }
+    }
+
+    fn path(&mut self) -> Result<String, String> {
+        let mut path = self.word()?.to_string();
+        while self.rest().starts_with("::") {
+            self.pos += 2;
+            path.push_str("::");
+            path.push_str(self.word()?);
+        }
+        Ok(path)
+    }
+
+    /// A struct, enum, field or variant name.
+    fn name(&mut self) -> Result<String, String> {
+        match self.peek("\"") {
+            true => self.string(),
+            false => self.word().map(str::to_string),
+        }
+    }
+
+    fn schema_name(&mut self) -> Result<SchemaName, String> {
+        let path = match self.peek("\"") {
+            true => self.string()?,
+            false => self.path()?,
+        };
+        self.arguments(SchemaName::new(path))
+    }
+
+    fn arguments(&mut self, mut name: SchemaName) -> Result<SchemaName, String> {
+        if self.eat("<") {
+            self.list(">", |p| {
+                name.1.push(p.schema_name()?);
+                Ok(())
+            })?;
+        }
+        Ok(name)
+    }
+
+    fn integer<T: FromStr>(&mut self) -> Result<T, String> {
+        self.skip_ws();
+        let rest = self.rest();
+        let len = rest
+            .char_indices()
+            .find(|&(i, c)| !(c.is_ascii_digit() || (i == 0 && c == '-')))
+            .map_or(rest.len(), |(i, _)| i);
+        let n = rest[..len]
+            .parse()
+            .map_err(|_| self.error("expected an integer"))?;
+        self.pos += len;
+        Ok(n)
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.skip_ws();
+        self.quoted('"')
+    }
+
+    /// A string between `quote`s, with Rust's escapes.
+    fn quoted(&mut self, quote: char) -> Result<String, String> {
+        if !self.rest().starts_with(quote) {
+            return Err(self.error(&format!("expected {quote:?}")));
+        }
+        let mut out = String::new();
+        let mut chars = self.rest().char_indices().skip(1);
+        while let Some((i, c)) = chars.next() {
+            if c == quote {
+                self.pos += i + 1;
+                return Ok(out);
+            }
+            if c != '\\' {
+                out.push(c);
+                continue;
+            }
+            let escaped = match chars.next().map(|(_, c)| c) {
+                Some('n') => Some('\n'),
+                Some('r') => Some('\r'),
+                Some('t') => Some('\t'),
+                Some('0') => Some('\0'),
+                Some(c @ ('\\' | '\'' | '"')) => Some(c),
+                Some('x') => {
+                    let hex: String = chars.by_ref().take(2).map(|(_, c)| c).collect();
+                    u8::from_str_radix(&hex, 16).ok().map(char::from)
+                }
+                Some('u') => match chars.next() {
+                    Some((_, '{')) => {
+                        let hex: String = chars
+                            .by_ref()
+                            .map(|(_, c)| c)
+                            .take_while(|&c| c != '}')
+                            .collect();
+                        u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32)
+                    }
+                    _ => None,
+                },
+                _ => None,
+            };
+            out.push(escaped.ok_or_else(|| self.error("invalid escape"))?);
+        }
+        Err(self.error("unterminated string"))
+    }
+
+    fn description(&mut self) -> Result<Description, String> {
+        if !self.keyword("root") {
+            return Err(self.error("expected \"root\""));
+        }
+        let mut description = Description::new(self.item()?);
+        self.expect(";")?;
+        loop {
+            self.skip_ws();
+            if self.rest().is_empty() {
+                return Ok(description);
+            }
+            let pos = self.pos;
+            let (name, schema) = self.declaration()?;
+            if description.items.insert(name, schema).is_some() {
+                let at = location(self.input, pos);
+                return Err(format!("a second declaration of a schema at {at}"));
+            }
+        }
+    }
+
+    fn declaration(&mut self) -> Result<(SchemaName, Schema), String> {
+        let attrs = self.attributes()?;
+        if self.keyword("struct") {
+            let name = self.schema_name()?;
+            let schema = self.structure(short(&name).to_string(), attrs)?;
+            if !matches!(&schema, Schema::Struct(s) if matches!(s.fields, FieldsSchema::Named(_))) {
+                self.expect(";")?;
+            }
+            Ok((name, schema))
+        } else if self.keyword("enum") {
+            let name = self.schema_name()?;
+            let schema = self.enumeration(short(&name).to_string(), attrs)?;
+            Ok((name, schema))
+        } else if self.keyword("type") {
+            attrs.finish("a type alias", false)?;
+            let name = self.schema_name()?;
+            self.expect("=")?;
+            self.skip_ws();
+            let pos = self.pos;
+            let item = self.item()?;
+            self.expect(";")?;
+            match item {
+                SchemaItem::Schema(schema) => Ok((name, *schema)),
+                SchemaItem::Named(_) => Err(format!(
+                    "expected a schema, not a reference, at {}",
+                    location(self.input, pos)
+                )),
+            }
+        } else {
+            Err(self.error("expected \"struct\", \"enum\" or \"type\""))
+        }
+    }
+
+    /// Run `parse` a level deeper.
+    fn nested<T, F>(&mut self, parse: F) -> Result<T, String>
+    where
+        F: FnOnce(&mut Self) -> Result<T, String>,
+    {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error(&format!("nesting deeper than {MAX_DEPTH} levels")));
+        }
+        self.depth += 1;
+        let parsed = parse(self);
+        self.depth -= 1;
+        parsed
+    }
+
+    fn item(&mut self) -> Result<SchemaItem, String> {
+        self.nested(Self::type_expr)
+    }
+
+    fn type_expr(&mut self) -> Result<SchemaItem, String> {
+        if let Some(constraints) = self.constraints()? {
+            return Ok(ConstrainedSchema::new(self.item()?, constraints).into());
+        }
+        let attrs = self.attributes()?;
+        if self.keyword("struct") {
+            let name = self.name()?;
+            return Ok(SchemaItem::Schema(Box::new(self.structure(name, attrs)?)));
+        }
+        if self.keyword("enum") {
+            let name = self.name()?;
+            return Ok(SchemaItem::Schema(Box::new(self.enumeration(name, attrs)?)));
+        }
+        attrs.finish("a type", false)?;
+        if self.peek("(") {
+            let (mut values, comma) = self.tuple()?;
+            return Ok(match values.len() == 1 && !comma {
+                true => values.remove(0),
+                false => TupleSchema { values }.into(),
+            });
+        }
+        if self.eat("[") {
+            let value = self.item()?;
+            self.expect(";")?;
+            let len = self.integer()?;
+            self.expect("]")?;
+            return Ok(ArraySchema::new(value, len).into());
+        }
+        if self.peek("\"") {
+            let name = SchemaName::new(self.string()?);
+            return Ok(self.arguments(name)?.into());
+        }
+        let path = self.path()?;
+        if let Some(simple) = simple(&path) {
+            return Ok(simple.into());
+        }
+        let mut generic = |count| -> Result<Vec<SchemaItem>, String> {
+            self.expect("<")?;
+            let mut args = vec![self.item()?];
+            while args.len() < count {
+                self.expect(",")?;
+                args.push(self.item()?);
+            }
+            self.expect(">")?;
+            Ok(args)
+        };
+        let schema: Schema = match path.as_str() {
+            "any" => Schema::Any,
+            "Option" => OptionSchema::new(generic(1)?.remove(0)).into(),
+            "Vec" => SeqSchema::new(generic(1)?.remove(0)).into(),
+            "Set" => SetSchema::new(generic(1)?.remove(0)).into(),
+            "Map" => {
+                let mut args = generic(2)?;
+                let key = args.remove(0);
+                MapSchema::new(key, args.remove(0)).into()
+            }
+            "HumanReadable" => {
+                let mut args = generic(2)?;
+                let readable = args.remove(0);
+                HumanReadableSchema::new(readable, args.remove(0)).into()
+            }
+            _ => return Ok(self.arguments(SchemaName::new(path))?.into()),
+        };
+        Ok(SchemaItem::Schema(Box::new(schema)))
+    }
+
+    /// The types of a parenthesized list, and whether it ends with a comma.
+    fn tuple(&mut self) -> Result<(Vec<SchemaItem>, bool), String> {
+        self.expect("(")?;
+        let mut values = Vec::new();
+        let mut comma = false;
+        while !self.eat(")") {
+            values.push(self.item()?);
+            comma = self.eat(",");
+            if !comma {
+                self.expect(")")?;
+                break;
+            }
+        }
+        Ok((values, comma))
+    }
+
+    /// The constraints of a `#[validate]` attribute, if one comes next.
+    fn constraints(&mut self) -> Result<Option<Constraints>, String> {
+        let start = self.pos;
+        if !(self.eat("#[") && self.keyword("validate")) {
+            self.pos = start;
+            return Ok(None);
+        }
+        let mut constraints = Constraints::new();
+        if self.eat("(") {
+            self.list(")", |p| {
+                let key = p.word()?;
+                if key == "unique" {
+                    constraints.unique = true;
+                    return Ok(());
+                }
+                p.expect("=")?;
+                match key {
+                    "min" => constraints.min = Some(p.number()?),
+                    "max" => constraints.max = Some(p.number()?),
+                    "min_length" => constraints.min_length = Some(p.integer()?),
+                    "max_length" => constraints.max_length = Some(p.integer()?),
+                    "pattern" => constraints.pattern = Some(p.string()?),
+                    "key_pattern" => constraints.key_pattern = Some(p.string()?),
+                    _ => return Err(p.error(&format!("unknown constraint {key:?}"))),
+                }
+                Ok(())
+            })?;
+        }
+        self.expect("]")?;
+        Ok(Some(constraints))
+    }
+
+    /// A JSON number, the bound of a constraint.
+    fn number(&mut self) -> Result<serde_json::Number, String> {
+        self.skip_ws();
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| !(c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E')))
+            .unwrap_or(rest.len());
+        let n = serde_json::from_str(&rest[..len]).map_err(|_| self.error("expected a number"))?;
+        self.pos += len;
+        Ok(n)
+    }
+
+    fn attributes(&mut self) -> Result<Attributes<'a>, String> {
+        self.skip_ws();
+        let mut attrs = Attributes {
+            input: self.input,
+            pos: self.pos,
+            meta: Metadata::default(),
+            serde: Vec::new(),
+            repr: None,
+            index: None,
+        };
+        loop {
+            if self.peek("///") {
+                self.pos += 3;
+                let line = self.rest().split('\n').next().unwrap_or_default();
+                self.pos += line.len();
+                push_doc(&mut attrs.meta, line.strip_prefix(' ').unwrap_or(line));
+                continue;
+            }
+            if !self.eat("#[") {
+                return Ok(attrs);
+            }
+            let word = self.word()?;
+            match word {
+                "doc" => {
+                    self.expect("=")?;
+                    let doc = self.string()?;
+                    push_doc(&mut attrs.meta, &doc);
+                }
+                "deprecated" if attrs.meta.deprecated.is_none() => {
+                    let mut deprecated = Deprecated::new();
+                    if self.eat("(") {
+                        self.list(")", |p| {
+                            let key = p.word()?;
+                            p.expect("=")?;
+                            match key {
+                                "since" => deprecated.since = Some(p.string()?),
+                                "note" => deprecated.note = Some(p.string()?),
+                                _ => return Err(p.error("expected \"since\" or \"note\"")),
+                            }
+                            Ok(())
+                        })?;
+                    }
+                    attrs.meta.deprecated = Some(deprecated);
+                }
+                "example" => {
+                    self.expect("(")?;
+                    attrs.meta.examples.push(self.value()?);
+                    self.expect(")")?;
+                }
+                "annotation" => {
+                    self.expect("(")?;
+                    self.list(")", |p| {
+                        let key = p.name()?;
+                        p.expect("=")?;
+                        attrs.meta.annotations.insert(key, p.string()?);
+                        Ok(())
+                    })?;
+                }
+                "serde" => {
+                    self.expect("(")?;
+                    self.list(")", |p| {
+                        let key = p.word()?;
+                        let value = match p.eat("=") {
+                            true => Some(p.value()?),
+                            false => None,
+                        };
+                        attrs.serde.push((key, value));
+                        Ok(())
+                    })?;
+                }
+                "repr" if attrs.repr.is_none() => {
+                    self.expect("(")?;
+                    let repr = self.word()?;
+                    attrs.repr =
+                        Some(simple(repr).ok_or_else(|| self.error("expected a simple schema"))?);
+                    self.expect(")")?;
+                }
+                "index" if attrs.index.is_none() => {
+                    self.expect("=")?;
+                    attrs.index = Some(self.integer()?);
+                }
+                "deprecated" | "repr" | "index" => {
+                    return Err(self.error(&format!("a second {word} attribute")))
+                }
+                _ => return Err(self.error(&format!("unexpected attribute {word:?}"))),
+            }
+            self.expect("]")?;
+        }
+    }
+
+    /// The rest of a struct after its name: named fields, a tuple, the
+    /// value of a newtype struct, or nothing for a unit struct.
+    fn structure(&mut self, name: String, mut attrs: Attributes) -> Result<Schema, String> {
+        let rename = attrs.string("rename")?;
+        if self.peek("{") {
+            let mut s = StructSchema::new(name, self.named_fields()?);
+            s.rename = rename;
+            s.rename_all = attrs.rename_all()?;
+            s.meta = attrs.finish("a struct", true)?;
+            return Ok(s.into());
+        }
+        if !self.peek("(") {
+            attrs.finish("a unit struct", false)?;
+            return Ok(UnitStructSchema { name, rename }.into());
+        }
+        let (mut values, comma) = self.tuple()?;
+        if values.len() == 1 && !comma {
+            let mut s = NewtypeSchema::new(name, values.remove(0));
+            s.rename = rename;
+            s.transparent = attrs.flag("transparent")?;
+            attrs.finish("a newtype struct", false)?;
+            return Ok(s.into());
+        }
+        let mut s = StructSchema::new(name, TupleSchema { values });
+        s.rename = rename;
+        s.rename_all = attrs.rename_all()?;
+        s.meta = attrs.finish("a struct", true)?;
+        Ok(s.into())
+    }
+
+    fn named_fields(&mut self) -> Result<NamedFieldsSchema, String> {
+        let mut fields = NamedFieldsSchema::new();
+        self.expect("{")?;
+        self.list("}", |p| {
+            let mut attrs = p.attributes()?;
+            let name = p.name()?;
+            p.expect(":")?;
+            let mut field = NamedFieldSchema::new(name, p.item()?);
+            field.rename = attrs.string("rename")?;
+            field.aliases = attrs.strings("alias")?;
+            field.default = attrs.value("default")?;
+            field.flatten = attrs.flag("flatten")?;
+            let skip = attrs.flag("skip")?;
+            field.skip_serializing = attrs.flag("skip_serializing")? || skip;
+            field.skip_deserializing = attrs.flag("skip_deserializing")? || skip;
+            field.skip_serializing_if = attrs.string("skip_serializing_if")?;
+            field.meta = attrs.finish("a field", true)?;
+            fields.fields.push(field);
+            Ok(())
+        })?;
+        Ok(fields)
+    }
+
+    fn enumeration(&mut self, name: String, mut attrs: Attributes) -> Result<Schema, String> {
+        let mut s = EnumSchema::new(name);
+        let tag = attrs.string("tag")?;
+        let content = attrs.string("content")?;
+        s.repr = match (attrs.repr.take(), tag, content) {
+            (None, None, None) => EnumRepr::ExternallyTagged,
+            (None, Some(tag), None) => EnumRepr::InternallyTagged { tag },
+            (None, Some(tag), Some(content)) => EnumRepr::AdjacentlyTagged { tag, content },
+            (Some(repr), None, None) => EnumRepr::Integer { repr },
+            _ => return Err(attrs.error("conflicting representations of an enum")),
+        };
+        s.rename = attrs.string("rename")?;
+        s.rename_all = attrs.rename_all()?;
+        s.meta = attrs.finish("an enum", true)?;
+        self.expect("{")?;
+        self.list("}", |p| {
+            s.variants.push(p.variant()?);
+            Ok(())
+        })?;
+        Ok(s.into())
+    }
+
+    fn variant(&mut self) -> Result<VariantSchema, String> {
+        let mut attrs = self.attributes()?;
+        let name = self.name()?;
+        let fields: FieldsSchema = if self.peek("{") {
+            self.named_fields()?.into()
+        } else if self.peek("(") {
+            TupleSchema {
+                values: self.tuple()?.0,
+            }
+            .into()
+        } else {
+            TupleSchema::new().into()
+        };
+        let mut variant = VariantSchema::new(name, fields);
+        if self.eat("=") {
+            variant.discriminant = Some(self.integer()?);
+        }
+        variant.rename = attrs.string("rename")?;
+        variant.aliases = attrs.strings("alias")?;
+        variant.other = attrs.flag("other")?;
+        variant.id = attrs.index.take();
+        variant.meta = attrs.finish("a variant", true)?;
+        Ok(variant)
+    }
+
+    fn value(&mut self) -> Result<Value, String> {
+        self.nested(Self::value_expr)
+    }
+
+    fn value_expr(&mut self) -> Result<Value, String> {
+        self.skip_ws();
+        let rest = self.rest();
+        if rest.starts_with('"') {
+            return self.string().map(Value::String);
+        }
+        if rest.starts_with('\'') {
+            let s = self.quoted('\'')?;
+            let mut chars = s.chars();
+            return match (chars.next(), chars.next()) {
+                (Some(c), None) => Ok(Value::Char(c)),
+                _ => Err(self.error("expected a single character")),
+            };
+        }
+        if rest.starts_with("b\"") {
+            self.pos += 1;
+            let bytes: Result<Vec<u8>, _> = self.quoted('"')?.chars().map(u8::try_from).collect();
+            return bytes
+                .map(Value::Bytes)
+                .map_err(|_| self.error("expected ASCII or \\x escapes in a byte string"));
+        }
+        if rest.starts_with(|c: char| c == '-' || c.is_ascii_digit()) {
+            return self.suffixed();
+        }
+        if self.eat("(") {
+            self.expect(")")?;
+            return Ok(Value::Unit);
+        }
+        if self.eat("[") {
+            let mut values = Vec::new();
+            self.list("]", |p| {
+                values.push(p.value()?);
+                Ok(())
+            })?;
+            return Ok(Value::Seq(values));
+        }
+        if self.eat("{") {
+            let mut entries = std::collections::BTreeMap::new();
+            self.list("}", |p| {
+                let key = p.value()?;
+                p.expect(":")?;
+                entries.insert(key, p.value()?);
+                Ok(())
+            })?;
+            return Ok(Value::Map(entries));
+        }
+        let wrapped = |p: &mut Self| -> Result<Box<Value>, String> {
+            p.expect("(")?;
+            let value = p.value()?;
+            p.expect(")")?;
+            Ok(Box::new(value))
+        };
+        Ok(match self.path()?.as_str() {
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            "None" => Value::Option(None),
+            "Some" => Value::Option(Some(wrapped(self)?)),
+            "Newtype" => Value::Newtype(wrapped(self)?),
+            "f32::NAN" => Value::F32(f32::NAN),
+            "f32::INFINITY" => Value::F32(f32::INFINITY),
+            "f32::NEG_INFINITY" => Value::F32(f32::NEG_INFINITY),
+            "f64::NAN" => Value::F64(f64::NAN),
+            "f64::INFINITY" => Value::F64(f64::INFINITY),
+            "f64::NEG_INFINITY" => Value::F64(f64::NEG_INFINITY),
+            _ => return Err(self.error("expected a value")),
+        })
+    }
+
+    /// A number with its type as a suffix, e.g. `1u8` or `-0.5f32`.
+    fn suffixed(&mut self) -> Result<Value, String> {
+        let rest = self.rest();
+        let len = rest
+            .char_indices()
+            .find(|&(i, c)| !(c.is_ascii_alphanumeric() || c == '.' || (i == 0 && c == '-')))
+            .map_or(rest.len(), |(i, _)| i);
+        let token = &rest[..len];
+        let split = token.rfind(['u', 'i', 'f']).unwrap_or(len);
+        let (n, suffix) = token.split_at(split);
+        let value = match suffix {
+            "u8" => n.parse().ok().map(Value::U8),
+            "u16" => n.parse().ok().map(Value::U16),
+            "u32" => n.parse().ok().map(Value::U32),
+            "u64" => n.parse().ok().map(Value::U64),
+            "i8" => n.parse().ok().map(Value::I8),
+            "i16" => n.parse().ok().map(Value::I16),
+            "i32" => n.parse().ok().map(Value::I32),
+            "i64" => n.parse().ok().map(Value::I64),
+            "f32" => n.parse().ok().map(Value::F32),
+            "f64" => n.parse().ok().map(Value::F64),
+            _ => None,
+        };
+        let value = value.ok_or_else(|| {
+            self.error(&format!(
+                "invalid number {token:?}, which needs a type suffix such as u8 or f64"
+            ))
+        })?;
+        self.pos += len;
+        Ok(value)
+    }
+}
+
+fn push_doc(meta: &mut Metadata, line: &str) {
+    match &mut meta.doc {
+        Some(doc) => {
+            doc.push('\n');
+            doc.push_str(line);
+        }
+        None => meta.doc = Some(line.to_string()),
+    }
+}
+
+impl Attributes<'_> {
+    fn error(&self, msg: &str) -> String {
+        format!("{msg} at {}", location(self.input, self.pos))
+    }
+
+    fn take(&mut self, key: &str) -> Option<Option<Value>> {
+        let i = self.serde.iter().position(|(k, _)| *k == key)?;
+        Some(self.serde.remove(i).1)
+    }
+
+    fn value(&mut self, key: &str) -> Result<Option<Value>, String> {
+        match self.take(key) {
+            Some(None) => Err(self.error(&format!("serde({key}) takes a value"))),
+            value => Ok(value.flatten()),
+        }
+    }
+
+    fn string(&mut self, key: &str) -> Result<Option<String>, String> {
+        match self.value(key)? {
+            None => Ok(None),
+            Some(Value::String(s)) => Ok(Some(s)),
+            Some(_) => Err(self.error(&format!("serde({key}) takes a string"))),
+        }
+    }
+
+    fn strings(&mut self, key: &str) -> Result<Vec<String>, String> {
+        let mut strings = Vec::new();
+        while let Some(s) = self.string(key)? {
+            strings.push(s);
+        }
+        Ok(strings)
+    }
+
+    fn flag(&mut self, key: &str) -> Result<bool, String> {
+        match self.take(key) {
+            None => Ok(false),
+            Some(None) => Ok(true),
+            Some(Some(_)) => Err(self.error(&format!("serde({key}) takes no value"))),
+        }
+    }
+
+    fn rename_all(&mut self) -> Result<Option<RenameAll>, String> {
+        self.string("rename_all")?
+            .map(|rule| {
+                serde_json::from_value(serde_json::Value::String(rule))
+                    .map_err(|_| self.error("unknown rename_all rule"))
+            })
+            .transpose()
+    }
+
+    /// The metadata, if what the attributes are on can have it, failing on
+    /// the attributes that have not been taken.
+    fn finish(self, what: &str, meta: bool) -> Result<Metadata, String> {
+        if let Some((key, _)) = self.serde.first() {
+            return Err(self.error(&format!("unexpected serde({key}) on {what}")));
+        }
+        if self.repr.is_some() || self.index.is_some() {
+            return Err(self.error(&format!("unexpected attribute on {what}")));
+        }
+        if !meta && self.meta != Metadata::default() {
+            return Err(self.error(&format!("unexpected documentation on {what}")));
+        }
+        Ok(self.meta)
+    }
+}
@@ -0,0 +1,432 @@
+use std::cell::RefCell;
+
+use serde_json::{json, Map, Value};
+
+use crate::{
+    Constraints, Deprecated, Description, EnumRepr, EnumSchema, FieldsSchema, MapSchema, Metadata,
+    NamedFieldSchema, NamedFieldsSchema, RenameAll, Schema, SchemaItem, SchemaName, SimpleSchema,
+    StructSchema, VariantSchema,
+};
+
+impl Description {
+    /// Export the description as a JSON Schema (draft 2020-12) of the JSON
+    /// that `serde_json` writes for the described type. Named schemas become
+    /// `$defs`. Documentation and examples are `description` and `examples`;
+    /// deprecations are `deprecated`, with their note and version in
+    /// `x-deprecated`; annotations are `x-<key>` properties.
+    pub fn json_schema(&self) -> Value {
+        let exporter = Exporter {
+            descr: self,
+            flattening: RefCell::new(Vec::new()),
+        };
+        let mut root = Map::new();
+        root.insert(
+            "$schema".to_string(),
+            json!("https://json-schema.org/draft/2020-12/schema"),
+        );
+        root.extend(object(exporter.item(&self.schema)));
+        if !self.items.is_empty() {
+            let defs = self
+                .items
+                .iter()
+                .map(|(name, schema)| (name.to_string(), exporter.schema(schema)))
+                .collect();
+            root.insert("$defs".to_string(), Value::Object(defs));
+        }
+        Value::Object(root)
+    }
+
+    /// Look through names, newtypes and constraints to what `item` is
+    /// written as in JSON, if it is defined and not a cycle.
+    pub(crate) fn resolve_json<'s>(&'s self, mut item: &'s SchemaItem) -> Option<&'s Schema> {
+        // A chain of names longer than the description is a cycle.
+        for _ in 0..=self.items.len() + 1 {
+            let schema = match item {
+                SchemaItem::Schema(schema) => schema,
+                SchemaItem::Named(name) => self.items.get(name)?,
+            };
+            item = match schema {
+                Schema::Newtype(s) => &s.value,
+                Schema::Constrained(s) => &s.value,
+                Schema::HumanReadable(s) => &s.readable,
+                schema => return Some(schema),
+            };
+        }
+        None
+    }
+
+    /// Whether serde rejects JSON without the field. Missing options decode
+    /// as `None`.
+    pub(crate) fn json_required(&self, field: &NamedFieldSchema) -> bool {
+        field.default.is_none()
+            && !field.skip_deserializing
+            && field.skip_serializing_if.is_none()
+            && !matches!(self.resolve_json(&field.value), Some(Schema::Option(_)))
+    }
+}
+
+struct Exporter<'a> {
+    descr: &'a Description,
+    /// The flattened maps whose values are being exported. Their values are
+    /// inlined, so one that holds itself would never end.
+    flattening: RefCell<Vec<*const MapSchema>>,
+}
+
+/// What a value looks like in JSON, which decides the keywords that
+/// constraints become.
+#[derive(PartialEq, Clone, Copy)]
+enum Kind {
+    Number,
+    String,
+    Array,
+    Object,
+    Other,
+}
+
+impl Exporter<'_> {
+    fn item(&self, item: &SchemaItem) -> Value {
+        match item {
+            SchemaItem::Schema(schema) => self.schema(schema),
+            SchemaItem::Named(name) => json!({ "$ref": def_ref(name) }),
+        }
+    }
+
+    fn schema(&self, schema: &Schema) -> Value {
+        match schema {
+            Schema::Simple(s) => simple(*s),
+            Schema::Option(s) => json!({ "anyOf": [self.item(&s.value), { "type": "null" }] }),
+            Schema::Tuple(s) => self.tuple(&s.values),
+            Schema::Seq(s) => json!({ "type": "array", "items": self.item(&s.value) }),
+            Schema::Set(s) => json!({
+                "type": "array",
+                "items": self.item(&s.value),
+                "uniqueItems": true,
+            }),
+            Schema::Array(s) => json!({
+                "type": "array",
+                "items": self.item(&s.value),
+                "minItems": s.len,
+                "maxItems": s.len,
+            }),
+            Schema::Map(s) => {
+                let mut map = Map::new();
+                map.insert("type".to_string(), json!("object"));
+                map.insert("additionalProperties".to_string(), self.item(&s.value));
+                // Keys are written as strings, numbers included.
+                match self.kind(&s.key) {
+                    Kind::String => {
+                        let keys = self.item(&s.key);
+                        if keys != json!({ "type": "string" }) {
+                            map.insert("propertyNames".to_string(), keys);
+                        }
+                    }
+                    Kind::Number => {
+                        map.insert(
+                            "propertyNames".to_string(),
+                            json!({ "pattern": "^-?[0-9]+(\\.[0-9]+)?$" }),
+                        );
+                    }
+                    _ => {}
+                }
+                Value::Object(map)
+            }
+            Schema::Struct(s) => self.struct_schema(s),
+            Schema::Enum(s) => self.enum_schema(s),
+            // serde_json writes newtype structs as their content.
+            Schema::Newtype(s) => self.item(&s.value),
+            Schema::UnitStruct(_) => json!({ "type": "null" }),
+            Schema::HumanReadable(s) => self.item(&s.readable),
+            Schema::Constrained(s) => {
+                let mut map = object(self.item(&s.value));
+                map.extend(constraints(&s.constraints, self.kind(&s.value)));
+                Value::Object(map)
+            }
+            Schema::Any => json!({}),
+        }
+    }
+
+    fn tuple(&self, values: &[SchemaItem]) -> Value {
+        json!({
+            "type": "array",
+            "prefixItems": values.iter().map(|v| self.item(v)).collect::<Vec<_>>(),
+            "items": false,
+            "minItems": values.len(),
+        })
+    }
+
+    fn struct_schema(&self, s: &StructSchema) -> Value {
+        let mut map = match &s.fields {
+            FieldsSchema::Tuple(t) => object(self.tuple(&t.values)),
+            FieldsSchema::Named(fields) => self.fields(fields, s.rename_all),
+        };
+        map.extend(metadata(&s.meta));
+        Value::Object(map)
+    }
+
+    fn fields(
+        &self,
+        fields: &NamedFieldsSchema,
+        rename_all: Option<RenameAll>,
+    ) -> Map<String, Value> {
+        let mut properties = Map::new();
+        let mut required = Vec::new();
+        let mut flattened = Vec::new();
+        let mut unevaluated = None;
+        for field in fields.fields.iter().filter(|f| !f.skip_serializing) {
+            if field.flatten {
+                // A flattened map takes the entries no other field claims.
+                match self.resolve(&field.value) {
+                    Some(Schema::Map(map)) => {
+                        let ptr = std::ptr::from_ref(map);
+                        if self.flattening.borrow().contains(&ptr) {
+                            unevaluated = Some(json!({}));
+                            continue;
+                        }
+                        self.flattening.borrow_mut().push(ptr);
+                        unevaluated = Some(self.item(&map.value));
+                        self.flattening.borrow_mut().pop();
+                    }
+                    Some(Schema::Any) => {}
+                    _ => flattened.push(self.item(&field.value)),
+                }
+                continue;
+            }
+            let name = field.wire_name(rename_all).into_owned();
+            if self.descr.json_required(field) {
+                required.push(json!(name));
+            }
+            properties.insert(name, self.field(field));
+        }
+        let mut map = Map::new();
+        map.insert("type".to_string(), json!("object"));
+        map.insert("properties".to_string(), Value::Object(properties));
+        if !required.is_empty() {
+            map.insert("required".to_string(), Value::Array(required));
+        }
+        if !flattened.is_empty() {
+            map.insert("allOf".to_string(), Value::Array(flattened));
+        }
+        if let Some(unevaluated) = unevaluated {
+            map.insert("unevaluatedProperties".to_string(), unevaluated);
+        }
+        map
+    }
+
+    fn field(&self, field: &NamedFieldSchema) -> Value {
+        let mut map = object(self.item(&field.value));
+        if let Some(default) = &field.default {
+            if let Ok(default) = serde_json::to_value(default) {
+                map.insert("default".to_string(), default);
+            }
+        }
+        map.extend(metadata(&field.meta));
+        Value::Object(map)
+    }
+
+    fn enum_schema(&self, s: &EnumSchema) -> Value {
+        let mut map = match &s.repr {
+            EnumRepr::Integer { repr } => {
+                let mut map = object(simple(*repr));
+                map.insert("enum".to_string(), json!(s.discriminants()));
+                map
+            }
+            repr => {
+                let variants = s
+                    .variants
+                    .iter()
+                    .map(|v| {
+                        let name = v.wire_name(s.rename_all).into_owned();
+                        let mut map = object(self.variant(repr, name, v));
+                        map.extend(metadata(&v.meta));
+                        Value::Object(map)
+                    })
+                    .collect();
+                let mut map = Map::new();
+                map.insert("oneOf".to_string(), Value::Array(variants));
+                map
+            }
+        };
+        map.extend(metadata(&s.meta));
+        Value::Object(map)
+    }
+
+    fn variant(&self, repr: &EnumRepr, name: String, v: &VariantSchema) -> Value {
+        let content = match &v.fields {
+            FieldsSchema::Tuple(t) if t.values.is_empty() => None,
+            FieldsSchema::Tuple(t) if t.values.len() == 1 => Some(self.item(&t.values[0])),
+            FieldsSchema::Tuple(t) => Some(self.tuple(&t.values)),
+            FieldsSchema::Named(fields) => Some(Value::Object(self.fields(fields, None))),
+        };
+        let tagged = |properties: Map<String, Value>| {
+            let required: Vec<_> = properties.keys().cloned().collect();
+            json!({ "type": "object", "properties": properties, "required": required })
+        };
+        match (repr, content) {
+            (EnumRepr::ExternallyTagged, None) => json!({ "const": name }),
+            (EnumRepr::ExternallyTagged, Some(content)) => {
+                let mut map = object(tagged(Map::from_iter([(name, content)])));
+                map.insert("additionalProperties".to_string(), json!(false));
+                Value::Object(map)
+            }
+            (EnumRepr::InternallyTagged { tag }, content) => {
+                let tag = tagged(Map::from_iter([(tag.clone(), json!({ "const": name }))]));
+                match content {
+                    None => tag,
+                    Some(content) => json!({ "allOf": [tag, content] }),
+                }
+            }
+            (EnumRepr::AdjacentlyTagged { tag, content: key }, content) => {
+                let mut properties = Map::from_iter([(tag.clone(), json!({ "const": name }))]);
+                if let Some(content) = content {
+                    properties.insert(key.clone(), content);
+                }
+                tagged(properties)
+            }
+            (EnumRepr::Integer { .. }, _) => unreachable!("integer enums have no variant schemas"),
+        }
+    }
+
+    fn resolve<'s>(&'s self, item: &'s SchemaItem) -> Option<&'s Schema> {
+        self.descr.resolve_json(item)
+    }
+
+    fn kind<'s>(&'s self, mut item: &'s SchemaItem) -> Kind {
+        // Options of options only end if they don't go round in circles.
+        let mut options = Vec::new();
+        while let Some(Schema::Option(s)) = self.resolve(item) {
+            if options.contains(&std::ptr::from_ref(s)) {
+                return Kind::Other;
+            }
+            options.push(std::ptr::from_ref(s));
+            item = &s.value;
+        }
+        match self.resolve(item) {
+            Some(Schema::Simple(s)) => match s {
+                SimpleSchema::Char | SimpleSchema::String => Kind::String,
+                SimpleSchema::Bytes => Kind::Array,
+                SimpleSchema::Unit | SimpleSchema::Bool => Kind::Other,
+                _ => Kind::Number,
+            },
+            Some(Schema::Seq(_) | Schema::Set(_) | Schema::Array(_) | Schema::Tuple(_)) => {
+                Kind::Array
+            }
+            Some(Schema::Map(_)) => Kind::Object,
+            _ => Kind::Other,
+        }
+    }
+}
+
+fn simple(s: SimpleSchema) -> Value {
+    let integer = |min: i64, max: u64| json!({ "type": "integer", "minimum": min, "maximum": max });
+    match s {
+        SimpleSchema::Unit => json!({ "type": "null" }),
+        SimpleSchema::Bool => json!({ "type": "boolean" }),
+        SimpleSchema::U8 => integer(0, u8::MAX.into()),
+        SimpleSchema::U16 => integer(0, u16::MAX.into()),
+        SimpleSchema::U32 => integer(0, u32::MAX.into()),
+        SimpleSchema::U64 => integer(0, u64::MAX),
+        SimpleSchema::U128 => json!({ "type": "integer", "minimum": 0 }),
+        SimpleSchema::I8 => integer(i8::MIN.into(), i8::MAX as u64),
+        SimpleSchema::I16 => integer(i16::MIN.into(), i16::MAX as u64),
+        SimpleSchema::I32 => integer(i32::MIN.into(), i32::MAX as u64),
+        SimpleSchema::I64 => integer(i64::MIN, i64::MAX as u64),
+        SimpleSchema::I128 => json!({ "type": "integer" }),
+        SimpleSchema::F32 | SimpleSchema::F64 => json!({ "type": "number" }),
+        SimpleSchema::Char => json!({ "type": "string", "minLength": 1, "maxLength": 1 }),
+        SimpleSchema::String => json!({ "type": "string" }),
+        SimpleSchema::Bytes => json!({
+            "type": "array",
+            "items": { "type": "integer", "minimum": 0, "maximum": 255 },
+        }),
+    }
+}
+
+fn constraints(c: &Constraints, kind: Kind) -> Map<String, Value> {
+    let mut map = Map::new();
+    let mut set = |key: &str, value: Value| {
+        map.insert(key.to_string(), value);
+    };
+    let (min_length, max_length) = match kind {
+        Kind::String => ("minLength", "maxLength"),
+        Kind::Array => ("minItems", "maxItems"),
+        Kind::Object => ("minProperties", "maxProperties"),
+        Kind::Number | Kind::Other => ("", ""),
+    };
+    if kind == Kind::Number {
+        if let Some(min) = &c.min {
+            set("minimum", Value::Number(min.clone()));
+        }
+        if let Some(max) = &c.max {
+            set("maximum", Value::Number(max.clone()));
+        }
+    }
+    if !min_length.is_empty() {
+        if let Some(len) = c.min_length {
+            set(min_length, json!(len));
+        }
+        if let Some(len) = c.max_length {
+            set(max_length, json!(len));
+        }
+    }
+    if let (Kind::String, Some(pattern)) = (kind, &c.pattern) {
+        set("pattern", json!(pattern));
+    }
+    if kind == Kind::Array && c.unique {
+        set("uniqueItems", json!(true));
+    }
+    if let (Kind::Object, Some(pattern)) = (kind, &c.key_pattern) {
+        set("propertyNames", json!({ "pattern": pattern }));
+    }
+    map
+}
+
+fn metadata(meta: &Metadata) -> Map<String, Value> {
+    let mut map = Map::new();
+    if let Some(doc) = &meta.doc {
+        map.insert("description".to_string(), json!(doc));
+    }
+    if let Some(deprecated) = &meta.deprecated {
+        map.insert("deprecated".to_string(), json!(true));
+        // JSON Schema has no keyword for why, or since when.
+        if *deprecated != Deprecated::default() {
+            map.insert("x-deprecated".to_string(), json!(deprecated));
+        }
+    }
+    let examples: Vec<_> = meta
+        .examples
+        .iter()
+        .filter_map(|example| serde_json::to_value(example).ok())
+        .collect();
+    if !examples.is_empty() {
+        map.insert("examples".to_string(), Value::Array(examples));
+    }
+    for (key, value) in &meta.annotations {
+        map.insert(format!("x-{key}"), json!(value));
+    }
+    map
+}
+
+/// A schema as an object, to add keywords to. `$ref` may have siblings since
+/// draft 2019-09.
+fn object(value: Value) -> Map<String, Value> {
+    match value {
+        Value::Object(map) => map,
+        value => Map::from_iter([("allOf".to_string(), json!([value]))]),
+    }
+}
+
+/// A reference to the definition of a named schema: a JSON pointer, escaped
+/// as a URI fragment.
+fn def_ref(name: &SchemaName) -> String {
+    let pointer = name.to_string().replace('~', "~0").replace('/', "~1");
+    let mut fragment = String::new();
+    for byte in pointer.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                fragment.push(byte as char)
+            }
+            byte => fragment.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    format!("#/$defs/{fragment}")
+}
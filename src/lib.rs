@@ -1,15 +1,46 @@
+mod annotate;
+pub mod any;
+mod avro;
+mod changes;
 mod describe;
 mod deserializer;
+mod diff;
+mod external;
+#[cfg(feature = "arbitrary")]
+mod fuzz;
+mod generate;
+mod idl;
+mod json_schema;
+mod lint;
+mod markdown;
+mod project;
+mod protobuf;
+mod query;
 mod schema;
-// mod seed;
-// mod value;
+mod serializer;
+pub mod snapshot;
+mod typescript;
+mod validate;
 
+pub use annotate::Annotation;
+pub use changes::{ChangeKind, Element, SchemaChange};
 pub use describe::{Describe, Description};
+pub use deserializer::{
+    DeserializerOptions, EnumTag, IntEncoding, OptionFormat, SchemaDecoder, SchemaDeserializer,
+    SchemaSeed, StructFormat,
+};
+pub use diff::ValueChange;
+pub use generate::Generator;
+pub use lint::Lint;
+pub use project::{ProjectSeed, Projection};
+pub use query::{Query, QuerySeed};
 pub use schema::{
-    EnumSchema, Expected, FieldsSchema, MapSchema, NamedFieldSchema, NamedFieldsSchema,
-    OptionSchema, Schema, SchemaItem, SchemaName, SeqSchema, SimpleSchema, StructSchema,
-    TupleSchema, VariantSchema,
+    ArraySchema, ConstrainedSchema, Constraints, Deprecated, EnumRepr, EnumSchema, Expected,
+    FieldsSchema, HumanReadableSchema, MapSchema, Metadata, NamedFieldSchema, NamedFieldsSchema,
+    NewtypeSchema, OptionSchema, RenameAll, Schema, SchemaItem, SchemaName, SeqSchema, SetSchema,
+    SimpleSchema, StructSchema, TupleSchema, UnitStructSchema, VariantSchema,
 };
-// pub use seed::SchemaSeed;
+pub use serializer::SchemaValue;
+pub use validate::Violation;
 
 pub(crate) use schema::is_default;
@@ -0,0 +1,111 @@
+use std::fmt::Display;
+
+use crate::{
+    Description, DeserializerOptions, FieldsSchema, NamedFieldsSchema, Schema, SchemaItem,
+    StructFormat,
+};
+
+/// Something in a description that a profile can't decode reliably, at a
+/// path like `Type.field` or `Enum::Variant.field`.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Lint {
+    pub path: String,
+    pub message: String,
+}
+
+impl Display for Lint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+impl Description {
+    /// Check the description against the profile it will be decoded with.
+    pub fn lint(&self, opts: &DeserializerOptions) -> Vec<Lint> {
+        let mut lints = Vec::new();
+        if let SchemaItem::Schema(schema) = &self.schema {
+            lint_schema(String::new(), schema, opts, &mut lints);
+        }
+        for (name, schema) in &self.items {
+            lint_schema(name.to_string(), schema, opts, &mut lints);
+        }
+        lints
+    }
+}
+
+fn lint_item(path: String, item: &SchemaItem, opts: &DeserializerOptions, lints: &mut Vec<Lint>) {
+    // Named schemas are checked on their own.
+    if let SchemaItem::Schema(schema) = item {
+        lint_schema(path, schema, opts, lints);
+    }
+}
+
+fn lint_schema(path: String, schema: &Schema, opts: &DeserializerOptions, lints: &mut Vec<Lint>) {
+    match schema {
+        Schema::Simple(_) | Schema::UnitStruct(_) | Schema::Any => {}
+        Schema::Option(s) => lint_item(path, &s.value, opts, lints),
+        Schema::Seq(s) => lint_item(format!("{path}[]"), &s.value, opts, lints),
+        Schema::Set(s) => lint_item(format!("{path}[]"), &s.value, opts, lints),
+        Schema::Array(s) => lint_item(format!("{path}[]"), &s.value, opts, lints),
+        Schema::Map(s) => {
+            lint_item(path.clone(), &s.key, opts, lints);
+            lint_item(format!("{path}[]"), &s.value, opts, lints);
+        }
+        Schema::Tuple(s) => s.values.iter().enumerate().for_each(|(i, value)| {
+            lint_item(format!("{path}.{i}"), value, opts, lints);
+        }),
+        Schema::Newtype(s) => lint_item(path, &s.value, opts, lints),
+        Schema::Constrained(s) => lint_item(path, &s.value, opts, lints),
+        Schema::HumanReadable(s) => {
+            lint_item(path.clone(), &s.readable, opts, lints);
+            lint_item(path, &s.compact, opts, lints);
+        }
+        Schema::Struct(s) => lint_fields(path, &s.fields, opts, lints),
+        Schema::Enum(s) => s.variants.iter().for_each(|v| {
+            lint_fields(format!("{path}::{}", v.name), &v.fields, opts, lints);
+        }),
+    }
+}
+
+fn lint_fields(
+    path: String,
+    fields: &FieldsSchema,
+    opts: &DeserializerOptions,
+    lints: &mut Vec<Lint>,
+) {
+    match fields {
+        FieldsSchema::Tuple(s) => s.values.iter().enumerate().for_each(|(i, value)| {
+            lint_item(format!("{path}.{i}"), value, opts, lints);
+        }),
+        FieldsSchema::Named(s) => lint_named_fields(path, s, opts, lints),
+    }
+}
+
+fn lint_named_fields(
+    path: String,
+    fields: &NamedFieldsSchema,
+    opts: &DeserializerOptions,
+    lints: &mut Vec<Lint>,
+) {
+    for field in &fields.fields {
+        let path = format!("{path}.{}", field.name);
+        if opts.struct_format == StructFormat::Tuple && !field.skip_serializing {
+            let mut lint = |message: String| {
+                lints.push(Lint {
+                    path: path.clone(),
+                    message,
+                })
+            };
+            if field.flatten {
+                lint("flattened fields need a format that writes field names".to_string());
+            }
+            if let Some(predicate) = &field.skip_serializing_if {
+                lint(format!(
+                    "skip_serializing_if = {predicate:?} makes the field's presence depend on \
+                     its value, which a format without field names can't express"
+                ));
+            }
+        }
+        lint_item(path, &field.value, opts, lints);
+    }
+}
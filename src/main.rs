@@ -1,120 +1,548 @@
-// use bincode::Options;
-use serde::{Deserialize, Serialize};
-use serde_descr::{
-    Describe, Description, EnumSchema, NamedFieldSchema, NamedFieldsSchema, SchemaName,
-    StructSchema, TupleSchema, VariantSchema,
+use std::{
+    cell::RefCell,
+    io::{Read, Write},
+    marker::PhantomData,
+    process::ExitCode,
+    rc::Rc,
 };
 
-fn main() {
-    let my_struct_schema = MyStruct::<MyEnum>::describe();
-
-    // let value = MyStruct {
-    //     string: String::from("another"),
-    //     int: 42,
-    //     r#enum: MyEnum::StructVariant { float_field: 42.1 },
-    // };
-
-    // let options = bincode::DefaultOptions::new();
-    // let bytes = options.serialize(&value).unwrap();
-    // let value1 = options
-    //     .deserialize_seed(&SchemaSeed::new(&my_struct_schema), &bytes)
-    //     .unwrap();
-
-    // let bytes = serde_json::to_vec(&value2).unwrap();
-    // let value1 = serde_json::from_slice::<MyStruct>(&bytes).unwrap();
-
-    println!(
-        "Schema: {}",
-        serde_json::to_string_pretty(&my_struct_schema).unwrap()
-    );
-}
-
-#[derive(Serialize, Deserialize)]
-struct MyStruct<T> {
-    string: String,
-    int: u64,
-    r#enum: T,
-}
-
-#[derive(Serialize, Deserialize)]
-struct MyStruct2 {
-    new_field: String,
-    string: String,
-    int: u64,
-    r#enum: MyEnum,
-}
-
-#[derive(Serialize, Deserialize)]
-enum MyEnum {
-    UnitVariant,
-    NewtypeVariant(String),
-    TupleVariant(String, u64),
-    StructVariant { float_field: f64 },
-}
-
-impl<T: Describe> Describe for MyStruct<T> {
-    fn schema_name() -> SchemaName {
-        SchemaName::new("MyStruct").argument(T::schema_name())
-    }
-
-    fn add_schema(map: &mut Description) {
-        map.add(Self::schema_name(), || {
-            StructSchema::new(
-                "MyStruct",
-                NamedFieldsSchema::new()
-                    .field(NamedFieldSchema::new("string", String::schema()))
-                    .field(NamedFieldSchema::new("int", u64::schema()))
-                    .field(NamedFieldSchema::new("enum", T::schema())),
-            )
-        });
-        T::add_schema(map);
-    }
-}
-
-impl Describe for MyStruct2 {
-    fn schema_name() -> SchemaName {
-        SchemaName::new("MyStruct2")
-    }
-
-    fn add_schema(map: &mut Description) {
-        map.add(Self::schema_name(), || {
-            StructSchema::new(
-                "MyStruct2",
-                NamedFieldsSchema::new()
-                    .field(NamedFieldSchema::new("new_field", String::schema()))
-                    .field(NamedFieldSchema::new("string", String::schema()))
-                    .field(NamedFieldSchema::new("int", u64::schema()))
-                    .field(NamedFieldSchema::new("enum", MyEnum::schema())),
-            )
-        });
-        MyEnum::add_schema(map);
-    }
-}
-
-impl Describe for MyEnum {
-    fn schema_name() -> SchemaName {
-        SchemaName::new("MyEnum")
-    }
-
-    fn add_schema(map: &mut Description) {
-        map.add(Self::schema_name(), || {
-            EnumSchema::new("MyEnum")
-                .variant(VariantSchema::new("UnitVariant", TupleSchema::new()))
-                .variant(VariantSchema::new(
-                    "NewtypeVariant",
-                    TupleSchema::new().element(String::schema()),
-                ))
-                .variant(VariantSchema::new(
-                    "TupleVariant",
-                    TupleSchema::new()
-                        .element(String::schema())
-                        .element(u64::schema()),
-                ))
-                .variant(VariantSchema::new(
-                    "StructVariant",
-                    NamedFieldsSchema::new()
-                        .field(NamedFieldSchema::new("float_field", f64::schema())),
-                ))
-        });
+use bincode::Options;
+use serde::{
+    de::{DeserializeSeed, Error},
+    Deserialize, Deserializer,
+};
+use serde_descr::{Description, DeserializerOptions, Generator, IntEncoding, Query, SchemaDecoder};
+use serde_value::Value;
+
+const USAGE: &str = "\
+usage: serde-descr COMMAND [OPTIONS] [ARGS]
+
+Commands:
+  show                  print a description in its text form, or --to json
+  validate [INPUT]      decode values and check the constraints of the schema
+  transcode [INPUT]     decode values and write them in the format of --to
+  dump [INPUT]          print the schema of every part of the values
+  query QUERY [INPUT]   print what QUERY selects in the values, as JSON
+  diff OLD NEW          list the changes from one description to another,
+                        as text or --to json
+  compat OLD NEW        check that NEW decodes sample values written with OLD
+  fingerprint           print a hash of a description
+  export                write a description as --to jsonschema, ts, proto,
+                        avro or md
+  gen                   write sample values of the schema
+
+Options:
+  --schema FILE         the description of the values, as JSON or in the text
+                        form; show, fingerprint and export read it from stdin
+                        without one
+  --format FORMAT       the format of the values read or written, and the one
+                        diff checks changes against: json (the default),
+                        bincode, bincode-fixint or bitcode
+  --to FORMAT           the output format of show, diff, transcode and export
+  --count N             how many values gen (1) and compat (100) make
+  --seed N              the seed of the values gen and compat make (0)
+
+Values are read from INPUT, or stdin if it is missing or -, and written to
+stdout. JSON input holds any number of values, JSON output one per line.
+
+Exit status: 0 on success, 1 when the input does not decode or validate,
+diff finds breaking changes or compat samples that fail to decode, and 2 on
+bad arguments or I/O errors.";
+
+/// The formats values are read and written in.
+const FORMATS: &str = "json, bincode, bincode-fixint or bitcode";
+
+/// Why a command failed, which decides the exit status.
+enum Failure {
+    /// The input was checked and found wanting: exit status 1.
+    Check(String),
+    /// The command could not run: exit status 2.
+    Error(String),
+}
+
+impl From<String> for Failure {
+    fn from(error: String) -> Self {
+        Failure::Error(error)
+    }
+}
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let Some((command, rest)) = args.split_first() else {
+        eprintln!("{USAGE}");
+        return ExitCode::from(2);
+    };
+    let result = Args::parse(rest).and_then(|args| match command.as_str() {
+        "show" => show(&args),
+        "validate" => validate(&args),
+        "transcode" => transcode(&args),
+        "dump" => dump(&args),
+        "query" => query(&args),
+        "diff" => diff(&args),
+        "compat" => compat(&args),
+        "fingerprint" => fingerprint(&args),
+        "export" => export(&args),
+        "gen" => generate(&args),
+        "help" | "--help" | "-h" => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        _ => Err(Failure::Error(format!(
+            "unknown command {command:?}\n\n{USAGE}"
+        ))),
+    });
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(Failure::Check(message)) => {
+            eprintln!("{message}");
+            ExitCode::from(1)
+        }
+        Err(Failure::Error(message)) => {
+            eprintln!("error: {message}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+#[derive(Default)]
+struct Args {
+    schema: Option<String>,
+    format: Option<String>,
+    to: Option<String>,
+    count: Option<usize>,
+    seed: Option<u64>,
+    positional: Vec<String>,
+}
+
+impl Args {
+    fn parse(args: &[String]) -> Result<Self, Failure> {
+        let mut parsed = Args::default();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .cloned()
+                    .ok_or_else(|| format!("{arg} takes a value\n\n{USAGE}"))
+            };
+            let mut number = || -> Result<u64, String> {
+                let number = value()?;
+                number
+                    .parse()
+                    .map_err(|_| format!("{arg} takes a number, not {number:?}"))
+            };
+            match arg.as_str() {
+                "--schema" => parsed.schema = Some(value()?),
+                "--format" => parsed.format = Some(value()?),
+                "--to" => parsed.to = Some(value()?),
+                "--count" => parsed.count = Some(number()? as usize),
+                "--seed" => parsed.seed = Some(number()?),
+                "-" => parsed.positional.push(arg.clone()),
+                _ if arg.starts_with('-') => {
+                    return Err(Failure::Error(format!("unknown option {arg}\n\n{USAGE}")))
+                }
+                _ => parsed.positional.push(arg.clone()),
+            }
+        }
+        Ok(parsed)
+    }
+
+    /// The positional arguments, if there are between `min` and `max`.
+    fn positional(&self, min: usize, max: usize) -> Result<&[String], Failure> {
+        match self.positional.len() {
+            n if (min..=max).contains(&n) => Ok(&self.positional),
+            _ => Err(Failure::Error(USAGE.to_string())),
+        }
+    }
+
+    /// The positional arguments, if there are exactly `N`.
+    fn exactly<const N: usize>(&self) -> Result<[&str; N], Failure> {
+        let positional: Vec<_> = self.positional.iter().map(String::as_str).collect();
+        positional
+            .try_into()
+            .map_err(|_| Failure::Error(USAGE.to_string()))
+    }
+
+    /// The description given by `--schema`, or read from stdin if `stdin`.
+    fn description(&self, stdin: bool) -> Result<Description, Failure> {
+        match (&self.schema, stdin) {
+            (Some(path), _) => read_description(path),
+            (None, true) => read_description("-"),
+            (None, false) => Err(Failure::Error(format!("missing --schema\n\n{USAGE}"))),
+        }
+    }
+
+    fn format(&self) -> Result<Format, Failure> {
+        self.format
+            .as_deref()
+            .map_or(Ok(Format::Json), Format::parse)
+    }
+
+    fn to(&self) -> Result<&str, Failure> {
+        self.to
+            .as_deref()
+            .ok_or_else(|| Failure::Error(format!("missing --to\n\n{USAGE}")))
+    }
+}
+
+fn read(path: &str) -> Result<Vec<u8>, Failure> {
+    let mut bytes = Vec::new();
+    match path {
+        "-" => std::io::stdin()
+            .read_to_end(&mut bytes)
+            .map_err(|e| format!("stdin: {e}"))?,
+        path => std::fs::File::open(path)
+            .and_then(|mut file| file.read_to_end(&mut bytes))
+            .map_err(|e| format!("{path}: {e}"))?,
+    };
+    Ok(bytes)
+}
+
+/// Read a description, as JSON if it is an object and in the text form of
+/// `Description::idl` otherwise.
+fn read_description(path: &str) -> Result<Description, Failure> {
+    let bytes = read(path)?;
+    let text = String::from_utf8_lossy(&bytes);
+    let description = match text.trim_start().starts_with('{') {
+        true => serde_json::from_str(&text).map_err(|e| e.to_string()),
+        false => Description::from_idl(&text),
+    };
+    description.map_err(|e| Failure::Error(format!("{path}: invalid description: {e}")))
+}
+
+fn write(bytes: &[u8]) -> Result<(), Failure> {
+    let mut stdout = std::io::stdout().lock();
+    stdout
+        .write_all(bytes)
+        .and_then(|()| stdout.flush())
+        .map_err(|e| Failure::Error(format!("stdout: {e}")))
+}
+
+/// Decode the values in the input, the positional argument at `index`.
+fn decode(args: &Args, index: usize, description: &Description) -> Result<Vec<Value>, Failure> {
+    let input = read(args.positional.get(index).map_or("-", String::as_str))?;
+    args.format()?
+        .decode(description, &input)
+        .map_err(Failure::Check)
+}
+
+#[derive(Clone, Copy)]
+enum Format {
+    Json,
+    Bincode,
+    BincodeFixint,
+    Bitcode,
+}
+
+impl Format {
+    fn parse(format: &str) -> Result<Self, Failure> {
+        match format {
+            "json" => Ok(Format::Json),
+            "bincode" => Ok(Format::Bincode),
+            "bincode-fixint" => Ok(Format::BincodeFixint),
+            "bitcode" => Ok(Format::Bitcode),
+            // The library has profiles for these, but the tool doesn't
+            // read or write them.
+            "postcard" | "msgpack" | "msgpack-compact" | "msgpack-named" | "cbor" => Err(
+                Failure::Error(format!("unsupported format {format:?}: expected {FORMATS}")),
+            ),
+            _ => Err(Failure::Error(format!(
+                "unknown format {format:?}: expected {FORMATS}"
+            ))),
+        }
+    }
+
+    fn options(self) -> DeserializerOptions {
+        match self {
+            Format::Json => DeserializerOptions::json(),
+            Format::Bincode => DeserializerOptions::bincode_varint(),
+            Format::BincodeFixint => DeserializerOptions::bincode_fixint(),
+            Format::Bitcode => DeserializerOptions::bitcode(),
+        }
+    }
+
+    /// All the values in `input`. Bitcode values don't say where they end,
+    /// so bitcode input holds one.
+    fn decode(self, description: &Description, input: &[u8]) -> Result<Vec<Value>, String> {
+        let opts = self.options();
+        match self {
+            Format::Bitcode => {
+                let scope = Rc::new((description.clone(), opts));
+                BITCODE.set(Some(scope));
+                let decoded = bitcode::deserialize(input);
+                BITCODE.set(None);
+                let Bitcode(value) = decoded.map_err(|e| e.to_string())?;
+                Ok(vec![value])
+            }
+            _ => {
+                let decoder = SchemaDecoder::new(description);
+                self.decode_seed(input, || decoder.seed(&opts, PhantomData))
+            }
+        }
+    }
+
+    /// What a seed from `seed` reads from each of the values in `input`, in
+    /// any format but bitcode, which takes no seed.
+    fn decode_seed<F, S, T>(self, input: &[u8], seed: F) -> Result<Vec<T>, String>
+    where
+        F: Fn() -> S,
+        S: for<'de> DeserializeSeed<'de, Value = T>,
+    {
+        let mut values = Vec::new();
+        match self {
+            Format::Json => {
+                for json in serde_json::Deserializer::from_slice(input).into_iter() {
+                    let json: serde_json::Value = json.map_err(|e| e.to_string())?;
+                    let value = seed()
+                        .deserialize(json)
+                        .map_err(|e| format!("value {}: {e}", values.len() + 1))?;
+                    values.push(value);
+                }
+            }
+            Format::Bincode | Format::BincodeFixint => {
+                let mut rest = input;
+                while !rest.is_empty() {
+                    let value = match self.options().int_encoding() {
+                        IntEncoding::Varint => {
+                            bincode::DefaultOptions::new().deserialize_from_seed(seed(), &mut rest)
+                        }
+                        _ => bincode::DefaultOptions::new()
+                            .with_fixint_encoding()
+                            .deserialize_from_seed(seed(), &mut rest),
+                    };
+                    values.push(value.map_err(|e| format!("value {}: {e}", values.len() + 1))?);
+                }
+            }
+            Format::Bitcode => unreachable!("bitcode takes no seed"),
+        }
+        Ok(values)
+    }
+
+    fn encode(self, decoder: &SchemaDecoder, value: &Value) -> Result<Vec<u8>, String> {
+        let value = decoder.serializable(value);
+        match self {
+            Format::Json => serde_json::to_vec(&value)
+                .map(|mut json| {
+                    json.push(b'\n');
+                    json
+                })
+                .map_err(|e| e.to_string()),
+            Format::Bincode | Format::BincodeFixint => match self.options().int_encoding() {
+                IntEncoding::Varint => bincode::DefaultOptions::new().serialize(&value),
+                _ => bincode::DefaultOptions::new()
+                    .with_fixint_encoding()
+                    .serialize(&value),
+            }
+            .map_err(|e| e.to_string()),
+            Format::Bitcode => bitcode::serialize(&value).map_err(|e| e.to_string()),
+        }
+    }
+}
+
+thread_local! {
+    /// The description and options [`Bitcode`] values decode with.
+    static BITCODE: RefCell<Option<Rc<(Description, DeserializerOptions)>>> =
+        const { RefCell::new(None) };
+}
+
+/// A value decoded with the description in [`BITCODE`], as bitcode only
+/// reads `DeserializeOwned` types and takes no seed.
+struct Bitcode(Value);
+
+impl<'de> Deserialize<'de> for Bitcode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let scope = BITCODE.with_borrow(Option::clone);
+        let scope = scope.ok_or_else(|| D::Error::custom("no description to decode with"))?;
+        let (description, opts) = &*scope;
+        SchemaDecoder::new(description)
+            .seed(opts, PhantomData)
+            .deserialize(deserializer)
+            .map(Bitcode)
+    }
+}
+
+fn show(args: &Args) -> Result<(), Failure> {
+    args.positional(0, 0)?;
+    let description = args.description(true)?;
+    let out = match args.to.as_deref() {
+        None | Some("idl") => description.idl(),
+        Some("json") => {
+            serde_json::to_string_pretty(&description).map_err(|e| e.to_string())? + "\n"
+        }
+        Some(to) => {
+            return Err(Failure::Error(format!(
+                "cannot show as {to:?}: expected idl or json"
+            )))
+        }
+    };
+    write(out.as_bytes())
+}
+
+fn validate(args: &Args) -> Result<(), Failure> {
+    args.positional(0, 1)?;
+    let description = args.description(false)?;
+    let decoder = SchemaDecoder::new(&description);
+    let values = decode(args, 0, &description)?;
+    let mut violations = Vec::new();
+    for (i, value) in values.iter().enumerate() {
+        // Decoding leaves out missing fields, which writing the value finds.
+        let missing = serde_json::to_value(decoder.serializable(value)).err();
+        let found = missing.map(|e| e.to_string()).into_iter();
+        for violation in found.chain(description.validate(value).iter().map(|v| v.to_string())) {
+            violations.push(match values.len() {
+                1 => violation,
+                _ => format!("value {}: {violation}", i + 1),
+            });
+        }
+    }
+    match violations.is_empty() {
+        true => Ok(()),
+        false => Err(Failure::Check(violations.join("\n"))),
+    }
+}
+
+fn transcode(args: &Args) -> Result<(), Failure> {
+    args.positional(0, 1)?;
+    let description = args.description(false)?;
+    let decoder = SchemaDecoder::new(&description);
+    let to = Format::parse(args.to()?)?;
+    let mut out = Vec::new();
+    for value in decode(args, 0, &description)? {
+        out.extend(to.encode(&decoder, &value)?);
+    }
+    write(&out)
+}
+
+fn dump(args: &Args) -> Result<(), Failure> {
+    args.positional(0, 1)?;
+    let description = args.description(false)?;
+    let decoder = SchemaDecoder::new(&description);
+    let mut out = String::new();
+    for (i, value) in decode(args, 0, &description)?.iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        for annotation in decoder.annotate(value) {
+            out.push_str(&format!("{annotation}\n"));
+        }
+    }
+    write(out.as_bytes())
+}
+
+fn query(args: &Args) -> Result<(), Failure> {
+    let positional = args.positional(1, 2)?;
+    let description = args.description(false)?;
+    let decoder = SchemaDecoder::new(&description);
+    let query = positional[0].parse::<Query>()?;
+    let format = args.format()?;
+    let opts = format.options();
+    let seed = query.seed(&decoder, &opts)?;
+    // Payloads are read through the query, decoding only what it selects,
+    // except in bitcode, which decodes whole values to run the query on.
+    let results = match format {
+        Format::Bitcode => decode(args, 1, &description)?
+            .iter()
+            .map(|value| query.eval(&decoder, value))
+            .collect::<Result<Vec<_>, _>>()?,
+        _ => {
+            let input = read(positional.get(1).map_or("-", String::as_str))?;
+            format
+                .decode_seed(&input, || &seed)
+                .map_err(Failure::Check)?
+        }
+    };
+    let mut out = String::new();
+    for result in results.iter().flatten() {
+        let json = serde_json::to_string(result).map_err(|e| e.to_string())?;
+        out.push_str(&format!("{json}\n"));
+    }
+    write(out.as_bytes())
+}
+
+/// List the changes from OLD to NEW, judged by the values of --format.
+fn diff(args: &Args) -> Result<(), Failure> {
+    let [old, new] = args.exactly()?;
+    let (old, new) = (read_description(old)?, read_description(new)?);
+    let changes = old.diff(&new, &args.format()?.options());
+    let out = match args.to.as_deref() {
+        None | Some("text") => changes.iter().map(|change| format!("{change}\n")).collect(),
+        Some("json") => serde_json::to_string_pretty(&changes).map_err(|e| e.to_string())? + "\n",
+        Some(to) => {
+            return Err(Failure::Error(format!(
+                "cannot list changes as {to:?}: expected text or json"
+            )))
+        }
+    };
+    write(out.as_bytes())?;
+    match changes.iter().filter(|change| change.breaking).count() {
+        0 => Ok(()),
+        n => Err(Failure::Check(format!(
+            "{n} of {} changes are breaking",
+            changes.len()
+        ))),
+    }
+}
+
+/// Write sample values of OLD and decode them with NEW.
+fn compat(args: &Args) -> Result<(), Failure> {
+    let [old, new] = args.exactly()?;
+    let (old, new) = (read_description(old)?, read_description(new)?);
+    let format = args.format()?;
+    let mut generator = Generator::new(&old).seed(args.seed.unwrap_or(0));
+    let mut failures = Vec::new();
+    let count = args.count.unwrap_or(100);
+    for i in 0..count {
+        let value = generator.generate()?;
+        let bytes = format.encode(generator.decoder(), &value)?;
+        if let Err(e) = format.decode(&new, &bytes) {
+            let sample = serde_json::to_string(&generator.decoder().serializable(&value))
+                .unwrap_or_else(|_| format!("{value:?}"));
+            failures.push(format!("sample {}: {e}\n  {sample}", i + 1));
+        }
+    }
+    match failures.len() {
+        0 => Ok(()),
+        n => Err(Failure::Check(format!(
+            "{}\n{n} of {count} samples failed to decode",
+            failures[..n.min(10)].join("\n")
+        ))),
+    }
+}
+
+fn fingerprint(args: &Args) -> Result<(), Failure> {
+    args.positional(0, 0)?;
+    let description = args.description(true)?;
+    write(format!("{:016x}\n", description.fingerprint()).as_bytes())
+}
+
+fn export(args: &Args) -> Result<(), Failure> {
+    args.positional(0, 0)?;
+    let description = args.description(true)?;
+    let pretty =
+        |json: serde_json::Value| serde_json::to_string_pretty(&json).map(|json| json + "\n");
+    let out = match args.to()? {
+        "jsonschema" => pretty(description.json_schema()).map_err(|e| e.to_string())?,
+        "ts" => description.typescript(),
+        "proto" => description.protobuf(),
+        "avro" => pretty(description.avro()).map_err(|e| e.to_string())?,
+        "md" => description.markdown(),
+        to => {
+            return Err(Failure::Error(format!(
+                "cannot export to {to:?}: expected jsonschema, ts, proto, avro or md"
+            )))
+        }
+    };
+    write(out.as_bytes())
+}
+
+fn generate(args: &Args) -> Result<(), Failure> {
+    args.positional(0, 0)?;
+    let description = args.description(false)?;
+    let format = args.format()?;
+    let mut generator = Generator::new(&description).seed(args.seed.unwrap_or(0));
+    let mut out = Vec::new();
+    for _ in 0..args.count.unwrap_or(1) {
+        let value = generator.generate()?;
+        out.extend(format.encode(generator.decoder(), &value)?);
     }
+    write(&out)
 }
@@ -0,0 +1,327 @@
+use crate::{
+    Description, EnumRepr, EnumSchema, FieldsSchema, Metadata, NamedFieldsSchema, RenameAll,
+    Schema, SchemaItem, SchemaName, SimpleSchema,
+};
+
+impl Description {
+    /// Document the description in Markdown: a section for every named
+    /// schema, with tables of the fields of structs and the variants of
+    /// enums. Fields and variants go by the names they are written with.
+    pub fn markdown(&self) -> String {
+        let mut out = match &self.schema {
+            SchemaItem::Named(name) => {
+                format!(
+                    "# {name}\n\nThe described type is {}.\n",
+                    type_of(&self.schema)
+                )
+            }
+            SchemaItem::Schema(schema) => {
+                let mut out = format!(
+                    "# Root\n\nThe described type is {}.\n",
+                    type_of(&self.schema)
+                );
+                out.push_str(&section(schema));
+                out
+            }
+        };
+        for (name, schema) in &self.items {
+            out.push_str(&format!("\n## `{name}`\n"));
+            out.push_str(&section(schema));
+        }
+        out
+    }
+}
+
+/// The description of a schema, under its heading.
+fn section(schema: &Schema) -> String {
+    match schema {
+        Schema::Struct(s) => {
+            let mut out = metadata(&s.meta);
+            match &s.fields {
+                FieldsSchema::Named(fields) => out.push_str(&fields_table(fields, s.rename_all)),
+                FieldsSchema::Tuple(t) => {
+                    out.push_str("\n| Index | Type |\n| --- | --- |\n");
+                    for (i, value) in t.values.iter().enumerate() {
+                        out.push_str(&format!("| {i} | {} |\n", type_of(value)));
+                    }
+                }
+            }
+            out
+        }
+        Schema::Enum(s) => enum_section(s),
+        Schema::Newtype(s) if s.transparent => {
+            format!("\nA transparent newtype of {}.\n", type_of(&s.value))
+        }
+        Schema::Newtype(s) => format!("\nA newtype of {}.\n", type_of(&s.value)),
+        Schema::UnitStruct(_) => "\nA unit struct.\n".to_string(),
+        schema => format!("\nAn alias of {}.\n", expression(schema).render()),
+    }
+}
+
+fn fields_table(fields: &NamedFieldsSchema, rename_all: Option<RenameAll>) -> String {
+    let mut out =
+        "\n| Field | Type | Required | Description |\n| --- | --- | --- | --- |\n".to_string();
+    for field in &fields.fields {
+        let mut notes = cell_metadata(&field.meta);
+        if let Some(default) = &field.default {
+            if let Ok(default) = serde_json::to_string(default) {
+                notes.push(format!("Defaults to `{}`.", cell(&default)));
+            }
+        }
+        if !field.aliases.is_empty() {
+            let aliases: Vec<_> = field.aliases.iter().map(|a| format!("`{a}`")).collect();
+            notes.push(format!("Also read as {}.", aliases.join(", ")));
+        }
+        if field.flatten {
+            notes.push("Its fields are flattened into this struct.".to_string());
+        }
+        match (field.skip_serializing, field.skip_deserializing) {
+            (true, true) => notes.push("Skipped.".to_string()),
+            (true, false) => notes.push("Never written.".to_string()),
+            (false, true) => notes.push("Ignored when read.".to_string()),
+            (false, false) => {}
+        }
+        let required = match field.default.is_none()
+            && !field.skip_deserializing
+            && field.skip_serializing_if.is_none()
+        {
+            true => "yes",
+            false => "no",
+        };
+        out.push_str(&format!(
+            "| `{}` | {} | {required} | {} |\n",
+            field.wire_name(rename_all),
+            type_of(&field.value),
+            notes.join(" ")
+        ));
+    }
+    out
+}
+
+fn enum_section(s: &EnumSchema) -> String {
+    let mut out = metadata(&s.meta);
+    out.push_str(&match &s.repr {
+        EnumRepr::ExternallyTagged => "\nExternally tagged.\n".to_string(),
+        EnumRepr::InternallyTagged { tag } => format!("\nInternally tagged by `{tag}`.\n"),
+        EnumRepr::AdjacentlyTagged { tag, content } => {
+            format!("\nAdjacently tagged by `{tag}`, with the content in `{content}`.\n")
+        }
+        EnumRepr::Integer { repr } => format!("\nWritten as the `{repr}` discriminant.\n"),
+    });
+    let integer = matches!(s.repr, EnumRepr::Integer { .. });
+    out.push_str("\n| Variant | Fields | Description |\n| --- | --- | --- |\n");
+    let discriminants = s.discriminants();
+    for (v, discriminant) in s.variants.iter().zip(discriminants) {
+        let name = match integer {
+            true => format!("`{}` = {discriminant}", v.name),
+            false => format!("`{}`", v.wire_name(s.rename_all)),
+        };
+        let fields = match &v.fields {
+            FieldsSchema::Tuple(t) if t.values.is_empty() => String::new(),
+            FieldsSchema::Tuple(t) => {
+                let mut expr = Expr::default();
+                expr.code("(");
+                expr.list(t.values.iter().map(expression_of));
+                expr.code(")");
+                expr.render()
+            }
+            FieldsSchema::Named(fields) => {
+                let mut expr = Expr::default();
+                expr.code("{ ");
+                expr.list(fields.fields.iter().map(|f| {
+                    let mut field = Expr::default();
+                    field.code(&format!("{}: ", f.wire_name(None)));
+                    field.append(expression_of(&f.value));
+                    field
+                }));
+                expr.code(" }");
+                expr.render()
+            }
+        };
+        let mut notes = cell_metadata(&v.meta);
+        if v.other {
+            notes.push("Read for unknown variants.".to_string());
+        }
+        out.push_str(&format!("| {name} | {fields} | {} |\n", notes.join(" ")));
+    }
+    out
+}
+
+fn metadata(meta: &Metadata) -> String {
+    let mut out = String::new();
+    if let Some(deprecated) = &meta.deprecated {
+        out.push_str("\n> **Deprecated**");
+        if let Some(since) = &deprecated.since {
+            out.push_str(&format!(" since {since}"));
+        }
+        match &deprecated.note {
+            Some(note) => out.push_str(&format!(": {note}\n")),
+            None => out.push_str(".\n"),
+        }
+    }
+    if let Some(doc) = &meta.doc {
+        out.push_str(&format!("\n{doc}\n"));
+    }
+    let examples: Vec<_> = meta
+        .examples
+        .iter()
+        .filter_map(|example| serde_json::to_string_pretty(example).ok())
+        .collect();
+    if !examples.is_empty() {
+        out.push_str(match examples.len() {
+            1 => "\nExample:\n",
+            _ => "\nExamples:\n",
+        });
+        for example in examples {
+            out.push_str(&format!("\n```json\n{example}\n```\n"));
+        }
+    }
+    if !meta.annotations.is_empty() {
+        out.push('\n');
+        for (key, value) in &meta.annotations {
+            out.push_str(&format!("- `{key}`: {value}\n"));
+        }
+    }
+    out
+}
+
+/// The metadata of a field or variant, as notes in its table row.
+fn cell_metadata(meta: &Metadata) -> Vec<String> {
+    let mut notes = Vec::new();
+    if let Some(deprecated) = &meta.deprecated {
+        notes.push(match &deprecated.note {
+            Some(note) => format!("**Deprecated:** {}.", cell(note.trim_end_matches('.'))),
+            None => "**Deprecated.**".to_string(),
+        });
+    }
+    notes.extend(meta.doc.iter().map(|doc| cell(doc)));
+    for example in &meta.examples {
+        if let Ok(example) = serde_json::to_string(example) {
+            notes.push(format!("Example: `{}`.", cell(&example)));
+        }
+    }
+    for (key, value) in &meta.annotations {
+        notes.push(format!("`{}`: {}.", cell(key), cell(value)));
+    }
+    notes
+}
+
+/// Text that fits in a table cell.
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+fn type_of(item: &SchemaItem) -> String {
+    expression_of(item).render()
+}
+
+fn expression_of(item: &SchemaItem) -> Expr {
+    match item {
+        SchemaItem::Schema(schema) => expression(schema),
+        SchemaItem::Named(name) => {
+            let mut expr = Expr::default();
+            expr.link(name);
+            expr
+        }
+    }
+}
+
+/// A Rust-like type expression, e.g. `Vec<Option<u32>>`.
+fn expression(schema: &Schema) -> Expr {
+    let mut expr = Expr::default();
+    let mut generic = |name: &str, args: &[&SchemaItem]| {
+        expr.code(&format!("{name}<"));
+        expr.list(args.iter().map(|arg| expression_of(arg)));
+        expr.code(">");
+    };
+    match schema {
+        Schema::Simple(SimpleSchema::Unit) => expr.code("()"),
+        Schema::Simple(s) => expr.code(&s.to_string()),
+        Schema::Option(s) => generic("Option", &[&s.value]),
+        Schema::Seq(s) => generic("Vec", &[&s.value]),
+        Schema::Set(s) => generic("Set", &[&s.value]),
+        Schema::Map(s) => generic("Map", &[&s.key, &s.value]),
+        Schema::HumanReadable(s) => generic("HumanReadable", &[&s.readable, &s.compact]),
+        Schema::Array(s) => {
+            expr.code("[");
+            expr.append(expression_of(&s.value));
+            expr.code(&format!("; {}]", s.len));
+        }
+        Schema::Tuple(s) => {
+            expr.code("(");
+            expr.list(s.values.iter().map(expression_of));
+            expr.code(")");
+        }
+        Schema::Constrained(s) => expr.append(expression_of(&s.value)),
+        Schema::Struct(s) => expr.code(&s.name),
+        Schema::Enum(s) => expr.code(&s.name),
+        Schema::Newtype(s) => expr.code(&s.name),
+        Schema::UnitStruct(s) => expr.code(&s.name),
+        Schema::Any => expr.code("any"),
+    }
+    expr
+}
+
+/// Code spans, and links to the sections of named schemas.
+#[derive(Default)]
+struct Expr(Vec<Part>);
+
+enum Part {
+    Code(String),
+    Link(String, String),
+}
+
+impl Expr {
+    fn code(&mut self, code: &str) {
+        match self.0.last_mut() {
+            Some(Part::Code(last)) => last.push_str(code),
+            _ => self.0.push(Part::Code(code.to_string())),
+        }
+    }
+
+    fn link(&mut self, name: &SchemaName) {
+        let text = name.to_string();
+        let anchor = anchor(&format!("`{text}`"));
+        self.0.push(Part::Link(text, anchor));
+    }
+
+    fn append(&mut self, other: Expr) {
+        for part in other.0 {
+            match part {
+                Part::Code(code) => self.code(&code),
+                link => self.0.push(link),
+            }
+        }
+    }
+
+    fn list(&mut self, items: impl Iterator<Item = Expr>) {
+        for (i, item) in items.enumerate() {
+            if i > 0 {
+                self.code(", ");
+            }
+            self.append(item);
+        }
+    }
+
+    fn render(&self) -> String {
+        self.0
+            .iter()
+            .map(|part| match part {
+                Part::Code(code) => format!("`{}`", cell(code)),
+                Part::Link(text, anchor) => format!("[`{}`](#{anchor})", cell(text)),
+            })
+            .collect()
+    }
+}
+
+/// The anchor that GitHub gives a heading.
+fn anchor(heading: &str) -> String {
+    heading
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c.to_ascii_lowercase()),
+            _ => None,
+        })
+        .collect()
+}
@@ -0,0 +1,702 @@
+use std::{borrow::Cow, collections::BTreeMap};
+
+use serde::{
+    de::{
+        value::MapAccessDeserializer, DeserializeSeed, Deserializer, IgnoredAny, MapAccess,
+        SeqAccess, Visitor,
+    },
+    Deserialize,
+};
+use serde_value::Value;
+
+use crate::{
+    deserializer::{FieldsDecode, SchemaDecode, SchemaDecodeItems},
+    query::path_segments,
+    DeserializerOptions, EnumRepr, SchemaDecoder, SchemaSeed,
+};
+
+/// A set of paths to decode from a value, skipping everything else.
+///
+/// Paths are dot-separated: struct fields and map keys by name, tuple and
+/// sequence elements by index and enum variants by name, e.g.
+/// `"enum.StructVariant.float_field"`. Names with dots are quoted as in a
+/// [`Query`](crate::Query), e.g. `map."a.b"`. Options are looked through.
+/// The empty path selects the whole value.
+pub struct Projection<'a, 'b> {
+    decoder: &'a SchemaDecoder<'b>,
+    root: Select,
+    len: usize,
+}
+
+/// Decodes the paths of a [`Projection`], in the order they were given.
+/// Paths that are absent from the value (another variant, a `None`, an index
+/// out of bounds) decode as `None`.
+pub struct ProjectSeed<'a> {
+    root: &'a Select,
+    len: usize,
+}
+
+/// A node in the tree of selected paths.
+#[derive(Default)]
+struct Select {
+    /// The indices of the paths that end here.
+    output: Vec<usize>,
+    shape: Shape,
+    children: BTreeMap<String, Select>,
+}
+
+/// How the dynamic view presents the value at a node.
+#[derive(Default, PartialEq)]
+pub(crate) enum Shape {
+    /// A struct, map, tuple or sequence, whose children are its entries.
+    #[default]
+    Entries,
+    /// An enum, whose children are its variants.
+    Enum(Repr),
+}
+
+#[derive(PartialEq)]
+pub(crate) enum Repr {
+    External,
+    Internal(String),
+    Adjacent(String, String),
+    /// Variants written as their discriminants, with their names.
+    Integer(Vec<(i64, String)>),
+}
+
+impl Repr {
+    /// The name of the variant an integer enum holds.
+    fn variant_name(&self, value: &Value) -> Option<&str> {
+        let discriminant = match value {
+            Value::U8(v) => *v as i64,
+            Value::U16(v) => *v as i64,
+            Value::U32(v) => *v as i64,
+            Value::U64(v) => i64::try_from(*v).ok()?,
+            Value::I8(v) => *v as i64,
+            Value::I16(v) => *v as i64,
+            Value::I32(v) => *v as i64,
+            Value::I64(v) => *v,
+            _ => return None,
+        };
+        match self {
+            Repr::Integer(variants) => variants
+                .iter()
+                .find(|(d, _)| *d == discriminant)
+                .map(|(_, name)| name.as_str()),
+            _ => None,
+        }
+    }
+}
+
+/// A place in the schema a path can point to.
+#[derive(Clone, Copy)]
+pub(crate) enum Position<'a, 'b> {
+    Schema(&'a SchemaDecode<'b>),
+    Fields(&'a FieldsDecode<'b>),
+}
+
+impl<'b> SchemaDecoder<'b> {
+    /// Prepare to decode only `paths` from values of this schema.
+    pub fn project<S: AsRef<str>>(&self, paths: &[S]) -> Result<Projection<'_, 'b>, String> {
+        let mut root = Select::default();
+        let position = Position::Schema(self.schema.lookup(&self.items));
+        for (i, path) in paths.iter().enumerate() {
+            let path = path.as_ref();
+            let segments =
+                path_segments(path).map_err(|e| format!("invalid path {path:?}: {e}"))?;
+            let segments: Vec<_> = segments.iter().map(String::as_str).collect();
+            root.insert(i, &segments, &position, &self.items)
+                .map_err(|e| format!("invalid path {path:?}: {e}"))?;
+        }
+        Ok(Projection {
+            decoder: self,
+            root,
+            len: paths.len(),
+        })
+    }
+}
+
+impl<'a, 'b> Projection<'a, 'b> {
+    /// Wrap this projection in a seed that reads its input through the
+    /// decoder, e.g. `options.deserialize_seed(projection.seed(&opts), bytes)`.
+    pub fn seed<'c>(
+        &'c self,
+        opts: &'c DeserializerOptions,
+    ) -> SchemaSeed<'c, 'b, ProjectSeed<'c>> {
+        self.decoder.seed(
+            opts,
+            ProjectSeed {
+                root: &self.root,
+                len: self.len,
+            },
+        )
+    }
+
+    /// Apply the projection to an already decoded dynamic value.
+    pub fn extract(&self, value: &Value) -> Vec<Option<Value>> {
+        let mut out = vec![None; self.len];
+        self.root.extract(value, &mut out);
+        out
+    }
+}
+
+impl Select {
+    fn insert<'a, 'b>(
+        &mut self,
+        i: usize,
+        segments: &[&str],
+        position: &Position<'a, 'b>,
+        items: &'a SchemaDecodeItems<'b>,
+    ) -> Result<(), String> {
+        let position = position.unwrap_options(items);
+        self.shape = position.shape();
+        match segments.split_first() {
+            None => {
+                self.output.push(i);
+                Ok(())
+            }
+            Some((segment, rest)) => {
+                let child = position.step(segment, items)?;
+                self.children
+                    .entry(segment.to_string())
+                    .or_default()
+                    .insert(i, rest, &child, items)
+            }
+        }
+    }
+
+    fn set(&self, value: &Value, out: &mut [Option<Value>]) {
+        for &i in &self.output {
+            out[i] = Some(value.clone());
+        }
+    }
+
+    fn extract(&self, value: &Value, out: &mut [Option<Value>]) {
+        self.set(value, out);
+        if self.children.is_empty() {
+            return;
+        }
+        for (name, value) in self.shape.entries(value) {
+            if let Some(child) = self.children.get(&name) {
+                child.extract(&value, out)
+            }
+        }
+    }
+}
+
+impl Shape {
+    /// The entries of `value` as paths address them: by field name, map key,
+    /// index or, for an enum, the name of the variant it holds.
+    pub(crate) fn entries<'v>(&self, value: &'v Value) -> Vec<(String, Cow<'v, Value>)> {
+        match (self, unwrap_value(value)) {
+            (Shape::Entries, Value::Map(entries)) => entries
+                .iter()
+                .filter_map(|(k, v)| Some((key_string(k)?, Cow::Borrowed(v))))
+                .collect(),
+            (Shape::Entries, Value::Seq(values)) => values
+                .iter()
+                .enumerate()
+                .map(|(i, v)| (i.to_string(), Cow::Borrowed(v)))
+                .collect(),
+            (Shape::Enum(Repr::External), Value::String(name)) => {
+                vec![(name.clone(), Cow::Owned(Value::Unit))]
+            }
+            (Shape::Enum(Repr::External), Value::Map(entries)) => match entries.iter().next() {
+                Some((Value::String(name), v)) => vec![(name.clone(), Cow::Borrowed(v))],
+                _ => Vec::new(),
+            },
+            (Shape::Enum(Repr::Internal(tag)), Value::Map(entries)) => {
+                let key = Value::String(tag.clone());
+                match entries.get(&key) {
+                    Some(Value::String(name)) => {
+                        let mut entries = entries.clone();
+                        entries.remove(&key);
+                        vec![(name.clone(), Cow::Owned(Value::Map(entries)))]
+                    }
+                    _ => Vec::new(),
+                }
+            }
+            (Shape::Enum(Repr::Adjacent(tag, content)), Value::Map(entries)) => {
+                match entries.get(&Value::String(tag.clone())) {
+                    Some(Value::String(name)) => {
+                        let content = match entries.get(&Value::String(content.clone())) {
+                            Some(v) => Cow::Borrowed(v),
+                            None => Cow::Owned(Value::Unit),
+                        };
+                        vec![(name.clone(), content)]
+                    }
+                    _ => Vec::new(),
+                }
+            }
+            (Shape::Enum(repr @ Repr::Integer(_)), value) => match repr.variant_name(value) {
+                Some(name) => vec![(name.to_string(), Cow::Owned(Value::Unit))],
+                None => Vec::new(),
+            },
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Look through options and newtypes, as paths do.
+pub(crate) fn unwrap_value(mut value: &Value) -> &Value {
+    while let Value::Option(Some(inner)) | Value::Newtype(inner) = value {
+        value = inner;
+    }
+    value
+}
+
+pub(crate) fn key_string(key: &Value) -> Option<String> {
+    match key {
+        Value::String(s) => Some(s.clone()),
+        Value::Char(c) => Some(c.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        Value::U8(n) => Some(n.to_string()),
+        Value::U16(n) => Some(n.to_string()),
+        Value::U32(n) => Some(n.to_string()),
+        Value::U64(n) => Some(n.to_string()),
+        Value::I8(n) => Some(n.to_string()),
+        Value::I16(n) => Some(n.to_string()),
+        Value::I32(n) => Some(n.to_string()),
+        Value::I64(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+impl<'a, 'b> Position<'a, 'b> {
+    /// Look through options, newtypes and constraints, and through
+    /// human-readable schemas to their compact alternative: paths address
+    /// the structure of compact formats.
+    pub(crate) fn unwrap_options(&self, items: &'a SchemaDecodeItems<'b>) -> Self {
+        match self {
+            Position::Schema(schema) => {
+                Position::Schema(schema.peel(items, |schema| match schema {
+                    SchemaDecode::Option(s) => Some(&s.value),
+                    SchemaDecode::Newtype(s) => Some(&s.value),
+                    SchemaDecode::Constrained(s) => Some(&s.value),
+                    SchemaDecode::HumanReadable(s) => Some(&s.compact),
+                    _ => None,
+                }))
+            }
+            Position::Fields(fields) => Position::Fields(fields),
+        }
+    }
+
+    /// Like [`Position::unwrap_options`], but picks the readable alternative
+    /// of a human-readable schema when `value` was decoded as a string.
+    pub(crate) fn unwrap_for(&self, items: &'a SchemaDecodeItems<'b>, value: &Value) -> Self {
+        match self {
+            Position::Schema(schema) => {
+                Position::Schema(schema.peel(items, |schema| match schema {
+                    SchemaDecode::Option(s) => Some(&s.value),
+                    SchemaDecode::Newtype(s) => Some(&s.value),
+                    SchemaDecode::Constrained(s) => Some(&s.value),
+                    SchemaDecode::HumanReadable(s) => match unwrap_value(value) {
+                        Value::String(_) => Some(&s.readable),
+                        _ => Some(&s.compact),
+                    },
+                    _ => None,
+                }))
+            }
+            Position::Fields(fields) => Position::Fields(fields),
+        }
+    }
+
+    pub(crate) fn shape(&self) -> Shape {
+        match self {
+            Position::Schema(SchemaDecode::Enum(s)) => Shape::Enum(match s.repr {
+                EnumRepr::ExternallyTagged => Repr::External,
+                EnumRepr::InternallyTagged { tag } => Repr::Internal(tag.clone()),
+                EnumRepr::AdjacentlyTagged { tag, content } => {
+                    Repr::Adjacent(tag.clone(), content.clone())
+                }
+                EnumRepr::Integer { .. } => Repr::Integer(
+                    s.variants
+                        .iter()
+                        .map(|v| (v.discriminant, v.name.to_string()))
+                        .collect(),
+                ),
+            }),
+            _ => Shape::Entries,
+        }
+    }
+
+    pub(crate) fn step(
+        &self,
+        segment: &str,
+        items: &'a SchemaDecodeItems<'b>,
+    ) -> Result<Self, String> {
+        let index = || {
+            segment
+                .parse::<usize>()
+                .map_err(|_| format!("expected an index, found {segment:?}"))
+        };
+        let element = |values: &'a [_]| -> Result<Self, String> {
+            let i = index()?;
+            values
+                .get(i)
+                .map(|v: &'a crate::deserializer::SchemaDecodeItem<'b>| {
+                    Position::Schema(v.lookup(items))
+                })
+                .ok_or_else(|| format!("index {i} out of bounds"))
+        };
+        match self {
+            Position::Schema(SchemaDecode::Struct(s)) => {
+                Position::Fields(&s.fields).step(segment, items)
+            }
+            Position::Schema(SchemaDecode::Tuple(s)) => element(&s.values),
+            Position::Schema(SchemaDecode::Array(s)) => match index()? {
+                i if i < s.len => Ok(Position::Schema(s.value.lookup(items))),
+                i => Err(format!("index {i} out of bounds")),
+            },
+            Position::Schema(SchemaDecode::Seq(s) | SchemaDecode::Set(s)) => {
+                index()?;
+                Ok(Position::Schema(s.value.lookup(items)))
+            }
+            Position::Schema(SchemaDecode::Map(s)) => Ok(Position::Schema(s.value.lookup(items))),
+            Position::Schema(SchemaDecode::Enum(s)) => {
+                let variant = s
+                    .by_name(segment)
+                    .map(|i| &s.variants[i])
+                    .ok_or_else(|| format!("no variant {segment:?} in enum {}", s.name))?;
+                Ok(match &variant.fields {
+                    FieldsDecode::Tuple(t) if t.values.len() == 1 => {
+                        Position::Schema(t.values[0].lookup(items))
+                    }
+                    fields => Position::Fields(fields),
+                })
+            }
+            Position::Schema(s) => Err(format!("cannot select {segment:?} in {}", s.expected())),
+            Position::Fields(FieldsDecode::Tuple(s)) => element(&s.values),
+            Position::Fields(FieldsDecode::Named(s)) => s
+                .find(segment)
+                .map(|i| &s.fields[i].value)
+                .or_else(|| s.find_flattened(items, segment))
+                .map(|value| Position::Schema(value.lookup(items)))
+                .ok_or_else(|| format!("no field {segment:?}")),
+        }
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for ProjectSeed<'_> {
+    type Value = Vec<Option<Value>>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut out = vec![None; self.len];
+        SelectSeed {
+            select: self.root,
+            out: &mut out,
+        }
+        .deserialize(deserializer)?;
+        Ok(out)
+    }
+}
+
+struct SelectSeed<'a> {
+    select: &'a Select,
+    out: &'a mut [Option<Value>],
+}
+
+impl<'de> DeserializeSeed<'de> for SelectSeed<'_> {
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match self.select.output.is_empty() {
+            true => deserializer.deserialize_any(SelectVisitor {
+                select: self.select,
+                out: self.out,
+            }),
+            false => {
+                let value = Value::deserialize(deserializer)?;
+                self.select.extract(&value, self.out);
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Decodes only the children of a node that were selected, skipping the
+/// other entries without building them.
+struct SelectVisitor<'a> {
+    select: &'a Select,
+    out: &'a mut [Option<Value>],
+}
+
+impl SelectVisitor<'_> {
+    fn child_seed<'a>(&'a mut self, child: &'a Select) -> SelectSeed<'a> {
+        SelectSeed {
+            select: child,
+            out: self.out,
+        }
+    }
+
+    fn unit_variant(&mut self, name: &str) {
+        if let Some(child) = self.select.children.get(name) {
+            child.extract(&Value::Unit, self.out)
+        }
+    }
+
+    fn integer_variant(&mut self, discriminant: Value) {
+        let select = self.select;
+        if let Shape::Enum(repr) = &select.shape {
+            if let Some(name) = repr.variant_name(&discriminant) {
+                self.unit_variant(name);
+            }
+        }
+    }
+}
+
+impl<'de> Visitor<'de> for SelectVisitor<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "any value")
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        SelectSeed {
+            select: self.select,
+            out: self.out,
+        }
+        .deserialize(deserializer)
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.visit_some(deserializer)
+    }
+
+    fn visit_str<E>(mut self, v: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        if self.select.shape == Shape::Enum(Repr::External) {
+            self.unit_variant(v);
+        }
+        Ok(())
+    }
+
+    fn visit_seq<A>(mut self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut i = 0;
+        let mut key = [0; 20];
+        loop {
+            let child = match self.select.children.is_empty() {
+                true => None,
+                false => self.select.children.get(index_key(i, &mut key)),
+            };
+            let found = match child {
+                Some(child) => seq.next_element_seed(self.child_seed(child))?,
+                None => seq.next_element::<IgnoredAny>()?.map(|_| ()),
+            };
+            if found.is_none() {
+                return Ok(());
+            }
+            i += 1;
+        }
+    }
+
+    fn visit_map<A>(mut self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        match &self.select.shape {
+            Shape::Entries | Shape::Enum(Repr::External | Repr::Integer(_)) => {
+                while let Some(child) = map.next_key_seed(KeySeed(self.select))? {
+                    match child {
+                        Some(child) => map.next_value_seed(self.child_seed(child))?,
+                        None => map.next_value::<IgnoredAny>().map(|_| ())?,
+                    }
+                }
+                Ok(())
+            }
+            Shape::Enum(Repr::Internal(_)) => {
+                // The schema deserializer always presents the tag first.
+                let child = match map.next_key::<IgnoredAny>()? {
+                    Some(_) => map.next_value_seed(VariantSeed(self.select))?,
+                    None => None,
+                };
+                let content = MapAccessDeserializer::new(map);
+                match child {
+                    Some(child) => self.child_seed(child).deserialize(content),
+                    None => content.deserialize_any(IgnoredAny).map(|_| ()),
+                }
+            }
+            Shape::Enum(Repr::Adjacent(_, _)) => {
+                let child = match map.next_key::<IgnoredAny>()? {
+                    Some(_) => map.next_value_seed(VariantSeed(self.select))?,
+                    None => None,
+                };
+                let mut found = false;
+                while map.next_key::<IgnoredAny>()?.is_some() {
+                    found = true;
+                    match child {
+                        Some(child) => map.next_value_seed(self.child_seed(child))?,
+                        None => map.next_value::<IgnoredAny>().map(|_| ())?,
+                    }
+                }
+                if let (Some(child), false) = (child, found) {
+                    child.extract(&Value::Unit, self.out);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn visit_bool<E>(self, _: bool) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_i64<E>(mut self, v: i64) -> Result<Self::Value, E> {
+        self.integer_variant(Value::I64(v));
+        Ok(())
+    }
+
+    fn visit_i128<E>(self, _: i128) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_u64<E>(mut self, v: u64) -> Result<Self::Value, E> {
+        self.integer_variant(Value::U64(v));
+        Ok(())
+    }
+
+    fn visit_u128<E>(self, _: u128) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_f64<E>(self, _: f64) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_bytes<E>(self, _: &[u8]) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E> {
+        Ok(())
+    }
+}
+
+/// The decimal digits of an element index, written to `buf`.
+fn index_key(mut i: usize, buf: &mut [u8; 20]) -> &str {
+    let mut start = buf.len();
+    loop {
+        start -= 1;
+        buf[start] = b'0' + (i % 10) as u8;
+        i /= 10;
+        if i == 0 {
+            break;
+        }
+    }
+    std::str::from_utf8(&buf[start..]).expect("digits are ASCII")
+}
+
+/// Resolves an entry key to the selected child, without allocating.
+struct KeySeed<'a>(&'a Select);
+
+impl<'de, 'a> DeserializeSeed<'de> for KeySeed<'a> {
+    type Value = Option<&'a Select>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de, 'a> Visitor<'de> for KeySeed<'a> {
+    type Value = Option<&'a Select>;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "a key")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(self.0.children.get(v))
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(self.0.children.get(&v.to_string()))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(self.0.children.get(&v.to_string()))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(self.0.children.get(&v.to_string()))
+    }
+
+    fn visit_char<E>(self, v: char) -> Result<Self::Value, E> {
+        Ok(self.0.children.get(&v.to_string()))
+    }
+
+    fn visit_bytes<E>(self, _: &[u8]) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        IgnoredAny.visit_seq(seq).map(|_| None)
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        IgnoredAny.visit_map(map).map(|_| None)
+    }
+}
+
+/// Resolves the variant tag of an internally or adjacently tagged enum to
+/// the selected child.
+struct VariantSeed<'a>(&'a Select);
+
+impl<'de, 'a> DeserializeSeed<'de> for VariantSeed<'a> {
+    type Value = Option<&'a Select>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(KeySeed(self.0))
+    }
+}
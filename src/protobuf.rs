@@ -0,0 +1,403 @@
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+};
+
+use crate::{
+    schema::{identifier, identifiers},
+    Description, EnumRepr, EnumSchema, FieldsSchema, Metadata, RenameAll, Schema, SchemaItem,
+    SchemaName, SimpleSchema, StructSchema,
+};
+
+const EMPTY: &str = "google/protobuf/empty.proto";
+const STRUCT: &str = "google/protobuf/struct.proto";
+
+/// The types that `map<K, V>` takes as keys.
+const MAP_KEYS: [&str; 5] = ["bool", "int32", "int64", "uint32", "uint64"];
+
+impl Description {
+    /// Export the description as a proto3 file with a message or enum for
+    /// every named struct and enum, and `Root` for the described type unless
+    /// it is one of them. Protocol buffers say less than descriptions:
+    /// flattened fields are fields of their own, 128-bit integers are
+    /// `bytes`, and messages are made up for nested options, sequences and
+    /// maps, and for named schemas that are neither structs, enums nor
+    /// scalars.
+    pub fn protobuf(&self) -> String {
+        let exporter = Exporter {
+            descr: self,
+            names: identifiers(self.items.keys()),
+            imports: RefCell::default(),
+        };
+        let mut definitions = Vec::new();
+        if let SchemaItem::Schema(schema) = &self.schema {
+            definitions.push(exporter.definition("Root", schema).unwrap_or_else(|| {
+                let field = exporter.schema(schema, "Value", &mut Vec::new());
+                message(
+                    "Root",
+                    None,
+                    Vec::new(),
+                    vec![field.declare("value", 1, "")],
+                )
+            }));
+        }
+        for (name, schema) in &self.items {
+            definitions.extend(exporter.definition(&exporter.names[name], schema));
+        }
+
+        let mut out = "syntax = \"proto3\";\n".to_string();
+        let imports = exporter.imports.into_inner();
+        if !imports.is_empty() {
+            out.push('\n');
+        }
+        for import in imports {
+            out.push_str(&format!("import \"{import}\";\n"));
+        }
+        for definition in definitions {
+            out.push('\n');
+            for line in definition {
+                out.push_str(&line);
+                out.push('\n');
+            }
+        }
+        out
+    }
+}
+
+/// The type and label of a message field.
+enum Field {
+    Single(String),
+    Optional(String),
+    Repeated(String),
+    Map(String, String),
+}
+
+impl Field {
+    fn declare(&self, name: &str, number: usize, options: &str) -> String {
+        match self {
+            Field::Single(ty) => format!("{ty} {name} = {number}{options};"),
+            Field::Optional(ty) => format!("optional {ty} {name} = {number}{options};"),
+            Field::Repeated(ty) => format!("repeated {ty} {name} = {number}{options};"),
+            Field::Map(key, value) => format!("map<{key}, {value}> {name} = {number}{options};"),
+        }
+    }
+}
+
+struct Exporter<'a> {
+    descr: &'a Description,
+    names: BTreeMap<&'a SchemaName, String>,
+    imports: RefCell<BTreeSet<&'static str>>,
+}
+
+impl Exporter<'_> {
+    /// The lines that define a named schema, or `None` for the schemas
+    /// that are written as scalars where they are used.
+    fn definition(&self, name: &str, schema: &Schema) -> Option<Vec<String>> {
+        if self.scalar(schema).is_some() {
+            return None;
+        }
+        Some(match schema {
+            Schema::Struct(s) => self.message(name, s),
+            Schema::Enum(s) => self.enumeration(name, s),
+            schema => {
+                let mut nested = Vec::new();
+                let field = self.schema(schema, "Value", &mut nested);
+                message(name, None, nested, vec![field.declare("value", 1, "")])
+            }
+        })
+    }
+
+    /// The scalar type of schemas without fields of their own.
+    fn scalar(&self, schema: &Schema) -> Option<String> {
+        let schema = match schema {
+            Schema::Newtype(s) => self.descr.resolve_json(&s.value)?,
+            Schema::Constrained(s) => self.descr.resolve_json(&s.value)?,
+            Schema::HumanReadable(s) => self.descr.resolve_json(&s.readable)?,
+            schema => schema,
+        };
+        let ty = match schema {
+            Schema::Simple(SimpleSchema::Unit) | Schema::UnitStruct(_) => {
+                self.imports.borrow_mut().insert(EMPTY);
+                "google.protobuf.Empty"
+            }
+            Schema::Simple(s) => simple(*s),
+            Schema::Any => {
+                self.imports.borrow_mut().insert(STRUCT);
+                "google.protobuf.Value"
+            }
+            _ => return None,
+        };
+        Some(ty.to_string())
+    }
+
+    /// The field type of `item`. Messages made up for it are named after
+    /// `hint` and added to `nested`.
+    fn item(&self, item: &SchemaItem, hint: &str, nested: &mut Vec<Vec<String>>) -> Field {
+        match item {
+            SchemaItem::Schema(schema) => self.schema(schema, hint, nested),
+            SchemaItem::Named(name) => match self.descr.items.get(name) {
+                Some(schema) => Field::Single(
+                    self.scalar(schema)
+                        .unwrap_or_else(|| self.names[name].clone()),
+                ),
+                None => Field::Single("bytes".to_string()),
+            },
+        }
+    }
+
+    fn schema(&self, schema: &Schema, hint: &str, nested: &mut Vec<Vec<String>>) -> Field {
+        if let Some(ty) = self.scalar(schema) {
+            return Field::Single(ty);
+        }
+        match schema {
+            Schema::Option(s) => match self.item(&s.value, &format!("{hint}Value"), nested) {
+                Field::Single(ty) => Field::Optional(ty),
+                field => wrap(field, hint, nested),
+            },
+            Schema::Seq(s) => self.repeated(&s.value, hint, nested),
+            Schema::Set(s) => self.repeated(&s.value, hint, nested),
+            Schema::Array(s) => self.repeated(&s.value, hint, nested),
+            Schema::Map(s) => {
+                let key = self.item(&s.key, &format!("{hint}Key"), nested);
+                let value = self.item(&s.value, &format!("{hint}Value"), nested);
+                match (key, value) {
+                    (Field::Single(key), Field::Single(value))
+                        if key == "string" || MAP_KEYS.contains(&key.as_str()) =>
+                    {
+                        Field::Map(key, value)
+                    }
+                    (key, value) => {
+                        let entry = format!("{hint}Entry");
+                        let fields = vec![key.declare("key", 1, ""), value.declare("value", 2, "")];
+                        nested.push(message(&entry, None, Vec::new(), fields));
+                        Field::Repeated(entry)
+                    }
+                }
+            }
+            Schema::Tuple(s) => {
+                let mut inner = Vec::new();
+                let fields =
+                    tuple_fields(&s.values, |item, hint| self.item(item, hint, &mut inner));
+                nested.push(message(hint, None, inner, fields));
+                Field::Single(hint.to_string())
+            }
+            Schema::Struct(s) => {
+                nested.push(self.message(hint, s));
+                Field::Single(hint.to_string())
+            }
+            Schema::Enum(s) => {
+                nested.push(self.enumeration(hint, s));
+                Field::Single(hint.to_string())
+            }
+            Schema::Newtype(s) => self.item(&s.value, hint, nested),
+            Schema::Constrained(s) => self.item(&s.value, hint, nested),
+            Schema::HumanReadable(s) => self.item(&s.readable, hint, nested),
+            Schema::Simple(_) | Schema::UnitStruct(_) | Schema::Any => {
+                unreachable!("scalars are handled above")
+            }
+        }
+    }
+
+    fn repeated(&self, item: &SchemaItem, hint: &str, nested: &mut Vec<Vec<String>>) -> Field {
+        match self.item(item, &format!("{hint}Item"), nested) {
+            Field::Single(ty) => Field::Repeated(ty),
+            field => wrap(field, hint, nested),
+        }
+    }
+
+    fn message(&self, name: &str, s: &StructSchema) -> Vec<String> {
+        let mut nested = Vec::new();
+        let fields = match &s.fields {
+            FieldsSchema::Tuple(t) => {
+                tuple_fields(&t.values, |item, hint| self.item(item, hint, &mut nested))
+            }
+            FieldsSchema::Named(fields) => fields
+                .fields
+                .iter()
+                .filter(|f| !(f.skip_serializing && f.skip_deserializing))
+                .enumerate()
+                .flat_map(|(i, f)| {
+                    let name = identifier(&f.wire_name(s.rename_all));
+                    let field = self.item(&f.value, &pascal(&name), &mut nested);
+                    let mut lines = comment(&f.meta);
+                    lines.push(field.declare(&name, i + 1, deprecated(&f.meta)));
+                    lines
+                })
+                .collect(),
+        };
+        message(name, Some(&s.meta), nested, fields)
+    }
+
+    /// A protobuf enum for enums without data, and a message with a `oneof`
+    /// of the variants for others.
+    fn enumeration(&self, name: &str, s: &EnumSchema) -> Vec<String> {
+        let unit = s.variants.iter().all(|v| match &v.fields {
+            FieldsSchema::Tuple(t) => t.values.is_empty(),
+            FieldsSchema::Named(_) => false,
+        });
+        if unit || matches!(s.repr, EnumRepr::Integer { .. }) {
+            let prefix = screaming(name);
+            let values: Vec<i64> = match s.repr {
+                EnumRepr::Integer { .. } => s.discriminants(),
+                _ => (0..s.variants.len() as i64).collect(),
+            };
+            let mut entries: Vec<(i64, Vec<String>)> = s
+                .variants
+                .iter()
+                .zip(values)
+                .map(|(v, value)| {
+                    let variant = screaming(&identifier(&v.wire_name(s.rename_all)));
+                    let mut lines = comment(&v.meta);
+                    lines.push(format!(
+                        "{prefix}_{variant} = {value}{};",
+                        deprecated(&v.meta)
+                    ));
+                    (value, lines)
+                })
+                .collect();
+            // The first value of a proto3 enum is its default, and zero.
+            match entries.iter().position(|(value, _)| *value == 0) {
+                Some(i) => {
+                    let zero = entries.remove(i);
+                    entries.insert(0, zero);
+                }
+                None => entries.insert(0, (0, vec![format!("{prefix}_UNSPECIFIED = 0;")])),
+            }
+            let mut out = comment(&s.meta);
+            out.push(format!("enum {name} {{"));
+            let lines = entries.into_iter().flat_map(|(_, lines)| lines);
+            out.extend(lines.map(|line| format!("  {line}")));
+            out.push("}".to_string());
+            return out;
+        }
+
+        let mut nested = Vec::new();
+        let mut fields = vec!["oneof value {".to_string()];
+        for (i, v) in s.variants.iter().enumerate() {
+            let name = identifier(&v.wire_name(s.rename_all));
+            let hint = pascal(&name);
+            let field = match &v.fields {
+                FieldsSchema::Tuple(t) if t.values.len() == 1 => {
+                    self.item(&t.values[0], &hint, &mut nested)
+                }
+                FieldsSchema::Tuple(t) if t.values.is_empty() => {
+                    self.imports.borrow_mut().insert(EMPTY);
+                    Field::Single("google.protobuf.Empty".to_string())
+                }
+                FieldsSchema::Tuple(t) => {
+                    self.schema(&Schema::Tuple(t.clone()), &hint, &mut nested)
+                }
+                FieldsSchema::Named(_) => {
+                    let s = StructSchema::new(hint.clone(), v.fields.clone());
+                    self.schema(&Schema::Struct(s), &hint, &mut nested)
+                }
+            };
+            // Fields of a `oneof` take no labels.
+            let field = match field {
+                Field::Single(ty) => Field::Single(ty),
+                field => wrap(field, &hint, &mut nested),
+            };
+            fields.extend(comment(&v.meta).into_iter().map(|line| format!("  {line}")));
+            let name = snake(&name);
+            fields.push(format!(
+                "  {}",
+                field.declare(&name, i + 1, deprecated(&v.meta))
+            ));
+        }
+        fields.push("}".to_string());
+        message(name, Some(&s.meta), nested, fields)
+    }
+}
+
+fn simple(s: SimpleSchema) -> &'static str {
+    match s {
+        SimpleSchema::Bool => "bool",
+        SimpleSchema::U8 | SimpleSchema::U16 | SimpleSchema::U32 => "uint32",
+        SimpleSchema::U64 => "uint64",
+        SimpleSchema::I8 | SimpleSchema::I16 | SimpleSchema::I32 => "int32",
+        SimpleSchema::I64 => "int64",
+        SimpleSchema::F32 => "float",
+        SimpleSchema::F64 => "double",
+        SimpleSchema::Char | SimpleSchema::String => "string",
+        SimpleSchema::Unit | SimpleSchema::U128 | SimpleSchema::I128 | SimpleSchema::Bytes => {
+            "bytes"
+        }
+    }
+}
+
+/// A message with a single `value` field, for fields that take no label.
+fn wrap(field: Field, name: &str, nested: &mut Vec<Vec<String>>) -> Field {
+    nested.push(message(
+        name,
+        None,
+        Vec::new(),
+        vec![field.declare("value", 1, "")],
+    ));
+    Field::Single(name.to_string())
+}
+
+/// Fields `_0`, `_1`, ... for the elements of a tuple.
+fn tuple_fields(
+    values: &[SchemaItem],
+    mut item: impl FnMut(&SchemaItem, &str) -> Field,
+) -> Vec<String> {
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| item(value, &format!("Field{i}")).declare(&format!("_{i}"), i + 1, ""))
+        .collect()
+}
+
+fn message(
+    name: &str,
+    meta: Option<&Metadata>,
+    nested: Vec<Vec<String>>,
+    fields: Vec<String>,
+) -> Vec<String> {
+    let mut out = meta.map(comment).unwrap_or_default();
+    out.push(format!("message {name} {{"));
+    if meta.is_some_and(|meta| meta.deprecated.is_some()) {
+        out.push("  option deprecated = true;".to_string());
+    }
+    for line in nested.into_iter().flatten().chain(fields) {
+        out.push(format!("  {line}"));
+    }
+    out.push("}".to_string());
+    out
+}
+
+fn comment(meta: &Metadata) -> Vec<String> {
+    meta.doc
+        .iter()
+        .flat_map(|doc| doc.lines())
+        .map(|line| format!("// {line}").trim_end().to_string())
+        .collect()
+}
+
+fn deprecated(meta: &Metadata) -> &'static str {
+    match meta.deprecated {
+        Some(_) => " [deprecated = true]",
+        None => "",
+    }
+}
+
+fn pascal(name: &str) -> String {
+    RenameAll::Pascal.apply_to_field(name)
+}
+
+fn snake(name: &str) -> String {
+    screaming(name).to_ascii_lowercase()
+}
+
+fn screaming(name: &str) -> String {
+    let mut out = String::new();
+    let mut lower = false;
+    for c in name.chars() {
+        if c.is_ascii_uppercase() && lower {
+            out.push('_');
+        }
+        lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        out.push(c.to_ascii_uppercase());
+    }
+    out
+}
@@ -0,0 +1,603 @@
+use std::{cmp::Ordering, str::FromStr};
+
+use serde::de::{DeserializeSeed, Deserializer, Error};
+use serde_value::Value;
+
+use crate::{
+    deserializer::SchemaDecodeItems,
+    diff::quote,
+    project::{unwrap_value, Position, Shape},
+    DeserializerOptions, Projection, SchemaDecoder,
+};
+
+/// A query over values of a described type, in a small jq-like language.
+///
+/// A query is a sequence of steps, each producing zero or more values from
+/// every value produced by the previous one:
+///
+/// - `.field` or `."field"` selects a struct field, map key or tuple element;
+/// - `::Variant` selects the content of an enum variant, if it is the one
+///   present;
+/// - `[2]`, `[-1]` select a sequence element and `[1:3]` a slice of them;
+/// - `["key"]` or `[5]` on a map select an entry by key;
+/// - `[]` selects every field, entry or element;
+/// - `[?cond]` selects the fields, entries or elements for which `cond`
+///   holds, e.g. `[?.size > 10 && !.deleted]`.
+///
+/// Conditions compare a relative query to a literal (a number, a string,
+/// `true`, `false` or `null`) with `==`, `!=`, `<`, `<=`, `>` or `>=`, or
+/// test that it produces a value other than `false` or `null`. They are
+/// combined with `&&`, `||`, `!` and parentheses. Options are looked
+/// through, and `null` matches `None`. A condition does not hold for entries
+/// its query does not apply to.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Query {
+    steps: Vec<Step>,
+}
+
+#[derive(PartialEq, Clone, Debug)]
+enum Step {
+    Field(String),
+    Variant(String),
+    Index(i64),
+    Slice(Option<i64>, Option<i64>),
+    Key(String),
+    Iterate,
+    Filter(Condition),
+}
+
+#[derive(PartialEq, Clone, Debug)]
+enum Condition {
+    Test(Query),
+    Compare(Query, Op, Literal),
+    Not(Box<Condition>),
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(PartialEq, Clone, Debug)]
+enum Literal {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+}
+
+/// Runs a [`Query`] against a payload, decoding the prefix of the query that
+/// addresses a single place through a [`Projection`] and skipping the rest of
+/// the payload.
+pub struct QuerySeed<'a, 'b> {
+    projection: Projection<'a, 'b>,
+    position: Position<'a, 'b>,
+    items: &'a SchemaDecodeItems<'b>,
+    rest: &'a [Step],
+    opts: &'a DeserializerOptions,
+}
+
+impl Query {
+    /// Prepare to run the query against payloads read through `decoder`.
+    pub fn seed<'a, 'b>(
+        &'a self,
+        decoder: &'a SchemaDecoder<'b>,
+        opts: &'a DeserializerOptions,
+    ) -> Result<QuerySeed<'a, 'b>, String> {
+        let items = &decoder.items;
+        let mut position = Position::Schema(decoder.schema.lookup(items));
+        let mut path = Vec::new();
+        for step in &self.steps {
+            let segment = match step {
+                Step::Field(name) | Step::Variant(name) => name,
+                _ => break,
+            };
+            let current = position.unwrap_options(items);
+            check_step(step, &current)?;
+            position = current.step(segment, items)?;
+            path.push(quote(segment));
+        }
+        let rest = &self.steps[path.len()..];
+        Ok(QuerySeed {
+            projection: decoder.project(&[path.join(".")])?,
+            position,
+            items,
+            rest,
+            opts,
+        })
+    }
+
+    /// Run the query against a dynamic value decoded with `decoder`.
+    pub fn eval(&self, decoder: &SchemaDecoder, value: &Value) -> Result<Vec<Value>, String> {
+        let items = &decoder.items;
+        let mut out = Vec::new();
+        eval(
+            &self.steps,
+            Position::Schema(decoder.schema.lookup(items)),
+            value,
+            items,
+            &mut out,
+        )?;
+        Ok(out)
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for QuerySeed<'_, '_> {
+    type Value = Vec<Value>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        (&self).deserialize(deserializer)
+    }
+}
+
+/// The same seed runs against any number of payloads.
+impl<'de> DeserializeSeed<'de> for &QuerySeed<'_, '_> {
+    type Value = Vec<Value>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let projected = self.projection.seed(self.opts).deserialize(deserializer)?;
+        let mut out = Vec::new();
+        if let Some(Some(value)) = projected.first() {
+            eval(self.rest, self.position, value, self.items, &mut out)
+                .map_err(D::Error::custom)?;
+        }
+        Ok(out)
+    }
+}
+
+/// Fails if `step` can never apply to values at `position`.
+fn check_step(step: &Step, position: &Position) -> Result<(), String> {
+    match (step, position.shape()) {
+        (Step::Variant(name), Shape::Entries) => Err(format!(
+            "cannot match variant {name:?} on a value that is not an enum"
+        )),
+        (Step::Field(name), Shape::Enum(_)) => Err(format!(
+            "cannot select field {name:?} of an enum, use ::Variant first"
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn eval<'a, 'b>(
+    steps: &[Step],
+    position: Position<'a, 'b>,
+    value: &Value,
+    items: &'a SchemaDecodeItems<'b>,
+    out: &mut Vec<Value>,
+) -> Result<(), String> {
+    let (step, rest) = match steps.split_first() {
+        Some(split) => split,
+        None => {
+            out.push(value.clone());
+            return Ok(());
+        }
+    };
+    let position = position.unwrap_for(items, value);
+    check_step(step, &position)?;
+    let shape = position.shape();
+    let next = |name: &str, value: &Value, out: &mut Vec<Value>| {
+        eval(rest, position.step(name, items)?, value, items, out)
+    };
+    match step {
+        Step::Field(name) | Step::Variant(name) => {
+            position.step(name, items)?;
+            for (key, value) in shape.entries(value) {
+                if key == *name {
+                    next(&key, &value, out)?;
+                }
+            }
+        }
+        Step::Key(key) => {
+            for (k, value) in shape.entries(value) {
+                if k == *key {
+                    next(&k, &value, out)?;
+                }
+            }
+        }
+        Step::Index(i) if matches!(unwrap_value(value), Value::Map(_)) => {
+            let key = i.to_string();
+            for (k, value) in shape.entries(value) {
+                if k == key {
+                    next(&k, &value, out)?;
+                }
+            }
+        }
+        Step::Index(_) | Step::Slice(_, _) => {
+            let elements = match unwrap_value(value) {
+                Value::Seq(values) => values,
+                Value::Option(None) => return Ok(()),
+                _ => return Err(format!("cannot index {}", describe(value))),
+            };
+            let len = elements.len() as i64;
+            let resolve = |i: i64| if i < 0 { len + i } else { i };
+            let range = match step {
+                Step::Index(i) => resolve(*i)..resolve(*i).saturating_add(1),
+                Step::Slice(start, end) => {
+                    resolve(start.unwrap_or(0)).max(0)..resolve(end.unwrap_or(len)).min(len)
+                }
+                _ => unreachable!(),
+            };
+            for i in range.filter(|i| (0..len).contains(i)) {
+                next(&i.to_string(), &elements[i as usize], out)?;
+            }
+        }
+        Step::Iterate => {
+            for (key, value) in shape.entries(value) {
+                next(&key, &value, out)?;
+            }
+        }
+        Step::Filter(condition) => {
+            for (key, value) in shape.entries(value) {
+                let child = position.step(&key, items)?;
+                if condition.holds(child, &value, items)? {
+                    eval(rest, child, &value, items, out)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn describe(value: &Value) -> &'static str {
+    match unwrap_value(value) {
+        Value::Map(_) => "a map",
+        Value::Seq(_) => "a sequence",
+        Value::String(_) => "a string",
+        Value::Unit | Value::Option(None) => "null",
+        _ => "a scalar",
+    }
+}
+
+impl Condition {
+    fn holds<'a, 'b>(
+        &self,
+        position: Position<'a, 'b>,
+        value: &Value,
+        items: &'a SchemaDecodeItems<'b>,
+    ) -> Result<bool, String> {
+        // A query that does not apply to this entry, e.g. a field of another
+        // type of value, produces nothing rather than failing the filter.
+        let results = |query: &Query| {
+            let mut out = Vec::new();
+            eval(&query.steps, position, value, items, &mut out).map_or(Vec::new(), |_| out)
+        };
+        Ok(match self {
+            Condition::Test(query) => results(query).iter().any(|v| {
+                !matches!(
+                    unwrap_value(v),
+                    Value::Bool(false) | Value::Unit | Value::Option(None)
+                )
+            }),
+            Condition::Compare(query, op, literal) => results(query).iter().any(|v| {
+                literal
+                    .compare(v)
+                    .is_some_and(|ord| op.matches(ord.reverse()))
+            }),
+            Condition::Not(c) => !c.holds(position, value, items)?,
+            Condition::And(a, b) => {
+                a.holds(position, value, items)? && b.holds(position, value, items)?
+            }
+            Condition::Or(a, b) => {
+                a.holds(position, value, items)? || b.holds(position, value, items)?
+            }
+        })
+    }
+}
+
+impl Op {
+    fn matches(self, ord: Ordering) -> bool {
+        match self {
+            Op::Eq => ord.is_eq(),
+            Op::Ne => ord.is_ne(),
+            Op::Lt => ord.is_lt(),
+            Op::Le => ord.is_le(),
+            Op::Gt => ord.is_gt(),
+            Op::Ge => ord.is_ge(),
+        }
+    }
+}
+
+impl Literal {
+    /// Compare the literal to a value, if they are of comparable types.
+    fn compare(&self, value: &Value) -> Option<Ordering> {
+        let value = unwrap_value(value);
+        match (self, value) {
+            (Literal::Null, Value::Unit | Value::Option(None)) => Some(Ordering::Equal),
+            (Literal::Null, _) => Some(Ordering::Less),
+            (Literal::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+            (Literal::String(a), Value::String(b)) => Some(a.as_str().cmp(b)),
+            (Literal::String(a), Value::Char(b)) => {
+                Some(a.as_str().cmp(b.encode_utf8(&mut [0; 4])))
+            }
+            (Literal::Number(a), value) => a.partial_cmp(&number(value)?),
+            _ => None,
+        }
+    }
+}
+
+fn number(value: &Value) -> Option<f64> {
+    Some(match value {
+        Value::U8(n) => *n as f64,
+        Value::U16(n) => *n as f64,
+        Value::U32(n) => *n as f64,
+        Value::U64(n) => *n as f64,
+        Value::I8(n) => *n as f64,
+        Value::I16(n) => *n as f64,
+        Value::I32(n) => *n as f64,
+        Value::I64(n) => *n as f64,
+        Value::F32(n) => *n as f64,
+        Value::F64(n) => *n,
+        _ => return None,
+    })
+}
+
+impl FromStr for Query {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser { input: s, pos: 0 };
+        let query = parser.query()?;
+        parser.skip_ws();
+        match parser.rest().is_empty() {
+            true => Ok(query),
+            false => Err(parser.error("unexpected input")),
+        }
+    }
+}
+
+/// The segments of a [`Projection`](crate::Projection) path: names
+/// separated by dots, each bare or quoted as in a query.
+pub(crate) fn path_segments(path: &str) -> Result<Vec<String>, String> {
+    let mut parser = Parser {
+        input: path,
+        pos: 0,
+    };
+    let mut segments = Vec::new();
+    while !path.is_empty() {
+        match parser.rest().starts_with('"') {
+            true => segments.push(parser.string()?),
+            false => {
+                let len = parser.rest().find('.').unwrap_or(parser.rest().len());
+                segments.push(parser.rest()[..len].to_string());
+                parser.pos += len;
+            }
+        }
+        if parser.rest().is_empty() {
+            break;
+        }
+        if !parser.eat(".") {
+            return Err(parser.error("expected \".\""));
+        }
+    }
+    Ok(segments)
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn error(&self, msg: &str) -> String {
+        format!("{msg} at {:?}", self.rest())
+    }
+
+    fn skip_ws(&mut self) {
+        self.pos = self.input.len() - self.rest().trim_start().len();
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        match self.rest().starts_with(token) {
+            true => {
+                self.pos += token.len();
+                true
+            }
+            false => false,
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), String> {
+        self.skip_ws();
+        match self.eat(token) {
+            true => Ok(()),
+            false => Err(self.error(&format!("expected {token:?}"))),
+        }
+    }
+
+    fn query(&mut self) -> Result<Query, String> {
+        self.skip_ws();
+        let mut steps = Vec::new();
+        // A leading `@` or lone `.` stands for the value itself. Any other
+        // `.` is followed by a name.
+        let named = |c: char| c == '"' || c == '.' || is_ident_char(c);
+        if !self.eat("@") && self.rest().starts_with('.') && !self.rest()[1..].starts_with(named) {
+            self.pos += 1;
+        }
+        loop {
+            if self.eat("::") {
+                steps.push(Step::Variant(self.ident()?));
+            } else if self.eat(".") {
+                match self.rest().starts_with('"') {
+                    true => steps.push(Step::Field(self.string()?)),
+                    false => steps.push(Step::Field(self.ident()?)),
+                }
+            } else if self.eat("[") {
+                steps.push(self.bracket()?);
+            } else {
+                return Ok(Query { steps });
+            }
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, String> {
+        let len = self
+            .rest()
+            .find(|c| !is_ident_char(c))
+            .unwrap_or(self.rest().len());
+        match len {
+            0 => Err(self.error("expected a name")),
+            _ => {
+                let ident = &self.rest()[..len];
+                self.pos += len;
+                Ok(ident.to_string())
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect("\"")?;
+        let mut s = String::new();
+        let mut chars = self.rest().char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += i + 1;
+                    return Ok(s);
+                }
+                '\\' => match chars.next() {
+                    Some((_, 'n')) => s.push('\n'),
+                    Some((_, 't')) => s.push('\t'),
+                    Some((_, c)) => s.push(c),
+                    None => break,
+                },
+                c => s.push(c),
+            }
+        }
+        Err(self.error("unterminated string"))
+    }
+
+    fn integer(&mut self) -> Result<Option<i64>, String> {
+        self.skip_ws();
+        let len = self
+            .rest()
+            .char_indices()
+            .find(|&(i, c)| !(c.is_ascii_digit() || (i == 0 && c == '-')))
+            .map_or(self.rest().len(), |(i, _)| i);
+        match len {
+            0 => Ok(None),
+            _ => {
+                let n = self.rest()[..len]
+                    .parse()
+                    .map_err(|_| self.error("invalid integer"))?;
+                self.pos += len;
+                Ok(Some(n))
+            }
+        }
+    }
+
+    fn bracket(&mut self) -> Result<Step, String> {
+        self.skip_ws();
+        let step = if self.eat("]") {
+            return Ok(Step::Iterate);
+        } else if self.eat("?") {
+            Step::Filter(self.or()?)
+        } else if self.rest().starts_with('"') {
+            Step::Key(self.string()?)
+        } else {
+            let start = self.integer()?;
+            self.skip_ws();
+            match self.eat(":") {
+                true => Step::Slice(start, self.integer()?),
+                false => Step::Index(start.ok_or_else(|| self.error("expected an index"))?),
+            }
+        };
+        self.expect("]")?;
+        Ok(step)
+    }
+
+    fn or(&mut self) -> Result<Condition, String> {
+        let mut condition = self.and()?;
+        loop {
+            self.skip_ws();
+            match self.eat("||") {
+                true => condition = Condition::Or(Box::new(condition), Box::new(self.and()?)),
+                false => return Ok(condition),
+            }
+        }
+    }
+
+    fn and(&mut self) -> Result<Condition, String> {
+        let mut condition = self.unary()?;
+        loop {
+            self.skip_ws();
+            match self.eat("&&") {
+                true => condition = Condition::And(Box::new(condition), Box::new(self.unary()?)),
+                false => return Ok(condition),
+            }
+        }
+    }
+
+    fn unary(&mut self) -> Result<Condition, String> {
+        self.skip_ws();
+        if self.eat("!") && !self.rest().starts_with('=') {
+            return Ok(Condition::Not(Box::new(self.unary()?)));
+        }
+        if self.eat("(") {
+            let condition = self.or()?;
+            self.expect(")")?;
+            return Ok(condition);
+        }
+        let query = self.query()?;
+        self.skip_ws();
+        let op = [
+            ("==", Op::Eq),
+            ("!=", Op::Ne),
+            ("<=", Op::Le),
+            (">=", Op::Ge),
+            ("<", Op::Lt),
+            (">", Op::Gt),
+        ]
+        .into_iter()
+        .find(|(token, _)| self.eat(token));
+        match op {
+            Some((_, op)) => Ok(Condition::Compare(query, op, self.literal()?)),
+            None => Ok(Condition::Test(query)),
+        }
+    }
+
+    fn literal(&mut self) -> Result<Literal, String> {
+        self.skip_ws();
+        if self.rest().starts_with('"') {
+            return Ok(Literal::String(self.string()?));
+        }
+        for (token, literal) in [
+            ("null", Literal::Null),
+            ("true", Literal::Bool(true)),
+            ("false", Literal::Bool(false)),
+        ] {
+            if self.eat(token) {
+                return Ok(literal);
+            }
+        }
+        let len = self
+            .rest()
+            .find(|c: char| !(c.is_ascii_alphanumeric() || "+-.".contains(c)))
+            .unwrap_or(self.rest().len());
+        let n = self.rest()[..len]
+            .parse()
+            .map_err(|_| self.error("expected a literal"))?;
+        self.pos += len;
+        Ok(Literal::Number(n))
+    }
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
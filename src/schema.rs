@@ -1,4 +1,4 @@
-use std::{fmt::Display, str::FromStr};
+use std::{borrow::Cow, collections::BTreeMap, fmt::Display, ops::RangeInclusive, str::FromStr};
 
 use serde::{Deserialize, Serialize};
 use serde_with::{DeserializeFromStr, SerializeDisplay};
@@ -10,7 +10,7 @@ pub enum SchemaItem {
 }
 
 #[derive(SerializeDisplay, DeserializeFromStr, PartialEq, Eq, PartialOrd, Ord, Clone, Debug)]
-pub struct SchemaName(String, Vec<SchemaName>);
+pub struct SchemaName(pub(crate) String, pub(crate) Vec<SchemaName>);
 
 impl SchemaName {
     pub fn new<T: Into<String>>(name: T) -> Self {
@@ -40,8 +40,88 @@ impl FromStr for SchemaName {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        todo!()
+        let (name, rest) = parse_schema_name(s)?;
+        match rest.is_empty() {
+            true => Ok(name),
+            false => Err(format!("unexpected trailing input: {rest:?}")),
+        }
+    }
+}
+
+fn parse_schema_name(s: &str) -> Result<(SchemaName, &str), String> {
+    let end = s.find(['<', ',', '>']).unwrap_or(s.len());
+    let name = s[..end].trim();
+    if name.is_empty() {
+        return Err(format!("missing schema name at {s:?}"));
+    }
+    let mut name = SchemaName::new(name);
+    let mut rest = &s[end..];
+    if let Some(args) = rest.strip_prefix('<') {
+        rest = args;
+        loop {
+            let (arg, next) = parse_schema_name(rest)?;
+            name.1.push(arg);
+            rest = next.trim_start();
+            if let Some(next) = rest.strip_prefix(',') {
+                rest = next;
+            } else if let Some(next) = rest.strip_prefix('>') {
+                rest = next;
+                break;
+            } else {
+                return Err(format!("expected ',' or '>' at {rest:?}"));
+            }
+        }
+    }
+    Ok((name, rest))
+}
+
+/// Identifiers for named schemas in languages without paths or generics:
+/// `std::ops::Range<u32>` becomes `Range_u32`. Names that end up the same
+/// are numbered.
+pub(crate) fn identifiers<'a, I>(names: I) -> BTreeMap<&'a SchemaName, String>
+where
+    I: IntoIterator<Item = &'a SchemaName>,
+{
+    fn flatten(name: &SchemaName, out: &mut String) {
+        out.push_str(name.0.rsplit("::").next().unwrap_or_default());
+        for arg in &name.1 {
+            out.push('_');
+            flatten(arg, out);
+        }
+    }
+
+    let mut taken = std::collections::BTreeSet::new();
+    names
+        .into_iter()
+        .map(|name| {
+            let mut flat = String::new();
+            flatten(name, &mut flat);
+            let base = identifier(&flat);
+            let mut id = base.clone();
+            for n in 2.. {
+                if taken.insert(id.clone()) {
+                    break;
+                }
+                id = format!("{base}{n}");
+            }
+            (name, id)
+        })
+        .collect()
+}
+
+/// `name` with the characters that identifiers cannot hold replaced.
+pub(crate) fn identifier(name: &str) -> String {
+    let mut id: String = name
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() {
+            true => c,
+            false => '_',
+        })
+        .collect();
+    if !id.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        id.insert(0, '_');
     }
+    id
 }
 
 impl From<SchemaName> for SchemaItem {
@@ -56,9 +136,18 @@ pub enum Schema {
     Option(OptionSchema),
     Tuple(TupleSchema),
     Seq(SeqSchema),
+    Set(SetSchema),
+    Array(ArraySchema),
     Map(MapSchema),
     Struct(StructSchema),
     Enum(EnumSchema),
+    Newtype(NewtypeSchema),
+    UnitStruct(UnitStructSchema),
+    HumanReadable(HumanReadableSchema),
+    Constrained(ConstrainedSchema),
+    /// A dynamically typed value, e.g. `serde_json::Value`. Compact formats
+    /// hold it in the envelope written by [`crate::any`].
+    Any,
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
@@ -67,21 +156,36 @@ pub enum Expected<'a> {
     Option,
     Tuple(usize),
     Seq,
+    Set,
+    Array(usize),
     Map,
     Struct(&'a str),
     Enum(&'a str),
+    Newtype(&'a str),
+    UnitStruct(&'a str),
+    HumanReadable,
+    Constrained,
+    Any,
+    Invalid(&'a str),
 }
 
 impl Schema {
-    pub fn expected(&self) -> Expected {
+    pub fn expected(&self) -> Expected<'_> {
         match self {
             Schema::Simple(s) => Expected::Simple(*s),
             Schema::Option(_) => Expected::Option,
             Schema::Tuple(s) => Expected::Tuple(s.values.len()),
             Schema::Seq(_) => Expected::Seq,
+            Schema::Set(_) => Expected::Set,
+            Schema::Array(s) => Expected::Array(s.len),
             Schema::Map(_) => Expected::Map,
             Schema::Struct(s) => Expected::Struct(&s.name),
             Schema::Enum(s) => Expected::Enum(&s.name),
+            Schema::Newtype(s) => Expected::Newtype(&s.name),
+            Schema::UnitStruct(s) => Expected::UnitStruct(&s.name),
+            Schema::HumanReadable(_) => Expected::HumanReadable,
+            Schema::Constrained(_) => Expected::Constrained,
+            Schema::Any => Expected::Any,
         }
     }
 }
@@ -93,9 +197,17 @@ impl Display for Expected<'_> {
             Expected::Option => write!(f, "option"),
             Expected::Tuple(n) => write!(f, "{n}-element tuple"),
             Expected::Seq => write!(f, "sequence"),
+            Expected::Set => write!(f, "set"),
+            Expected::Array(n) => write!(f, "array of {n} elements"),
             Expected::Map => write!(f, "map"),
             Expected::Struct(name) => write!(f, "struct {name}"),
             Expected::Enum(name) => write!(f, "enum {name}"),
+            Expected::Newtype(name) => write!(f, "newtype struct {name}"),
+            Expected::UnitStruct(name) => write!(f, "unit struct {name}"),
+            Expected::HumanReadable => write!(f, "human-readable or compact value"),
+            Expected::Constrained => write!(f, "constrained value"),
+            Expected::Any => write!(f, "any value"),
+            Expected::Invalid(reason) => write!(f, "{reason}"),
         }
     }
 }
@@ -118,6 +230,10 @@ pub enum SimpleSchema {
     F64,
     Char,
     String,
+    /// A byte string, e.g. from `serde_bytes`. Without a derive there is no
+    /// `#[describe(bytes)]`: a field with `#[serde(with = "serde_bytes")]`
+    /// gets this schema in its struct's hand-written `Describe` impl, and
+    /// the `serde_bytes` types have it with the feature of that name.
     Bytes,
 }
 
@@ -207,6 +323,63 @@ impl From<SeqSchema> for SchemaItem {
     }
 }
 
+/// A sequence of distinct elements, e.g. `BTreeSet<T>`. It is written like a
+/// [`SeqSchema`].
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct SetSchema {
+    pub(crate) value: SchemaItem,
+}
+
+impl SetSchema {
+    pub fn new<V: Into<SchemaItem>>(value: V) -> Self {
+        Self {
+            value: value.into(),
+        }
+    }
+}
+
+impl From<SetSchema> for Schema {
+    fn from(value: SetSchema) -> Self {
+        Schema::Set(value)
+    }
+}
+
+impl From<SetSchema> for SchemaItem {
+    fn from(value: SetSchema) -> Self {
+        SchemaItem::Schema(Box::new(value.into()))
+    }
+}
+
+/// A fixed number of elements of one type, e.g. `[T; N]`. It is written
+/// like a [`TupleSchema`], without a length prefix. TypeScript exports hold
+/// it as `Array<T>`, without its length.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct ArraySchema {
+    pub(crate) value: SchemaItem,
+    pub(crate) len: usize,
+}
+
+impl ArraySchema {
+    pub fn new<V: Into<SchemaItem>>(value: V, len: usize) -> Self {
+        Self {
+            value: value.into(),
+            len,
+        }
+    }
+}
+
+impl From<ArraySchema> for Schema {
+    fn from(value: ArraySchema) -> Self {
+        Schema::Array(value)
+    }
+}
+
+impl From<ArraySchema> for SchemaItem {
+    fn from(value: ArraySchema) -> Self {
+        SchemaItem::Schema(Box::new(value.into()))
+    }
+}
+
 #[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
 pub struct MapSchema {
     pub(crate) key: SchemaItem,
@@ -234,7 +407,169 @@ impl From<MapSchema> for SchemaItem {
     }
 }
 
+/// A type that serializes differently depending on
+/// [`Serializer::is_human_readable`](serde::Serializer::is_human_readable),
+/// e.g. `IpAddr` as a string in JSON but as an enum in bincode.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct HumanReadableSchema {
+    pub(crate) readable: SchemaItem,
+    pub(crate) compact: SchemaItem,
+}
+
+impl HumanReadableSchema {
+    pub fn new<R: Into<SchemaItem>, C: Into<SchemaItem>>(readable: R, compact: C) -> Self {
+        Self {
+            readable: readable.into(),
+            compact: compact.into(),
+        }
+    }
+}
+
+impl From<HumanReadableSchema> for Schema {
+    fn from(value: HumanReadableSchema) -> Self {
+        Schema::HumanReadable(value)
+    }
+}
+
+impl From<HumanReadableSchema> for SchemaItem {
+    fn from(value: HumanReadableSchema) -> Self {
+        SchemaItem::Schema(Box::new(value.into()))
+    }
+}
+
+/// A value with invariants beyond its type, e.g. a port that is never 0.
+/// Constraints play no part in decoding: they are checked by
+/// [`Description::validate`](crate::Description::validate) and emitted by
+/// exporters.
 #[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct ConstrainedSchema {
+    pub(crate) value: SchemaItem,
+    pub(crate) constraints: Constraints,
+}
+
+impl ConstrainedSchema {
+    pub fn new<T: Into<SchemaItem>>(value: T, constraints: Constraints) -> Self {
+        Self {
+            value: value.into(),
+            constraints,
+        }
+    }
+}
+
+impl From<ConstrainedSchema> for Schema {
+    fn from(value: ConstrainedSchema) -> Self {
+        Schema::Constrained(value)
+    }
+}
+
+impl From<ConstrainedSchema> for SchemaItem {
+    fn from(value: ConstrainedSchema) -> Self {
+        SchemaItem::Schema(Box::new(value.into()))
+    }
+}
+
+/// Refinements of a value. Each applies to the kinds of values it makes
+/// sense for and is ignored by the others: bounds to numbers, lengths to
+/// strings (in characters), bytes, sequences and maps, `pattern` to strings,
+/// `unique` to sequences and `key_pattern` to the keys of maps.
+///
+/// Patterns are regular expressions that, as in JSON Schema, match anywhere
+/// in the string unless anchored.
+///
+/// The crate has no derive, and so no `#[describe(range(..))]` attributes:
+/// a `Describe` impl wraps the schema in a [`ConstrainedSchema`], e.g.
+/// `ConstrainedSchema::new(u16::schema(), Constraints::new().range(1..=65535))`.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Default, Clone, Debug)]
+pub struct Constraints {
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub(crate) min: Option<serde_json::Number>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub(crate) max: Option<serde_json::Number>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub(crate) min_length: Option<usize>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub(crate) max_length: Option<usize>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub(crate) pattern: Option<String>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub(crate) unique: bool,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub(crate) key_pattern: Option<String>,
+}
+
+impl Constraints {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inclusive bounds, e.g. `range(1..=65535)`.
+    pub fn range<N: Into<serde_json::Number>>(self, range: RangeInclusive<N>) -> Self {
+        let (min, max) = range.into_inner();
+        self.min(min).max(max)
+    }
+
+    pub fn min<N: Into<serde_json::Number>>(mut self, min: N) -> Self {
+        self.min = Some(min.into());
+        self
+    }
+
+    pub fn max<N: Into<serde_json::Number>>(mut self, max: N) -> Self {
+        self.max = Some(max.into());
+        self
+    }
+
+    /// Inclusive floating-point bounds, e.g. `range_f64(0.0..=100.0)`.
+    pub fn range_f64(self, range: RangeInclusive<f64>) -> Self {
+        let (min, max) = range.into_inner();
+        self.min_f64(min).max_f64(max)
+    }
+
+    /// A floating-point minimum. JSON has no NaN or infinities, so these
+    /// unset the minimum: an infinite one bounds nothing.
+    pub fn min_f64(mut self, min: f64) -> Self {
+        self.min = serde_json::Number::from_f64(min);
+        self
+    }
+
+    /// A floating-point maximum, unset by NaN and infinities as in
+    /// [`min_f64`](Self::min_f64).
+    pub fn max_f64(mut self, max: f64) -> Self {
+        self.max = serde_json::Number::from_f64(max);
+        self
+    }
+
+    pub fn min_length(mut self, len: usize) -> Self {
+        self.min_length = Some(len);
+        self
+    }
+
+    pub fn max_length(mut self, len: usize) -> Self {
+        self.max_length = Some(len);
+        self
+    }
+
+    /// Shorthand for `min_length(1)`.
+    pub fn non_empty(self) -> Self {
+        self.min_length(1)
+    }
+
+    pub fn pattern<T: Into<String>>(mut self, pattern: T) -> Self {
+        self.pattern = Some(pattern.into());
+        self
+    }
+
+    pub fn unique(mut self) -> Self {
+        self.unique = true;
+        self
+    }
+
+    pub fn key_pattern<T: Into<String>>(mut self, pattern: T) -> Self {
+        self.key_pattern = Some(pattern.into());
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Default, Clone, Debug)]
 pub struct TupleSchema {
     pub(crate) values: Vec<SchemaItem>,
 }
@@ -270,6 +605,8 @@ pub struct StructSchema {
     pub(crate) rename: Option<String>,
     #[serde(default, skip_serializing_if = "is_default")]
     pub(crate) rename_all: Option<RenameAll>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub(crate) meta: Metadata,
 }
 
 impl StructSchema {
@@ -279,8 +616,20 @@ impl StructSchema {
             fields: fields.into(),
             rename: None,
             rename_all: None,
+            meta: Metadata::default(),
         }
     }
+
+    pub fn rename<T: Into<String>>(mut self, name: T) -> Self {
+        self.rename = Some(name.into());
+        self
+    }
+
+    /// Rename the named fields, unless they have a `rename` of their own.
+    pub fn rename_all(mut self, rule: RenameAll) -> Self {
+        self.rename_all = Some(rule);
+        self
+    }
 }
 
 impl From<StructSchema> for Schema {
@@ -295,6 +644,85 @@ impl From<StructSchema> for SchemaItem {
     }
 }
 
+/// A struct with a single unnamed field, e.g. `struct Id(u64)`.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct NewtypeSchema {
+    pub(crate) name: String,
+    pub(crate) value: SchemaItem,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub(crate) rename: Option<String>,
+    /// `#[serde(transparent)]`: the value is written without the wrapper.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub(crate) transparent: bool,
+}
+
+impl NewtypeSchema {
+    pub fn new<T: Into<String>, V: Into<SchemaItem>>(name: T, value: V) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+            rename: None,
+            transparent: false,
+        }
+    }
+
+    pub fn rename<T: Into<String>>(mut self, name: T) -> Self {
+        self.rename = Some(name.into());
+        self
+    }
+
+    pub fn transparent(mut self) -> Self {
+        self.transparent = true;
+        self
+    }
+}
+
+impl From<NewtypeSchema> for Schema {
+    fn from(value: NewtypeSchema) -> Self {
+        Schema::Newtype(value)
+    }
+}
+
+impl From<NewtypeSchema> for SchemaItem {
+    fn from(value: NewtypeSchema) -> Self {
+        SchemaItem::Schema(Box::new(value.into()))
+    }
+}
+
+/// A struct without fields, e.g. `struct Marker;`.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct UnitStructSchema {
+    pub(crate) name: String,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub(crate) rename: Option<String>,
+}
+
+impl UnitStructSchema {
+    pub fn new<T: Into<String>>(name: T) -> Self {
+        Self {
+            name: name.into(),
+            rename: None,
+        }
+    }
+
+    pub fn rename<T: Into<String>>(mut self, name: T) -> Self {
+        self.rename = Some(name.into());
+        self
+    }
+}
+
+impl From<UnitStructSchema> for Schema {
+    fn from(value: UnitStructSchema) -> Self {
+        Schema::UnitStruct(value)
+    }
+}
+
+impl From<UnitStructSchema> for SchemaItem {
+    fn from(value: UnitStructSchema) -> Self {
+        SchemaItem::Schema(Box::new(value.into()))
+    }
+}
+
 #[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
 pub enum FieldsSchema {
     Tuple(TupleSchema),
@@ -307,9 +735,9 @@ impl From<TupleSchema> for FieldsSchema {
     }
 }
 
-#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+#[derive(Serialize, Deserialize, PartialEq, Eq, Default, Clone, Debug)]
 pub struct NamedFieldsSchema {
-    fields: Vec<NamedFieldSchema>,
+    pub(crate) fields: Vec<NamedFieldSchema>,
 }
 
 impl NamedFieldsSchema {
@@ -341,6 +769,19 @@ pub struct NamedFieldSchema {
     pub(crate) default: Option<serde_value::Value>,
     #[serde(default, skip_serializing_if = "is_default")]
     pub(crate) flatten: bool,
+    /// `#[serde(skip_serializing)]` (or `skip`): the field is never written.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub(crate) skip_serializing: bool,
+    /// `#[serde(skip_deserializing)]` (or `skip`): the field is written, but
+    /// the type ignores it when reading.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub(crate) skip_deserializing: bool,
+    /// `#[serde(skip_serializing_if = "...")]`, with the predicate's path:
+    /// whether the field is written depends on its value.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub(crate) skip_serializing_if: Option<String>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub(crate) meta: Metadata,
 }
 
 impl NamedFieldSchema {
@@ -352,6 +793,54 @@ impl NamedFieldSchema {
             aliases: Vec::new(),
             default: None,
             flatten: false,
+            skip_serializing: false,
+            skip_deserializing: false,
+            skip_serializing_if: None,
+            meta: Metadata::default(),
+        }
+    }
+
+    pub fn rename<T: Into<String>>(mut self, name: T) -> Self {
+        self.rename = Some(name.into());
+        self
+    }
+
+    /// `#[serde(flatten)]`: the fields of the value, a struct or a map, are
+    /// written into the parent map.
+    pub fn flatten(mut self) -> Self {
+        self.flatten = true;
+        self
+    }
+
+    /// `#[serde(skip)]`. There is no derive to read the `skip` attributes
+    /// from a type: its `Describe` impl mirrors them with these builders,
+    /// e.g. `NamedFieldSchema::new("cache", Cache::schema()).skip()`.
+    pub fn skip(self) -> Self {
+        self.skip_serializing().skip_deserializing()
+    }
+
+    pub fn skip_serializing(mut self) -> Self {
+        self.skip_serializing = true;
+        self
+    }
+
+    pub fn skip_deserializing(mut self) -> Self {
+        self.skip_deserializing = true;
+        self
+    }
+
+    pub fn skip_serializing_if<T: Into<String>>(mut self, predicate: T) -> Self {
+        self.skip_serializing_if = Some(predicate.into());
+        self
+    }
+
+    /// The name of the field on the wire: its `rename`, or its name with
+    /// the container's `rename_all` rule applied.
+    pub fn wire_name(&self, rule: Option<RenameAll>) -> Cow<'_, str> {
+        match (&self.rename, rule) {
+            (Some(rename), _) => Cow::Borrowed(rename),
+            (None, Some(rule)) => Cow::Owned(rule.apply_to_field(&self.name)),
+            (None, None) => Cow::Borrowed(&self.name),
         }
     }
 }
@@ -366,6 +855,8 @@ pub struct EnumSchema {
     pub(crate) rename: Option<String>,
     #[serde(default, skip_serializing_if = "is_default")]
     pub(crate) rename_all: Option<RenameAll>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub(crate) meta: Metadata,
 }
 
 impl EnumSchema {
@@ -376,6 +867,7 @@ impl EnumSchema {
             repr: EnumRepr::ExternallyTagged,
             rename: None,
             rename_all: None,
+            meta: Metadata::default(),
         }
     }
 
@@ -383,6 +875,36 @@ impl EnumSchema {
         self.variants.push(variant);
         self
     }
+
+    pub fn repr(mut self, repr: EnumRepr) -> Self {
+        self.repr = repr;
+        self
+    }
+
+    pub fn rename<T: Into<String>>(mut self, name: T) -> Self {
+        self.rename = Some(name.into());
+        self
+    }
+
+    /// Rename the variants, unless they have a `rename` of their own.
+    pub fn rename_all(mut self, rule: RenameAll) -> Self {
+        self.rename_all = Some(rule);
+        self
+    }
+
+    /// The discriminants of the variants, following Rust's rules: a variant
+    /// without an explicit one takes the previous variant's plus one.
+    pub fn discriminants(&self) -> Vec<i64> {
+        let mut next = 0;
+        self.variants
+            .iter()
+            .map(|v| {
+                let discriminant = v.discriminant.unwrap_or(next);
+                next = discriminant.wrapping_add(1);
+                discriminant
+            })
+            .collect()
+    }
 }
 
 impl From<EnumSchema> for Schema {
@@ -409,6 +931,11 @@ pub struct VariantSchema {
     pub(crate) aliases: Vec<String>,
     #[serde(default, skip_serializing_if = "is_default")]
     pub(crate) other: bool,
+    /// An explicit discriminant, e.g. `Variant = 0x10`.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub(crate) discriminant: Option<i64>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub(crate) meta: Metadata,
 }
 
 impl VariantSchema {
@@ -420,11 +947,34 @@ impl VariantSchema {
             rename: None,
             aliases: Vec::new(),
             other: false,
+            discriminant: None,
+            meta: Metadata::default(),
+        }
+    }
+
+    pub fn discriminant(mut self, discriminant: i64) -> Self {
+        self.discriminant = Some(discriminant);
+        self
+    }
+
+    pub fn rename<T: Into<String>>(mut self, name: T) -> Self {
+        self.rename = Some(name.into());
+        self
+    }
+
+    /// The name of the variant on the wire: its `rename`, or its name with
+    /// the enum's `rename_all` rule applied.
+    pub fn wire_name(&self, rule: Option<RenameAll>) -> Cow<'_, str> {
+        match (&self.rename, rule) {
+            (Some(rename), _) => Cow::Borrowed(rename),
+            (None, Some(rule)) => Cow::Owned(rule.apply_to_variant(&self.name)),
+            (None, None) => Cow::Borrowed(&self.name),
         }
     }
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Eq, Default, Clone, Debug)]
+#[allow(clippy::enum_variant_names)]
 pub enum EnumRepr {
     #[default]
     ExternallyTagged,
@@ -435,6 +985,11 @@ pub enum EnumRepr {
         tag: String,
         content: String,
     },
+    /// A C-like enum written as the integer discriminant of its variant, as
+    /// with `serde_repr`. `repr` is the integer type.
+    Integer {
+        repr: SimpleSchema,
+    },
     //Untagged,
 }
 
@@ -458,6 +1013,146 @@ pub enum RenameAll {
     ScreamingKebab,
 }
 
+/// The case conversions of `#[serde(rename_all = "...")]`, which assume
+/// fields are written in snake_case and variants in PascalCase, like
+/// serde_derive does.
+impl RenameAll {
+    pub fn apply_to_field(self, field: &str) -> String {
+        match self {
+            RenameAll::Lower | RenameAll::Snake => field.to_string(),
+            RenameAll::Upper | RenameAll::ScreamingSnake => field.to_ascii_uppercase(),
+            RenameAll::Pascal => {
+                let mut pascal = String::new();
+                let mut capitalize = true;
+                for ch in field.chars() {
+                    if ch == '_' {
+                        capitalize = true;
+                    } else if capitalize {
+                        pascal.push(ch.to_ascii_uppercase());
+                        capitalize = false;
+                    } else {
+                        pascal.push(ch);
+                    }
+                }
+                pascal
+            }
+            RenameAll::Camel => {
+                let pascal = RenameAll::Pascal.apply_to_field(field);
+                let mut chars = pascal.chars();
+                match chars.next() {
+                    Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+                    None => pascal,
+                }
+            }
+            RenameAll::Kebab => field.replace('_', "-"),
+            RenameAll::ScreamingKebab => field.to_ascii_uppercase().replace('_', "-"),
+        }
+    }
+
+    pub fn apply_to_variant(self, variant: &str) -> String {
+        match self {
+            RenameAll::Pascal => variant.to_string(),
+            RenameAll::Lower => variant.to_ascii_lowercase(),
+            RenameAll::Upper => variant.to_ascii_uppercase(),
+            RenameAll::Camel => {
+                let mut chars = variant.chars();
+                match chars.next() {
+                    Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+                    None => String::new(),
+                }
+            }
+            RenameAll::Snake => {
+                let mut snake = String::new();
+                for (i, ch) in variant.char_indices() {
+                    if i > 0 && ch.is_uppercase() {
+                        snake.push('_');
+                    }
+                    snake.push(ch.to_ascii_lowercase());
+                }
+                snake
+            }
+            RenameAll::ScreamingSnake => RenameAll::Snake
+                .apply_to_variant(variant)
+                .to_ascii_uppercase(),
+            RenameAll::Kebab => RenameAll::Snake.apply_to_variant(variant).replace('_', "-"),
+            RenameAll::ScreamingKebab => RenameAll::ScreamingSnake
+                .apply_to_variant(variant)
+                .replace('_', "-"),
+        }
+    }
+}
+
+/// Documentation of a struct, enum, field or variant. It plays no part in
+/// decoding.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Default, Clone, Debug)]
+pub struct Metadata {
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub(crate) doc: Option<String>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub(crate) deprecated: Option<Deprecated>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub(crate) examples: Vec<serde_value::Value>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub(crate) annotations: BTreeMap<String, String>,
+}
+
+/// `#[deprecated(since = "...", note = "...")]`.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Default, Clone, Debug)]
+pub struct Deprecated {
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub(crate) since: Option<String>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub(crate) note: Option<String>,
+}
+
+impl Deprecated {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn since<T: Into<String>>(mut self, version: T) -> Self {
+        self.since = Some(version.into());
+        self
+    }
+
+    pub fn note<T: Into<String>>(mut self, note: T) -> Self {
+        self.note = Some(note.into());
+        self
+    }
+}
+
+/// Builders for the [`Metadata`] of the schemas that carry it.
+macro_rules! metadata_builders {
+    ($($t:ty),+) => {
+        $(
+            impl $t {
+                /// Documentation, e.g. from `///` comments.
+                pub fn doc<T: Into<String>>(mut self, doc: T) -> Self {
+                    self.meta.doc = Some(doc.into());
+                    self
+                }
+
+                pub fn deprecated(mut self, deprecated: Deprecated) -> Self {
+                    self.meta.deprecated = Some(deprecated);
+                    self
+                }
+
+                pub fn example(mut self, example: serde_value::Value) -> Self {
+                    self.meta.examples.push(example);
+                    self
+                }
+
+                pub fn annotation<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+                    self.meta.annotations.insert(key.into(), value.into());
+                    self
+                }
+            }
+        )+
+    };
+}
+
+metadata_builders!(StructSchema, NamedFieldSchema, EnumSchema, VariantSchema);
+
 pub(crate) fn is_default<T: Default + PartialEq>(value: &T) -> bool {
     value == &T::default()
 }
@@ -0,0 +1,562 @@
+use serde::{
+    ser::{
+        Error, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple,
+        SerializeTupleStruct, SerializeTupleVariant,
+    },
+    Serialize, Serializer,
+};
+use serde_value::Value;
+
+use crate::{
+    deserializer::{
+        intern, FieldsDecode, NamedFieldsDecode, SchemaDecode, SchemaDecodeItem, SchemaDecodeItems,
+        StructDecode, VariantDecode, CYCLE,
+    },
+    project::{unwrap_value, Position},
+    EnumRepr, Expected, SchemaDecoder, SimpleSchema,
+};
+
+/// A dynamic value paired with its schema, which serializes as the
+/// described type would: structs, enums and integer widths come from the
+/// schema rather than from the value. See [`SchemaDecoder::serializable`].
+pub struct SchemaValue<'a, 'b> {
+    schema: &'a SchemaDecode<'b>,
+    items: &'a SchemaDecodeItems<'b>,
+    value: &'a Value,
+    /// How many references were followed since the value last changed.
+    references: usize,
+}
+
+impl<'b> SchemaDecoder<'b> {
+    /// Wrap a dynamic value of this schema, e.g. one decoded with this
+    /// decoder or made by a [`Generator`](crate::Generator), so that it can
+    /// be written to any format: `bincode::serialize(&decoder.serializable(&value))`.
+    pub fn serializable<'a>(&'a self, value: &'a Value) -> SchemaValue<'a, 'b> {
+        SchemaValue {
+            schema: self.schema.lookup(&self.items),
+            items: &self.items,
+            value,
+            references: 0,
+        }
+    }
+}
+
+impl<'a, 'b> SchemaValue<'a, 'b> {
+    /// The value of `item` in this one. Options, newtypes and the like can
+    /// wrap a value without it being wrapped in turn, but following more
+    /// references than there are items for the same value goes round in
+    /// circles.
+    fn child(&self, item: &'a SchemaDecodeItem<'b>, value: &'a Value) -> Self {
+        let references = match (std::ptr::eq(value, self.value), item) {
+            (false, _) => 0,
+            (true, SchemaDecodeItem::Named(_)) => self.references + 1,
+            (true, SchemaDecodeItem::Decode(_)) => self.references,
+        };
+        SchemaValue {
+            schema: match references > self.items.len() {
+                true => &CYCLE,
+                false => item.lookup(self.items),
+            },
+            items: self.items,
+            value,
+            references,
+        }
+    }
+
+    fn invalid<E: Error>(&self) -> E {
+        E::custom(format!(
+            "invalid value {}, expected {}",
+            crate::diff::show(self.value),
+            self.schema.expected()
+        ))
+    }
+}
+
+impl Serialize for SchemaValue<'_, '_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let value = unwrap_value(self.value);
+        match self.schema {
+            SchemaDecode::Simple(s) => {
+                serialize_simple(*s, value, serializer).unwrap_or_else(|| Err(self.invalid()))
+            }
+            SchemaDecode::Option(s) => match self.value {
+                Value::Option(None) => serializer.serialize_none(),
+                Value::Option(Some(value)) => {
+                    serializer.serialize_some(&self.child(&s.value, value))
+                }
+                value => serializer.serialize_some(&self.child(&s.value, value)),
+            },
+            SchemaDecode::Tuple(s) => {
+                let values = elements(value, Some(s.values.len())).ok_or_else(|| self.invalid())?;
+                let mut tuple = serializer.serialize_tuple(values.len())?;
+                for (item, value) in s.values.iter().zip(values) {
+                    tuple.serialize_element(&self.child(item, value))?;
+                }
+                tuple.end()
+            }
+            SchemaDecode::Seq(s) | SchemaDecode::Set(s) => {
+                let values = elements(value, None).ok_or_else(|| self.invalid())?;
+                let mut seq = serializer.serialize_seq(Some(values.len()))?;
+                for value in values {
+                    seq.serialize_element(&self.child(&s.value, value))?;
+                }
+                seq.end()
+            }
+            SchemaDecode::Array(s) => {
+                let values = elements(value, Some(s.len)).ok_or_else(|| self.invalid())?;
+                let mut tuple = serializer.serialize_tuple(s.len)?;
+                for value in values {
+                    tuple.serialize_element(&self.child(&s.value, value))?;
+                }
+                tuple.end()
+            }
+            SchemaDecode::Map(s) => {
+                let Value::Map(entries) = value else {
+                    return Err(self.invalid());
+                };
+                let mut map = serializer.serialize_map(Some(entries.len()))?;
+                for (key, value) in entries {
+                    map.serialize_entry(&self.child(&s.key, key), &self.child(&s.value, value))?;
+                }
+                map.end()
+            }
+            SchemaDecode::Struct(s) => match &s.fields {
+                FieldsDecode::Tuple(t) => {
+                    let values =
+                        elements(value, Some(t.values.len())).ok_or_else(|| self.invalid())?;
+                    let mut tuple = serializer.serialize_tuple_struct(s.name, values.len())?;
+                    for (item, value) in t.values.iter().zip(values) {
+                        tuple.serialize_field(&self.child(item, value))?;
+                    }
+                    tuple.end()
+                }
+                FieldsDecode::Named(fields) => {
+                    let fields = Fields {
+                        name: s.name,
+                        fields,
+                        items: self.items,
+                        value,
+                    };
+                    fields.serialize(serializer)
+                }
+            },
+            SchemaDecode::Enum(s) => {
+                let shape = Position::Schema(self.schema).shape();
+                let (name, content) = shape
+                    .entries(value)
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| self.invalid())?;
+                let variant = s
+                    .by_name(&name)
+                    .map(|i| &s.variants[i])
+                    .ok_or_else(|| S::Error::custom(format!("unknown variant {name:?}")))?;
+                let variant = Variant {
+                    enum_name: s.name,
+                    variant,
+                    items: self.items,
+                    content: &content,
+                };
+                match s.repr {
+                    EnumRepr::ExternallyTagged => variant.serialize_external(serializer),
+                    EnumRepr::InternallyTagged { tag } => {
+                        variant.serialize_internal(tag, serializer)
+                    }
+                    EnumRepr::AdjacentlyTagged { tag, content } => {
+                        variant.serialize_adjacent(intern(tag), intern(content), serializer)
+                    }
+                    EnumRepr::Integer { repr } => serialize_simple(
+                        *repr,
+                        &Value::I64(variant.variant.discriminant),
+                        serializer,
+                    )
+                    .unwrap_or_else(|| Err(self.invalid())),
+                }
+            }
+            SchemaDecode::Newtype(s) => {
+                let inner = match self.value {
+                    Value::Newtype(inner) => inner,
+                    value => value,
+                };
+                match s.transparent {
+                    true => self.child(&s.value, inner).serialize(serializer),
+                    false => {
+                        serializer.serialize_newtype_struct(s.name, &self.child(&s.value, inner))
+                    }
+                }
+            }
+            SchemaDecode::UnitStruct(s) => serializer.serialize_unit_struct(s.name),
+            SchemaDecode::HumanReadable(s) => match serializer.is_human_readable() {
+                true => self.child(&s.readable, self.value).serialize(serializer),
+                false => self.child(&s.compact, self.value).serialize(serializer),
+            },
+            SchemaDecode::Constrained(s) => self.child(&s.value, self.value).serialize(serializer),
+            SchemaDecode::Any => crate::any::serialize(self.value, serializer),
+            SchemaDecode::Invalid(reason) => {
+                Err(S::Error::custom(format!("invalid description: {reason}")))
+            }
+        }
+    }
+}
+
+/// The fields of a struct or struct variant, from a map keyed by field name.
+struct Fields<'a, 'b> {
+    name: &'static str,
+    fields: &'a NamedFieldsDecode<'b>,
+    items: &'a SchemaDecodeItems<'b>,
+    value: &'a Value,
+}
+
+impl<'a, 'b> Fields<'a, 'b> {
+    fn entries<E: Error>(&self) -> Result<Vec<(&'static str, Option<SchemaValue<'a, 'b>>)>, E> {
+        let Value::Map(entries) = self.value else {
+            return Err(E::custom(format!(
+                "invalid value {}, expected {}",
+                crate::diff::show(self.value),
+                Expected::Struct(self.name)
+            )));
+        };
+        let mut out = Vec::new();
+        for field in self.fields.fields.iter().filter(|f| !f.flatten) {
+            let value = entries
+                .get(&Value::String(field.name.to_string()))
+                .map(|value| SchemaValue {
+                    schema: field.value.lookup(self.items),
+                    items: self.items,
+                    value,
+                    references: 0,
+                });
+            if value.is_none() && !field.optional {
+                return Err(E::custom(format!("missing field `{}`", field.name)));
+            }
+            out.push((field.name, value));
+        }
+        Ok(out)
+    }
+
+    /// Entries that no field claims, which belong to flattened fields.
+    fn flattened<M: SerializeMap>(&self, map: &mut M) -> Result<(), M::Error> {
+        let Value::Map(entries) = self.value else {
+            return Ok(());
+        };
+        for (key, value) in entries {
+            let name = match key {
+                Value::String(name) if self.fields.find(name).is_none() => name,
+                _ => continue,
+            };
+            let item = self
+                .fields
+                .find_flattened(self.items, name)
+                .ok_or_else(|| M::Error::custom(format!("unknown field `{name}`")))?;
+            map.serialize_entry(
+                name,
+                &SchemaValue {
+                    schema: item.lookup(self.items),
+                    items: self.items,
+                    value,
+                    references: 0,
+                },
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Write the fields into a map that already holds an enum tag.
+    fn serialize_into<M: SerializeMap>(&self, map: &mut M) -> Result<(), M::Error> {
+        for (name, value) in self.entries()? {
+            if let Some(value) = value {
+                map.serialize_entry(name, &value)?;
+            }
+        }
+        self.flattened(map)
+    }
+}
+
+impl Serialize for Fields<'_, '_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // Like serde, structs with flattened fields are written as maps.
+        if self.fields.flattened().is_some() {
+            let mut map = serializer.serialize_map(None)?;
+            self.serialize_into(&mut map)?;
+            return map.end();
+        }
+        let entries = self.entries()?;
+        let len = entries.iter().filter(|(_, value)| value.is_some()).count();
+        let mut st = serializer.serialize_struct(self.name, len)?;
+        for (name, value) in entries {
+            match value {
+                Some(value) => st.serialize_field(name, &value)?,
+                None => st.skip_field(name)?,
+            }
+        }
+        st.end()
+    }
+}
+
+struct Variant<'a, 'b> {
+    enum_name: &'static str,
+    variant: &'a VariantDecode<'b>,
+    items: &'a SchemaDecodeItems<'b>,
+    content: &'a Value,
+}
+
+impl<'a, 'b> Variant<'a, 'b> {
+    fn value(&self, item: &'a SchemaDecodeItem<'b>, value: &'a Value) -> SchemaValue<'a, 'b> {
+        SchemaValue {
+            schema: item.lookup(self.items),
+            items: self.items,
+            value,
+            references: 0,
+        }
+    }
+
+    fn fields(&self, fields: &'a NamedFieldsDecode<'b>) -> Fields<'a, 'b> {
+        Fields {
+            name: self.variant.name,
+            fields,
+            items: self.items,
+            value: unwrap_value(self.content),
+        }
+    }
+
+    fn elements<E: Error>(&self, len: usize) -> Result<&'a [Value], E> {
+        elements(unwrap_value(self.content), Some(len)).ok_or_else(|| {
+            E::custom(format!(
+                "invalid value {}, expected {len} fields of variant {}",
+                crate::diff::show(self.content),
+                self.variant.name
+            ))
+        })
+    }
+
+    fn serialize_external<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (name, index) = (self.variant.name, self.variant.index as u32);
+        match &self.variant.fields {
+            FieldsDecode::Tuple(t) if t.values.is_empty() => {
+                serializer.serialize_unit_variant(self.enum_name, index, name)
+            }
+            FieldsDecode::Tuple(t) if t.values.len() == 1 => serializer.serialize_newtype_variant(
+                self.enum_name,
+                index,
+                name,
+                &self.value(&t.values[0], self.content),
+            ),
+            FieldsDecode::Tuple(t) => {
+                let values = self.elements(t.values.len())?;
+                let mut tuple = serializer.serialize_tuple_variant(
+                    self.enum_name,
+                    index,
+                    name,
+                    values.len(),
+                )?;
+                for (item, value) in t.values.iter().zip(values) {
+                    tuple.serialize_field(&self.value(item, value))?;
+                }
+                tuple.end()
+            }
+            FieldsDecode::Named(fields) => {
+                let entries = self.fields(fields).entries()?;
+                let len = entries.iter().filter(|(_, value)| value.is_some()).count();
+                let mut st =
+                    serializer.serialize_struct_variant(self.enum_name, index, name, len)?;
+                for (name, value) in entries {
+                    match value {
+                        Some(value) => st.serialize_field(name, &value)?,
+                        None => st.skip_field(name)?,
+                    }
+                }
+                st.end()
+            }
+        }
+    }
+
+    fn serialize_internal<S: Serializer>(
+        &self,
+        tag: &str,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry(tag, self.variant.name)?;
+        match &self.variant.fields {
+            FieldsDecode::Tuple(t) if t.values.is_empty() => {}
+            // The content of a newtype variant is a struct or a map, whose
+            // entries sit next to the tag.
+            FieldsDecode::Tuple(t) if t.values.len() == 1 => {
+                let schema = t.values[0].lookup(self.items);
+                let schema = schema.peel(self.items, |schema| match schema {
+                    SchemaDecode::Newtype(s) => Some(&s.value),
+                    SchemaDecode::Constrained(s) => Some(&s.value),
+                    _ => None,
+                });
+                let content = unwrap_value(self.content);
+                match (schema, content) {
+                    (
+                        SchemaDecode::Struct(StructDecode {
+                            name,
+                            fields: FieldsDecode::Named(fields),
+                        }),
+                        _,
+                    ) => Fields {
+                        name,
+                        fields,
+                        items: self.items,
+                        value: content,
+                    }
+                    .serialize_into(&mut map)?,
+                    (SchemaDecode::Map(s), Value::Map(entries)) => {
+                        for (key, value) in entries {
+                            map.serialize_entry(
+                                &self.value(&s.key, key),
+                                &self.value(&s.value, value),
+                            )?;
+                        }
+                    }
+                    (SchemaDecode::Any, Value::Map(entries)) => {
+                        for (key, value) in entries {
+                            map.serialize_entry(key, value)?;
+                        }
+                    }
+                    (SchemaDecode::UnitStruct(_), _) => {}
+                    (schema, _) => {
+                        return Err(S::Error::custom(format!(
+                            "cannot write {} in internally tagged variant {}",
+                            schema.expected(),
+                            self.variant.name
+                        )))
+                    }
+                }
+            }
+            FieldsDecode::Tuple(_) => {
+                return Err(S::Error::custom(format!(
+                    "cannot write tuple variant {} of an internally tagged enum",
+                    self.variant.name
+                )))
+            }
+            FieldsDecode::Named(fields) => self.fields(fields).serialize_into(&mut map)?,
+        }
+        map.end()
+    }
+
+    fn serialize_adjacent<S: Serializer>(
+        &self,
+        tag: &'static str,
+        content: &'static str,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let unit = matches!(&self.variant.fields, FieldsDecode::Tuple(t) if t.values.is_empty());
+        let mut st = serializer.serialize_struct(self.enum_name, if unit { 1 } else { 2 })?;
+        st.serialize_field(tag, self.variant.name)?;
+        match &self.variant.fields {
+            _ if unit => {}
+            FieldsDecode::Tuple(t) if t.values.len() == 1 => {
+                st.serialize_field(content, &self.value(&t.values[0], self.content))?
+            }
+            FieldsDecode::Tuple(t) => {
+                let values = self.elements(t.values.len())?;
+                let content_value = Elements {
+                    values: t
+                        .values
+                        .iter()
+                        .zip(values)
+                        .map(|(item, value)| self.value(item, value))
+                        .collect(),
+                };
+                st.serialize_field(content, &content_value)?
+            }
+            FieldsDecode::Named(fields) => st.serialize_field(content, &self.fields(fields))?,
+        }
+        st.end()
+    }
+}
+
+struct Elements<'a, 'b> {
+    values: Vec<SchemaValue<'a, 'b>>,
+}
+
+impl Serialize for Elements<'_, '_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut tuple = serializer.serialize_tuple(self.values.len())?;
+        for value in &self.values {
+            tuple.serialize_element(value)?;
+        }
+        tuple.end()
+    }
+}
+
+/// The elements of a sequence, or of a byte string, checking their number.
+fn elements(value: &Value, len: Option<usize>) -> Option<&[Value]> {
+    match value {
+        Value::Seq(values) if len.is_none_or(|len| len == values.len()) => Some(values),
+        Value::Unit if len == Some(0) => Some(&[]),
+        _ => None,
+    }
+}
+
+/// Write a value of a simple schema at the width of the schema, or `None`
+/// if it doesn't fit.
+fn serialize_simple<S: Serializer>(
+    schema: SimpleSchema,
+    value: &Value,
+    serializer: S,
+) -> Option<Result<S::Ok, S::Error>> {
+    let int = match *value {
+        Value::U8(n) => Some(i128::from(n)),
+        Value::U16(n) => Some(i128::from(n)),
+        Value::U32(n) => Some(i128::from(n)),
+        Value::U64(n) => Some(i128::from(n)),
+        Value::I8(n) => Some(i128::from(n)),
+        Value::I16(n) => Some(i128::from(n)),
+        Value::I32(n) => Some(i128::from(n)),
+        Value::I64(n) => Some(i128::from(n)),
+        _ => None,
+    };
+    let float = match *value {
+        Value::F32(n) => Some(f64::from(n)),
+        Value::F64(n) => Some(n),
+        _ => int.map(|n| n as f64),
+    };
+    Some(match (schema, value) {
+        (SimpleSchema::Unit, Value::Unit) => serializer.serialize_unit(),
+        (SimpleSchema::Bool, Value::Bool(b)) => serializer.serialize_bool(*b),
+        (SimpleSchema::U8, _) => serializer.serialize_u8(int?.try_into().ok()?),
+        (SimpleSchema::U16, _) => serializer.serialize_u16(int?.try_into().ok()?),
+        (SimpleSchema::U32, _) => serializer.serialize_u32(int?.try_into().ok()?),
+        (SimpleSchema::U64, _) => serializer.serialize_u64(int?.try_into().ok()?),
+        (SimpleSchema::U128, _) => serializer.serialize_u128(int?.try_into().ok()?),
+        (SimpleSchema::I8, _) => serializer.serialize_i8(int?.try_into().ok()?),
+        (SimpleSchema::I16, _) => serializer.serialize_i16(int?.try_into().ok()?),
+        (SimpleSchema::I32, _) => serializer.serialize_i32(int?.try_into().ok()?),
+        (SimpleSchema::I64, _) => serializer.serialize_i64(int?.try_into().ok()?),
+        (SimpleSchema::I128, _) => serializer.serialize_i128(int?),
+        (SimpleSchema::F32, _) => serializer.serialize_f32(float? as f32),
+        (SimpleSchema::F64, _) => serializer.serialize_f64(float?),
+        (SimpleSchema::Char, Value::Char(c)) => serializer.serialize_char(*c),
+        (SimpleSchema::Char, Value::String(s)) if s.chars().count() == 1 => {
+            serializer.serialize_char(s.chars().next()?)
+        }
+        (SimpleSchema::String, Value::String(s)) => serializer.serialize_str(s),
+        (SimpleSchema::String, Value::Char(c)) => serializer.serialize_char(*c),
+        (SimpleSchema::Bytes, Value::Bytes(b)) => serializer.serialize_bytes(b),
+        (SimpleSchema::Bytes, Value::Seq(values)) => {
+            let bytes = values
+                .iter()
+                .map(|v| match *v {
+                    Value::U8(b) => Some(b),
+                    Value::U64(b) => b.try_into().ok(),
+                    _ => None,
+                })
+                .collect::<Option<Vec<u8>>>()?;
+            serializer.serialize_bytes(&bytes)
+        }
+        _ => return None,
+    })
+}
@@ -0,0 +1,169 @@
+//! Checked-in descriptions for tests, so that an accidental change to how a
+//! type is serialized fails `cargo test`:
+//!
+//! ```text
+//! #[test]
+//! fn schemas() {
+//!     // Any change, down to documentation, to `schemas/Config.json`.
+//!     serde_descr::assert_schema_snapshot!(Config);
+//!     // A change that values written with version 3 cannot survive.
+//!     serde_descr::assert_schema_compatible_with!(Config, "schemas/config.v3.json");
+//! }
+//! ```
+//!
+//! Paths are relative to the directory of the crate under test. Files ending
+//! in `.json` hold the serialized description and others its text form, see
+//! [`Description::idl`]; both are read either way.
+//!
+//! Running the tests with [`UPDATE`] set to `1` writes the current
+//! descriptions to snapshots that differ, and to the files of compatibility
+//! checks that don't exist yet. Existing ones are never overwritten: they
+//! stand for released versions.
+
+use std::path::{Path, PathBuf};
+
+use crate::{schema::identifiers, Description, DeserializerOptions, SchemaName};
+
+/// The environment variable for writing descriptions instead of checking.
+pub const UPDATE: &str = "SERDE_DESCR_UPDATE";
+
+/// Compare `T::describe()` with a checked-in file, by default
+/// `schemas/<name>.json` for a type named `<name>`, see [`snapshot_path`].
+#[macro_export]
+macro_rules! assert_schema_snapshot {
+    ($ty:ty $(,)?) => {
+        $crate::assert_schema_snapshot!(
+            $ty,
+            $crate::snapshot::snapshot_path(&<$ty as $crate::Describe>::schema_name())
+        )
+    };
+    ($ty:ty, $path:expr $(,)?) => {
+        $crate::snapshot::assert_snapshot(
+            &<$ty as $crate::Describe>::describe(),
+            ::std::path::Path::new(::core::env!("CARGO_MANIFEST_DIR")).join($path),
+        )
+    };
+}
+
+/// Check that values written with the description in a checked-in file
+/// decode the same with `T::describe()`, in the JSON profile unless
+/// [`DeserializerOptions`](crate::DeserializerOptions) are given.
+#[macro_export]
+macro_rules! assert_schema_compatible_with {
+    ($ty:ty, $path:expr $(,)?) => {
+        $crate::assert_schema_compatible_with!($ty, $path, $crate::DeserializerOptions::json())
+    };
+    ($ty:ty, $path:expr, $opts:expr $(,)?) => {
+        $crate::snapshot::assert_compatible(
+            &<$ty as $crate::Describe>::describe(),
+            ::std::path::Path::new(::core::env!("CARGO_MANIFEST_DIR")).join($path),
+            &$opts,
+        )
+    };
+}
+
+/// Where [`assert_schema_snapshot!`](crate::assert_schema_snapshot) keeps
+/// the description of a type by default: `std::ops::Range<u32>` in
+/// `schemas/Range_u32.json`.
+pub fn snapshot_path(name: &SchemaName) -> PathBuf {
+    let file = &identifiers([name])[name];
+    Path::new("schemas").join(format!("{file}.json"))
+}
+
+/// Panic unless `description` is the one in the file at `path`, or write it
+/// there in update mode.
+#[track_caller]
+pub fn assert_snapshot(description: &Description, path: impl AsRef<Path>) {
+    let path = path.as_ref();
+    match read(path) {
+        Some(snapshot) if snapshot == *description => {}
+        _ if update() => write(path, description),
+        None => panic!(
+            "no schema snapshot at {}, run with {UPDATE}=1 to write it",
+            path.display()
+        ),
+        Some(snapshot) => {
+            let changes = snapshot.diff(description, &DeserializerOptions::json());
+            let changes = match changes.is_empty() {
+                true => "only documentation or other metadata changed\n".to_string(),
+                false => changes.iter().map(|c| format!("{c}\n")).collect(),
+            };
+            panic!(
+                "the schema differs from the snapshot at {}:\n{changes}\
+                 run with {UPDATE}=1 to update it if the change is intended",
+                path.display()
+            )
+        }
+    }
+}
+
+/// Panic if values written with the description in the file at `path` may
+/// fail to decode with `description`, or decode differently, in the `opts`
+/// profile. Writes the file in update mode if it doesn't exist.
+#[track_caller]
+pub fn assert_compatible(
+    description: &Description,
+    path: impl AsRef<Path>,
+    opts: &DeserializerOptions,
+) {
+    let path = path.as_ref();
+    let Some(old) = read(path) else {
+        match update() {
+            true => return write(path, description),
+            false => panic!(
+                "no schema at {}, run with {UPDATE}=1 to write it",
+                path.display()
+            ),
+        }
+    };
+    let changes = old.diff(description, opts);
+    let breaking = changes.iter().filter(|c| c.breaking).count();
+    if breaking > 0 {
+        let text: String = changes.iter().map(|c| format!("{c}\n")).collect();
+        panic!(
+            "{breaking} of {} changes since {} are breaking:\n{text}",
+            changes.len(),
+            path.display()
+        )
+    }
+}
+
+fn update() -> bool {
+    std::env::var_os(UPDATE).is_some_and(|value| !value.is_empty() && value != "0")
+}
+
+/// The description in a file, or `None` if there is no file.
+#[track_caller]
+fn read(path: &Path) -> Option<Description> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+        Err(e) => panic!("{}: {e}", path.display()),
+    };
+    let description = match text.trim_start().starts_with('{') {
+        true => serde_json::from_str(&text).map_err(|e| e.to_string()),
+        false => Description::from_idl(&text),
+    };
+    match description {
+        Ok(description) => Some(description),
+        Err(e) => panic!("{}: invalid description: {e}", path.display()),
+    }
+}
+
+#[track_caller]
+fn write(path: &Path, description: &Description) {
+    let text = match path.extension().is_some_and(|ext| ext == "json") {
+        true => {
+            let json = serde_json::to_string_pretty(description);
+            json.expect("descriptions serialize to JSON") + "\n"
+        }
+        false => description.idl(),
+    };
+    let written = match path.parent() {
+        Some(dir) => std::fs::create_dir_all(dir),
+        None => Ok(()),
+    };
+    if let Err(e) = written.and_then(|()| std::fs::write(path, text)) {
+        panic!("{}: {e}", path.display())
+    }
+}
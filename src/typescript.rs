@@ -0,0 +1,282 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    schema::identifiers, Description, EnumRepr, EnumSchema, FieldsSchema, Metadata,
+    NamedFieldsSchema, RenameAll, Schema, SchemaItem, SchemaName, SimpleSchema, VariantSchema,
+};
+
+impl Description {
+    /// Export the description as TypeScript declarations of the JSON that
+    /// `serde_json` writes for the described type: a type alias for every
+    /// named schema, and `Root` for the described type unless it is one of
+    /// them. 64- and 128-bit integers are `number`s, which JavaScript reads
+    /// with a loss of precision. Sets are the arrays JSON holds them as,
+    /// marked `/* unique */`, rather than a `Set`, and fixed-length arrays
+    /// such as `[T; 3]` are `Array<T>` rather than a tuple type of their
+    /// length.
+    pub fn typescript(&self) -> String {
+        let exporter = Exporter {
+            descr: self,
+            names: identifiers(self.items.keys()),
+        };
+        let mut out = String::new();
+        if let SchemaItem::Schema(schema) = &self.schema {
+            out.push_str(&exporter.declaration("Root", schema));
+        }
+        for (name, schema) in &self.items {
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            out.push_str(&exporter.declaration(&exporter.names[name], schema));
+        }
+        out
+    }
+}
+
+struct Exporter<'a> {
+    descr: &'a Description,
+    names: BTreeMap<&'a SchemaName, String>,
+}
+
+impl Exporter<'_> {
+    fn declaration(&self, name: &str, schema: &Schema) -> String {
+        let meta = match schema {
+            Schema::Struct(s) => Some(&s.meta),
+            Schema::Enum(s) => Some(&s.meta),
+            _ => None,
+        };
+        let doc = meta.map(|meta| doc_comment(meta, "")).unwrap_or_default();
+        let body = self.schema(schema, "");
+        let space = match body.starts_with('\n') {
+            true => "",
+            false => " ",
+        };
+        format!("{doc}export type {name} ={space}{body};\n")
+    }
+
+    fn item(&self, item: &SchemaItem, indent: &str) -> String {
+        match item {
+            SchemaItem::Schema(schema) => self.schema(schema, indent),
+            SchemaItem::Named(name) => match self.names.get(name) {
+                Some(name) => name.clone(),
+                None => "never".to_string(),
+            },
+        }
+    }
+
+    fn schema(&self, schema: &Schema, indent: &str) -> String {
+        match schema {
+            Schema::Simple(s) => simple(*s).to_string(),
+            Schema::Option(s) => format!("{} | null", self.item(&s.value, indent)),
+            Schema::Tuple(s) => self.tuple(&s.values, indent),
+            Schema::Seq(s) => format!("Array<{}>", self.item(&s.value, indent)),
+            // JSON holds sets as arrays, not as a `Set`.
+            Schema::Set(s) => format!("Array<{}> /* unique */", self.item(&s.value, indent)),
+            Schema::Array(s) => format!("Array<{}>", self.item(&s.value, indent)),
+            // Keys are written as strings, numbers included.
+            Schema::Map(s) => format!("Record<string, {}>", self.item(&s.value, indent)),
+            Schema::Struct(s) => match &s.fields {
+                FieldsSchema::Tuple(t) => self.tuple(&t.values, indent),
+                FieldsSchema::Named(fields) => self.fields(fields, s.rename_all, &[], indent),
+            },
+            Schema::Enum(s) => self.enum_schema(s, indent),
+            // serde_json writes newtype structs as their content.
+            Schema::Newtype(s) => self.item(&s.value, indent),
+            Schema::UnitStruct(_) => "null".to_string(),
+            Schema::HumanReadable(s) => self.item(&s.readable, indent),
+            Schema::Constrained(s) => self.item(&s.value, indent),
+            Schema::Any => "unknown".to_string(),
+        }
+    }
+
+    fn tuple(&self, values: &[SchemaItem], indent: &str) -> String {
+        let values: Vec<_> = values.iter().map(|v| self.item(v, indent)).collect();
+        format!("[{}]", values.join(", "))
+    }
+
+    /// An object type with the `extra` properties (an enum tag) followed by
+    /// the fields, intersected with the types of flattened fields.
+    fn fields(
+        &self,
+        fields: &NamedFieldsSchema,
+        rename_all: Option<RenameAll>,
+        extra: &[(&str, String)],
+        indent: &str,
+    ) -> String {
+        let inner = format!("{indent}  ");
+        let mut properties: Vec<_> = extra
+            .iter()
+            .map(|(name, ty)| format!("{inner}{}: {ty};\n", property(name)))
+            .collect();
+        let mut flattened = Vec::new();
+        for field in fields.fields.iter().filter(|f| !f.skip_serializing) {
+            // A flattened map's type is a `Record` already.
+            if field.flatten {
+                flattened.push(group(self.item(&field.value, indent)));
+                continue;
+            }
+            let optional = match self.descr.json_required(field) {
+                true => "",
+                false => "?",
+            };
+            properties.push(format!(
+                "{}{inner}{}{optional}: {};\n",
+                doc_comment(&field.meta, &inner),
+                property(&field.wire_name(rename_all)),
+                self.item(&field.value, &inner)
+            ));
+        }
+        let object = match properties.is_empty() {
+            true => "{}".to_string(),
+            false => format!("{{\n{}{indent}}}", properties.concat()),
+        };
+        std::iter::once(object)
+            .chain(flattened)
+            .collect::<Vec<_>>()
+            .join(" & ")
+    }
+
+    fn enum_schema(&self, s: &EnumSchema, indent: &str) -> String {
+        if let EnumRepr::Integer { .. } = s.repr {
+            let discriminants: Vec<_> = s.discriminants().iter().map(i64::to_string).collect();
+            return discriminants.join(" | ");
+        }
+        let inner = format!("{indent}  ");
+        let variants: Vec<_> = s
+            .variants
+            .iter()
+            .map(|v| {
+                let name = v.wire_name(s.rename_all);
+                let doc = doc_comment(&v.meta, &inner);
+                format!(
+                    "\n{doc}{inner}| {}",
+                    self.variant(&s.repr, &name, v, &inner)
+                )
+            })
+            .collect();
+        match variants.is_empty() {
+            true => "never".to_string(),
+            false => variants.concat(),
+        }
+    }
+
+    fn variant(&self, repr: &EnumRepr, name: &str, v: &VariantSchema, indent: &str) -> String {
+        let tag = serde_json::to_string(name).unwrap_or_default();
+        let content = match &v.fields {
+            FieldsSchema::Tuple(t) if t.values.is_empty() => None,
+            FieldsSchema::Tuple(t) if t.values.len() == 1 => Some(self.item(&t.values[0], indent)),
+            FieldsSchema::Tuple(t) => Some(self.tuple(&t.values, indent)),
+            FieldsSchema::Named(fields) => Some(self.fields(fields, None, &[], indent)),
+        };
+        match (repr, content) {
+            (EnumRepr::ExternallyTagged, None) => tag,
+            (EnumRepr::ExternallyTagged, Some(content)) => {
+                format!("{{ {}: {content} }}", property(name))
+            }
+            // The tag goes in with the fields of struct variants, and next
+            // to the content of newtype variants.
+            (EnumRepr::InternallyTagged { tag: key }, _) => match &v.fields {
+                FieldsSchema::Named(fields) => self.fields(fields, None, &[(key, tag)], indent),
+                FieldsSchema::Tuple(t) if t.values.len() == 1 => format!(
+                    "{{ {}: {tag} }} & {}",
+                    property(key),
+                    group(self.item(&t.values[0], indent))
+                ),
+                _ => format!("{{ {}: {tag} }}", property(key)),
+            },
+            (
+                EnumRepr::AdjacentlyTagged {
+                    tag: key,
+                    content: _,
+                },
+                None,
+            ) => {
+                format!("{{ {}: {tag} }}", property(key))
+            }
+            (
+                EnumRepr::AdjacentlyTagged {
+                    tag: key,
+                    content: c,
+                },
+                Some(content),
+            ) => {
+                format!("{{ {}: {tag}; {}: {content} }}", property(key), property(c))
+            }
+            (EnumRepr::Integer { .. }, _) => unreachable!("integer enums have no variant types"),
+        }
+    }
+}
+
+fn simple(s: SimpleSchema) -> &'static str {
+    match s {
+        SimpleSchema::Unit => "null",
+        SimpleSchema::Bool => "boolean",
+        SimpleSchema::Char | SimpleSchema::String => "string",
+        SimpleSchema::Bytes => "Array<number>",
+        _ => "number",
+    }
+}
+
+/// A property name, quoted unless it is an identifier.
+fn property(name: &str) -> String {
+    let mut chars = name.chars();
+    let identifier = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    match identifier {
+        true => name.to_string(),
+        false => serde_json::to_string(name).unwrap_or_default(),
+    }
+}
+
+/// Parenthesize a union, to intersect it with other types.
+fn group(ty: String) -> String {
+    let mut depth = 0;
+    for c in ty.chars() {
+        match c {
+            '{' | '[' | '(' | '<' => depth += 1,
+            '}' | ']' | ')' | '>' => depth -= 1,
+            '|' if depth == 0 => return format!("({ty})"),
+            _ => {}
+        }
+    }
+    ty
+}
+
+/// A JSDoc comment of the documentation, with `@deprecated`, `@example`
+/// and annotation tags.
+fn doc_comment(meta: &Metadata, indent: &str) -> String {
+    let mut lines: Vec<String> = meta
+        .doc
+        .iter()
+        .flat_map(|doc| doc.lines())
+        .map(str::to_string)
+        .collect();
+    if let Some(deprecated) = &meta.deprecated {
+        lines.push(match &deprecated.note {
+            Some(note) => format!("@deprecated {note}"),
+            None => "@deprecated".to_string(),
+        });
+    }
+    for example in &meta.examples {
+        if let Ok(json) = serde_json::to_string(example) {
+            lines.push(format!("@example {json}"));
+        }
+    }
+    // Annotations are custom tags.
+    for (key, value) in &meta.annotations {
+        lines.push(format!("@{key} {value}").trim_end().to_string());
+    }
+    match lines.len() {
+        0 => String::new(),
+        1 => format!("{indent}/** {} */\n", lines[0]),
+        _ => {
+            let lines: String = lines
+                .iter()
+                .map(|line| format!("{indent} * {line}\n").replace(" * \n", " *\n"))
+                .collect();
+            format!("{indent}/**\n{lines}{indent} */\n")
+        }
+    }
+}
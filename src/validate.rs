@@ -0,0 +1,252 @@
+use std::{
+    cmp::Ordering,
+    collections::{BTreeSet, HashMap},
+    fmt::Display,
+};
+
+use regex::Regex;
+use serde_value::Value;
+
+use crate::{
+    deserializer::{SchemaDecode, SchemaDecodeItems},
+    diff::{field_path, key_path, show},
+    project::{key_string, unwrap_value, Position, Shape},
+    Constraints, Description, SchemaDecoder,
+};
+
+/// A value that breaks a [`Constraints`] of its schema, at a path in the
+/// syntax of [`Query`](crate::Query).
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Violation {
+    pub path: String,
+    pub message: String,
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.path.is_empty() {
+            true => write!(f, ".: {}", self.message),
+            false => write!(f, "{}: {}", self.path, self.message),
+        }
+    }
+}
+
+impl Description {
+    /// Check a dynamic value of the described type against the constraints
+    /// of its schema. See [`SchemaDecoder::validate`].
+    pub fn validate(&self, value: &Value) -> Vec<Violation> {
+        SchemaDecoder::new(self).validate(value)
+    }
+}
+
+impl SchemaDecoder<'_> {
+    /// Check a dynamic value decoded with this decoder against the
+    /// constraints of its schema. Parts of the value that don't have the
+    /// shape of the schema are skipped: decoding already rejects those.
+    pub fn validate(&self, value: &Value) -> Vec<Violation> {
+        let mut validation = Validation::default();
+        validate(
+            String::new(),
+            Position::Schema(self.schema.lookup(&self.items)),
+            &self.items,
+            value,
+            &mut validation,
+        );
+        validation.violations
+    }
+}
+
+/// The violations found so far, and the patterns compiled to find them:
+/// each once per call, however many values it checks.
+#[derive(Default)]
+struct Validation {
+    violations: Vec<Violation>,
+    patterns: HashMap<String, Result<Regex, regex::Error>>,
+}
+
+fn validate<'a, 'b>(
+    path: String,
+    mut position: Position<'a, 'b>,
+    items: &'a SchemaDecodeItems<'b>,
+    value: &Value,
+    validation: &mut Validation,
+) {
+    if let Position::Schema(schema) = position {
+        let mut none = false;
+        let schema = schema.peel(items, |schema| match schema {
+            SchemaDecode::Constrained(s) => {
+                check(&path, s.constraints, value, validation);
+                Some(&s.value)
+            }
+            SchemaDecode::Option(s) => match unwrap_value(value) {
+                Value::Option(None) => {
+                    none = true;
+                    None
+                }
+                _ => Some(&s.value),
+            },
+            SchemaDecode::Newtype(s) => Some(&s.value),
+            SchemaDecode::HumanReadable(s) => match unwrap_value(value) {
+                Value::String(_) => Some(&s.readable),
+                _ => Some(&s.compact),
+            },
+            _ => None,
+        });
+        if none {
+            return;
+        }
+        position = Position::Schema(schema);
+    }
+    let value = unwrap_value(value);
+    if let (Position::Schema(SchemaDecode::Set(_)), Value::Seq(_)) = (position, value) {
+        let unique = Constraints::new().unique();
+        check(&path, &unique, value, validation);
+    }
+    match (position, value) {
+        // Map keys have a schema of their own, which may be constrained too.
+        (Position::Schema(SchemaDecode::Map(s)), Value::Map(entries)) => {
+            for (key, entry) in entries {
+                let path = format!("{path}{}", key_path(key));
+                let (key_schema, value_schema) = (s.key.lookup(items), s.value.lookup(items));
+                validate(
+                    path.clone(),
+                    Position::Schema(key_schema),
+                    items,
+                    key,
+                    validation,
+                );
+                validate(
+                    path,
+                    Position::Schema(value_schema),
+                    items,
+                    entry,
+                    validation,
+                );
+            }
+        }
+        (Position::Schema(SchemaDecode::Enum(_)), _) | (_, Value::Map(_) | Value::Seq(_)) => {
+            let shape = position.shape();
+            for (name, entry) in shape.entries(value) {
+                let path = match (&shape, value) {
+                    (Shape::Enum(_), _) => format!("{path}::{name}"),
+                    (_, Value::Seq(_)) => format!("{path}[{name}]"),
+                    _ => format!("{path}{}", field_path(&name)),
+                };
+                if let Ok(child) = position.step(&name, items) {
+                    validate(path, child, items, &entry, validation);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn check(path: &str, constraints: &Constraints, value: &Value, validation: &mut Validation) {
+    let Validation {
+        violations,
+        patterns,
+    } = validation;
+    let mut violation = |message: String| {
+        violations.push(Violation {
+            path: path.to_string(),
+            message,
+        })
+    };
+    let value = unwrap_value(value);
+
+    if let Some(min) = &constraints.min {
+        if compare(value, min) == Some(Ordering::Less) {
+            violation(format!("{} is less than the minimum {min}", show(value)));
+        }
+    }
+    if let Some(max) = &constraints.max {
+        if compare(value, max) == Some(Ordering::Greater) {
+            violation(format!("{} is greater than the maximum {max}", show(value)));
+        }
+    }
+
+    let len = match value {
+        Value::String(s) => Some(s.chars().count()),
+        Value::Bytes(b) => Some(b.len()),
+        Value::Seq(values) => Some(values.len()),
+        Value::Map(entries) => Some(entries.len()),
+        _ => None,
+    };
+    if let Some(len) = len {
+        if let Some(min) = constraints.min_length.filter(|&min| len < min) {
+            violation(format!("length {len} is less than the minimum {min}"));
+        }
+        if let Some(max) = constraints.max_length.filter(|&max| len > max) {
+            violation(format!("length {len} is greater than the maximum {max}"));
+        }
+    }
+
+    if let (Some(pattern), Value::String(s)) = (&constraints.pattern, value) {
+        match compile(patterns, pattern) {
+            Ok(regex) if !regex.is_match(s) => {
+                violation(format!("{s:?} does not match pattern {pattern:?}"))
+            }
+            Ok(_) => {}
+            Err(e) => violation(format!("invalid pattern {pattern:?}: {e}")),
+        }
+    }
+
+    if let (true, Value::Seq(values)) = (constraints.unique, value) {
+        let mut seen = BTreeSet::new();
+        for (i, element) in values.iter().enumerate() {
+            if !seen.insert(element) {
+                violation(format!("element {i} is a duplicate"));
+            }
+        }
+    }
+
+    if let (Some(pattern), Value::Map(entries)) = (&constraints.key_pattern, value) {
+        match compile(patterns, pattern) {
+            Ok(regex) => {
+                for key in entries.keys().filter_map(key_string) {
+                    if !regex.is_match(&key) {
+                        violation(format!("key {key:?} does not match pattern {pattern:?}"));
+                    }
+                }
+            }
+            Err(e) => violation(format!("invalid pattern {pattern:?}: {e}")),
+        }
+    }
+}
+
+fn compile<'p>(
+    patterns: &'p mut HashMap<String, Result<Regex, regex::Error>>,
+    pattern: &str,
+) -> &'p Result<Regex, regex::Error> {
+    if !patterns.contains_key(pattern) {
+        patterns.insert(pattern.to_string(), Regex::new(pattern));
+    }
+    &patterns[pattern]
+}
+
+/// Compare a number with a bound, exactly for integers.
+fn compare(value: &Value, bound: &serde_json::Number) -> Option<Ordering> {
+    let int = match *value {
+        Value::U8(n) => Some(i128::from(n)),
+        Value::U16(n) => Some(i128::from(n)),
+        Value::U32(n) => Some(i128::from(n)),
+        Value::U64(n) => Some(i128::from(n)),
+        Value::I8(n) => Some(i128::from(n)),
+        Value::I16(n) => Some(i128::from(n)),
+        Value::I32(n) => Some(i128::from(n)),
+        Value::I64(n) => Some(i128::from(n)),
+        _ => None,
+    };
+    let bound_int = (bound.as_i64().map(i128::from)).or_else(|| bound.as_u64().map(i128::from));
+    match (int, bound_int) {
+        (Some(n), Some(bound)) => Some(n.cmp(&bound)),
+        _ => {
+            let float = match *value {
+                Value::F32(n) => f64::from(n),
+                Value::F64(n) => n,
+                _ => int? as f64,
+            };
+            float.partial_cmp(&bound.as_f64()?)
+        }
+    }
+}
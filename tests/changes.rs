@@ -0,0 +1,291 @@
+mod common;
+
+use std::marker::PhantomData;
+
+use bincode::Options;
+use common::*;
+use serde::de::DeserializeSeed;
+use serde_descr::{
+    ChangeKind, Describe, Description, DeserializerOptions, Element, Generator, SchemaChange,
+    SchemaDecoder,
+};
+
+/// `MyStruct<External>` a few releases later.
+const MY_STRUCT_2: &str = r#"root MyStruct2<External>;
+
+enum External {
+    Unit,
+    Newtype(string),
+    #[serde(rename = "Pair")]
+    Tuple(string, u64),
+    Extra,
+    Struct {
+        float: f64,
+    },
+}
+
+struct MyStruct2<External> {
+    #[serde(rename = "name", alias = "string")]
+    string: string,
+    int: Option<u64>,
+    value: External,
+    #[serde(default = 0u32)]
+    extra: u32,
+}
+"#;
+
+fn text(changes: &[SchemaChange]) -> String {
+    changes.iter().map(|c| format!("{c}\n")).collect()
+}
+
+#[test]
+fn evolution() {
+    let old = MyStruct::<External>::describe();
+    let new = Description::from_idl(MY_STRUCT_2).unwrap();
+    assert_eq!(
+        text(&old.diff(&new, &DeserializerOptions::json())),
+        "~ MyStruct2<External>: type renamed MyStruct<External> -> MyStruct2<External>
+~ MyStruct2<External>.string: field renamed string -> name
+~ MyStruct2<External>.int: type u64 -> Option<u64>
++ MyStruct2<External>.extra: field added
+~ External::Tuple: variant renamed Tuple -> Pair (breaking)
++ External::Extra: variant added
+~ External::Struct: position 3 -> 4
+"
+    );
+    // Bincode writes neither field nor variant names, but every field and
+    // the index of every variant.
+    assert_eq!(
+        text(&old.diff(&new, &DeserializerOptions::bincode_varint())),
+        "~ MyStruct2<External>: type renamed MyStruct<External> -> MyStruct2<External>
+~ MyStruct2<External>.string: field renamed string -> name
+~ MyStruct2<External>.int: type u64 -> Option<u64> (breaking)
++ MyStruct2<External>.extra: field added (breaking)
+~ External::Tuple: variant renamed Tuple -> Pair
++ External::Extra: variant added
+~ External::Struct: position 3 -> 4 (breaking)
+"
+    );
+
+    let json = serde_json::to_value(old.diff(&new, &DeserializerOptions::json())).unwrap();
+    assert_eq!(
+        json[4],
+        serde_json::json!({
+            "path": "External::Tuple",
+            "change": "renamed",
+            "element": "variant",
+            "old": "Tuple",
+            "new": "Pair",
+            "breaking": true,
+        })
+    );
+}
+
+#[test]
+fn unchanged() {
+    for description in [
+        MyStruct::<Internal>::describe(),
+        MyStruct::<Adjacent>::describe(),
+        Containers::describe(),
+        StdTypes::describe(),
+        Network::describe(),
+        Wrappers::describe(),
+        Flattened::describe(),
+        Skipped::describe(),
+        Instruction::describe(),
+        Listener::describe(),
+    ] {
+        for opts in [
+            DeserializerOptions::json(),
+            DeserializerOptions::bincode_varint(),
+        ] {
+            assert_eq!(description.diff(&description, &opts), []);
+        }
+    }
+}
+
+#[test]
+fn changes() {
+    let old = Description::from_idl(
+        r#"root A;
+
+#[serde(tag = "t")]
+enum E {
+    X,
+    Y { a: u8 },
+}
+
+#[repr(u8)]
+enum Level {
+    Low,
+    High = 5,
+}
+
+struct A {
+    #[serde(default = 1u8, skip_serializing_if = "is_one")]
+    a: u8,
+    b: Vec<E>,
+    c: Level,
+    d: (u8, string),
+}
+"#,
+    )
+    .unwrap();
+    let new = Description::from_idl(
+        r#"root A;
+
+enum E {
+    X,
+    Y { a: u8 },
+}
+
+#[repr(u8)]
+enum Level {
+    Low,
+    High = 6,
+}
+
+struct A {
+    c: Level,
+    #[serde(default = 2u8, skip_serializing_if = "is_one")]
+    a: u8,
+    b: Set<E>,
+    d: (u8,),
+}
+"#,
+    )
+    .unwrap();
+    let changes = old.diff(&new, &DeserializerOptions::json());
+    assert_eq!(
+        text(&changes),
+        "~ A.c: position 2 -> 0
+~ A.a: default 1u8 -> 2u8 (breaking)
+~ A.b: type Vec<E> -> Set<E>
+~ A.d: type (u8, string) -> (u8,) (breaking)
+~ E: representation tag = \"t\" -> externally tagged (breaking)
+~ Level::High: discriminant 5 -> 6 (breaking)
+"
+    );
+    assert_eq!(
+        changes[0].kind,
+        ChangeKind::Moved { old: 2, new: 0 },
+        "{changes:?}"
+    );
+
+    // A type that is gone is only breaking where it was used.
+    let changes = Tree::describe().diff(&u8::describe(), &DeserializerOptions::json());
+    assert_eq!(
+        changes[1],
+        SchemaChange {
+            path: "Tree".to_string(),
+            kind: ChangeKind::Removed {
+                element: Element::Type,
+            },
+            breaking: false,
+        }
+    );
+    assert_eq!(text(&changes[..1]), "~ root: type Tree -> u8 (breaking)\n");
+}
+
+/// Encode values generated from `old` and decode them with `new`.
+fn decodes(old: &Description, new: &Description, opts: &DeserializerOptions) -> bool {
+    let decoder = SchemaDecoder::new(new);
+    (0..64).all(|seed| {
+        let mut generator = Generator::new(old).seed(seed).max_depth(4);
+        let value = generator.generate().unwrap();
+        let serializable = generator.decoder().serializable(&value);
+        let seed = decoder.seed(opts, PhantomData::<serde_value::Value>);
+        match opts.int_encoding() {
+            serde_descr::IntEncoding::Native => {
+                let json = serde_json::to_string(&serializable).unwrap();
+                seed.deserialize(&mut serde_json::Deserializer::from_str(&json))
+                    .is_ok()
+            }
+            _ => {
+                let bytes = bincode::DefaultOptions::new()
+                    .serialize(&serializable)
+                    .unwrap();
+                bincode::DefaultOptions::new()
+                    .deserialize_seed(seed, &bytes)
+                    .is_ok()
+            }
+        }
+    })
+}
+
+/// Whether changes are breaking agrees with whether values decode.
+#[test]
+fn breaking_changes_fail_to_decode() {
+    // The old and the new root type, and whether the change breaks JSON and
+    // bincode.
+    let cases = [
+        ("u8", "u32", false, true),
+        ("u16", "u64", false, false),
+        ("i32", "u64", true, true),
+        ("f32", "f64", false, true),
+        ("u8", "Option<u8>", false, true),
+        ("Vec<u8>", "Set<u8>", false, false),
+        ("[u8; 2]", "(u8, u8)", false, false),
+        ("[u8; 2]", "Vec<u8>", false, true),
+        ("struct A(u8);", "u8", false, false),
+        ("u8", "#[validate(max = 10)] u8", true, true),
+        (
+            "struct A { a: u8 }",
+            "struct A { a: u8, b: Option<u8> }",
+            false,
+            true,
+        ),
+        (
+            "struct A { a: u8 }",
+            "struct A { a: u8, b: u8 }",
+            true,
+            true,
+        ),
+        (
+            "struct A { a: u8, b: u8 }",
+            "struct A { b: u8, a: u8 }",
+            false,
+            true,
+        ),
+        (
+            "struct A { a: u8 }",
+            "struct A { #[serde(alias = \"a\")] b: u8 }",
+            false,
+            false,
+        ),
+        ("struct A { a: u8 }", "struct A { b: u8 }", true, false),
+        ("enum E { A, B }", "enum E { A, B, C }", false, false),
+        ("enum E { A, B }", "enum E { C, A, B }", false, true),
+        ("enum E { A, B }", "enum E { A }", true, true),
+        ("enum E { A(u16), B }", "enum E { A(u32), B }", false, false),
+    ];
+    let root = |ty: &str| match ty.starts_with("struct") || ty.starts_with("enum") {
+        true => {
+            let name = ty.split_whitespace().nth(1).unwrap();
+            let name: String = name.chars().take_while(|c| c.is_alphanumeric()).collect();
+            Description::from_idl(&format!("root {name};\n{ty}")).unwrap()
+        }
+        false => Description::from_idl(&format!("root {ty};")).unwrap(),
+    };
+    for (old, new, json, bincode) in cases {
+        let (old_description, new_description) = (root(old), root(new));
+        for (opts, breaking) in [
+            (DeserializerOptions::json(), json),
+            (DeserializerOptions::bincode_varint(), bincode),
+        ] {
+            let changes = old_description.diff(&new_description, &opts);
+            assert!(!changes.is_empty(), "{old} -> {new}");
+            assert_eq!(
+                changes.iter().any(|c| c.breaking),
+                breaking,
+                "{old} -> {new}: {changes:?}"
+            );
+            if !breaking {
+                assert!(
+                    decodes(&old_description, &new_description, &opts),
+                    "{old} -> {new}"
+                );
+            }
+        }
+    }
+}
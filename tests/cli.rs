@@ -0,0 +1,212 @@
+mod common;
+
+use std::{
+    io::Write,
+    path::PathBuf,
+    process::{Command, Output, Stdio},
+};
+
+use common::*;
+use serde_descr::{Describe, Description, DeserializerOptions};
+
+/// Write a description where the binary can read it.
+fn schema(name: &str, description: &Description) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("serde-descr-cli-{name}.json"));
+    std::fs::write(&path, serde_json::to_vec(description).unwrap()).unwrap();
+    path
+}
+
+fn run(args: &[&str], stdin: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_serde-descr"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn validate() {
+    let listener = schema("listener", &Listener::describe());
+    let listener = listener.to_str().unwrap();
+    let valid = br#"{"name": "api", "port": 80, "load": 0.5, "hosts": ["a"], "limits": {}}"#;
+    let output = run(&["validate", "--schema", listener], valid);
+    assert_eq!(output.status.code(), Some(0));
+
+    let output = run(&["validate", "--schema", listener], br#"{"name": "api"}"#);
+    assert_eq!(output.status.code(), Some(1));
+    let output = run(
+        &["validate", "--schema", listener, "--format", "yaml"],
+        valid,
+    );
+    assert_eq!(output.status.code(), Some(2));
+    let output = run(&["validate"], valid);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn formats() {
+    let tree = schema("tree-formats", &Tree::describe());
+    let tree = tree.to_str().unwrap();
+    for format in ["postcard", "msgpack-compact", "cbor", "yaml"] {
+        let output = run(&["gen", "--schema", tree, "--format", format], b"");
+        assert_eq!(output.status.code(), Some(2));
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(
+            stderr.contains(&format!(
+                "format \"{format}\": expected json, bincode, bincode-fixint or bitcode"
+            )),
+            "{stderr}"
+        );
+    }
+}
+
+#[test]
+fn gen_and_transcode() {
+    let tree = schema("tree", &Tree::describe());
+    let tree = tree.to_str().unwrap();
+    let generated = run(
+        &["gen", "--schema", tree, "--count", "3", "--seed", "1"],
+        b"",
+    );
+    assert_eq!(generated.status.code(), Some(0));
+    assert_eq!(generated.stdout.iter().filter(|&&b| b == b'\n').count(), 3);
+
+    let bincode = run(
+        &["transcode", "--schema", tree, "--to", "bincode"],
+        &generated.stdout,
+    );
+    assert_eq!(bincode.status.code(), Some(0));
+    let json = run(
+        &[
+            "transcode",
+            "--schema",
+            tree,
+            "--format",
+            "bincode",
+            "--to",
+            "json",
+        ],
+        &bincode.stdout,
+    );
+    assert_eq!(json.stdout, generated.stdout);
+}
+
+#[test]
+fn query() {
+    let tree = schema("query-tree", &Tree::describe());
+    let tree = tree.to_str().unwrap();
+    let json = br#"{"value": 1, "children": [{"value": 2, "children": []}]}
+{"value": 3, "children": []}"#;
+    let bincode = run(&["transcode", "--schema", tree, "--to", "bincode"], json);
+    for (format, input) in [("json", &json[..]), ("bincode", &bincode.stdout)] {
+        let query = |query| {
+            run(
+                &["query", "--schema", tree, "--format", format, query],
+                input,
+            )
+        };
+        let output = query(".value");
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), "1\n3\n");
+        let output = query(".children[].value");
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), "2\n");
+        let output = query(".children[9223372036854775807]");
+        assert_eq!(output.status.code(), Some(0));
+        assert!(output.stdout.is_empty());
+        assert_eq!(query(".value.[0]").status.code(), Some(2));
+    }
+}
+
+#[test]
+fn compat() {
+    let external = schema("external", &MyStruct::<External>::describe());
+    let internal = schema("internal", &MyStruct::<Internal>::describe());
+    let (external, internal) = (external.to_str().unwrap(), internal.to_str().unwrap());
+    let output = run(&["compat", external, external, "--count", "20"], b"");
+    assert_eq!(output.status.code(), Some(0));
+    let output = run(&["compat", external, internal, "--count", "20"], b"");
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.ends_with("20 of 20 samples failed to decode\n"));
+}
+
+#[test]
+fn diff() {
+    let external = schema("diff-external", &MyStruct::<External>::describe());
+    let internal = schema("diff-internal", &MyStruct::<Internal>::describe());
+    let (external, internal) = (external.to_str().unwrap(), internal.to_str().unwrap());
+    let output = run(&["diff", external, external], b"");
+    assert_eq!(output.status.code(), Some(0));
+    assert!(output.stdout.is_empty());
+    for args in [
+        &["diff", external][..],
+        &["diff", external, external, external],
+    ] {
+        assert_eq!(run(args, b"").status.code(), Some(2));
+    }
+
+    let output = run(&["diff", external, internal, "--to", "json"], b"");
+    assert_eq!(output.status.code(), Some(1));
+    let changes: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let expected = MyStruct::<External>::describe().diff(
+        &MyStruct::<Internal>::describe(),
+        &DeserializerOptions::json(),
+    );
+    assert_eq!(changes, serde_json::to_value(&expected).unwrap());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let breaking = expected.iter().filter(|change| change.breaking).count();
+    assert!(stderr.contains(&format!(
+        "{breaking} of {} changes are breaking",
+        expected.len()
+    )));
+
+    // Renaming a field only breaks formats that write field names.
+    let tree = schema("diff-tree", &Tree::describe());
+    let renamed = Description::from_idl(
+        r#"root Tree; struct Tree { value: u16, #[serde(rename = "kids")] children: Vec<Tree> }"#,
+    )
+    .unwrap();
+    let renamed = schema("diff-renamed", &renamed);
+    let (tree, renamed) = (tree.to_str().unwrap(), renamed.to_str().unwrap());
+    let output = run(&["diff", tree, renamed], b"");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "~ Tree.children: field renamed children -> kids (breaking)\n"
+    );
+    let output = run(&["diff", tree, renamed, "--format", "bincode"], b"");
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn export() {
+    let description = serde_json::to_vec(&Tree::describe()).unwrap();
+    let output = run(&["export", "--to", "ts"], &description);
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        Tree::describe().typescript()
+    );
+    let output = run(&["fingerprint"], &description);
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!("{:016x}\n", Tree::describe().fingerprint())
+    );
+}
+
+#[test]
+fn show() {
+    let description = MyStruct::<Internal>::describe();
+    let output = run(&["show"], &serde_json::to_vec(&description).unwrap());
+    let idl = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(idl, description.idl());
+
+    // The text form is read back wherever a description is.
+    let output = run(&["show", "--to", "json"], idl.as_bytes());
+    let json: Description = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json, description);
+    let output = run(&["show"], b"root u8;\nstruct");
+    assert_eq!(output.status.code(), Some(2));
+}
@@ -0,0 +1,83 @@
+//! The checks of the fuzz target, shared with the deterministic corpus in
+//! `tests/fuzz.rs`: whatever the description and the input, decoding and
+//! everything done with a decoded value returns instead of panicking,
+//! descriptions read back from their text form unchanged, and differ from
+//! themselves in nothing.
+//!
+//! Compact formats can claim sequences of any length of elements that take
+//! no input, so they are decoded with limits.
+
+use std::marker::PhantomData;
+
+use bincode::Options;
+use serde::de::DeserializeSeed;
+use serde_descr::{Description, DeserializerOptions, Generator, SchemaDecoder};
+use serde_value::Value;
+
+use super::scoped::{scoped, Scoped};
+
+/// Decode `bytes`, and use what decodes.
+pub fn decode(description: &Description, bytes: &[u8]) {
+    let decoder = SchemaDecoder::new(description);
+    let _ = description.lint(&DeserializerOptions::bincode_varint());
+    let _ = description.json_schema();
+    let _ = description.typescript();
+    let _ = description.protobuf();
+    let _ = description.avro();
+    let _ = description.markdown();
+    assert_eq!(
+        Description::from_idl(&description.idl()).as_ref(),
+        Ok(description)
+    );
+    assert_eq!(
+        description.diff(description, &DeserializerOptions::json()),
+        []
+    );
+
+    let limited = |opts: DeserializerOptions| opts.max_len(1 << 12).max_bytes(1 << 20);
+    let opts = limited(DeserializerOptions::bincode_varint());
+    let decoded = bincode::DefaultOptions::new()
+        .with_limit(1 << 20)
+        .deserialize_seed(decoder.seed(&opts, PhantomData::<Value>), bytes);
+    if let Ok(value) = decoded {
+        use_value(&decoder, description, &value);
+    }
+    let opts = limited(DeserializerOptions::bitcode());
+    if let Ok(Scoped(value)) = scoped(description, &opts, || {
+        bitcode::deserialize::<Scoped<Value>>(bytes)
+    }) {
+        use_value(&decoder, description, &value);
+    }
+
+    if let Ok(json) = std::str::from_utf8(bytes) {
+        let decoded = decoder
+            .seed(&DeserializerOptions::json(), PhantomData::<Value>)
+            .deserialize(&mut serde_json::Deserializer::from_str(json));
+        if let Ok(value) = decoded {
+            use_value(&decoder, description, &value);
+        }
+    }
+}
+
+/// Generate values of the description, encode them and decode them again.
+/// Generated values needn't decode: descriptions of no Rust type can
+/// describe values that no encoding of them reads back.
+pub fn generate(description: &Description, seed: u64) {
+    let mut generator = Generator::new(description).seed(seed).max_depth(4);
+    if let Ok(value) = generator.generate() {
+        use_value(generator.decoder(), description, &value);
+        if let Ok(json) = serde_json::to_string(&generator.decoder().serializable(&value)) {
+            decode(description, json.as_bytes());
+        }
+        if let Ok(bytes) = bincode::serialize(&generator.decoder().serializable(&value)) {
+            decode(description, &bytes);
+        }
+    }
+}
+
+fn use_value(decoder: &SchemaDecoder, description: &Description, value: &Value) {
+    let _ = description.validate(value);
+    let _ = decoder.annotate(value);
+    let _ = serde_json::to_string(&decoder.serializable(value));
+    let _ = bincode::serialize(&decoder.serializable(value));
+}
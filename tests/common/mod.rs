@@ -0,0 +1,760 @@
+#![allow(dead_code)]
+
+pub mod fuzz;
+pub mod roundtrip;
+pub mod scoped;
+
+use std::{
+    cell::{Cell, RefCell},
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet, HashMap, LinkedList, VecDeque},
+    ffi::OsString,
+    marker::PhantomData,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV6},
+    num::{NonZeroU32, Wrapping},
+    ops::{Bound, Range, RangeInclusive},
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_descr::{
+    ConstrainedSchema, Constraints, Describe, Description, EnumRepr, EnumSchema, MapSchema,
+    NamedFieldSchema, NamedFieldsSchema, NewtypeSchema, OptionSchema, SchemaName, SeqSchema,
+    SimpleSchema, StructSchema, TupleSchema, UnitStructSchema, VariantSchema,
+};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct MyStruct<T> {
+    pub string: String,
+    pub int: u64,
+    pub value: T,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub enum External {
+    Unit,
+    Newtype(String),
+    Tuple(String, u64),
+    Struct { float: f64 },
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(tag = "type")]
+pub enum Internal {
+    Unit,
+    Newtype(Inner),
+    Struct { id: u32, name: String },
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Inner {
+    pub flag: bool,
+    pub small: i8,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(tag = "t", content = "c")]
+pub enum Adjacent {
+    Unit,
+    Newtype(i32),
+    Tuple(u8, String),
+    Struct { letter: char },
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Tree {
+    pub value: u16,
+    pub children: Vec<Tree>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Containers {
+    pub btree: BTreeMap<String, i64>,
+    pub hash: HashMap<u32, Vec<bool>>,
+    pub some: Option<f32>,
+    pub none: Option<String>,
+    pub pair: (char, i16),
+}
+
+impl<T: Describe> Describe for MyStruct<T> {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("MyStruct").argument(T::schema_name())
+    }
+
+    fn add_schema(map: &mut Description) {
+        if map.add(Self::schema_name(), || {
+            StructSchema::new(
+                "MyStruct",
+                NamedFieldsSchema::new()
+                    .field(NamedFieldSchema::new("string", String::schema()))
+                    .field(NamedFieldSchema::new("int", u64::schema()))
+                    .field(NamedFieldSchema::new("value", T::schema())),
+            )
+        }) {
+            T::add_schema(map);
+        }
+    }
+}
+
+impl Describe for External {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("External")
+    }
+
+    fn add_schema(map: &mut Description) {
+        map.add(Self::schema_name(), || {
+            EnumSchema::new("External")
+                .variant(VariantSchema::new("Unit", TupleSchema::new()))
+                .variant(VariantSchema::new(
+                    "Newtype",
+                    TupleSchema::new().element(String::schema()),
+                ))
+                .variant(VariantSchema::new(
+                    "Tuple",
+                    TupleSchema::new()
+                        .element(String::schema())
+                        .element(u64::schema()),
+                ))
+                .variant(VariantSchema::new(
+                    "Struct",
+                    NamedFieldsSchema::new().field(NamedFieldSchema::new("float", f64::schema())),
+                ))
+        });
+    }
+}
+
+impl Describe for Internal {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("Internal")
+    }
+
+    fn add_schema(map: &mut Description) {
+        if map.add(Self::schema_name(), || {
+            EnumSchema::new("Internal")
+                .repr(EnumRepr::InternallyTagged {
+                    tag: "type".to_string(),
+                })
+                .variant(VariantSchema::new("Unit", TupleSchema::new()))
+                .variant(VariantSchema::new(
+                    "Newtype",
+                    TupleSchema::new().element(Inner::schema()),
+                ))
+                .variant(VariantSchema::new(
+                    "Struct",
+                    NamedFieldsSchema::new()
+                        .field(NamedFieldSchema::new("id", u32::schema()))
+                        .field(NamedFieldSchema::new("name", String::schema())),
+                ))
+        }) {
+            Inner::add_schema(map);
+        }
+    }
+}
+
+impl Describe for Inner {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("Inner")
+    }
+
+    fn add_schema(map: &mut Description) {
+        map.add(Self::schema_name(), || {
+            StructSchema::new(
+                "Inner",
+                NamedFieldsSchema::new()
+                    .field(NamedFieldSchema::new("flag", bool::schema()))
+                    .field(NamedFieldSchema::new("small", i8::schema())),
+            )
+        });
+    }
+}
+
+impl Describe for Adjacent {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("Adjacent")
+    }
+
+    fn add_schema(map: &mut Description) {
+        map.add(Self::schema_name(), || {
+            EnumSchema::new("Adjacent")
+                .repr(EnumRepr::AdjacentlyTagged {
+                    tag: "t".to_string(),
+                    content: "c".to_string(),
+                })
+                .variant(VariantSchema::new("Unit", TupleSchema::new()))
+                .variant(VariantSchema::new(
+                    "Newtype",
+                    TupleSchema::new().element(i32::schema()),
+                ))
+                .variant(VariantSchema::new(
+                    "Tuple",
+                    TupleSchema::new()
+                        .element(u8::schema())
+                        .element(String::schema()),
+                ))
+                .variant(VariantSchema::new(
+                    "Struct",
+                    NamedFieldsSchema::new()
+                        .field(NamedFieldSchema::new("letter", SimpleSchema::Char)),
+                ))
+        });
+    }
+}
+
+impl Describe for Tree {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("Tree")
+    }
+
+    fn add_schema(map: &mut Description) {
+        if map.add(Self::schema_name(), || {
+            StructSchema::new(
+                "Tree",
+                NamedFieldsSchema::new()
+                    .field(NamedFieldSchema::new("value", u16::schema()))
+                    .field(NamedFieldSchema::new("children", Vec::<Tree>::schema())),
+            )
+        }) {
+            Vec::<Tree>::add_schema(map);
+        }
+    }
+}
+
+impl Describe for Containers {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("Containers")
+    }
+
+    fn add_schema(map: &mut Description) {
+        map.add(Self::schema_name(), || {
+            StructSchema::new(
+                "Containers",
+                NamedFieldsSchema::new()
+                    .field(NamedFieldSchema::new(
+                        "btree",
+                        BTreeMap::<String, i64>::schema(),
+                    ))
+                    .field(NamedFieldSchema::new(
+                        "hash",
+                        MapSchema::new(u32::schema(), SeqSchema::new(bool::schema())),
+                    ))
+                    .field(NamedFieldSchema::new(
+                        "some",
+                        OptionSchema::new(f32::schema()),
+                    ))
+                    .field(NamedFieldSchema::new(
+                        "none",
+                        OptionSchema::new(String::schema()),
+                    ))
+                    .field(NamedFieldSchema::new(
+                        "pair",
+                        TupleSchema::new()
+                            .element(SimpleSchema::Char)
+                            .element(i16::schema()),
+                    )),
+            )
+        });
+    }
+}
+
+pub fn my_struct<T>(value: T) -> MyStruct<T> {
+    MyStruct {
+        string: "hello".to_string(),
+        int: 300,
+        value,
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct StdTypes {
+    pub deque: VecDeque<u8>,
+    pub list: LinkedList<String>,
+    pub cell: Cell<u16>,
+    pub ref_cell: RefCell<String>,
+    pub boxed: Box<str>,
+    pub duration: Duration,
+    pub time: SystemTime,
+    pub path: PathBuf,
+    pub os_string: OsString,
+    pub non_zero: NonZeroU32,
+    pub wrapping: Wrapping<i16>,
+    pub reverse: Reverse<u8>,
+    pub range: Range<u32>,
+    pub range_inclusive: RangeInclusive<i8>,
+    pub bound: Bound<u64>,
+    pub phantom: PhantomData<String>,
+    pub letter: char,
+    pub array: [u16; 3],
+    pub tuple: (u8, String, bool),
+    pub option: Option<Option<i32>>,
+}
+
+impl Describe for StdTypes {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("StdTypes")
+    }
+
+    fn add_schema(map: &mut Description) {
+        if map.add(Self::schema_name(), || {
+            StructSchema::new(
+                "StdTypes",
+                NamedFieldsSchema::new()
+                    .field(NamedFieldSchema::new("deque", VecDeque::<u8>::schema()))
+                    .field(NamedFieldSchema::new(
+                        "list",
+                        LinkedList::<String>::schema(),
+                    ))
+                    .field(NamedFieldSchema::new("cell", Cell::<u16>::schema()))
+                    .field(NamedFieldSchema::new(
+                        "ref_cell",
+                        RefCell::<String>::schema(),
+                    ))
+                    .field(NamedFieldSchema::new("boxed", Box::<str>::schema()))
+                    .field(NamedFieldSchema::new("duration", Duration::schema()))
+                    .field(NamedFieldSchema::new("time", SystemTime::schema()))
+                    .field(NamedFieldSchema::new("path", PathBuf::schema()))
+                    .field(NamedFieldSchema::new("os_string", OsString::schema()))
+                    .field(NamedFieldSchema::new("non_zero", NonZeroU32::schema()))
+                    .field(NamedFieldSchema::new("wrapping", Wrapping::<i16>::schema()))
+                    .field(NamedFieldSchema::new("reverse", Reverse::<u8>::schema()))
+                    .field(NamedFieldSchema::new("range", Range::<u32>::schema()))
+                    .field(NamedFieldSchema::new(
+                        "range_inclusive",
+                        RangeInclusive::<i8>::schema(),
+                    ))
+                    .field(NamedFieldSchema::new("bound", Bound::<u64>::schema()))
+                    .field(NamedFieldSchema::new(
+                        "phantom",
+                        PhantomData::<String>::schema(),
+                    ))
+                    .field(NamedFieldSchema::new("letter", char::schema()))
+                    .field(NamedFieldSchema::new("array", <[u16; 3]>::schema()))
+                    .field(NamedFieldSchema::new(
+                        "tuple",
+                        <(u8, String, bool)>::schema(),
+                    ))
+                    .field(NamedFieldSchema::new(
+                        "option",
+                        Option::<Option<i32>>::schema(),
+                    )),
+            )
+        }) {
+            Duration::add_schema(map);
+            SystemTime::add_schema(map);
+            OsString::add_schema(map);
+            Range::<u32>::add_schema(map);
+            RangeInclusive::<i8>::add_schema(map);
+            Bound::<u64>::add_schema(map);
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Network {
+    pub v4: Ipv4Addr,
+    pub v6: Ipv6Addr,
+    pub ip: IpAddr,
+    pub socket: SocketAddr,
+    pub socket_v6: SocketAddrV6,
+}
+
+impl Describe for Network {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("Network")
+    }
+
+    fn add_schema(map: &mut Description) {
+        if map.add(Self::schema_name(), || {
+            StructSchema::new(
+                "Network",
+                NamedFieldsSchema::new()
+                    .field(NamedFieldSchema::new("v4", Ipv4Addr::schema()))
+                    .field(NamedFieldSchema::new("v6", Ipv6Addr::schema()))
+                    .field(NamedFieldSchema::new("ip", IpAddr::schema()))
+                    .field(NamedFieldSchema::new("socket", SocketAddr::schema()))
+                    .field(NamedFieldSchema::new("socket_v6", SocketAddrV6::schema())),
+            )
+        }) {
+            Ipv4Addr::add_schema(map);
+            Ipv6Addr::add_schema(map);
+            IpAddr::add_schema(map);
+            SocketAddr::add_schema(map);
+            SocketAddrV6::add_schema(map);
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Event {
+    pub id: u32,
+    #[serde(with = "serde_descr::any")]
+    pub extra: serde_json::Value,
+}
+
+impl Describe for Event {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("Event")
+    }
+
+    fn add_schema(map: &mut Description) {
+        map.add(Self::schema_name(), || {
+            StructSchema::new(
+                "Event",
+                NamedFieldsSchema::new()
+                    .field(NamedFieldSchema::new("id", u32::schema()))
+                    .field(NamedFieldSchema::new("extra", serde_json::Value::schema())),
+            )
+        });
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct Id(pub u64);
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Marker;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(transparent)]
+pub struct Meters {
+    pub value: f64,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Wrappers {
+    pub id: Id,
+    pub marker: Marker,
+    pub meters: Meters,
+    pub names: BTreeMap<Id, Option<Id>>,
+}
+
+impl Describe for Id {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("Id")
+    }
+
+    fn add_schema(map: &mut Description) {
+        map.add(Self::schema_name(), || {
+            NewtypeSchema::new("Id", u64::schema())
+        });
+    }
+}
+
+impl Describe for Marker {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("Marker")
+    }
+
+    fn add_schema(map: &mut Description) {
+        map.add(Self::schema_name(), || UnitStructSchema::new("Marker"));
+    }
+}
+
+impl Describe for Meters {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("Meters")
+    }
+
+    fn add_schema(map: &mut Description) {
+        map.add(Self::schema_name(), || {
+            NewtypeSchema::new("Meters", f64::schema()).transparent()
+        });
+    }
+}
+
+impl Describe for Wrappers {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("Wrappers")
+    }
+
+    fn add_schema(map: &mut Description) {
+        if map.add(Self::schema_name(), || {
+            StructSchema::new(
+                "Wrappers",
+                NamedFieldsSchema::new()
+                    .field(NamedFieldSchema::new("id", Id::schema()))
+                    .field(NamedFieldSchema::new("marker", Marker::schema()))
+                    .field(NamedFieldSchema::new("meters", Meters::schema()))
+                    .field(NamedFieldSchema::new(
+                        "names",
+                        BTreeMap::<Id, Option<Id>>::schema(),
+                    )),
+            )
+        }) {
+            Id::add_schema(map);
+            Marker::add_schema(map);
+            Meters::add_schema(map);
+            BTreeMap::<Id, Option<Id>>::add_schema(map);
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Flattened {
+    pub id: u32,
+    #[serde(flatten)]
+    pub meta: Meta,
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, u32>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Meta {
+    pub version: u16,
+    pub label: Option<String>,
+}
+
+impl Describe for Flattened {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("Flattened")
+    }
+
+    fn add_schema(map: &mut Description) {
+        if map.add(Self::schema_name(), || {
+            StructSchema::new(
+                "Flattened",
+                NamedFieldsSchema::new()
+                    .field(NamedFieldSchema::new("id", u32::schema()))
+                    .field(NamedFieldSchema::new("meta", Meta::schema()).flatten())
+                    .field(
+                        NamedFieldSchema::new("extra", BTreeMap::<String, u32>::schema()).flatten(),
+                    ),
+            )
+        }) {
+            Meta::add_schema(map);
+            BTreeMap::<String, u32>::add_schema(map);
+        }
+    }
+}
+
+impl Describe for Meta {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("Meta")
+    }
+
+    fn add_schema(map: &mut Description) {
+        map.add(Self::schema_name(), || {
+            StructSchema::new(
+                "Meta",
+                NamedFieldsSchema::new()
+                    .field(NamedFieldSchema::new("version", u16::schema()))
+                    .field(NamedFieldSchema::new("label", Option::<String>::schema())),
+            )
+        });
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Skipped {
+    pub id: u32,
+    #[serde(skip)]
+    pub cache: Vec<u8>,
+    #[serde(skip_serializing, default)]
+    pub secret: String,
+    #[serde(skip_deserializing)]
+    pub computed: u8,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Sparse {
+    pub id: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+impl Describe for Skipped {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("Skipped")
+    }
+
+    fn add_schema(map: &mut Description) {
+        map.add(Self::schema_name(), || {
+            StructSchema::new(
+                "Skipped",
+                NamedFieldsSchema::new()
+                    .field(NamedFieldSchema::new("id", u32::schema()))
+                    .field(NamedFieldSchema::new("cache", Vec::<u8>::schema()).skip())
+                    .field(NamedFieldSchema::new("secret", String::schema()).skip_serializing())
+                    .field(NamedFieldSchema::new("computed", u8::schema()).skip_deserializing()),
+            )
+        });
+    }
+}
+
+impl Describe for Sparse {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("Sparse")
+    }
+
+    fn add_schema(map: &mut Description) {
+        map.add(Self::schema_name(), || {
+            StructSchema::new(
+                "Sparse",
+                NamedFieldsSchema::new()
+                    .field(NamedFieldSchema::new("id", u32::schema()))
+                    .field(
+                        NamedFieldSchema::new("note", Option::<String>::schema())
+                            .skip_serializing_if("Option::is_none"),
+                    ),
+            )
+        });
+    }
+}
+
+#[derive(Serialize_repr, Deserialize_repr, PartialEq, Debug)]
+#[repr(u8)]
+pub enum Opcode {
+    Nop,
+    Push = 0x10,
+    Pop,
+    Halt = 0xff,
+}
+
+#[derive(Serialize_repr, Deserialize_repr, PartialEq, Debug)]
+#[repr(i16)]
+pub enum Level {
+    Low = -1,
+    Mid,
+    High = 10,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Instruction {
+    pub op: Opcode,
+    pub level: Level,
+}
+
+impl Describe for Opcode {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("Opcode")
+    }
+
+    fn add_schema(map: &mut Description) {
+        map.add(Self::schema_name(), || {
+            EnumSchema::new("Opcode")
+                .variant(VariantSchema::new("Nop", TupleSchema::new()))
+                .variant(VariantSchema::new("Push", TupleSchema::new()).discriminant(0x10))
+                .variant(VariantSchema::new("Pop", TupleSchema::new()))
+                .variant(VariantSchema::new("Halt", TupleSchema::new()).discriminant(0xff))
+                .repr(EnumRepr::Integer {
+                    repr: SimpleSchema::U8,
+                })
+        });
+    }
+}
+
+impl Describe for Level {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("Level")
+    }
+
+    fn add_schema(map: &mut Description) {
+        map.add(Self::schema_name(), || {
+            EnumSchema::new("Level")
+                .variant(VariantSchema::new("Low", TupleSchema::new()).discriminant(-1))
+                .variant(VariantSchema::new("Mid", TupleSchema::new()))
+                .variant(VariantSchema::new("High", TupleSchema::new()).discriminant(10))
+                .repr(EnumRepr::Integer {
+                    repr: SimpleSchema::I16,
+                })
+        });
+    }
+}
+
+impl Describe for Instruction {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("Instruction")
+    }
+
+    fn add_schema(map: &mut Description) {
+        if map.add(Self::schema_name(), || {
+            StructSchema::new(
+                "Instruction",
+                NamedFieldsSchema::new()
+                    .field(NamedFieldSchema::new("op", Opcode::schema()))
+                    .field(NamedFieldSchema::new("level", Level::schema())),
+            )
+        }) {
+            Opcode::add_schema(map);
+            Level::add_schema(map);
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Listener {
+    pub name: String,
+    pub port: u16,
+    pub load: f64,
+    pub hosts: Vec<String>,
+    pub limits: BTreeMap<String, u8>,
+}
+
+impl Describe for Listener {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("Listener")
+    }
+
+    fn add_schema(map: &mut Description) {
+        map.add(Self::schema_name(), || {
+            let name = Constraints::new()
+                .pattern("^[a-z][a-z0-9-]*$")
+                .max_length(16);
+            let hosts = Constraints::new().non_empty().unique();
+            let limits = Constraints::new().key_pattern("^[a-z]+$");
+            let percent = ConstrainedSchema::new(u8::schema(), Constraints::new().range(0..=100));
+            StructSchema::new(
+                "Listener",
+                NamedFieldsSchema::new()
+                    .field(NamedFieldSchema::new(
+                        "name",
+                        ConstrainedSchema::new(String::schema(), name),
+                    ))
+                    .field(NamedFieldSchema::new(
+                        "port",
+                        ConstrainedSchema::new(u16::schema(), Constraints::new().range(1..=65535)),
+                    ))
+                    .field(NamedFieldSchema::new(
+                        "load",
+                        ConstrainedSchema::new(f64::schema(), Constraints::new().min(0)),
+                    ))
+                    .field(NamedFieldSchema::new(
+                        "hosts",
+                        ConstrainedSchema::new(Vec::<String>::schema(), hosts),
+                    ))
+                    .field(NamedFieldSchema::new(
+                        "limits",
+                        ConstrainedSchema::new(MapSchema::new(String::schema(), percent), limits),
+                    )),
+            )
+        });
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Fixed {
+    pub position: [f32; 3],
+    pub digest: [u8; 32],
+    pub none: [u32; 0],
+    pub tags: BTreeSet<String>,
+}
+
+impl Describe for Fixed {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("Fixed")
+    }
+
+    fn add_schema(map: &mut Description) {
+        map.add(Self::schema_name(), || {
+            StructSchema::new(
+                "Fixed",
+                NamedFieldsSchema::new()
+                    .field(NamedFieldSchema::new("position", <[f32; 3]>::schema()))
+                    .field(NamedFieldSchema::new("digest", <[u8; 32]>::schema()))
+                    .field(NamedFieldSchema::new("none", <[u32; 0]>::schema()))
+                    .field(NamedFieldSchema::new("tags", BTreeSet::<String>::schema())),
+            )
+        });
+    }
+}
@@ -0,0 +1,248 @@
+//! Round-trip checks of the schema deserializer against several formats.
+
+use std::{fmt::Debug, marker::PhantomData};
+
+use bincode::Options;
+use serde::{
+    de::{DeserializeOwned, DeserializeSeed, Error},
+    Deserialize, Deserializer, Serialize,
+};
+use serde_descr::{Describe, DeserializerOptions, SchemaDecoder};
+
+use super::scoped::{scoped, Scoped};
+
+/// Decode `bytes` both as `T` and as a dynamic value, and check both against
+/// `value`. `serde_value` serializes as a human-readable format, so types that
+/// encode differently in compact formats can only check the typed decode.
+pub fn check<T, F>(format: &str, value: &T, opts: &DeserializerOptions, dynamic: bool, decode: F)
+where
+    T: Describe + Serialize + DeserializeOwned + PartialEq + Debug,
+    F: Fn(&SchemaDecoder, &DeserializerOptions, Kind) -> Result<Decoded<T>, String>,
+{
+    let description = T::describe();
+    let decoder = SchemaDecoder::new(&description);
+    match decode(&decoder, opts, Kind::Typed) {
+        Ok(Decoded::Typed(decoded)) => assert_eq!(&decoded, value, "{format}: typed"),
+        Ok(Decoded::Dynamic(_)) => unreachable!(),
+        Err(e) => panic!("{format}: typed decode of {value:?} failed: {e}"),
+    }
+    match decode(&decoder, opts, Kind::Dynamic) {
+        Ok(Decoded::Dynamic(decoded)) => {
+            if dynamic {
+                assert_eq!(
+                    decoded,
+                    serde_value::to_value(value).unwrap(),
+                    "{format}: dynamic"
+                )
+            }
+        }
+        Ok(Decoded::Typed(_)) => unreachable!(),
+        Err(e) => panic!("{format}: dynamic decode of {value:?} failed: {e}"),
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum Kind {
+    Typed,
+    Dynamic,
+}
+
+pub enum Decoded<T> {
+    Typed(T),
+    Dynamic(serde_value::Value),
+}
+
+pub fn roundtrip<T>(value: T)
+where
+    T: Describe + Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    roundtrip_compact(&value, true);
+    roundtrip_self_describing(&value, true);
+    roundtrip_text(&value);
+}
+
+/// Check decoding from formats that are not human-readable.
+pub fn roundtrip_compact<T>(value: &T, dynamic: bool)
+where
+    T: Describe + Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    let bytes = bincode::DefaultOptions::new().serialize(value).unwrap();
+    check(
+        "bincode (varint)",
+        value,
+        &DeserializerOptions::bincode_varint(),
+        dynamic,
+        |decoder, opts, kind| {
+            let options = bincode::DefaultOptions::new();
+            match kind {
+                Kind::Typed => options
+                    .deserialize_seed(decoder.seed(opts, PhantomData::<T>), &bytes)
+                    .map(Decoded::Typed),
+                Kind::Dynamic => options
+                    .deserialize_seed(decoder.seed(opts, PhantomData), &bytes)
+                    .map(Decoded::Dynamic),
+            }
+            .map_err(|e| e.to_string())
+        },
+    );
+
+    let bytes = bincode::serialize(value).unwrap();
+    check(
+        "bincode (fixint)",
+        value,
+        &DeserializerOptions::bincode_fixint(),
+        dynamic,
+        |decoder, opts, kind| {
+            let options = bincode::DefaultOptions::new()
+                .with_fixint_encoding()
+                .allow_trailing_bytes();
+            match kind {
+                Kind::Typed => options
+                    .deserialize_seed(decoder.seed(opts, PhantomData::<T>), &bytes)
+                    .map(Decoded::Typed),
+                Kind::Dynamic => options
+                    .deserialize_seed(decoder.seed(opts, PhantomData), &bytes)
+                    .map(Decoded::Dynamic),
+            }
+            .map_err(|e| e.to_string())
+        },
+    );
+
+    let bytes = postcard::to_allocvec(value).unwrap();
+    check(
+        "postcard",
+        value,
+        &DeserializerOptions::postcard(),
+        dynamic,
+        |decoder, opts, kind| {
+            let mut deserializer = postcard::Deserializer::from_bytes(&bytes);
+            match kind {
+                Kind::Typed => decoder
+                    .seed(opts, PhantomData::<T>)
+                    .deserialize(&mut deserializer)
+                    .map(Decoded::Typed),
+                Kind::Dynamic => decoder
+                    .seed(opts, PhantomData)
+                    .deserialize(&mut deserializer)
+                    .map(Decoded::Dynamic),
+            }
+            .map_err(|e| e.to_string())
+        },
+    );
+
+    let bytes = bitcode::serialize(value).unwrap();
+    check(
+        "bitcode",
+        value,
+        &DeserializerOptions::bitcode(),
+        dynamic,
+        |_, opts, kind| {
+            scoped(&T::describe(), opts, || match kind {
+                Kind::Typed => bitcode::deserialize::<Scoped<T>>(&bytes)
+                    .map(|Scoped(value)| Decoded::Typed(value)),
+                Kind::Dynamic => bitcode::deserialize::<Scoped<serde_value::Value>>(&bytes)
+                    .map(|Scoped(value)| Decoded::Dynamic(value)),
+            })
+            .map_err(|e| e.to_string())
+        },
+    );
+}
+
+/// Check decoding from self-describing formats that are not human-readable.
+/// They are read through an erased deserializer, so that they share one copy
+/// of the decoder for each type: the test binaries get too big to build
+/// otherwise.
+pub fn roundtrip_self_describing<T>(value: &T, dynamic: bool)
+where
+    T: Describe + Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    for (format, bytes, opts) in [
+        (
+            "msgpack (compact)",
+            rmp_serde::to_vec(value).unwrap(),
+            DeserializerOptions::msgpack_compact(),
+        ),
+        (
+            "msgpack (named)",
+            rmp_serde::to_vec_named(value).unwrap(),
+            DeserializerOptions::msgpack_named(),
+        ),
+    ] {
+        check(format, value, &opts, dynamic, |decoder, opts, kind| {
+            let mut deserializer = rmp_serde::Deserializer::new(&bytes[..]);
+            let mut erased = <dyn erased_serde::Deserializer>::erase(&mut deserializer);
+            let erased: &mut dyn erased_serde::Deserializer = &mut erased;
+            match kind {
+                Kind::Typed => decoder
+                    .seed(opts, PhantomData::<T>)
+                    .deserialize(erased)
+                    .map(Decoded::Typed),
+                Kind::Dynamic => decoder
+                    .seed(opts, PhantomData)
+                    .deserialize(erased)
+                    .map(Decoded::Dynamic),
+            }
+            .map_err(|e| e.to_string())
+        });
+    }
+
+    let mut bytes = Vec::new();
+    ciborium::into_writer(value, &mut bytes).unwrap();
+    check(
+        "cbor",
+        value,
+        &DeserializerOptions::cbor(),
+        dynamic,
+        |_, opts, kind| {
+            scoped(&T::describe(), opts, || match kind {
+                Kind::Typed => ciborium::from_reader::<Erased<Scoped<T>>, _>(&bytes[..])
+                    .map(|Erased(Scoped(value))| Decoded::Typed(value)),
+                Kind::Dynamic => {
+                    ciborium::from_reader::<Erased<Scoped<serde_value::Value>>, _>(&bytes[..])
+                        .map(|Erased(Scoped(value))| Decoded::Dynamic(value))
+                }
+            })
+            .map_err(|e| e.to_string())
+        },
+    );
+}
+
+/// Deserializes `T` through an erased deserializer.
+struct Erased<T>(T);
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Erased<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut erased = <dyn erased_serde::Deserializer>::erase(deserializer);
+        T::deserialize(&mut erased as &mut dyn erased_serde::Deserializer)
+            .map(Erased)
+            .map_err(D::Error::custom)
+    }
+}
+
+/// Check decoding from human-readable formats.
+pub fn roundtrip_text<T>(value: &T)
+where
+    T: Describe + Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    let json = serde_json::to_string(value).unwrap();
+    check(
+        "json",
+        value,
+        &DeserializerOptions::json(),
+        true,
+        |decoder, opts, kind| {
+            let mut deserializer = serde_json::Deserializer::from_str(&json);
+            match kind {
+                Kind::Typed => decoder
+                    .seed(opts, PhantomData::<T>)
+                    .deserialize(&mut deserializer)
+                    .map(Decoded::Typed),
+                Kind::Dynamic => decoder
+                    .seed(opts, PhantomData)
+                    .deserialize(&mut deserializer)
+                    .map(Decoded::Dynamic),
+            }
+            .map_err(|e| e.to_string())
+        },
+    );
+}
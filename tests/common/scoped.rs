@@ -0,0 +1,53 @@
+//! Decoding through formats that only read `DeserializeOwned` types and take
+//! no seed, such as bitcode and CBOR: [`scoped`] puts a copy of the
+//! description in a thread local, which [`Scoped<T>`] values decode with.
+
+use std::{cell::RefCell, marker::PhantomData, rc::Rc};
+
+use serde::{
+    de::{DeserializeOwned, DeserializeSeed, Error},
+    Deserialize, Deserializer,
+};
+use serde_descr::{Description, DeserializerOptions, SchemaDecoder};
+
+type Scope = Rc<(Description, DeserializerOptions)>;
+
+thread_local! {
+    static SCOPE: RefCell<Option<Scope>> = const { RefCell::new(None) };
+}
+
+/// Run `f` with `description` in scope, e.g.
+/// `scoped(&description, &opts, || bitcode::deserialize::<Scoped<T>>(bytes))`.
+pub fn scoped<F: FnOnce() -> R, R>(
+    description: &Description,
+    opts: &DeserializerOptions,
+    f: F,
+) -> R {
+    struct Restore(Option<Scope>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            SCOPE.set(self.0.take());
+        }
+    }
+
+    let scope = Rc::new((description.clone(), opts.clone()));
+    let _restore = Restore(SCOPE.replace(Some(scope)));
+    f()
+}
+
+/// A value decoded with the description in scope, see [`scoped`].
+#[derive(PartialEq, Debug)]
+pub struct Scoped<T>(pub T);
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for Scoped<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let scope = SCOPE.with_borrow(Option::clone);
+        let (description, opts) =
+            &*scope.ok_or_else(|| D::Error::custom("no description in scope"))?;
+        SchemaDecoder::new(description)
+            .seed(opts, PhantomData::<T>)
+            .deserialize(deserializer)
+            .map(Scoped)
+    }
+}
@@ -0,0 +1,233 @@
+mod common;
+
+use std::marker::PhantomData;
+
+use bincode::Options;
+use common::*;
+use serde::{de::DeserializeSeed, Serialize};
+use serde_descr::{Describe, DeserializerOptions, SchemaDecoder, ValueChange};
+use serde_value::Value;
+
+/// Diff `old` encoded as bincode against `new` encoded as JSON.
+fn diff<T: Describe + Serialize>(old: &T, new: &T) -> Vec<ValueChange> {
+    let description = T::describe();
+    let decoder = SchemaDecoder::new(&description);
+    let bytes = bincode::DefaultOptions::new().serialize(old).unwrap();
+    let old = bincode::DefaultOptions::new()
+        .deserialize_seed(
+            decoder.seed(&DeserializerOptions::bincode_varint(), PhantomData),
+            &bytes,
+        )
+        .unwrap();
+    let json = serde_json::to_string(new).unwrap();
+    let new = decoder
+        .seed(&DeserializerOptions::json(), PhantomData)
+        .deserialize(&mut serde_json::Deserializer::from_str(&json))
+        .unwrap();
+    decoder.diff(&old, &new)
+}
+
+fn string(s: &str) -> Value {
+    Value::String(s.to_string())
+}
+
+#[test]
+fn equal_values() {
+    let value = my_struct(External::Tuple("a".to_string(), 1));
+    assert_eq!(diff(&value, &value), Vec::new());
+}
+
+#[test]
+fn fields_and_variants() {
+    let old = my_struct(External::Tuple("a".to_string(), 1));
+    let mut new = my_struct(External::Tuple("b".to_string(), 1));
+    new.int = 301;
+    assert_eq!(
+        diff(&old, &new),
+        vec![
+            ValueChange::Changed {
+                path: ".int".to_string(),
+                old: Value::U64(300),
+                new: Value::U64(301),
+            },
+            ValueChange::Changed {
+                path: ".value::Tuple[0]".to_string(),
+                old: string("a"),
+                new: string("b"),
+            },
+        ]
+    );
+
+    let changes = diff(&old, &my_struct(External::Unit));
+    assert!(matches!(
+        &changes[..],
+        [ValueChange::Variant { path, old, new, .. }]
+            if path == ".value" && old == "Tuple" && new == "Unit"
+    ));
+
+    let old = my_struct(Internal::Struct {
+        id: 1,
+        name: "a".to_string(),
+    });
+    let new = my_struct(Internal::Struct {
+        id: 2,
+        name: "a".to_string(),
+    });
+    assert_eq!(
+        diff(&old, &new),
+        vec![ValueChange::Changed {
+            path: ".value::Struct.id".to_string(),
+            old: Value::U32(1),
+            new: Value::U32(2),
+        }]
+    );
+    let changes = diff(&old, &my_struct(Internal::Unit));
+    assert!(matches!(&changes[..], [ValueChange::Variant { .. }]));
+}
+
+#[test]
+fn maps_and_options() {
+    let old = Containers {
+        btree: [("a".to_string(), 1), ("b".to_string(), 2)].into(),
+        hash: [(1, vec![true])].into(),
+        some: Some(0.5),
+        none: None,
+        pair: ('x', 1),
+    };
+    let new = Containers {
+        btree: [("b".to_string(), 3), ("c".to_string(), 4)].into(),
+        hash: [(1, vec![true])].into(),
+        some: None,
+        none: None,
+        pair: ('x', 1),
+    };
+    assert_eq!(
+        diff(&old, &new),
+        vec![
+            ValueChange::Removed {
+                path: r#".btree["a"]"#.to_string(),
+                value: Value::I64(1),
+            },
+            ValueChange::Changed {
+                path: r#".btree["b"]"#.to_string(),
+                old: Value::I64(2),
+                new: Value::I64(3),
+            },
+            ValueChange::Added {
+                path: r#".btree["c"]"#.to_string(),
+                value: Value::I64(4),
+            },
+            ValueChange::Changed {
+                path: ".some".to_string(),
+                old: Value::Option(Some(Box::new(Value::F32(0.5)))),
+                new: Value::Option(None),
+            },
+        ]
+    );
+}
+
+#[test]
+fn sequences() {
+    let values =
+        |values: &[&str]| my_struct(values.iter().map(|s| s.to_string()).collect::<Vec<_>>());
+    assert_eq!(
+        diff(
+            &values(&["a", "b", "c"]),
+            &values(&["a", "x", "b", "c", "d"])
+        ),
+        vec![
+            ValueChange::Added {
+                path: ".value[1]".to_string(),
+                value: string("x"),
+            },
+            ValueChange::Added {
+                path: ".value[4]".to_string(),
+                value: string("d"),
+            },
+        ]
+    );
+    assert_eq!(
+        diff(&values(&["a", "b", "c"]), &values(&["a", "c"])),
+        vec![ValueChange::Removed {
+            path: ".value[1]".to_string(),
+            value: string("b"),
+        }]
+    );
+    assert_eq!(
+        diff(&values(&["a", "b", "c"]), &values(&["a", "x", "c"])),
+        vec![ValueChange::Changed {
+            path: ".value[1]".to_string(),
+            old: string("b"),
+            new: string("x"),
+        }]
+    );
+}
+
+#[test]
+fn long_sequences() {
+    let old = my_struct((0..100_000u32).collect::<Vec<_>>());
+    let mut new = my_struct((0..100_000u32).collect::<Vec<_>>());
+    new.value.insert(50_000, 7);
+    assert_eq!(
+        diff(&old, &new),
+        vec![ValueChange::Added {
+            path: ".value[50000]".to_string(),
+            value: Value::U32(7),
+        }]
+    );
+
+    // Too different to align: compared by position.
+    new.value.reverse();
+    let changes = diff(&old, &new);
+    assert_eq!(changes.len(), 100_001);
+    assert_eq!(
+        changes[100_000],
+        ValueChange::Added {
+            path: ".value[100000]".to_string(),
+            value: Value::U32(0),
+        }
+    );
+}
+
+#[test]
+fn recursive() {
+    let leaf = |value| Tree {
+        value,
+        children: Vec::new(),
+    };
+    let old = Tree {
+        value: 1,
+        children: vec![leaf(2), leaf(3)],
+    };
+    let new = Tree {
+        value: 1,
+        children: vec![leaf(2), leaf(4)],
+    };
+    let changes = diff(&old, &new);
+    assert_eq!(
+        changes.iter().map(|c| c.to_string()).collect::<Vec<_>>(),
+        vec!["~ .children[1].value: 3 -> 4"]
+    );
+}
+
+#[test]
+fn integer_enum_variants() {
+    let old = Instruction {
+        op: Opcode::Push,
+        level: Level::Mid,
+    };
+    let new = Instruction {
+        op: Opcode::Halt,
+        level: Level::Mid,
+    };
+    assert_eq!(
+        diff(&old, &new),
+        vec![ValueChange::Variant {
+            path: ".op".to_string(),
+            old: "Push".to_string(),
+            new: "Halt".to_string(),
+            old_value: Value::U8(0x10),
+            new_value: Value::U8(0xff),
+        }]
+    );
+}
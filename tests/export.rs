@@ -0,0 +1,242 @@
+mod common;
+
+use std::marker::PhantomData;
+
+use common::*;
+use serde::de::DeserializeSeed;
+use serde_descr::{Describe, Description, DeserializerOptions, SchemaDecoder};
+use serde_json::json;
+
+#[test]
+fn typescript() {
+    assert_eq!(
+        Internal::describe().typescript(),
+        r#"export type Inner = {
+  flag: boolean;
+  small: number;
+};
+
+export type Internal =
+  | { type: "Unit" }
+  | { type: "Newtype" } & Inner
+  | {
+    type: "Struct";
+    id: number;
+    name: string;
+  };
+"#
+    );
+    let ts = Flattened::describe().typescript();
+    assert!(
+        ts.contains("export type Flattened = {\n  id: number;\n} & Meta & Record<string, number>;")
+    );
+    assert!(ts.contains("  label?: string | null;"));
+    assert!(Instruction::describe()
+        .typescript()
+        .contains("export type Level = -1 | 0 | 10;"));
+    assert!(Fixed::describe()
+        .typescript()
+        .contains("  tags: Array<string> /* unique */;\n"));
+}
+
+#[test]
+fn protobuf() {
+    let proto = Wrappers::describe().protobuf();
+    assert_eq!(
+        proto,
+        r#"syntax = "proto3";
+
+import "google/protobuf/empty.proto";
+
+message Wrappers {
+  message NamesEntry {
+    uint64 key = 1;
+    optional uint64 value = 2;
+  }
+  uint64 id = 1;
+  google.protobuf.Empty marker = 2;
+  double meters = 3;
+  repeated NamesEntry names = 4;
+}
+"#
+    );
+    // The zero value comes first, as proto3 requires.
+    let proto = Instruction::describe().protobuf();
+    assert!(
+        proto.contains("enum Level {\n  LEVEL_MID = 0;\n  LEVEL_LOW = -1;\n  LEVEL_HIGH = 10;\n}")
+    );
+    let proto = MyStruct::<External>::describe().protobuf();
+    assert!(proto
+        .contains("  oneof value {\n    google.protobuf.Empty unit = 1;\n    string newtype = 2;"));
+}
+
+#[test]
+fn avro() {
+    let avro = Tree::describe().avro();
+    assert_eq!(
+        avro,
+        json!({
+            "type": "record",
+            "name": "Tree",
+            "fields": [
+                { "name": "value", "type": "int" },
+                { "name": "children", "type": { "type": "array", "items": "Tree" } },
+            ],
+        })
+    );
+    let avro = Containers::describe().avro();
+    assert_eq!(
+        avro["fields"][1]["type"]["items"]["name"],
+        "Containers_HashEntry"
+    );
+    assert_eq!(
+        avro["fields"][2],
+        json!({ "name": "some", "type": ["null", "float"], "default": null })
+    );
+}
+
+#[test]
+fn markdown() {
+    let md = Wrappers::describe().markdown();
+    assert!(md.starts_with("# Wrappers\n\nThe described type is [`Wrappers`](#wrappers).\n"));
+    assert!(md.contains("## `Meters`\n\nA transparent newtype of `f64`.\n"));
+    assert!(md.contains("| `names` | `Map<`[`Id`](#id)`, Option<`[`Id`](#id)`>>` | yes |  |\n"));
+}
+
+/// Every kind of metadata, on a struct, a field, an enum and a variant.
+const METADATA: &str = r#"root Account;
+
+/// A user account.
+#[deprecated(since = "2.0", note = "use User")]
+#[example({"id": 42u64})]
+#[annotation(table = "accounts")]
+struct Account {
+    /// Unique per tenant.
+    #[deprecated(note = "use key")]
+    #[example(42u64)]
+    #[annotation(index = "unique")]
+    id: u64,
+    status: Status,
+}
+
+enum Status {
+    /// Usable.
+    #[deprecated(note = "use Open")]
+    #[example("Active")]
+    #[annotation(since = "1.2")]
+    Active,
+    Open,
+}
+"#;
+
+#[test]
+fn typescript_metadata() {
+    let ts = Description::from_idl(METADATA).unwrap().typescript();
+    assert!(ts.starts_with(
+        "/**\n * A user account.\n * @deprecated use User\n * @example {\"id\":42}\n \
+         * @table accounts\n */\nexport type Account = {\n"
+    ));
+    assert!(ts.contains(
+        "  /**\n   * Unique per tenant.\n   * @deprecated use key\n   * @example 42\n   \
+         * @index unique\n   */\n  id: number;\n"
+    ));
+    assert!(ts.contains(
+        "export type Status =\n  /**\n   * Usable.\n   * @deprecated use Open\n   \
+         * @example \"Active\"\n   * @since 1.2\n   */\n  | \"Active\"\n  | \"Open\";\n"
+    ));
+}
+
+#[test]
+fn json_schema_metadata() {
+    let schema = Description::from_idl(METADATA).unwrap().json_schema();
+    let account = &schema["$defs"]["Account"];
+    assert_eq!(account["description"], "A user account.");
+    assert_eq!(account["deprecated"], true);
+    assert_eq!(
+        account["x-deprecated"],
+        json!({ "since": "2.0", "note": "use User" })
+    );
+    assert_eq!(account["examples"], json!([{ "id": 42 }]));
+    assert_eq!(account["x-table"], "accounts");
+    let id = &account["properties"]["id"];
+    assert_eq!(id["description"], "Unique per tenant.");
+    assert_eq!(id["x-deprecated"], json!({ "note": "use key" }));
+    assert_eq!(id["examples"], json!([42]));
+    assert_eq!(id["x-index"], "unique");
+    assert_eq!(
+        schema["$defs"]["Status"]["oneOf"][0],
+        json!({
+            "const": "Active",
+            "description": "Usable.",
+            "deprecated": true,
+            "x-deprecated": { "note": "use Open" },
+            "examples": ["Active"],
+            "x-since": "1.2",
+        })
+    );
+}
+
+#[test]
+fn markdown_metadata() {
+    let md = Description::from_idl(METADATA).unwrap().markdown();
+    assert!(md.contains(
+        "## `Account`\n\n> **Deprecated** since 2.0: use User\n\nA user account.\n\n\
+         Example:\n\n```json\n{\n  \"id\": 42\n}\n```\n\n- `table`: accounts\n"
+    ));
+    assert!(md.contains(
+        "| `id` | `u64` | yes | **Deprecated:** use key. Unique per tenant. Example: `42`. \
+         `index`: unique. |\n"
+    ));
+    assert!(md.contains(
+        "| `Active` |  | **Deprecated:** use Open. Usable. Example: `\"Active\"`. \
+         `since`: 1.2. |\n"
+    ));
+}
+
+#[test]
+fn annotate() {
+    let description = MyStruct::<External>::describe();
+    let decoder = SchemaDecoder::new(&description);
+    let value = decoder
+        .seed(&DeserializerOptions::json(), PhantomData)
+        .deserialize(json!({ "string": "s", "int": 1, "value": { "Tuple": ["t", 2] } }))
+        .unwrap();
+    let annotations: Vec<_> = decoder
+        .annotate(&value)
+        .iter()
+        .map(|annotation| annotation.to_string())
+        .collect();
+    assert_eq!(
+        annotations,
+        [
+            ".: struct MyStruct",
+            ".int: u64 = 1",
+            ".string: string = \"s\"",
+            ".value: enum External::Tuple",
+            ".value::Tuple: tuple variant",
+            ".value::Tuple[0]: string = \"t\"",
+            ".value::Tuple[1]: u64 = 2",
+        ]
+    );
+}
+
+#[test]
+fn fingerprint() {
+    let fingerprint = Tree::describe().fingerprint();
+    assert_eq!(fingerprint, Tree::describe().fingerprint());
+    assert_ne!(fingerprint, Containers::describe().fingerprint());
+
+    // Only what's on the wire goes in.
+    let documented = Description::from_idl(METADATA).unwrap();
+    let wire: String = METADATA
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("///"))
+        .filter(|line| !line.trim_start().starts_with("#["))
+        .map(|line| format!("{line}\n"))
+        .collect();
+    let wire = Description::from_idl(&wire).unwrap();
+    assert_ne!(documented, wire);
+    assert_eq!(documented.fingerprint(), wire.fingerprint());
+    let renamed = Description::from_idl(&METADATA.replace("Open,", "Closed,")).unwrap();
+    assert_ne!(documented.fingerprint(), renamed.fingerprint());
+}
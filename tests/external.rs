@@ -0,0 +1,247 @@
+//! Round trips of the Describe impls for other crates' types. Run with
+//! `cargo test --all-features`.
+
+mod common;
+
+#[allow(unused_imports)]
+use common::roundtrip::*;
+
+/// Types that encode differently in human-readable formats can't be
+/// compared with their dynamic value in compact formats.
+#[allow(dead_code)]
+fn roundtrip_readable<T>(value: T)
+where
+    T: serde_descr::Describe
+        + serde::Serialize
+        + serde::de::DeserializeOwned
+        + PartialEq
+        + std::fmt::Debug,
+{
+    roundtrip_compact(&value, false);
+    roundtrip_self_describing(&value, false);
+    roundtrip_text(&value);
+}
+
+#[cfg(feature = "arrayvec")]
+#[test]
+fn arrayvec() {
+    use arrayvec::{ArrayString, ArrayVec};
+
+    roundtrip(ArrayVec::<u16, 4>::from([1, 2, 300, 4]));
+    roundtrip(ArrayString::<8>::from("array").unwrap());
+}
+
+#[cfg(feature = "bytes")]
+#[test]
+fn bytes() {
+    use bytes::{Bytes, BytesMut};
+
+    roundtrip(Bytes::from_static(b"\x00bytes\xff"));
+    roundtrip(BytesMut::from(&b"mutable"[..]));
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn chrono() {
+    use chrono::{DateTime, FixedOffset, Month, NaiveDate, Utc, Weekday};
+
+    let date = NaiveDate::from_ymd_opt(2024, 2, 29).unwrap();
+    let datetime = date.and_hms_nano_opt(12, 30, 5, 250_000_000).unwrap();
+    roundtrip(date);
+    roundtrip(datetime.time());
+    roundtrip(datetime);
+    roundtrip(DateTime::<Utc>::from_naive_utc_and_offset(datetime, Utc));
+    roundtrip(DateTime::<FixedOffset>::parse_from_rfc3339("2024-02-29T12:30:05+02:00").unwrap());
+    roundtrip(Weekday::Thu);
+    roundtrip(Month::February);
+}
+
+#[cfg(feature = "either")]
+#[test]
+fn either() {
+    use either::Either;
+
+    roundtrip(vec![Either::Left(1u8), Either::Right("right".to_string())]);
+}
+
+#[cfg(feature = "hashbrown")]
+#[test]
+fn hashbrown() {
+    use hashbrown::{HashMap, HashSet};
+
+    roundtrip(HashMap::from([
+        (1u32, "one".to_string()),
+        (2, "two".to_string()),
+    ]));
+    roundtrip(HashSet::from([-1i64, 1 << 40]));
+}
+
+#[cfg(feature = "indexmap")]
+#[test]
+fn indexmap() {
+    use indexmap::{IndexMap, IndexSet};
+
+    roundtrip(IndexMap::from([
+        ("z".to_string(), 1u8),
+        ("a".to_string(), 2),
+    ]));
+    roundtrip(IndexSet::from(['z', 'a']));
+}
+
+#[cfg(feature = "ipnet")]
+#[test]
+fn ipnet() {
+    use ipnet::{IpNet, Ipv4Net, Ipv6Net};
+
+    roundtrip_readable("10.1.0.0/16".parse::<Ipv4Net>().unwrap());
+    roundtrip_readable("fd00::/8".parse::<Ipv6Net>().unwrap());
+    roundtrip_readable(vec![
+        "192.168.0.0/24".parse::<IpNet>().unwrap(),
+        "::1/128".parse::<IpNet>().unwrap(),
+    ]);
+}
+
+#[cfg(feature = "ordered-float")]
+#[test]
+fn ordered_float() {
+    use ordered_float::{NotNan, OrderedFloat};
+
+    roundtrip(OrderedFloat(1.5f32));
+    roundtrip(NotNan::new(-2.25f64).unwrap());
+}
+
+#[cfg(feature = "rust_decimal")]
+#[test]
+fn rust_decimal() {
+    roundtrip("-12.3400".parse::<rust_decimal::Decimal>().unwrap());
+}
+
+#[cfg(feature = "semver")]
+#[test]
+fn semver() {
+    use semver::{Comparator, Version, VersionReq};
+
+    roundtrip(Version::parse("1.2.3-beta.1+build").unwrap());
+    roundtrip(VersionReq::parse(">=1.2, <2").unwrap());
+    roundtrip(Comparator::parse("^0.4").unwrap());
+}
+
+#[cfg(feature = "smallvec")]
+#[test]
+fn smallvec() {
+    use smallvec::SmallVec;
+
+    roundtrip(SmallVec::<[u8; 2]>::from_slice(&[1, 2, 3]));
+}
+
+#[cfg(feature = "time")]
+#[test]
+fn time() {
+    use time::{Date, Duration, Month, OffsetDateTime, Time, UtcOffset, Weekday};
+
+    let date = Date::from_calendar_date(2024, Month::February, 29).unwrap();
+    let time = Time::from_hms_nano(12, 30, 5, 250_000_000).unwrap();
+    let offset = UtcOffset::from_hms(-3, -30, 0).unwrap();
+    roundtrip_readable(date);
+    roundtrip_readable(time);
+    roundtrip_readable(date.with_time(time));
+    roundtrip_readable(OffsetDateTime::new_in_offset(date, time, offset));
+    roundtrip_readable(offset);
+    roundtrip_readable(Duration::new(-90, -5));
+    roundtrip_readable(Weekday::Thursday);
+    roundtrip_readable(Month::February);
+}
+
+#[cfg(feature = "url")]
+#[test]
+fn url() {
+    roundtrip(url::Url::parse("https://example.com/path?query#fragment").unwrap());
+}
+
+#[cfg(feature = "uuid")]
+#[test]
+fn uuid() {
+    roundtrip_readable(uuid::Uuid::from_u128(
+        0x0123_4567_89ab_cdef_0123_4567_89ab_cdef,
+    ));
+}
+
+#[cfg(feature = "serde_bytes")]
+#[test]
+fn serde_bytes() {
+    use std::marker::PhantomData;
+
+    use bincode::Options;
+    use serde::{de::DeserializeSeed, Deserialize, Serialize};
+    use serde_bytes::{ByteArray, ByteBuf};
+    use serde_descr::{
+        Describe, Description, DeserializerOptions, NamedFieldSchema, NamedFieldsSchema,
+        SchemaDecoder, SchemaName, SimpleSchema, StructSchema,
+    };
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Blob {
+        #[serde(with = "serde_bytes")]
+        data: Vec<u8>,
+        hash: ByteArray<4>,
+    }
+
+    impl Describe for Blob {
+        fn schema_name() -> SchemaName {
+            SchemaName::new("Blob")
+        }
+
+        fn add_schema(map: &mut Description) {
+            map.add(Self::schema_name(), || {
+                StructSchema::new(
+                    "Blob",
+                    NamedFieldsSchema::new()
+                        .field(NamedFieldSchema::new("data", SimpleSchema::Bytes))
+                        .field(NamedFieldSchema::new("hash", ByteArray::<4>::schema())),
+                )
+            });
+        }
+    }
+
+    roundtrip(ByteBuf::from(vec![0, 1, 255]));
+    roundtrip(Blob {
+        data: b"blob".to_vec(),
+        hash: ByteArray::new([0xde, 0xad, 0xbe, 0xef]),
+    });
+
+    // Types that read bytes as a sequence of u8 and vice versa.
+    fn decode<T: Describe, U: serde::de::DeserializeOwned>(bytes: &[u8], json: &str) -> [U; 2] {
+        let description = T::describe();
+        let decoder = SchemaDecoder::new(&description);
+        let opts = DeserializerOptions::bincode_varint();
+        let binary = bincode::DefaultOptions::new()
+            .deserialize_seed(decoder.seed(&opts, PhantomData::<U>), bytes)
+            .unwrap();
+        let opts = DeserializerOptions::json();
+        let text = decoder
+            .seed(&opts, PhantomData::<U>)
+            .deserialize(&mut serde_json::Deserializer::from_str(json))
+            .unwrap();
+        [binary, text]
+    }
+    let options = bincode::DefaultOptions::new();
+    let vec = vec![1u8, 2, 3];
+    let buf = ByteBuf::from(vec.clone());
+    let (bytes, json) = (
+        options.serialize(&buf).unwrap(),
+        serde_json::to_string(&buf).unwrap(),
+    );
+    assert_eq!(
+        decode::<ByteBuf, Vec<u8>>(&bytes, &json),
+        [vec.clone(), vec.clone()]
+    );
+    assert_eq!(decode::<ByteBuf, [u8; 3]>(&bytes, &json), [[1, 2, 3]; 2]);
+    let (bytes, json) = (
+        options.serialize(&vec).unwrap(),
+        serde_json::to_string(&vec).unwrap(),
+    );
+    assert_eq!(
+        decode::<Vec<u8>, ByteBuf>(&bytes, &json),
+        [buf.clone(), buf]
+    );
+}
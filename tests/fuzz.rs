@@ -0,0 +1,119 @@
+mod common;
+
+use std::marker::PhantomData;
+
+use bincode::Options;
+use common::fuzz::decode;
+use serde::de::DeserializeSeed;
+use serde_descr::{
+    Description, DeserializerOptions, NamedFieldSchema, NamedFieldsSchema, NewtypeSchema,
+    OptionSchema, Schema, SchemaDecoder, SchemaItem, SchemaName, SeqSchema, StructSchema,
+};
+use serde_value::Value;
+
+fn json(description: &Description, json: &str) -> Result<Value, String> {
+    SchemaDecoder::new(description)
+        .seed(&DeserializerOptions::json(), PhantomData)
+        .deserialize(&mut serde_json::Deserializer::from_str(json))
+        .map_err(|e| e.to_string())
+}
+
+fn bincode(description: &Description, bytes: &[u8]) -> Result<Value, String> {
+    bincode::DefaultOptions::new()
+        .deserialize_seed(
+            SchemaDecoder::new(description)
+                .seed(&DeserializerOptions::bincode_varint(), PhantomData),
+            bytes,
+        )
+        .map_err(|e| e.to_string())
+}
+
+fn named(name: &str) -> SchemaItem {
+    SchemaItem::Named(SchemaName::new(name))
+}
+
+fn recursive<S: Into<Schema>>(items: impl IntoIterator<Item = (&'static str, S)>) -> Description {
+    let mut description = Description::new(named("A"));
+    for (name, schema) in items {
+        description.add(SchemaName::new(name), || schema);
+    }
+    description
+}
+
+#[test]
+fn missing_references() {
+    let description = Description::new(named("Missing"));
+    assert_eq!(
+        json(&description, "1").unwrap_err(),
+        "invalid description: a reference to a missing schema"
+    );
+    decode(&description, b"1");
+
+    let description = Description::new(
+        StructSchema::new(
+            "S",
+            NamedFieldsSchema::new().field(NamedFieldSchema::new("s", named("Missing"))),
+        )
+        .into(),
+    );
+    decode(&description, br#"{"s": 1}"#);
+}
+
+#[test]
+fn transparent_cycles() {
+    let description = recursive([
+        ("A", NewtypeSchema::new("A", named("B")).transparent()),
+        ("B", NewtypeSchema::new("B", named("A")).transparent()),
+    ]);
+    assert_eq!(
+        json(&description, "1").unwrap_err(),
+        "invalid description: a cycle of transparent schemas"
+    );
+    decode(&description, b"1");
+}
+
+#[test]
+fn deep_nesting() {
+    let description = recursive([("A", OptionSchema::new(named("A")))]);
+    assert_eq!(
+        bincode(&description, &[1; 100_000]).unwrap_err(),
+        "nesting deeper than 128 levels"
+    );
+    let description = recursive([("A", SeqSchema::new(named("A")))]);
+    assert_eq!(
+        bincode(&description, &[1; 100_000]).unwrap_err(),
+        "nesting deeper than 128 levels"
+    );
+
+    // Envelopes of `Any` values in compact formats nest too.
+    let description = Description::new(SchemaItem::Schema(Box::new(Schema::Any)));
+    assert_eq!(
+        bincode(&description, &[16; 100_000]).unwrap_err(),
+        "value of enum Any nested too deeply"
+    );
+}
+
+/// Random descriptions and inputs, the same on every run.
+#[cfg(feature = "arbitrary")]
+#[test]
+fn corpus() {
+    use arbitrary::{Arbitrary, Unstructured};
+
+    let mut state = 0x5eed_u64;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    for seed in 0..5000 {
+        let len = (next() % 512) as usize;
+        let bytes: Vec<u8> = (0..len).map(|_| next() as u8).collect();
+        let mut u = Unstructured::new(&bytes);
+        let Ok(description) = Description::arbitrary(&mut u) else {
+            continue;
+        };
+        decode(&description, u.take_rest());
+        common::fuzz::generate(&description, seed);
+    }
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use std::fmt::Debug;
+
+use common::*;
+use serde::de::DeserializeOwned;
+use serde_descr::{Describe, Generator};
+
+/// Generate values of `T` and check that they are valid and decode as `T`
+/// from JSON, if `readable`, and from bincode, if `compact`. Human-readable
+/// types are generated in their compact form, which JSON can't write.
+fn generate<T>(readable: bool, compact: bool)
+where
+    T: Describe + DeserializeOwned + Debug,
+{
+    let description = T::describe();
+    for seed in 0..64 {
+        let mut generator = Generator::new(&description).seed(seed);
+        let value = generator.generate().unwrap();
+        assert_eq!(description.validate(&value), [], "{value:?}");
+
+        let serializable = generator.decoder().serializable(&value);
+        if readable {
+            let json = serde_json::to_string(&serializable).unwrap();
+            if let Err(e) = serde_json::from_str::<T>(&json) {
+                panic!("{json} is not a {}: {e}", std::any::type_name::<T>());
+            }
+        }
+        if compact {
+            let bytes = bincode::serialize(&serializable).unwrap();
+            if let Err(e) = bincode::deserialize::<T>(&bytes) {
+                panic!("{value:?} is not a {}: {e}", std::any::type_name::<T>());
+            }
+        }
+    }
+}
+
+#[test]
+fn generated_values_decode() {
+    generate::<MyStruct<u32>>(true, true);
+    generate::<External>(true, true);
+    generate::<Internal>(true, false);
+    generate::<Adjacent>(true, false);
+    generate::<Tree>(true, true);
+    generate::<Containers>(true, true);
+    generate::<Instruction>(true, true);
+    generate::<Listener>(true, true);
+    generate::<Fixed>(true, true);
+    generate::<Flattened>(true, false);
+    generate::<Network>(false, true);
+    generate::<Event>(false, true);
+    generate::<Wrappers>(true, true);
+    generate::<Sparse>(true, false);
+}
+
+#[test]
+fn seeds() {
+    let description = Listener::describe();
+    let values = |seed| {
+        let mut generator = Generator::new(&description).seed(seed);
+        [generator.generate().unwrap(), generator.generate().unwrap()]
+    };
+    assert_eq!(values(7), values(7));
+    assert_ne!(values(7), values(8));
+}
+
+#[test]
+fn depth() {
+    let description = Tree::describe();
+    let mut generator = Generator::new(&description).max_depth(0);
+    for _ in 0..16 {
+        let tree: Tree =
+            serde_value::Value::deserialize_into(generator.generate().unwrap()).unwrap();
+        assert!(tree.children.is_empty(), "{tree:?}");
+    }
+}
@@ -0,0 +1,179 @@
+mod common;
+
+use common::*;
+use serde_descr::{Describe, Description};
+use serde_json::json;
+
+#[test]
+fn print() {
+    assert_eq!(
+        MyStruct::<External>::describe().idl(),
+        r#"root MyStruct<External>;
+
+enum External {
+    Unit,
+    Newtype(string),
+    Tuple(string, u64),
+    Struct {
+        float: f64,
+    },
+}
+
+struct MyStruct<External> {
+    string: string,
+    int: u64,
+    value: External,
+}
+"#
+    );
+    assert!(Instruction::describe()
+        .idl()
+        .contains("#[repr(i16)]\nenum Level {\n    Low = -1,\n    Mid,\n    High = 10,\n}\n"));
+}
+
+#[test]
+fn round_trip() {
+    for description in [
+        MyStruct::<Internal>::describe(),
+        MyStruct::<Adjacent>::describe(),
+        Containers::describe(),
+        StdTypes::describe(),
+        Network::describe(),
+        Wrappers::describe(),
+        Flattened::describe(),
+        Skipped::describe(),
+        Instruction::describe(),
+        Listener::describe(),
+    ] {
+        assert_eq!(Description::from_idl(&description.idl()), Ok(description));
+    }
+}
+
+/// Every kind of schema, attribute and value, as it is printed.
+const EVERYTHING: &str = r#"root (Option<"u8">, [any; 0], (), (u8,), Set<bytes>);
+
+/// A struct.
+///
+///  Indented.
+#[serde(rename = "renamed", rename_all = "camelCase")]
+#[deprecated(since = "1.0", note = "use \"other\"")]
+#[example({"a": [1u8, -2i64], "b": Some(b"\x00\"\n")})]
+#[annotation(owner = "team", "x-key" = "é")]
+struct a::Everything {
+    /// A field.
+    #[serde(rename = "F", alias = "g", alias = "h", default = 1.5f32, skip_serializing_if = "is_zero")]
+    field: f32,
+    #[serde(default = '\'', flatten)]
+    "weird name": HumanReadable<char, Map<unit, Option<()>>>,
+    #[serde(skip)]
+    inline: #[doc = "Inline."] #[serde(rename_all = "kebab-case")] struct Inline { a_b: u8 },
+    #[deprecated]
+    nested: #[validate(min = -1, max = 2.5, min_length = 0, unique, key_pattern = "b")] #[validate] Vec<struct Unit>,
+    floats: #[serde(tag = "kind")] enum Floats { A { x: f64 } },
+    #[serde(default = [f64::NAN, -0f64, 0.1f64, f32::NEG_INFINITY, Newtype(None), {}])]
+    defaults: u8,
+}
+
+/// An enum.
+#[serde(tag = "t", content = "c", rename_all = "snake_case")]
+enum b::Enum {
+    Unit,
+    #[serde(rename = "new", alias = "n", other)]
+    #[index = 3]
+    Newtype(u16) = -5,
+    Tuple(u8, u8),
+    /// A struct variant.
+    #[deprecated]
+    Struct {
+        x: c::Tuple,
+    },
+    Empty {},
+}
+
+struct c::Empty();
+
+#[serde(rename = "N", transparent)]
+struct c::Newtype("struct");
+
+#[repr(i8)]
+enum c::Repr {
+    A = -1,
+    B,
+}
+
+struct c::Tuple(u8,);
+
+struct c::Unit;
+
+type d::Alias = Map<u8, string>;
+
+type "odd name"<"with space", c::Unit> = #[serde(rename = "R")] struct Other {};
+"#;
+
+#[test]
+fn everything() {
+    let description = Description::from_idl(EVERYTHING).unwrap();
+    assert_eq!(description.idl(), EVERYTHING);
+
+    let json = serde_json::to_value(&description).unwrap();
+    let everything = &json["items"]["a::Everything"]["Struct"];
+    assert_eq!(everything["meta"]["doc"], "A struct.\n\n Indented.");
+    let field = &everything["fields"]["Named"]["fields"][0];
+    assert_eq!(field["aliases"], json!(["g", "h"]));
+    assert_eq!(field["default"], 1.5);
+    assert_eq!(
+        json["items"]["b::Enum"]["Enum"]["repr"],
+        json!({ "AdjacentlyTagged": { "tag": "t", "content": "c" } })
+    );
+    assert_eq!(
+        json["items"]["c::Tuple"]["Struct"]["fields"]["Tuple"]["values"]
+            .as_array()
+            .unwrap()
+            .len(),
+        1
+    );
+    assert!(json["items"]["c::Newtype"].get("Newtype").is_some());
+}
+
+#[test]
+fn comments_and_spacing() {
+    let description = Description::from_idl(
+        "// A tree.\nroot Tree ;\nstruct Tree{value:u16,children:Vec<Tree>,}",
+    )
+    .unwrap();
+    assert_eq!(description, Tree::describe());
+}
+
+#[test]
+fn errors() {
+    let error = |idl: &str| Description::from_idl(idl).unwrap_err();
+    assert_eq!(
+        error("root u8;\n\nstruct A {\n    a: u8\n    b: u8,\n}\n"),
+        "expected \"}\" at line 5, column 5"
+    );
+    assert_eq!(
+        error("root A;\nstruct A;\nstruct A;\n"),
+        "a second declaration of a schema at line 3, column 1"
+    );
+    assert_eq!(
+        error("root u8;\n#[serde(flatten)]\nstruct A;\n"),
+        "unexpected serde(flatten) on a unit struct at line 2, column 1"
+    );
+    assert_eq!(
+        error("root u8;\ntype A = B;\n"),
+        "expected a schema, not a reference, at line 2, column 10"
+    );
+    assert_eq!(
+        error("root u8;\nstruct A {\n    #[serde(default = 1)]\n    a: u8,\n}\n"),
+        "invalid number \"1\", which needs a type suffix such as u8 or f64 at line 3, column 23"
+    );
+    assert_eq!(
+        error("root u8;\n#[serde(tag = \"t\")]\n#[repr(u8)]\nenum A {}\n"),
+        "conflicting representations of an enum at line 2, column 1"
+    );
+    let deep = format!("root {};", "Option<".repeat(100_000));
+    assert_eq!(
+        error(&deep),
+        "nesting deeper than 128 levels at line 1, column 902"
+    );
+}
@@ -0,0 +1,113 @@
+mod common;
+
+use std::{collections::BTreeMap, marker::PhantomData, mem::size_of};
+
+use bincode::Options;
+use common::*;
+use serde::de::DeserializeSeed;
+use serde_descr::{Describe, DeserializerOptions, SchemaDecoder};
+use serde_value::Value;
+
+fn json<T: Describe>(opts: DeserializerOptions, json: &str) -> Result<Value, String> {
+    let json: serde_json::Value = serde_json::from_str(json).unwrap();
+    SchemaDecoder::new(&T::describe())
+        .seed(&opts, PhantomData)
+        .deserialize(json)
+        .map_err(|e| e.to_string())
+}
+
+fn bincode<T: Describe>(opts: DeserializerOptions, bytes: &[u8]) -> Result<Value, String> {
+    bincode::DefaultOptions::new()
+        .deserialize_seed(
+            SchemaDecoder::new(&T::describe()).seed(&opts, PhantomData),
+            bytes,
+        )
+        .map_err(|e| e.to_string())
+}
+
+#[test]
+fn lengths() {
+    // A length prefix of `u64::MAX` units, which take no input.
+    let units = [253, 255, 255, 255, 255, 255, 255, 255, 255];
+    let opts = DeserializerOptions::bincode_varint().max_len(1000);
+    assert_eq!(
+        bincode::<Vec<()>>(opts, &units).unwrap_err(),
+        "sequence longer than 1000 elements"
+    );
+
+    let opts = DeserializerOptions::json().max_len(2);
+    assert!(json::<Vec<Vec<u8>>>(opts.clone(), "[[1, 2], [3]]").is_ok());
+    assert_eq!(
+        json::<Vec<Vec<u8>>>(opts.clone(), "[[1, 2, 3]]").unwrap_err(),
+        "sequence longer than 2 elements"
+    );
+    assert_eq!(
+        json::<BTreeMap<String, u8>>(opts, r#"{"a": 1, "b": 2, "c": 3}"#).unwrap_err(),
+        "map longer than 2 entries"
+    );
+}
+
+#[test]
+fn strings() {
+    let opts = DeserializerOptions::json().max_string_len(5);
+    assert!(json::<String>(opts.clone(), r#""abcde""#).is_ok());
+    assert_eq!(
+        json::<String>(opts, r#""abcdef""#).unwrap_err(),
+        "string longer than 5 bytes"
+    );
+
+    let opts = DeserializerOptions::bincode_varint().max_string_len(5);
+    assert_eq!(
+        bincode::<MyStruct<u32>>(opts, &[6, b'a', b'b', b'c', b'd', b'e', b'f', 0, 0]).unwrap_err(),
+        "string longer than 5 bytes"
+    );
+}
+
+#[test]
+fn bytes() {
+    // Every inner sequence is within the limit, but not all of them.
+    let elements = 8 * size_of::<Value>();
+    let input = "[[1, 2, 3], [4, 5, 6]]";
+    let opts = DeserializerOptions::json().max_bytes(elements);
+    assert!(json::<Vec<Vec<u8>>>(opts, input).is_ok());
+    let opts = DeserializerOptions::json().max_bytes(elements - 1);
+    assert_eq!(
+        json::<Vec<Vec<u8>>>(opts, input).unwrap_err(),
+        format!("decoded values take more than {} bytes", elements - 1)
+    );
+}
+
+#[test]
+fn depth() {
+    // A tree takes two levels: one of its own and one for its children.
+    let opts = DeserializerOptions::json().max_depth(6);
+    let tree = |depth| {
+        let open = r#"{"value": 1, "children": ["#.repeat(depth);
+        format!(
+            r#"{open}{{"value": 1, "children": []}}{}"#,
+            "]}".repeat(depth)
+        )
+    };
+    assert!(json::<Tree>(opts.clone(), &tree(2)).is_ok());
+    assert_eq!(
+        json::<Tree>(opts, &tree(3)).unwrap_err(),
+        "nesting deeper than 6 levels"
+    );
+}
+
+/// Content that is buffered before its variant is known is held to the same
+/// limits.
+#[test]
+fn buffered() {
+    let input = r#"{"type": "Struct", "id": 1, "name": "abcdefg"}"#;
+    let opts = DeserializerOptions::json().max_string_len(6);
+    assert_eq!(
+        json::<Internal>(opts, input).unwrap_err(),
+        "string longer than 6 bytes"
+    );
+    let opts = DeserializerOptions::json().max_len(2);
+    assert_eq!(
+        json::<Internal>(opts, input).unwrap_err(),
+        "map longer than 2 entries"
+    );
+}
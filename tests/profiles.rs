@@ -0,0 +1,81 @@
+mod common;
+
+use std::{collections::BTreeMap, marker::PhantomData};
+
+use common::{
+    scoped::{scoped, Scoped},
+    *,
+};
+use serde::de::DeserializeSeed;
+use serde_descr::{Describe, DeserializerOptions, SchemaDecoder};
+
+#[test]
+fn msgpack_options() {
+    // rmp-serde writes `Some(x)` as `x`, so `Some(None)` reads back as `None`.
+    let value = MyStruct {
+        string: "s".to_string(),
+        int: 1,
+        value: vec![None, Some(None), Some(Some(2u8))],
+    };
+    let description = MyStruct::<Vec<Option<Option<u8>>>>::describe();
+    let decoder = SchemaDecoder::new(&description);
+    for (bytes, opts) in [
+        (
+            rmp_serde::to_vec(&value).unwrap(),
+            DeserializerOptions::msgpack_compact(),
+        ),
+        (
+            rmp_serde::to_vec_named(&value).unwrap(),
+            DeserializerOptions::msgpack_named(),
+        ),
+    ] {
+        let expected: MyStruct<Vec<Option<Option<u8>>>> = rmp_serde::from_slice(&bytes).unwrap();
+        assert_eq!(expected.value, [None, None, Some(Some(2))]);
+        let decoded = decoder
+            .seed(&opts, PhantomData)
+            .deserialize(&mut rmp_serde::Deserializer::new(&bytes[..]))
+            .unwrap();
+        assert_eq!(expected, decoded);
+    }
+
+    // So a field may become optional without breaking the values written.
+    let (old, new) = (
+        MyStruct::<u8>::describe(),
+        MyStruct::<Option<u8>>::describe(),
+    );
+    let opts = DeserializerOptions::msgpack_compact();
+    let changes = old.diff(&new, &opts);
+    assert!(!changes.is_empty() && changes.iter().all(|change| !change.breaking));
+    let bytes = rmp_serde::to_vec(&my_struct(3u8)).unwrap();
+    let decoded: MyStruct<Option<u8>> = SchemaDecoder::new(&new)
+        .seed(&opts, PhantomData)
+        .deserialize(&mut rmp_serde::Deserializer::new(&bytes[..]))
+        .unwrap();
+    assert_eq!(decoded, my_struct(Some(3)));
+}
+
+#[test]
+fn cbor_keys_and_tags() {
+    // Integer keys stay integers, and a semantic tag (1, epoch time) is read
+    // through to its content.
+    let tagged = BTreeMap::from([(7u32, ciborium::tag::Required::<u64, 1>(1_700_000_000))]);
+    let mut bytes = Vec::new();
+    ciborium::into_writer(&tagged, &mut bytes).unwrap();
+    let description = BTreeMap::<u32, u64>::describe();
+    let Scoped(decoded) = scoped(&description, &DeserializerOptions::cbor(), || {
+        ciborium::from_reader::<Scoped<BTreeMap<u32, u64>>, _>(&bytes[..])
+    })
+    .unwrap();
+    assert_eq!(decoded, BTreeMap::from([(7, 1_700_000_000)]));
+    let Scoped(dynamic) = scoped(&description, &DeserializerOptions::cbor(), || {
+        ciborium::from_reader::<Scoped<serde_value::Value>, _>(&bytes[..])
+    })
+    .unwrap();
+    assert_eq!(
+        dynamic,
+        serde_value::Value::Map(BTreeMap::from([(
+            serde_value::Value::U32(7),
+            serde_value::Value::U64(1_700_000_000)
+        )]))
+    );
+}
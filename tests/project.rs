@@ -0,0 +1,191 @@
+mod common;
+
+use std::marker::PhantomData;
+
+use bincode::Options;
+use common::*;
+use serde::{de::DeserializeSeed, Serialize};
+use serde_descr::{Describe, DeserializerOptions, SchemaDecoder};
+use serde_value::Value;
+
+/// Project `paths` from `value` encoded as bincode and as JSON, and from its
+/// dynamic value, checking that all three agree.
+fn project<T: Describe + Serialize>(value: &T, paths: &[&str]) -> Vec<Option<Value>> {
+    let description = T::describe();
+    let decoder = SchemaDecoder::new(&description);
+    let projection = decoder.project(paths).unwrap();
+
+    let bytes = bincode::DefaultOptions::new().serialize(value).unwrap();
+    let opts = DeserializerOptions::bincode_varint();
+    let binary = bincode::DefaultOptions::new()
+        .deserialize_seed(projection.seed(&opts), &bytes)
+        .unwrap();
+
+    let json = serde_json::to_string(value).unwrap();
+    let opts = DeserializerOptions::json();
+    let text = projection
+        .seed(&opts)
+        .deserialize(&mut serde_json::Deserializer::from_str(&json))
+        .unwrap();
+    assert_eq!(binary, text, "json");
+
+    let dynamic = bincode::DefaultOptions::new()
+        .deserialize_seed(
+            decoder.seed(&DeserializerOptions::bincode_varint(), PhantomData),
+            &bytes,
+        )
+        .unwrap();
+    assert_eq!(binary, projection.extract(&dynamic), "dynamic");
+    binary
+}
+
+#[test]
+fn fields_and_variants() {
+    let value = my_struct(External::Struct { float: 2.5 });
+    assert_eq!(
+        project(&value, &["value.Struct.float", "int", "value.Unit"]),
+        vec![Some(Value::F64(2.5)), Some(Value::U64(300)), None]
+    );
+    let value = my_struct(External::Tuple("tuple".to_string(), 7));
+    assert_eq!(
+        project(&value, &["value.Tuple.1", "value.Struct.float", "string"]),
+        vec![
+            Some(Value::U64(7)),
+            None,
+            Some(Value::String("hello".to_string()))
+        ]
+    );
+    assert_eq!(
+        project(&my_struct(External::Unit), &["value.Unit"]),
+        vec![Some(Value::Unit)]
+    );
+}
+
+#[test]
+fn tagged_variants() {
+    let value = my_struct(Internal::Newtype(Inner {
+        flag: true,
+        small: -3,
+    }));
+    assert_eq!(
+        project(&value, &["value.Newtype.small", "value.Struct.id"]),
+        vec![Some(Value::I8(-3)), None]
+    );
+    let value = my_struct(Adjacent::Tuple(7, "seven".to_string()));
+    assert_eq!(
+        project(&value, &["value.Tuple.1", "value.Newtype", "int"]),
+        vec![
+            Some(Value::String("seven".to_string())),
+            None,
+            Some(Value::U64(300))
+        ]
+    );
+}
+
+#[test]
+fn containers() {
+    let value = Containers {
+        btree: [("a".to_string(), -1), ("b".to_string(), 2)].into(),
+        hash: [(1, vec![true, false])].into(),
+        some: Some(0.25),
+        none: None,
+        pair: ('x', -300),
+    };
+    assert_eq!(
+        project(
+            &value,
+            &["btree.b", "hash.1.1", "hash.2", "some", "none", "pair.0"]
+        ),
+        vec![
+            Some(Value::I64(2)),
+            Some(Value::Bool(false)),
+            None,
+            Some(Value::Option(Some(Box::new(Value::F32(0.25))))),
+            Some(Value::Option(None)),
+            Some(Value::Char('x')),
+        ]
+    );
+}
+
+#[test]
+fn quoted_names() {
+    let value = Containers {
+        btree: [("a.b".to_string(), 1), ("a".to_string(), 2)].into(),
+        hash: Default::default(),
+        some: None,
+        none: None,
+        pair: ('x', 0),
+    };
+    assert_eq!(
+        project(&value, &["btree.\"a.b\"", "\"btree\".a", "btree.\"a\\\"\""]),
+        vec![Some(Value::I64(1)), Some(Value::I64(2)), None]
+    );
+    let description = Containers::describe();
+    let decoder = SchemaDecoder::new(&description);
+    assert!(decoder.project(&["btree.\"a"]).is_err());
+    assert!(decoder.project(&["btree.\"a\"b"]).is_err());
+}
+
+#[test]
+fn skipped_strings() {
+    // Only what is selected counts against `max_bytes`: the string is
+    // skipped without being read into a value.
+    let value = MyStruct {
+        string: "x".repeat(1 << 16),
+        int: 1,
+        value: (),
+    };
+    let description = MyStruct::<()>::describe();
+    let decoder = SchemaDecoder::new(&description);
+    let projection = decoder.project(&["int"]).unwrap();
+    let bytes = bincode::DefaultOptions::new().serialize(&value).unwrap();
+    let opts = DeserializerOptions::bincode_varint().max_bytes(1 << 10);
+    let projected = bincode::DefaultOptions::new()
+        .deserialize_seed(projection.seed(&opts), &bytes)
+        .unwrap();
+    assert_eq!(projected, vec![Some(Value::U64(1))]);
+    let projection = decoder.project(&["string"]).unwrap();
+    assert!(bincode::DefaultOptions::new()
+        .deserialize_seed(projection.seed(&opts), &bytes)
+        .is_err());
+}
+
+#[test]
+fn nested_selections() {
+    let value = Tree {
+        value: 1,
+        children: vec![Tree {
+            value: 2,
+            children: Vec::new(),
+        }],
+    };
+    let projected = project(&value, &["children.0", "children.0.value", ""]);
+    assert_eq!(projected[1], Some(Value::U16(2)));
+    assert_eq!(
+        projected[2].as_ref(),
+        Some(&serde_value::to_value(&value).unwrap())
+    );
+
+    let leaf = |value| Tree {
+        value,
+        children: Vec::new(),
+    };
+    let value = Tree {
+        value: 0,
+        children: (0..120).map(leaf).collect(),
+    };
+    assert_eq!(
+        project(&value, &["children.7.value", "children.110.value"]),
+        vec![Some(Value::U16(7)), Some(Value::U16(110))]
+    );
+}
+
+#[test]
+fn invalid_paths() {
+    let description = MyStruct::<External>::describe();
+    let decoder = SchemaDecoder::new(&description);
+    assert!(decoder.project(&["missing"]).is_err());
+    assert!(decoder.project(&["value.Other"]).is_err());
+    assert!(decoder.project(&["int.0"]).is_err());
+    assert!(decoder.project(&["value.Tuple.x"]).is_err());
+}
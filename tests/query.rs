@@ -0,0 +1,160 @@
+mod common;
+
+use std::marker::PhantomData;
+
+use bincode::Options;
+use common::*;
+use serde::{de::DeserializeSeed, Serialize};
+use serde_descr::{Describe, DeserializerOptions, Query, SchemaDecoder};
+use serde_value::Value;
+
+/// Run `query` against `value` encoded as bincode and as JSON, and against
+/// its dynamic value, checking that all three agree.
+fn query<T: Describe + Serialize>(value: &T, query: &str) -> Vec<Value> {
+    let description = T::describe();
+    let decoder = SchemaDecoder::new(&description);
+    let query = query.parse::<Query>().unwrap();
+
+    let bytes = bincode::DefaultOptions::new().serialize(value).unwrap();
+    let opts = DeserializerOptions::bincode_varint();
+    let binary = bincode::DefaultOptions::new()
+        .deserialize_seed(query.seed(&decoder, &opts).unwrap(), &bytes)
+        .unwrap();
+
+    let json = serde_json::to_string(value).unwrap();
+    let opts = DeserializerOptions::json();
+    let text = query
+        .seed(&decoder, &opts)
+        .unwrap()
+        .deserialize(&mut serde_json::Deserializer::from_str(&json))
+        .unwrap();
+    assert_eq!(binary, text, "json");
+
+    let dynamic = bincode::DefaultOptions::new()
+        .deserialize_seed(
+            decoder.seed(&DeserializerOptions::bincode_varint(), PhantomData),
+            &bytes,
+        )
+        .unwrap();
+    assert_eq!(binary, query.eval(&decoder, &dynamic).unwrap(), "dynamic");
+    binary
+}
+
+fn strings(values: &[&str]) -> Vec<Value> {
+    values
+        .iter()
+        .map(|s| Value::String(s.to_string()))
+        .collect()
+}
+
+#[test]
+fn fields_and_variants() {
+    let value = my_struct(External::Struct { float: 2.5 });
+    assert_eq!(query(&value, ".value::Struct.float"), vec![Value::F64(2.5)]);
+    assert_eq!(query(&value, ".value::Unit"), Vec::<Value>::new());
+    assert_eq!(query(&value, ".int"), vec![Value::U64(300)]);
+    assert_eq!(
+        query(&value, "."),
+        vec![serde_value::to_value(&value).unwrap()]
+    );
+
+    let value = my_struct(Internal::Newtype(Inner {
+        flag: true,
+        small: -3,
+    }));
+    assert_eq!(query(&value, ".value::Newtype.small"), vec![Value::I8(-3)]);
+    let value = my_struct(Adjacent::Tuple(7, "seven".to_string()));
+    assert_eq!(query(&value, ".value::Tuple[1]"), strings(&["seven"]));
+    assert_eq!(query(&value, ".value[][0]"), vec![Value::U8(7)]);
+}
+
+#[test]
+fn indexing_and_slicing() {
+    let value = my_struct(["a", "b", "c", "d"].map(String::from).to_vec());
+    assert_eq!(query(&value, ".value[1]"), strings(&["b"]));
+    assert_eq!(query(&value, ".value[-1]"), strings(&["d"]));
+    assert_eq!(query(&value, ".value[4]"), Vec::<Value>::new());
+    assert_eq!(query(&value, ".value[1:3]"), strings(&["b", "c"]));
+    assert_eq!(query(&value, ".value[-2:]"), strings(&["c", "d"]));
+    assert_eq!(query(&value, ".value[]"), strings(&["a", "b", "c", "d"]));
+    assert_eq!(
+        query(&value, ".value[9223372036854775807]"),
+        Vec::<Value>::new()
+    );
+    assert_eq!(
+        query(&value, ".value[-9223372036854775808]"),
+        Vec::<Value>::new()
+    );
+}
+
+#[test]
+fn maps_and_filters() {
+    let value = Containers {
+        btree: [
+            ("a".to_string(), -1),
+            ("b".to_string(), 2),
+            ("c.d".to_string(), 3),
+        ]
+        .into(),
+        hash: [(1, vec![true, false]), (2, vec![false])].into(),
+        some: Some(0.25),
+        none: None,
+        pair: ('x', -300),
+    };
+    assert_eq!(query(&value, ".btree.b"), vec![Value::I64(2)]);
+    assert_eq!(query(&value, r#".btree["c.d"]"#), vec![Value::I64(3)]);
+    assert_eq!(query(&value, r#".btree."c.d""#), vec![Value::I64(3)]);
+    assert_eq!(query(&value, ".hash[2][0]"), vec![Value::Bool(false)]);
+    assert_eq!(
+        query(&value, ".btree[?@ >= 2]"),
+        vec![Value::I64(2), Value::I64(3)]
+    );
+    assert_eq!(
+        query(&value, ".hash[?.[0] && .[1] == false][0]"),
+        vec![Value::Bool(true)]
+    );
+    assert_eq!(
+        query(&value, ".some"),
+        vec![Value::Option(Some(Box::new(Value::F32(0.25))))]
+    );
+    assert_eq!(query(&value, "[?@ == null]"), vec![Value::Option(None)]);
+    assert_eq!(query(&value, "[?.[0] == \"x\"][1]"), vec![Value::I16(-300)]);
+}
+
+#[test]
+fn recursive_filters() {
+    let leaf = |value| Tree {
+        value,
+        children: Vec::new(),
+    };
+    let value = Tree {
+        value: 1,
+        children: vec![leaf(2), leaf(3), leaf(4)],
+    };
+    assert_eq!(
+        query(&value, ".children[?.value > 2 && !(.value == 4)].value"),
+        vec![Value::U16(3)]
+    );
+}
+
+#[test]
+fn invalid_queries() {
+    for invalid in [
+        ".value[",
+        ".value[?.x ==]",
+        ".value::",
+        ".value]",
+        "..int",
+        ".int.",
+        ".value.[0]",
+    ] {
+        assert!(invalid.parse::<Query>().is_err(), "{invalid}");
+    }
+    let description = MyStruct::<External>::describe();
+    let decoder = SchemaDecoder::new(&description);
+    let opts = DeserializerOptions::json();
+    for invalid in [".missing", ".value.Struct", ".int::Unit", ".value::Other"] {
+        let query = invalid.parse::<Query>().unwrap();
+        assert!(query.seed(&decoder, &opts).is_err(), "{invalid}");
+    }
+}
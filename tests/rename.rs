@@ -0,0 +1,134 @@
+mod common;
+
+use serde::{Deserialize, Serialize};
+use serde_descr::{
+    Describe, Description, EnumSchema, NamedFieldSchema, NamedFieldsSchema, RenameAll, SchemaName,
+    SimpleSchema, StructSchema, TupleSchema, VariantSchema,
+};
+
+use common::roundtrip::*;
+
+/// The rules must agree with the names serde_derive generates.
+#[test]
+fn rules_match_serde() {
+    macro_rules! check {
+        ($($rule:literal => $variant:expr),+ $(,)?) => {$({
+            #[derive(Serialize)]
+            #[serde(rename_all = $rule)]
+            struct Fields {
+                field: u8,
+                two_words: u8,
+                with_3_parts: u8,
+            }
+
+            #[derive(Serialize)]
+            #[serde(rename_all = $rule)]
+            enum Variants {
+                Variant,
+                TwoWords,
+                HTTPStatus,
+            }
+
+            let json = serde_json::to_value(Fields { field: 0, two_words: 0, with_3_parts: 0 })
+                .unwrap();
+            let fields = json.as_object().unwrap().keys().cloned().collect::<Vec<_>>();
+            let mut expected = ["field", "two_words", "with_3_parts"]
+                .map(|f| $variant.apply_to_field(f))
+                .to_vec();
+            expected.sort();
+            assert_eq!(fields, expected, "{}", $rule);
+
+            for (variant, name) in [
+                (Variants::Variant, "Variant"),
+                (Variants::TwoWords, "TwoWords"),
+                (Variants::HTTPStatus, "HTTPStatus"),
+            ] {
+                assert_eq!(
+                    serde_json::to_value(variant).unwrap(),
+                    $variant.apply_to_variant(name),
+                    "{}",
+                    $rule
+                );
+            }
+        })+};
+    }
+
+    check! {
+        "lowercase" => RenameAll::Lower,
+        "UPPERCASE" => RenameAll::Upper,
+        "PascalCase" => RenameAll::Pascal,
+        "camelCase" => RenameAll::Camel,
+        "snake_case" => RenameAll::Snake,
+        "SCREAMING_SNAKE_CASE" => RenameAll::ScreamingSnake,
+        "kebab-case" => RenameAll::Kebab,
+        "SCREAMING-KEBAB-CASE" => RenameAll::ScreamingKebab,
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Request {
+    request_id: u32,
+    #[serde(rename = "type")]
+    request_type: Method,
+    retry_count: Option<u8>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(rename_all = "SCREAMING-KEBAB-CASE")]
+enum Method {
+    Get,
+    PostForm(String),
+    #[serde(rename = "del")]
+    Delete,
+}
+
+impl Describe for Request {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("Request")
+    }
+
+    fn add_schema(map: &mut Description) {
+        if map.add(Self::schema_name(), || {
+            StructSchema::new(
+                "Request",
+                NamedFieldsSchema::new()
+                    .field(NamedFieldSchema::new("request_id", u32::schema()))
+                    .field(NamedFieldSchema::new("request_type", Method::schema()).rename("type"))
+                    .field(NamedFieldSchema::new("retry_count", Option::<u8>::schema())),
+            )
+            .rename_all(RenameAll::Camel)
+        }) {
+            Method::add_schema(map);
+        }
+    }
+}
+
+impl Describe for Method {
+    fn schema_name() -> SchemaName {
+        SchemaName::new("Method")
+    }
+
+    fn add_schema(map: &mut Description) {
+        map.add(Self::schema_name(), || {
+            EnumSchema::new("Method")
+                .variant(VariantSchema::new("Get", TupleSchema::new()))
+                .variant(VariantSchema::new(
+                    "PostForm",
+                    TupleSchema::new().element(SimpleSchema::String),
+                ))
+                .variant(VariantSchema::new("Delete", TupleSchema::new()).rename("del"))
+                .rename_all(RenameAll::ScreamingKebab)
+        });
+    }
+}
+
+#[test]
+fn renamed() {
+    roundtrip(Request {
+        request_id: 1,
+        request_type: Method::PostForm("form".to_string()),
+        retry_count: Some(3),
+    });
+    roundtrip(vec![Method::Get, Method::Delete]);
+}
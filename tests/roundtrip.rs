@@ -0,0 +1,369 @@
+mod common;
+
+use std::{
+    cmp::Reverse,
+    collections::BTreeMap,
+    marker::PhantomData,
+    net::Ipv6Addr,
+    num::Wrapping,
+    ops::Bound,
+    time::{Duration, SystemTime},
+};
+
+use bincode::Options;
+use common::{roundtrip::*, *};
+use serde::de::DeserializeSeed;
+use serde_descr::{Describe, DeserializerOptions, SchemaDecoder};
+
+#[test]
+fn externally_tagged() {
+    roundtrip(my_struct(External::Unit));
+    roundtrip(my_struct(External::Newtype("newtype".to_string())));
+    roundtrip(my_struct(External::Tuple("tuple".to_string(), 1 << 40)));
+    roundtrip(my_struct(External::Struct { float: -1.5 }));
+}
+
+#[test]
+fn internally_tagged() {
+    roundtrip(my_struct(Internal::Unit));
+    roundtrip(my_struct(Internal::Newtype(Inner {
+        flag: true,
+        small: -3,
+    })));
+    roundtrip(my_struct(Internal::Struct {
+        id: 70000,
+        name: "name".to_string(),
+    }));
+}
+
+#[test]
+fn adjacently_tagged() {
+    roundtrip(my_struct(Adjacent::Unit));
+    roundtrip(my_struct(Adjacent::Newtype(-70000)));
+    roundtrip(my_struct(Adjacent::Tuple(7, "seven".to_string())));
+    roundtrip(my_struct(Adjacent::Struct { letter: 'λ' }));
+}
+
+#[test]
+fn generic() {
+    roundtrip(my_struct(my_struct(vec![1u8, 2, 3])));
+    roundtrip(my_struct(vec![my_struct(true), my_struct(false)]));
+}
+
+#[test]
+fn recursive() {
+    roundtrip(Tree {
+        value: 1,
+        children: vec![
+            Tree {
+                value: 2,
+                children: Vec::new(),
+            },
+            Tree {
+                value: 3,
+                children: vec![Tree {
+                    value: 4,
+                    children: Vec::new(),
+                }],
+            },
+        ],
+    });
+}
+
+#[test]
+fn containers() {
+    roundtrip(Containers {
+        btree: [("a".to_string(), -1), ("b".to_string(), i64::MAX)].into(),
+        hash: [(1, vec![true]), (2, Vec::new())].into(),
+        some: Some(0.25),
+        none: None,
+        pair: ('x', -300),
+    });
+}
+
+#[test]
+fn arrays_and_sets() {
+    use serde_descr::{ArraySchema, SeqSchema, SetSchema, SimpleSchema};
+
+    roundtrip(Fixed {
+        position: [1.0, -0.5, 1e10],
+        digest: std::array::from_fn(|i| i as u8 * 8),
+        none: [],
+        tags: ["a".to_string(), "b".to_string()].into(),
+    });
+
+    assert_eq!(
+        <[u8; 32]>::schema(),
+        ArraySchema::new(SimpleSchema::U8, 32).into()
+    );
+    assert_eq!(Vec::<u8>::schema(), SeqSchema::new(SimpleSchema::U8).into());
+    assert_eq!(
+        std::collections::BTreeSet::<u8>::schema(),
+        SetSchema::new(SimpleSchema::U8).into()
+    );
+
+    // Arrays have a fixed length.
+    let description = Fixed::describe();
+    let decoder = SchemaDecoder::new(&description);
+    let json = r#"{"position":[1,2],"digest":[],"none":[],"tags":[]}"#;
+    let error = decoder
+        .seed(&DeserializerOptions::json(), PhantomData::<Fixed>)
+        .deserialize(&mut serde_json::Deserializer::from_str(json))
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid length 2, expected an array of length 3 at line 1 column 17"
+    );
+
+    // Sets are not checked for duplicates when decoding, but are validated.
+    let json = r#"{"position":[1,2,3],"digest":[0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0],"none":[],"tags":["a","a"]}"#;
+    let value = decoder
+        .seed(
+            &DeserializerOptions::json(),
+            PhantomData::<serde_value::Value>,
+        )
+        .deserialize(&mut serde_json::Deserializer::from_str(json))
+        .unwrap();
+    assert_eq!(
+        description
+            .validate(&value)
+            .iter()
+            .map(|violation| violation.to_string())
+            .collect::<Vec<_>>(),
+        [".tags: element 1 is a duplicate"]
+    );
+}
+
+#[test]
+fn std_types() {
+    roundtrip(StdTypes {
+        deque: [1, 2].into(),
+        list: ["a".to_string()].into(),
+        cell: 7.into(),
+        ref_cell: "cell".to_string().into(),
+        boxed: "boxed".into(),
+        duration: Duration::new(5, 6),
+        time: SystemTime::UNIX_EPOCH + Duration::new(1_700_000_000, 1),
+        path: "/tmp/file".into(),
+        os_string: "os".into(),
+        non_zero: 9.try_into().unwrap(),
+        wrapping: Wrapping(-2),
+        reverse: Reverse(3),
+        range: 1..4,
+        range_inclusive: -1..=1,
+        bound: Bound::Excluded(8),
+        phantom: PhantomData,
+        letter: 'q',
+        array: [1, 2, 3],
+        tuple: (1, "two".to_string(), true),
+        option: Some(Some(3)),
+    });
+}
+
+#[test]
+fn human_readable() {
+    let value = Network {
+        v4: [127, 0, 0, 1].into(),
+        v6: Ipv6Addr::LOCALHOST,
+        ip: [10, 0, 0, 1].into(),
+        socket: "[::1]:80".parse().unwrap(),
+        socket_v6: "[::1]:443".parse().unwrap(),
+    };
+    roundtrip_compact(&value, false);
+    roundtrip_self_describing(&value, false);
+    roundtrip_text(&value);
+}
+
+#[test]
+fn any() {
+    let extra = serde_json::json!({
+        "null": null,
+        "list": [true, -1, 2.5, "three"],
+        "nested": {"empty": {}},
+    });
+    roundtrip(Event { id: 1, extra });
+    roundtrip(Event {
+        id: 2,
+        extra: serde_json::Value::Null,
+    });
+}
+
+#[test]
+fn newtypes() {
+    roundtrip(Wrappers {
+        id: Id(1 << 40),
+        marker: Marker,
+        meters: Meters { value: 2.5 },
+        names: [(Id(1), Some(Id(2))), (Id(3), None)].into(),
+    });
+    roundtrip(vec![Marker, Marker]);
+    roundtrip(Id(7));
+}
+
+#[test]
+fn flattened() {
+    roundtrip_text(&Flattened {
+        id: 1,
+        meta: Meta {
+            version: 2,
+            label: Some("label".to_string()),
+        },
+        extra: [("a".to_string(), 3), ("z".to_string(), 4)].into(),
+    });
+    roundtrip_text(&Flattened {
+        id: 5,
+        meta: Meta {
+            version: 6,
+            label: None,
+        },
+        extra: BTreeMap::new(),
+    });
+
+    // Serde can't write flattened fields to formats without field names,
+    // so neither can we read them.
+    let description = Flattened::describe();
+    let decoder = SchemaDecoder::new(&description);
+    let opts = DeserializerOptions::bincode_varint();
+    let error = bincode::DefaultOptions::new()
+        .deserialize_seed(decoder.seed(&opts, PhantomData::<Flattened>), &[1, 2, 0])
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "cannot decode flattened field meta of struct Flattened: \
+         the format doesn't write field names"
+    );
+}
+
+#[test]
+fn skipped() {
+    roundtrip(Skipped {
+        id: 1,
+        cache: Vec::new(),
+        secret: String::new(),
+        computed: 0,
+    });
+
+    roundtrip_text(&Sparse {
+        id: 2,
+        note: Some("note".to_string()),
+    });
+    roundtrip_text(&Sparse { id: 3, note: None });
+
+    // Without field names, a skipped field can't be told from the next one.
+    let description = Sparse::describe();
+    let decoder = SchemaDecoder::new(&description);
+    let opts = DeserializerOptions::bincode_varint();
+    let error = bincode::DefaultOptions::new()
+        .deserialize_seed(decoder.seed(&opts, PhantomData::<Sparse>), &[3, 0])
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "cannot decode field note of struct Sparse: it is skipped depending on its value, \
+         and the format doesn't write field names"
+    );
+    let lints = description.lint(&opts);
+    assert_eq!(
+        lints
+            .iter()
+            .map(|lint| lint.to_string())
+            .collect::<Vec<_>>(),
+        [
+            "Sparse.note: skip_serializing_if = \"Option::is_none\" makes the field's presence \
+          depend on its value, which a format without field names can't express"
+        ]
+    );
+    assert_eq!(description.lint(&DeserializerOptions::json()), []);
+}
+
+#[test]
+fn integer_enums() {
+    roundtrip(vec![
+        Instruction {
+            op: Opcode::Push,
+            level: Level::Low,
+        },
+        Instruction {
+            op: Opcode::Pop,
+            level: Level::Mid,
+        },
+        Instruction {
+            op: Opcode::Halt,
+            level: Level::High,
+        },
+    ]);
+    roundtrip(Opcode::Nop);
+
+    // Integers that aren't discriminants are rejected.
+    let description = Opcode::describe();
+    let decoder = SchemaDecoder::new(&description);
+    let error = decoder
+        .seed(
+            &DeserializerOptions::json(),
+            PhantomData::<serde_value::Value>,
+        )
+        .deserialize(&mut serde_json::Deserializer::from_str("2"))
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid value: integer `2`, expected a discriminant of enum Opcode at line 1 column 1"
+    );
+}
+
+#[test]
+fn documented() {
+    use serde::{Deserialize, Serialize};
+    use serde_descr::{
+        Deprecated, Description, NamedFieldSchema, NamedFieldsSchema, SchemaName, StructSchema,
+    };
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Account {
+        id: u32,
+        login: String,
+    }
+
+    impl Describe for Account {
+        fn schema_name() -> SchemaName {
+            SchemaName::new("Account")
+        }
+
+        fn add_schema(map: &mut Description) {
+            map.add(Self::schema_name(), || {
+                StructSchema::new(
+                    "Account",
+                    NamedFieldsSchema::new()
+                        .field(
+                            NamedFieldSchema::new("id", u32::schema())
+                                .doc("Unique per tenant.")
+                                .example(serde_value::Value::U64(42)),
+                        )
+                        .field(
+                            NamedFieldSchema::new("login", String::schema())
+                                .deprecated(Deprecated::new().since("2.0").note("use email")),
+                        ),
+                )
+                .doc("A user account.")
+                .annotation("table", "accounts")
+            });
+        }
+    }
+
+    let description = Account::describe();
+    let json = serde_json::to_value(&description).unwrap();
+    let account = &json["items"]["Account"]["Struct"];
+    assert_eq!(account["meta"]["doc"], "A user account.");
+    assert_eq!(account["meta"]["annotations"]["table"], "accounts");
+    let fields = &account["fields"]["Named"]["fields"];
+    assert_eq!(fields[0]["meta"]["examples"][0], 42);
+    assert_eq!(fields[1]["meta"]["deprecated"]["note"], "use email");
+    assert_eq!(
+        serde_json::from_value::<Description>(json).unwrap(),
+        description
+    );
+
+    // Metadata plays no part in decoding.
+    roundtrip(Account {
+        id: 1,
+        login: "login".to_string(),
+    });
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use std::{fs, panic::catch_unwind, path::Path};
+
+use common::*;
+use serde_descr::{
+    assert_schema_compatible_with, assert_schema_snapshot,
+    snapshot::{assert_compatible, assert_snapshot, snapshot_path, UPDATE},
+    Describe, Description, DeserializerOptions,
+};
+
+fn panic_message(f: impl FnOnce() + std::panic::UnwindSafe) -> String {
+    let payload = catch_unwind(f).unwrap_err();
+    payload.downcast::<String>().map(|s| *s).unwrap()
+}
+
+#[test]
+fn snapshots() {
+    assert_schema_snapshot!(Tree, "tests/schemas/tree.json");
+    assert_schema_snapshot!(MyStruct<External>, "tests/schemas/my_struct.idl");
+    assert_eq!(
+        snapshot_path(&MyStruct::<External>::schema_name()),
+        Path::new("schemas/MyStruct_External.json")
+    );
+}
+
+#[test]
+fn compatibility() {
+    // `External` gained a variant at the end since.
+    assert_schema_compatible_with!(MyStruct<External>, "tests/schemas/my_struct.v1.idl");
+    assert_schema_compatible_with!(
+        MyStruct<External>,
+        "tests/schemas/my_struct.v1.idl",
+        DeserializerOptions::bincode_varint()
+    );
+
+    let message = panic_message(|| {
+        assert_schema_compatible_with!(MyStruct<Internal>, "tests/schemas/my_struct.v1.idl")
+    });
+    assert!(
+        message.starts_with("3 of 7 changes since ") && message.contains("my_struct.v1.idl"),
+        "{message}"
+    );
+    assert!(message
+        .contains("\n~ Internal: representation externally tagged -> tag = \"type\" (breaking)\n"));
+}
+
+#[test]
+fn update_mode() {
+    let dir = std::env::temp_dir().join("serde-descr-snapshot");
+    let _ = fs::remove_dir_all(&dir);
+    let (snapshot, compat) = (dir.join("tree.idl"), dir.join("v1/tree.json"));
+    let renamed = Description::from_idl(
+        r#"root Tree; struct Tree { value: u16, #[serde(rename = "kids")] children: Vec<Tree> }"#,
+    )
+    .unwrap();
+
+    std::env::remove_var(UPDATE);
+    let message = panic_message(|| assert_snapshot(&Tree::describe(), &snapshot));
+    assert!(message.starts_with("no schema snapshot at "), "{message}");
+
+    std::env::set_var(UPDATE, "1");
+    assert_snapshot(&renamed, &snapshot);
+    assert_compatible(&renamed, &compat, &DeserializerOptions::json());
+    assert_eq!(fs::read_to_string(&snapshot).unwrap(), renamed.idl());
+    let json: Description = serde_json::from_slice(&fs::read(&compat).unwrap()).unwrap();
+    assert_eq!(json, renamed);
+
+    // Snapshots are updated, but files of released versions are not.
+    assert_snapshot(&Tree::describe(), &snapshot);
+    assert_eq!(
+        fs::read_to_string(&snapshot).unwrap(),
+        Tree::describe().idl()
+    );
+    let message = panic_message(|| {
+        assert_compatible(&Tree::describe(), &compat, &DeserializerOptions::json())
+    });
+    assert!(message.ends_with("~ Tree.children: field renamed kids -> children (breaking)\n"));
+
+    std::env::remove_var(UPDATE);
+    let message = panic_message(|| assert_snapshot(&renamed, &snapshot));
+    assert!(
+        message.ends_with(
+            ":\n~ Tree.children: field renamed children -> kids (breaking)\n\
+             run with SERDE_DESCR_UPDATE=1 to update it if the change is intended"
+        ),
+        "{message}"
+    );
+}
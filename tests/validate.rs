@@ -0,0 +1,152 @@
+mod common;
+
+use std::{collections::BTreeMap, marker::PhantomData};
+
+use common::{roundtrip::roundtrip, *};
+use serde::de::DeserializeSeed;
+use serde_descr::{
+    ConstrainedSchema, Constraints, Describe, Description, DeserializerOptions, SchemaDecoder,
+};
+use serde_json::json;
+
+fn listener() -> Listener {
+    Listener {
+        name: "api".to_string(),
+        port: 8080,
+        load: 0.5,
+        hosts: vec!["a.example".to_string(), "b.example".to_string()],
+        limits: BTreeMap::from([("cpu".to_string(), 80)]),
+    }
+}
+
+fn violations(listener: &Listener) -> Vec<String> {
+    let description = Listener::describe();
+    let decoder = SchemaDecoder::new(&description);
+    let json = serde_json::to_string(listener).unwrap();
+    let value = decoder
+        .seed(&DeserializerOptions::json(), PhantomData)
+        .deserialize(&mut serde_json::Deserializer::from_str(&json))
+        .unwrap();
+    description
+        .validate(&value)
+        .iter()
+        .map(|violation| violation.to_string())
+        .collect()
+}
+
+#[test]
+fn constraints_do_not_change_decoding() {
+    roundtrip(listener());
+}
+
+#[test]
+fn validate() {
+    assert_eq!(violations(&listener()), Vec::<String>::new());
+
+    let invalid = Listener {
+        name: "0-api-gateway-internal".to_string(),
+        port: 0,
+        load: -1.5,
+        hosts: vec!["a.example".to_string(), "a.example".to_string()],
+        limits: BTreeMap::from([("cpu".to_string(), 150), ("Disk".to_string(), 10)]),
+    };
+    assert_eq!(
+        violations(&invalid),
+        [
+            ".hosts: element 1 is a duplicate",
+            ".limits: key \"Disk\" does not match pattern \"^[a-z]+$\"",
+            ".limits[\"cpu\"]: 150 is greater than the maximum 100",
+            ".load: -1.5 is less than the minimum 0",
+            ".name: length 22 is greater than the maximum 16",
+            ".name: \"0-api-gateway-internal\" does not match pattern \"^[a-z][a-z0-9-]*$\"",
+            ".port: 0 is less than the minimum 1",
+        ]
+    );
+
+    let empty = Listener {
+        hosts: Vec::new(),
+        ..listener()
+    };
+    assert_eq!(
+        violations(&empty),
+        [".hosts: length 0 is less than the minimum 1"]
+    );
+}
+
+#[test]
+fn float_bounds() {
+    let ratio = ConstrainedSchema::new(f64::schema(), Constraints::new().range_f64(0.0..=0.75));
+    let description = Description::new(ratio.into());
+    let violations = |n: f64| -> Vec<String> {
+        let violations = description.validate(&serde_value::Value::F64(n));
+        violations.iter().map(|v| v.to_string()).collect()
+    };
+    assert_eq!(violations(0.75), Vec::<String>::new());
+    assert_eq!(violations(0.8), [".: 0.8 is greater than the maximum 0.75"]);
+    assert_eq!(violations(-0.5), [".: -0.5 is less than the minimum 0.0"]);
+
+    // JSON numbers can't be infinite, and an infinite bound is no bound.
+    let unbounded = Constraints::new().min_f64(f64::NEG_INFINITY);
+    assert_eq!(unbounded, Constraints::new());
+}
+
+#[test]
+fn json_schema() {
+    let schema = Listener::describe().json_schema();
+    assert_eq!(
+        schema["$schema"],
+        "https://json-schema.org/draft/2020-12/schema"
+    );
+    assert_eq!(schema["$ref"], "#/$defs/Listener");
+    let properties = &schema["$defs"]["Listener"]["properties"];
+    assert_eq!(
+        properties["name"],
+        json!({ "type": "string", "maxLength": 16, "pattern": "^[a-z][a-z0-9-]*$" })
+    );
+    assert_eq!(
+        properties["port"],
+        json!({ "type": "integer", "minimum": 1, "maximum": 65535 })
+    );
+    assert_eq!(
+        properties["load"],
+        json!({ "type": "number", "minimum": 0 })
+    );
+    assert_eq!(
+        properties["hosts"],
+        json!({
+            "type": "array",
+            "items": { "type": "string" },
+            "minItems": 1,
+            "uniqueItems": true,
+        })
+    );
+    assert_eq!(
+        properties["limits"],
+        json!({
+            "type": "object",
+            "additionalProperties": { "type": "integer", "minimum": 0, "maximum": 100 },
+            "propertyNames": { "pattern": "^[a-z]+$" },
+        })
+    );
+    assert_eq!(
+        schema["$defs"]["Listener"]["required"],
+        json!(["name", "port", "load", "hosts", "limits"])
+    );
+}
+
+#[test]
+fn json_schema_enums() {
+    let schema = Instruction::describe().json_schema();
+    assert_eq!(
+        schema["$defs"]["Opcode"],
+        json!({ "type": "integer", "minimum": 0, "maximum": 255, "enum": [0, 16, 17, 255] })
+    );
+
+    let schema = Flattened::describe().json_schema();
+    let flattened = &schema["$defs"]["Flattened"];
+    assert_eq!(flattened["allOf"], json!([{ "$ref": "#/$defs/Meta" }]));
+    assert_eq!(
+        flattened["unevaluatedProperties"],
+        json!({ "type": "integer", "minimum": 0, "maximum": 4294967295u32 })
+    );
+}